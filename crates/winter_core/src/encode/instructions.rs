@@ -0,0 +1,412 @@
+use std::vec::Vec;
+
+use super::Encode;
+use crate::parse::{
+    instructions::{
+        BlockType, ControlInstruction, Expr, Instr, MemArg, MemoryInstruction, NumericInstruction,
+        ParametricInstruction, ReferenceInstruction, TableInstruction, VariableInstruction,
+        VectorInstruction,
+    },
+    values::{encode_leb128_i32, encode_leb128_i33, encode_leb128_i64, encode_leb128_u32},
+};
+
+/// Writes the opcode made of `prefix` and the sub-opcode `opcode`.
+fn prefixed_opcode(sink: &mut Vec<u8>, prefix: u8, opcode: u32) {
+    sink.push(prefix);
+    encode_leb128_u32(sink, opcode);
+}
+
+impl Encode for Expr {
+    /// Expressions are terminated by the `end` opcode.
+    fn encode(&self, sink: &mut Vec<u8>) {
+        for instr in &self.instr {
+            instr.encode(sink);
+        }
+        sink.push(0x0B);
+    }
+}
+
+impl Encode for BlockType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            BlockType::Empty => sink.push(0x40),
+            BlockType::ValType(t) => t.encode(sink),
+            // Type indices are encoded as positive signed 33-bit integers.
+            BlockType::TypeIndex(x) => encode_leb128_i33(sink, x.0.into()),
+        }
+    }
+}
+
+impl Encode for MemArg {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        encode_leb128_u32(sink, self.align);
+        encode_leb128_u32(sink, self.offset);
+    }
+}
+
+impl Encode for Instr {
+    /// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html)
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            Instr::Control(i) => i.encode(sink),
+            Instr::Reference(i) => i.encode(sink),
+            Instr::Parametric(i) => i.encode(sink),
+            Instr::Variable(i) => i.encode(sink),
+            Instr::Table(i) => i.encode(sink),
+            Instr::Memory(i) => i.encode(sink),
+            Instr::Numeric(i) => i.encode(sink),
+            Instr::Vector(i) => i.encode(sink),
+        }
+    }
+}
+
+impl Encode for ControlInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            ControlInstruction::Unreachable => sink.push(0x00),
+            ControlInstruction::Nop => sink.push(0x01),
+            ControlInstruction::Block(bt, body) | ControlInstruction::Loop(bt, body) => {
+                let opcode = match self {
+                    ControlInstruction::Block(..) => 0x02,
+                    _ => 0x03,
+                };
+                sink.push(opcode);
+                bt.encode(sink);
+                body.iter().for_each(|instr| instr.encode(sink));
+                sink.push(0x0B);
+            }
+            ControlInstruction::If(bt, then, otherwise) => {
+                sink.push(0x04);
+                bt.encode(sink);
+                then.iter().for_each(|instr| instr.encode(sink));
+                // An empty else branch is left out.
+                if !otherwise.is_empty() {
+                    sink.push(0x05);
+                    otherwise.iter().for_each(|instr| instr.encode(sink));
+                }
+                sink.push(0x0B);
+            }
+            ControlInstruction::Br(l) => {
+                sink.push(0x0C);
+                l.encode(sink);
+            }
+            ControlInstruction::BrIf(l) => {
+                sink.push(0x0D);
+                l.encode(sink);
+            }
+            ControlInstruction::BrTable(labels, default) => {
+                sink.push(0x0E);
+                labels.encode(sink);
+                default.encode(sink);
+            }
+            ControlInstruction::Return => sink.push(0x0F),
+            ControlInstruction::Call(x) => {
+                sink.push(0x10);
+                x.encode(sink);
+            }
+            ControlInstruction::CallIndirect(y, x) => {
+                sink.push(0x11);
+                y.encode(sink);
+                x.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for ReferenceInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            ReferenceInstruction::RefNull(t) => {
+                sink.push(0xD0);
+                t.encode(sink);
+            }
+            ReferenceInstruction::RefIsNull => sink.push(0xD1),
+            ReferenceInstruction::RefFunc(x) => {
+                sink.push(0xD2);
+                x.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for ParametricInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            ParametricInstruction::Drop => sink.push(0x1A),
+            ParametricInstruction::Select(None) => sink.push(0x1B),
+            ParametricInstruction::Select(Some(types)) => {
+                sink.push(0x1C);
+                types.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for VariableInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            VariableInstruction::LocalGet(x) => {
+                sink.push(0x20);
+                x.encode(sink);
+            }
+            VariableInstruction::LocalSet(x) => {
+                sink.push(0x21);
+                x.encode(sink);
+            }
+            VariableInstruction::LocalTee(x) => {
+                sink.push(0x22);
+                x.encode(sink);
+            }
+            VariableInstruction::GlobalGet(x) => {
+                sink.push(0x23);
+                x.encode(sink);
+            }
+            VariableInstruction::GlobalSet(x) => {
+                sink.push(0x24);
+                x.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for TableInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            TableInstruction::TableGet(x) => {
+                sink.push(0x25);
+                x.encode(sink);
+            }
+            TableInstruction::TableSet(x) => {
+                sink.push(0x26);
+                x.encode(sink);
+            }
+            TableInstruction::TableInit(y, x) => {
+                prefixed_opcode(sink, 0xFC, 12);
+                y.encode(sink);
+                x.encode(sink);
+            }
+            TableInstruction::ElemDrop(x) => {
+                prefixed_opcode(sink, 0xFC, 13);
+                x.encode(sink);
+            }
+            TableInstruction::TableCopy(x, y) => {
+                prefixed_opcode(sink, 0xFC, 14);
+                x.encode(sink);
+                y.encode(sink);
+            }
+            TableInstruction::TableGrow(x) => {
+                prefixed_opcode(sink, 0xFC, 15);
+                x.encode(sink);
+            }
+            TableInstruction::TableSize(x) => {
+                prefixed_opcode(sink, 0xFC, 16);
+                x.encode(sink);
+            }
+            TableInstruction::TableFill(x) => {
+                prefixed_opcode(sink, 0xFC, 17);
+                x.encode(sink);
+            }
+        }
+    }
+}
+
+impl MemoryInstruction {
+    /// Returns the opcode and the memory argument of loads and stores.
+    fn memarg(&self) -> Option<(u8, MemArg)> {
+        use MemoryInstruction::*;
+        Some(match *self {
+            I32Load(m) => (0x28, m),
+            I64Load(m) => (0x29, m),
+            F32Load(m) => (0x2A, m),
+            F64Load(m) => (0x2B, m),
+            I32Load8S(m) => (0x2C, m),
+            I32Load8U(m) => (0x2D, m),
+            I32Load16S(m) => (0x2E, m),
+            I32Load16U(m) => (0x2F, m),
+            I64Load8S(m) => (0x30, m),
+            I64Load8U(m) => (0x31, m),
+            I64Load16S(m) => (0x32, m),
+            I64Load16U(m) => (0x33, m),
+            I64Load32S(m) => (0x34, m),
+            I64Load32U(m) => (0x35, m),
+            I32Store(m) => (0x36, m),
+            I64Store(m) => (0x37, m),
+            F32Store(m) => (0x38, m),
+            F64Store(m) => (0x39, m),
+            I32Store8(m) => (0x3A, m),
+            I32Store16(m) => (0x3B, m),
+            I64Store8(m) => (0x3C, m),
+            I64Store16(m) => (0x3D, m),
+            I64Store32(m) => (0x3E, m),
+            _ => return None,
+        })
+    }
+}
+
+impl Encode for MemoryInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        if let Some((opcode, memarg)) = self.memarg() {
+            sink.push(opcode);
+            return memarg.encode(sink);
+        }
+        match self {
+            MemoryInstruction::MemorySize(x) => {
+                sink.push(0x3F);
+                x.encode(sink);
+            }
+            MemoryInstruction::MemoryGrow(x) => {
+                sink.push(0x40);
+                x.encode(sink);
+            }
+            MemoryInstruction::MemoryInit(y, x) => {
+                prefixed_opcode(sink, 0xFC, 8);
+                y.encode(sink);
+                x.encode(sink);
+            }
+            MemoryInstruction::DataDrop(x) => {
+                prefixed_opcode(sink, 0xFC, 9);
+                x.encode(sink);
+            }
+            MemoryInstruction::MemoryCopy(x, y) => {
+                prefixed_opcode(sink, 0xFC, 10);
+                x.encode(sink);
+                y.encode(sink);
+            }
+            MemoryInstruction::MemoryFill(x) => {
+                prefixed_opcode(sink, 0xFC, 11);
+                x.encode(sink);
+            }
+            // Loads and stores are encoded above.
+            _ => unreachable!("memory instruction without a memory argument"),
+        }
+    }
+}
+
+impl Encode for NumericInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        if let Some(opcode) = self.opcode() {
+            return sink.push(opcode);
+        }
+        if let Some(opcode) = self.prefixed_opcode() {
+            return prefixed_opcode(sink, 0xFC, opcode);
+        }
+        match *self {
+            NumericInstruction::I32Const(v) => {
+                sink.push(0x41);
+                encode_leb128_i32(sink, v);
+            }
+            NumericInstruction::I64Const(v) => {
+                sink.push(0x42);
+                encode_leb128_i64(sink, v);
+            }
+            NumericInstruction::F32Const(v) => {
+                sink.push(0x43);
+                sink.extend(v.to_le_bytes());
+            }
+            NumericInstruction::F64Const(v) => {
+                sink.push(0x44);
+                sink.extend(v.to_le_bytes());
+            }
+            // All other numeric instructions are in the opcode tables.
+            _ => unreachable!("numeric instruction without an opcode"),
+        }
+    }
+}
+
+impl VectorInstruction {
+    /// Returns the sub-opcode and the memory argument of the instructions
+    /// that take only a memory argument.
+    fn memarg(&self) -> Option<(u32, MemArg)> {
+        use VectorInstruction::*;
+        Some(match *self {
+            V128Load(m) => (0, m),
+            V128Load8x8S(m) => (1, m),
+            V128Load8x8U(m) => (2, m),
+            V128Load16x4S(m) => (3, m),
+            V128Load16x4U(m) => (4, m),
+            V128Load32x2S(m) => (5, m),
+            V128Load32x2U(m) => (6, m),
+            V128Load8Splat(m) => (7, m),
+            V128Load16Splat(m) => (8, m),
+            V128Load32Splat(m) => (9, m),
+            V128Load64Splat(m) => (10, m),
+            V128Store(m) => (11, m),
+            V128Load32Zero(m) => (92, m),
+            V128Load64Zero(m) => (93, m),
+            _ => return None,
+        })
+    }
+
+    /// Returns the sub-opcode and the lane index of the instructions that
+    /// take only a lane index.
+    fn lane(&self) -> Option<(u32, u8)> {
+        use VectorInstruction::*;
+        Some(match *self {
+            I8x16ExtractLaneS(l) => (21, l),
+            I8x16ExtractLaneU(l) => (22, l),
+            I8x16ReplaceLane(l) => (23, l),
+            I16x8ExtractLaneS(l) => (24, l),
+            I16x8ExtractLaneU(l) => (25, l),
+            I16x8ReplaceLane(l) => (26, l),
+            I32x4ExtractLane(l) => (27, l),
+            I32x4ReplaceLane(l) => (28, l),
+            I64x2ExtractLane(l) => (29, l),
+            I64x2ReplaceLane(l) => (30, l),
+            F32x4ExtractLane(l) => (31, l),
+            F32x4ReplaceLane(l) => (32, l),
+            F64x2ExtractLane(l) => (33, l),
+            F64x2ReplaceLane(l) => (34, l),
+            _ => return None,
+        })
+    }
+
+    /// Returns the sub-opcode, the memory argument and the lane index of
+    /// the instructions that take both.
+    fn memarg_lane(&self) -> Option<(u32, MemArg, u8)> {
+        use VectorInstruction::*;
+        Some(match *self {
+            V128Load8Lane(m, l) => (84, m, l),
+            V128Load16Lane(m, l) => (85, m, l),
+            V128Load32Lane(m, l) => (86, m, l),
+            V128Load64Lane(m, l) => (87, m, l),
+            V128Store8Lane(m, l) => (88, m, l),
+            V128Store16Lane(m, l) => (89, m, l),
+            V128Store32Lane(m, l) => (90, m, l),
+            V128Store64Lane(m, l) => (91, m, l),
+            _ => return None,
+        })
+    }
+}
+
+impl Encode for VectorInstruction {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        if let Some(opcode) = self.prefixed_opcode() {
+            return prefixed_opcode(sink, 0xFD, opcode);
+        }
+        if let Some((opcode, memarg)) = self.memarg() {
+            prefixed_opcode(sink, 0xFD, opcode);
+            return memarg.encode(sink);
+        }
+        if let Some((opcode, lane)) = self.lane() {
+            prefixed_opcode(sink, 0xFD, opcode);
+            return sink.push(lane);
+        }
+        if let Some((opcode, memarg, lane)) = self.memarg_lane() {
+            prefixed_opcode(sink, 0xFD, opcode);
+            memarg.encode(sink);
+            return sink.push(lane);
+        }
+        match self {
+            VectorInstruction::V128Const(v) => {
+                prefixed_opcode(sink, 0xFD, 12);
+                sink.extend(v.to_le_bytes());
+            }
+            VectorInstruction::I8x16Shuffle(lanes) => {
+                prefixed_opcode(sink, 0xFD, 13);
+                sink.extend(lanes);
+            }
+            // All other vector instructions are in the opcode table.
+            _ => unreachable!("vector instruction without an opcode"),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Encoding of modules in the WebAssembly binary format.
+//!
+//! Every value is written in its canonical form: integers use the shortest
+//! LEB128 encoding and segments use the most compact flags, so that a
+//! canonically encoded module is encoded back to the same bytes once parsed
+//! with [`module_parser`](crate::parse::modules::module::module_parser).
+//! Custom sections are written at the position they were parsed from.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/binary/index.html)
+
+mod instructions;
+mod sections;
+mod types;
+
+use std::vec::Vec;
+
+use crate::parse::{
+    modules::{
+        module::Module, DataIdx, ElemIdx, FuncIdx, GlobalIdx, LabelIdx, LocalIdx, MemIdx, TableIdx,
+        TypeIdx,
+    },
+    values::encode_leb128_u32,
+};
+
+/// Binary encoding of a part of a module.
+pub trait Encode {
+    /// Appends the encoding of `self` to `sink`.
+    fn encode(&self, sink: &mut Vec<u8>);
+}
+
+/// Returns the binary encoding of `module`.
+pub fn encode(module: &Module) -> Vec<u8> {
+    let mut sink = Vec::new();
+    module.encode(&mut sink);
+    sink
+}
+
+impl<T: Encode> Encode for [T] {
+    /// Vectors are encoded with their length followed by their elements.
+    fn encode(&self, sink: &mut Vec<u8>) {
+        encode_leb128_u32(sink, self.len() as u32);
+        for item in self {
+            item.encode(sink);
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.as_slice().encode(sink)
+    }
+}
+
+impl Encode for str {
+    /// Names are encoded as the vector of their UTF-8 bytes.
+    fn encode(&self, sink: &mut Vec<u8>) {
+        bytes(sink, self.as_bytes());
+    }
+}
+
+macro_rules! impl_encode_idx {
+    ($($typename:ident),*) => {
+        $(
+            impl Encode for $typename {
+                fn encode(&self, sink: &mut Vec<u8>) {
+                    encode_leb128_u32(sink, self.0);
+                }
+            }
+        )*
+    };
+}
+
+impl_encode_idx!(
+    TypeIdx, FuncIdx, TableIdx, MemIdx, GlobalIdx, ElemIdx, DataIdx, LocalIdx, LabelIdx
+);
+
+/// Writes the byte vector `data`, its length followed by its bytes.
+fn bytes(sink: &mut Vec<u8>, data: &[u8]) {
+    encode_leb128_u32(sink, data.len() as u32);
+    sink.extend_from_slice(data);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::modules::module::module_parser;
+
+    /// Returns `content` preceded by its size, which must be less than 128.
+    fn sized(content: &[u8]) -> Vec<u8> {
+        let mut sized = vec![content.len() as u8];
+        sized.extend(content);
+        sized
+    }
+
+    fn section(id: u8, content: &[u8]) -> Vec<u8> {
+        let mut section = vec![id];
+        section.extend(sized(content));
+        section
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut binary = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        // custom section "a" before all sections
+        binary.extend(section(0, &[0x01, b'a', 0x07]));
+        // (type (func (param i32 i64) (result f32))) (type (func))
+        binary.extend(section(
+            1,
+            &[0x02, 0x60, 0x02, 0x7F, 0x7E, 0x01, 0x7D, 0x60, 0x00, 0x00],
+        ));
+        // (import "m" "t" (table 1 funcref))
+        binary.extend(section(
+            2,
+            &[0x01, 0x01, b'm', 0x01, b't', 0x01, 0x70, 0x00, 0x01],
+        ));
+        binary.extend(section(3, &[0x02, 0x01, 0x01]));
+        // (memory 1 2)
+        binary.extend(section(5, &[0x01, 0x01, 0x01, 0x02]));
+        // (global (mut i64) (i64.const -300))
+        binary.extend(section(6, &[0x01, 0x7E, 0x01, 0x42, 0xD4, 0x7D, 0x0B]));
+        // (export "f" (func 1))
+        binary.extend(section(7, &[0x01, 0x01, b'f', 0x00, 0x01]));
+        // custom section "b" after the exports
+        binary.extend(section(0, &[0x01, b'b']));
+        binary.extend(section(8, &[0x01]));
+        // (elem (i32.const 0) func 1)
+        // (elem funcref (ref.null func))
+        // (elem (table 1) (i32.const 2) funcref (ref.func 0))
+        // (elem declare func 2)
+        binary.extend(section(
+            9,
+            &[
+                0x04, 0x00, 0x41, 0x00, 0x0B, 0x01, 0x01, 0x05, 0x70, 0x01, 0xD0, 0x70, 0x0B, 0x06,
+                0x01, 0x41, 0x02, 0x0B, 0x70, 0x01, 0xD2, 0x00, 0x0B, 0x03, 0x00, 0x01, 0x02,
+            ],
+        ));
+        binary.extend(section(12, &[0x02]));
+        let mut code = vec![0x02];
+        // (local i32 i32)
+        // block (result i32) i32.const 1 if else nop end i32.const 0
+        // br_table 0 0 end drop i32.const 0 i64.load offset=8 drop
+        code.extend(sized(&[
+            0x01, 0x02, 0x7F, 0x02, 0x7F, 0x41, 0x01, 0x04, 0x40, 0x05, 0x01, 0x0B, 0x41, 0x00,
+            0x0E, 0x01, 0x00, 0x00, 0x0B, 0x1A, 0x41, 0x00, 0x29, 0x03, 0x08, 0x1A, 0x0B,
+        ]));
+        // v128.const i32x4 1 0 0 0 i32x4.extract_lane 3 f64x2.convert_low_i32x4_u
+        // f64.const 1 i64.trunc_sat_f64_u memory.init 1 0 ref.func 2
+        // select (result funcref) call_indirect 0 (type 1)
+        let mut body = vec![0x00, 0xFD, 0x0C];
+        body.extend(1u128.to_le_bytes());
+        body.extend([0xFD, 0x1B, 0x03, 0xFD, 0xFF, 0x01, 0x44]);
+        body.extend(1f64.to_le_bytes());
+        body.extend([
+            0xFC, 0x07, 0xFC, 0x08, 0x01, 0x00, 0xD2, 0x02, 0x1C, 0x01, 0x70, 0x11, 0x01, 0x00,
+            0x0B,
+        ]);
+        code.extend(sized(&body));
+        binary.extend(section(10, &code));
+        // (data (i32.const 16) "hi") (data "")
+        binary.extend(section(
+            11,
+            &[0x02, 0x00, 0x41, 0x10, 0x0B, 0x02, b'h', b'i', 0x01, 0x00],
+        ));
+        // custom section "c" after the data
+        binary.extend(section(0, &[0x01, b'c', 0xAA, 0xBB]));
+
+        let mut module = module_parser(&binary).unwrap();
+        assert_eq!(module.custom_sections[1].after, 7);
+        assert_eq!(encode(&module), binary);
+
+        // The functions are encoded from their instructions once set.
+        let decoded = module_parser(&binary).unwrap().code.unwrap().code;
+        let code = &mut module.code.as_mut().unwrap().code;
+        for (code, decoded) in code.iter_mut().zip(decoded) {
+            code.set_func(decoded.into_func().unwrap());
+            assert!(code.body.is_none());
+        }
+        assert_eq!(encode(&module), binary);
+    }
+}
@@ -0,0 +1,313 @@
+use std::vec::Vec;
+
+use super::{bytes, Encode};
+use crate::parse::{
+    modules::{
+        code_section::{Code, CodeSection, Func, Locals},
+        custom_section::CustomSection,
+        data_count_section::DataCountSection,
+        data_section::{Data, DataMode, DataSection},
+        element_section::{Elem, ElemInit, ElemKind, ElemMode, ElementSection},
+        export_section::{Export, ExportDesc, ExportSection},
+        function_section::FuncSection,
+        global_section::{Global, GlobalSection},
+        import_section::{Import, ImportDesc, ImportSection},
+        memory_section::{Mem, MemSection},
+        module::Module,
+        start_section::StartSection,
+        table_section::{Table, TableSection},
+        type_section::TypeSection,
+        TableIdx,
+    },
+    values::encode_leb128_u32,
+};
+
+/// Ids of the known sections, in the order in which they appear in a
+/// module.
+const SECTION_ORDER: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 12, 10, 11];
+
+/// Writes the section `id`, its size followed by `content`.
+fn section(sink: &mut Vec<u8>, id: u8, content: &impl Encode) {
+    let mut data = Vec::new();
+    content.encode(&mut data);
+    sink.push(id);
+    bytes(sink, &data);
+}
+
+impl Encode for Module<'_> {
+    /// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#binary-module)
+    fn encode(&self, sink: &mut Vec<u8>) {
+        sink.extend([0x00, 0x61, 0x73, 0x6D]);
+        sink.extend([0x01, 0x00, 0x00, 0x00]);
+        self.custom_sections(sink, 0);
+        for id in SECTION_ORDER {
+            match id {
+                1 => self.func_type.iter().for_each(|s| section(sink, id, s)),
+                2 => self.import.iter().for_each(|s| section(sink, id, s)),
+                3 => self.type_idx.iter().for_each(|s| section(sink, id, s)),
+                4 => self.table.iter().for_each(|s| section(sink, id, s)),
+                5 => self.mem.iter().for_each(|s| section(sink, id, s)),
+                6 => self.global.iter().for_each(|s| section(sink, id, s)),
+                7 => self.export.iter().for_each(|s| section(sink, id, s)),
+                8 => self.start.iter().for_each(|s| section(sink, id, s)),
+                9 => self.elem.iter().for_each(|s| section(sink, id, s)),
+                12 => self.m.iter().for_each(|s| section(sink, id, s)),
+                10 => self.code.iter().for_each(|s| section(sink, id, s)),
+                11 => self.data.iter().for_each(|s| section(sink, id, s)),
+                _ => unreachable!(),
+            }
+            self.custom_sections(sink, id);
+        }
+    }
+}
+
+impl Module<'_> {
+    /// Writes the custom sections that follow the section with id `after`.
+    fn custom_sections(&self, sink: &mut Vec<u8>, after: u8) {
+        for custom in self.custom_sections.iter().filter(|s| s.after == after) {
+            section(sink, 0, custom);
+        }
+    }
+}
+
+impl Encode for CustomSection<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.name.encode(sink);
+        sink.extend_from_slice(self.data);
+    }
+}
+
+impl Encode for TypeSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.function_types.encode(sink);
+    }
+}
+
+impl Encode for Import<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.mod_name.encode(sink);
+        self.name.encode(sink);
+        match &self.desc {
+            ImportDesc::Func(x) => {
+                sink.push(0x00);
+                x.encode(sink);
+            }
+            ImportDesc::Table(t) => {
+                sink.push(0x01);
+                t.encode(sink);
+            }
+            ImportDesc::Mem(t) => {
+                sink.push(0x02);
+                t.encode(sink);
+            }
+            ImportDesc::Global(t) => {
+                sink.push(0x03);
+                t.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for ImportSection<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.imports.encode(sink);
+    }
+}
+
+impl Encode for FuncSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.functions.encode(sink);
+    }
+}
+
+impl Encode for Table {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.table_type.encode(sink);
+    }
+}
+
+impl Encode for TableSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.tables.encode(sink);
+    }
+}
+
+impl Encode for Mem {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.mem_type.encode(sink);
+    }
+}
+
+impl Encode for MemSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.tables.encode(sink);
+    }
+}
+
+impl Encode for Global {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.global_type.encode(sink);
+        self.expr.encode(sink);
+    }
+}
+
+impl Encode for GlobalSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.globals.encode(sink);
+    }
+}
+
+impl Encode for Export<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.name.encode(sink);
+        match &self.desc {
+            ExportDesc::Func(x) => {
+                sink.push(0x00);
+                x.encode(sink);
+            }
+            ExportDesc::Table(x) => {
+                sink.push(0x01);
+                x.encode(sink);
+            }
+            ExportDesc::Mem(x) => {
+                sink.push(0x02);
+                x.encode(sink);
+            }
+            ExportDesc::Global(x) => {
+                sink.push(0x03);
+                x.encode(sink);
+            }
+        }
+    }
+}
+
+impl Encode for ExportSection<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.exports.encode(sink);
+    }
+}
+
+impl Encode for StartSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.start.encode(sink);
+    }
+}
+
+impl Encode for Elem {
+    /// Segments are written with the most compact flags that decode back to
+    /// them: the table and the element type are left out of active segments
+    /// of table 0 with the implicit `funcref` type.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#element-section)
+    fn encode(&self, sink: &mut Vec<u8>) {
+        let exprs = matches!(self.init, ElemInit::Expr(_));
+        let implicit = matches!(
+            (&self.mode, &self.elem_type),
+            (
+                ElemMode::Active {
+                    table: TableIdx(0),
+                    ..
+                },
+                ElemKind::FuncRef
+            )
+        );
+        let flags = match &self.mode {
+            ElemMode::Active { .. } if implicit => 0,
+            ElemMode::Passive => 1,
+            ElemMode::Active { .. } => 2,
+            ElemMode::Declarative => 3,
+        };
+        sink.push(flags | if exprs { 4 } else { 0 });
+        if let ElemMode::Active { table, offset } = &self.mode {
+            if !implicit {
+                table.encode(sink);
+            }
+            offset.encode(sink);
+        }
+        if !implicit {
+            match exprs {
+                // elemkind, of which funcref is the only one
+                false => sink.push(0x00),
+                true => self.ref_type().encode(sink),
+            }
+        }
+        match &self.init {
+            ElemInit::Expr(exprs) => exprs.encode(sink),
+            ElemInit::FuncIdx(funcs) => funcs.encode(sink),
+        }
+    }
+}
+
+impl Encode for ElementSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.elems.encode(sink);
+    }
+}
+
+impl Encode for DataCountSection {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        encode_leb128_u32(sink, self.count);
+    }
+}
+
+impl Encode for Locals {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        encode_leb128_u32(sink, self.count);
+        self.val_type.encode(sink);
+    }
+}
+
+impl Encode for Func {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.locals.encode(sink);
+        self.expr.encode(sink);
+    }
+}
+
+impl Encode for Code<'_> {
+    /// The body of a function decoded from a binary module is written as
+    /// is, without being decoded. The size of other functions is computed
+    /// from their encoding.
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self.body {
+            Some(body) => bytes(sink, body),
+            None => {
+                let mut body = Vec::new();
+                self.func()
+                    .expect("functions without encoding are decoded")
+                    .encode(&mut body);
+                bytes(sink, &body);
+            }
+        }
+    }
+}
+
+impl Encode for CodeSection<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.code.encode(sink);
+    }
+}
+
+impl Encode for Data<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match &self.mode {
+            DataMode::Active { memory, offset } if memory.0 == 0 => {
+                sink.push(0x00);
+                offset.encode(sink);
+            }
+            DataMode::Passive => sink.push(0x01),
+            DataMode::Active { memory, offset } => {
+                sink.push(0x02);
+                memory.encode(sink);
+                offset.encode(sink);
+            }
+        }
+        bytes(sink, self.init);
+    }
+}
+
+impl Encode for DataSection<'_> {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.data.encode(sink);
+    }
+}
@@ -0,0 +1,85 @@
+use std::vec::Vec;
+
+use super::Encode;
+use crate::parse::{
+    types::{FuncType, GlobalType, Limits, NumType, RefType, TableType, ValType, VecType},
+    values::encode_leb128_u32,
+};
+
+impl Encode for NumType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        sink.push(match self {
+            NumType::I32 => 0x7F,
+            NumType::I64 => 0x7E,
+            NumType::F32 => 0x7D,
+            NumType::F64 => 0x7C,
+        });
+    }
+}
+
+impl Encode for VecType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            VecType::V128 => sink.push(0x7B),
+        }
+    }
+}
+
+impl Encode for RefType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        sink.push(match self {
+            RefType::FuncRef => 0x70,
+            RefType::ExternRef => 0x6F,
+        });
+    }
+}
+
+impl Encode for ValType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self {
+            ValType::NumType(t) => t.encode(sink),
+            ValType::VecType(t) => t.encode(sink),
+            ValType::RefType(t) => t.encode(sink),
+        }
+    }
+}
+
+impl Encode for FuncType {
+    /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#function-types)
+    fn encode(&self, sink: &mut Vec<u8>) {
+        sink.push(0x60);
+        self.params.encode(sink);
+        self.ret.encode(sink);
+    }
+}
+
+impl Encode for Limits {
+    /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#limits)
+    fn encode(&self, sink: &mut Vec<u8>) {
+        match self.max {
+            None => {
+                sink.push(0x00);
+                encode_leb128_u32(sink, self.min);
+            }
+            Some(max) => {
+                sink.push(0x01);
+                encode_leb128_u32(sink, self.min);
+                encode_leb128_u32(sink, max);
+            }
+        }
+    }
+}
+
+impl Encode for TableType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.ref_type.encode(sink);
+        self.limits.encode(sink);
+    }
+}
+
+impl Encode for GlobalType {
+    fn encode(&self, sink: &mut Vec<u8>) {
+        self.value_type.encode(sink);
+        sink.push(self.mutable.into());
+    }
+}
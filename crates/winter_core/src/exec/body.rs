@@ -0,0 +1,154 @@
+use std::{boxed::Box, vec::Vec};
+
+use crate::parse::instructions::{BlockType, ControlInstruction, Expr, Instr};
+
+/// An instruction of a [`FuncBody`]. Structured instructions are flattened
+/// into markers followed by the instructions that they contain, and know
+/// the positions that they branch to.
+#[derive(Debug)]
+pub(crate) enum Op {
+    /// An instruction that is not a structured instruction.
+    Instr(Instr),
+    /// A block whose `end` marker is at the position `end`.
+    Block {
+        block_type: BlockType,
+        end: usize,
+    },
+    Loop {
+        block_type: BlockType,
+    },
+    /// An `if` whose else branch starts at the position `otherwise`, after
+    /// its `else` marker, and whose `end` marker is at the position `end`.
+    If {
+        block_type: BlockType,
+        otherwise: usize,
+        end: usize,
+    },
+    /// The end of the first branch of an `if`, whose `end` marker is at the
+    /// position `end`. Every `if` has one, even if its else branch is empty.
+    Else {
+        end: usize,
+    },
+    /// The end of a structured instruction or of the function.
+    End,
+}
+
+/// The instructions of a function body, flattened so that they can be
+/// executed with a program counter rather than by recursion.
+#[derive(Debug)]
+pub struct FuncBody {
+    pub(crate) ops: Vec<Op>,
+    /// The location of each instruction reported in traps, or of the
+    /// preceding instruction for the markers of structured instructions.
+    pub(crate) offsets: Vec<usize>,
+}
+
+/// A sequence of instructions being flattened.
+struct Seq {
+    instrs: std::vec::IntoIter<Instr>,
+    /// The position of the structured instruction that contains the
+    /// sequence, if it is not the function body.
+    open: Option<usize>,
+    /// The else branch of an `if`, flattened after the sequence.
+    otherwise: Option<Vec<Instr>>,
+}
+
+impl FuncBody {
+    /// Flattens the instructions of `expr`. Instructions are located by
+    /// their offset from `base`, the start of the code section, or at 0 if
+    /// `expr` was not decoded from a binary.
+    pub(crate) fn new(expr: Expr, base: usize) -> Self {
+        let mut body = FuncBody {
+            ops: Vec::new(),
+            offsets: Vec::new(),
+        };
+        let spans = expr.spans;
+        let offset = |i: usize| spans.get(i).map_or(0, |span| span.start - base);
+        let mut count = 0;
+        let mut stack = Vec::from([Seq {
+            instrs: expr.instr.into_iter(),
+            open: None,
+            otherwise: None,
+        }]);
+        while let Some(seq) = stack.last_mut() {
+            let instr = match seq.instrs.next() {
+                Some(instr) => instr,
+                None => {
+                    let seq = stack.pop().expect("sequence is on the stack");
+                    body.close(seq, &mut stack, offset(count.max(1) - 1));
+                    continue;
+                }
+            };
+            let open = body.ops.len();
+            let (op, seq) = match instr {
+                Instr::Control(c) => match *c {
+                    ControlInstruction::Block(block_type, instrs) => {
+                        (Op::Block { block_type, end: 0 }, Some((instrs, None)))
+                    }
+                    ControlInstruction::Loop(block_type, instrs) => {
+                        (Op::Loop { block_type }, Some((instrs, None)))
+                    }
+                    ControlInstruction::If(block_type, then, otherwise) => (
+                        Op::If {
+                            block_type,
+                            otherwise: 0,
+                            end: 0,
+                        },
+                        Some((then, Some(otherwise))),
+                    ),
+                    c => (Op::Instr(Instr::Control(Box::new(c))), None),
+                },
+                instr => (Op::Instr(instr), None),
+            };
+            body.push(op, offset(count));
+            count += 1;
+            if let Some((instrs, otherwise)) = seq {
+                stack.push(Seq {
+                    instrs: instrs.into_iter(),
+                    open: Some(open),
+                    otherwise,
+                });
+            }
+        }
+        body
+    }
+
+    fn push(&mut self, op: Op, offset: usize) {
+        self.ops.push(op);
+        self.offsets.push(offset);
+    }
+
+    /// Ends the flattened sequence `seq`, either with the `else` marker of
+    /// an `if`, followed by its else branch, or with an `end` marker.
+    fn close(&mut self, seq: Seq, stack: &mut Vec<Seq>, offset: usize) {
+        let position = self.ops.len();
+        if let (Some(open), Some(otherwise)) = (seq.open, seq.otherwise) {
+            self.push(Op::Else { end: 0 }, offset);
+            if let Op::If { otherwise, .. } = &mut self.ops[open] {
+                *otherwise = position + 1;
+            }
+            stack.push(Seq {
+                instrs: otherwise.into_iter(),
+                open: Some(open),
+                otherwise: None,
+            });
+            return;
+        }
+        self.push(Op::End, offset);
+        let open = match seq.open {
+            Some(open) => open,
+            None => return,
+        };
+        match &mut self.ops[open] {
+            Op::Block { end, .. } => *end = position,
+            Op::If { otherwise, end, .. } => {
+                *end = position;
+                let otherwise = *otherwise;
+                if let Op::Else { end } = &mut self.ops[otherwise - 1] {
+                    *end = position;
+                }
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use core::fmt;
+use std::{rc::Rc, vec::Vec};
+
+use super::{Store, Trap, Value};
+use crate::parse::types::{FuncType, NumType, ValType, VecType};
+
+/// Signature of the Rust function backing a [`HostFunc`]. It receives the
+/// store and the arguments of the call, which have been checked against the
+/// function type, and returns the results.
+pub type HostFuncFn = dyn Fn(&mut Store, &[Value]) -> Result<Vec<Value>, Trap>;
+
+/// A function provided by the embedder that can be imported by modules.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#function-instances)
+#[derive(Clone)]
+pub struct HostFunc {
+    func_type: FuncType,
+    func: Rc<HostFuncFn>,
+}
+
+impl HostFunc {
+    /// Creates a host function of type `func_type`. Calls trap with
+    /// [`Trap::HostResultMismatch`] if `func` returns values that do not
+    /// match the result types of `func_type`.
+    pub fn new(
+        func_type: FuncType,
+        func: impl Fn(&mut Store, &[Value]) -> Result<Vec<Value>, Trap> + 'static,
+    ) -> Self {
+        HostFunc {
+            func_type,
+            func: Rc::new(func),
+        }
+    }
+
+    /// Creates a host function from a Rust closure whose parameter and
+    /// result types determine the function type, e.g. `|a: i32, b: i32|
+    /// a + b` has type `[i32 i32] -> [i32]`.
+    pub fn wrap<Params, Results>(func: impl IntoHostFunc<Params, Results>) -> Self {
+        func.into_host_func()
+    }
+
+    pub fn func_type(&self) -> &FuncType {
+        &self.func_type
+    }
+
+    pub(crate) fn call(&self, store: &mut Store, args: &[Value]) -> Result<Vec<Value>, Trap> {
+        let results = (self.func)(store, args)?;
+        match results
+            .iter()
+            .map(Value::val_type)
+            .eq(self.func_type.ret.iter().copied())
+        {
+            true => Ok(results),
+            false => Err(Trap::HostResultMismatch),
+        }
+    }
+}
+
+impl fmt::Debug for HostFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HostFunc")
+            .field("func_type", &self.func_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A Rust type that represents a WebAssembly value type.
+pub trait WasmType: Into<Value> + From<Value> {
+    const TYPE: ValType;
+}
+
+macro_rules! impl_wasm_type {
+    ($($ty:ty => $val_type:expr,)*) => {
+        $(impl WasmType for $ty {
+            const TYPE: ValType = $val_type;
+        })*
+    };
+}
+
+impl_wasm_type! {
+    i32 => ValType::NumType(NumType::I32),
+    i64 => ValType::NumType(NumType::I64),
+    f32 => ValType::NumType(NumType::F32),
+    f64 => ValType::NumType(NumType::F64),
+    u128 => ValType::VecType(VecType::V128),
+}
+
+/// The results of a host function: a single value, a tuple of values or
+/// `()`, optionally wrapped in a `Result` to trap.
+pub trait WasmResults {
+    fn types() -> Vec<ValType>;
+    fn into_values(self) -> Result<Vec<Value>, Trap>;
+}
+
+impl<R: WasmResults> WasmResults for Result<R, Trap> {
+    fn types() -> Vec<ValType> {
+        R::types()
+    }
+
+    fn into_values(self) -> Result<Vec<Value>, Trap> {
+        self?.into_values()
+    }
+}
+
+macro_rules! impl_wasm_results_single {
+    ($($ty:ty),*) => {
+        $(impl WasmResults for $ty {
+            fn types() -> Vec<ValType> {
+                [<$ty>::TYPE].to_vec()
+            }
+
+            fn into_values(self) -> Result<Vec<Value>, Trap> {
+                Ok([self.into()].to_vec())
+            }
+        })*
+    };
+}
+
+impl_wasm_results_single!(i32, i64, f32, f64, u128);
+
+/// Implements [`WasmResults`] for tuples and [`IntoHostFunc`] for closures
+/// taking the same number of parameters.
+macro_rules! impl_host_func {
+    ($($t:ident),*) => {
+        impl<$($t: WasmType),*> WasmResults for ($($t,)*) {
+            fn types() -> Vec<ValType> {
+                [$($t::TYPE),*].to_vec()
+            }
+
+            #[allow(non_snake_case)]
+            fn into_values(self) -> Result<Vec<Value>, Trap> {
+                let ($($t,)*) = self;
+                Ok([$($t.into()),*].to_vec())
+            }
+        }
+
+        impl<F, R, $($t: WasmType),*> IntoHostFunc<($($t,)*), R> for F
+        where
+            F: Fn($($t),*) -> R + 'static,
+            R: WasmResults,
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables)]
+            fn into_host_func(self) -> HostFunc {
+                let func_type = FuncType {
+                    params: [$($t::TYPE),*].to_vec(),
+                    ret: R::types(),
+                };
+                HostFunc::new(func_type, move |_, args| {
+                    let mut args = args.iter().copied();
+                    $(let $t = $t::from(args.next().unwrap());)*
+                    self($($t),*).into_values()
+                })
+            }
+        }
+    };
+}
+
+/// A Rust closure that can be turned into a [`HostFunc`].
+pub trait IntoHostFunc<Params, Results> {
+    fn into_host_func(self) -> HostFunc;
+}
+
+impl_host_func!();
+impl_host_func!(A);
+impl_host_func!(A, B);
+impl_host_func!(A, B, C);
+impl_host_func!(A, B, C, D);
+impl_host_func!(A, B, C, D, E);
+impl_host_func!(A, B, C, D, E, G);
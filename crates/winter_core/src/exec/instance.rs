@@ -0,0 +1,456 @@
+use std::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::{
+    interpreter::Interpreter, DataAddr, DataInst, ElemAddr, ElemInst, Error, ExternVal, FuncAddr,
+    FuncBody, FuncInst, GlobalAddr, GlobalInst, MemAddr, MemInst, ModuleAddr, Store, TableAddr,
+    TableInst, Value,
+};
+use crate::{
+    parse::{
+        instructions::{Expr, MemoryInstruction, TableInstruction},
+        modules::{
+            data_section::{DataMode, DataSection},
+            element_section::{ElemInit, ElemMode, ElementSection},
+            export_section::ExportDesc,
+            global_section::GlobalSection,
+            import_section::ImportDesc,
+            DataIdx, ElemIdx, FuncIdx,
+        },
+        types::FuncType,
+    },
+    validate::ValidatedModule,
+};
+
+/// An exported entity of a module instance.
+#[derive(Debug)]
+pub struct ExportInst {
+    pub name: String,
+    pub value: ExternVal,
+}
+
+/// A module instance is the runtime representation of a module. It holds
+/// the addresses of the entities of the module in the [`Store`], in their
+/// respective index spaces.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#module-instances)
+#[derive(Debug, Default)]
+pub struct ModuleInstance {
+    pub types: Vec<FuncType>,
+    pub func_addrs: Vec<FuncAddr>,
+    pub table_addrs: Vec<TableAddr>,
+    pub mem_addrs: Vec<MemAddr>,
+    pub global_addrs: Vec<GlobalAddr>,
+    pub elem_addrs: Vec<ElemAddr>,
+    pub data_addrs: Vec<DataAddr>,
+    pub exports: Vec<ExportInst>,
+    /// Names of the functions of the module, taken from its name section.
+    pub func_names: BTreeMap<u32, String>,
+}
+
+impl ModuleInstance {
+    /// Returns the export with the given name.
+    pub fn export(&self, name: &str) -> Option<ExternVal> {
+        self.exports
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.value)
+    }
+
+    /// Returns the name of the function at `index` in the name section of
+    /// the module, such as the function of a
+    /// [`TrapLocation`](super::TrapLocation).
+    pub fn func_name(&self, index: FuncIdx) -> Option<&str> {
+        self.func_names.get(&index.0).map(String::as_str)
+    }
+}
+
+impl Store {
+    /// Instantiates `module` with `imports`, the extern values that are
+    /// provided for each import of the module in order. Runs the start
+    /// function if there is one.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/exec/modules.html#instantiation)
+    pub fn instantiate(
+        &mut self,
+        module: ValidatedModule,
+        imports: &[ExternVal],
+    ) -> Result<ModuleAddr, Error> {
+        let module = module.into_inner();
+        let module_addr = ModuleAddr(self.modules.len());
+        let mut inst = ModuleInstance {
+            types: module
+                .func_type
+                .map(|t| t.function_types)
+                .unwrap_or_default(),
+            func_names: module
+                .names
+                .iter()
+                .flat_map(|names| &names.funcs.names)
+                .map(|&(index, name)| (index, name.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        let module_imports = module.import.map(|i| i.imports).unwrap_or_default();
+        if module_imports.len() != imports.len() {
+            return Err(Error::ImportCountMismatch);
+        }
+        for (i, (import, value)) in module_imports.iter().zip(imports).enumerate() {
+            match (&import.desc, *value) {
+                (ImportDesc::Func(t), ExternVal::Func(a))
+                    if self.funcs[a.0].func_type() == &inst.types[t.0 as usize] =>
+                {
+                    inst.func_addrs.push(a)
+                }
+                (ImportDesc::Table(t), ExternVal::Table(a))
+                    if self.tables[a.0].table_type().matches(t) =>
+                {
+                    inst.table_addrs.push(a)
+                }
+                (ImportDesc::Mem(t), ExternVal::Mem(a)) if self.mems[a.0].mem_type().matches(t) => {
+                    inst.mem_addrs.push(a)
+                }
+                (ImportDesc::Global(t), ExternVal::Global(a))
+                    if self.globals[a.0].global_type == *t =>
+                {
+                    inst.global_addrs.push(a)
+                }
+                _ => return Err(Error::ImportTypeMismatch(i)),
+            }
+        }
+
+        let functions = module.type_idx.map(|f| f.functions).unwrap_or_default();
+        let base = module.code.as_ref().map_or(0, |c| c.span.start);
+        let code = module.code.map(|c| c.code).unwrap_or_default();
+        for (t, code) in functions.into_iter().zip(code) {
+            let func = code
+                .into_func()
+                .expect("function bodies are decoded during validation");
+            let addr = self.alloc_func(FuncInst::Wasm {
+                func_type: inst.types[t.0 as usize].clone(),
+                module: module_addr,
+                locals: func.locals,
+                body: FuncBody::new(func.expr, base),
+            });
+            inst.func_addrs.push(addr);
+        }
+        for table in module.table.map(|t| t.tables).unwrap_or_default() {
+            let t = table.table_type;
+            let table =
+                TableInst::new(t.ref_type, t.limits.min, t.limits.max).ok_or(Error::OutOfMemory)?;
+            let addr = self.alloc_table(table);
+            inst.table_addrs.push(addr);
+        }
+        for mem in module.mem.map(|m| m.tables).unwrap_or_default() {
+            let mem = MemInst::new(mem.mem_type.min, mem.mem_type.max).ok_or(Error::OutOfMemory)?;
+            let addr = self.alloc_mem(mem);
+            inst.mem_addrs.push(addr);
+        }
+
+        // Global initializers and element segments are evaluated in the
+        // context of the module instance, which only holds the imported
+        // globals at this point.
+        self.modules.push(inst);
+        let result = self.initialize(module_addr, module.global, module.elem, module.data);
+        let inst = &mut self.modules[module_addr.0];
+        for export in module.export.map(|e| e.exports).unwrap_or_default() {
+            let value = match export.desc {
+                ExportDesc::Func(x) => ExternVal::Func(inst.func_addrs[x.0 as usize]),
+                ExportDesc::Table(x) => ExternVal::Table(inst.table_addrs[x.0 as usize]),
+                ExportDesc::Mem(x) => ExternVal::Mem(inst.mem_addrs[x.0 as usize]),
+                ExportDesc::Global(x) => ExternVal::Global(inst.global_addrs[x.0 as usize]),
+            };
+            inst.exports.push(ExportInst {
+                name: String::from(export.name),
+                value,
+            });
+        }
+        result?;
+
+        if let Some(start) = module.start {
+            let addr = self.modules[module_addr.0].func_addrs[start.start.0 as usize];
+            self.invoke(addr, &[])?;
+        }
+        Ok(module_addr)
+    }
+
+    /// Allocates the globals, element and data segments of a module and runs
+    /// the initialization of active segments.
+    fn initialize(
+        &mut self,
+        module_addr: ModuleAddr,
+        globals: Option<GlobalSection>,
+        elems: Option<ElementSection>,
+        datas: Option<DataSection>,
+    ) -> Result<(), Error> {
+        let globals = globals.map(|g| g.globals).unwrap_or_default();
+        let elems = elems.map(|e| e.elems).unwrap_or_default();
+        let datas = datas.map(|d| d.data).unwrap_or_default();
+
+        let mut values = Vec::with_capacity(globals.len());
+        for global in &globals {
+            values.push(self.eval_const(module_addr, &global.expr)?);
+        }
+        for (global, value) in globals.iter().zip(values) {
+            let addr = self.alloc_global(GlobalInst {
+                global_type: global.global_type,
+                value,
+            });
+            self.modules[module_addr.0].global_addrs.push(addr);
+        }
+
+        for elem in &elems {
+            let elements = match &elem.init {
+                ElemInit::FuncIdx(funcs) => funcs
+                    .iter()
+                    .map(|f| {
+                        Value::FuncRef(Some(self.modules[module_addr.0].func_addrs[f.0 as usize]))
+                    })
+                    .collect(),
+                ElemInit::Expr(exprs) => {
+                    let mut elements = Vec::with_capacity(exprs.len());
+                    for e in exprs {
+                        elements.push(self.eval_const(module_addr, e)?);
+                    }
+                    elements
+                }
+            };
+            self.elems.push(ElemInst {
+                ref_type: elem.ref_type(),
+                elements,
+            });
+            let addr = ElemAddr(self.elems.len() - 1);
+            self.modules[module_addr.0].elem_addrs.push(addr);
+        }
+        for data in &datas {
+            self.datas.push(DataInst {
+                data: data.init.to_vec(),
+            });
+            let addr = DataAddr(self.datas.len() - 1);
+            self.modules[module_addr.0].data_addrs.push(addr);
+        }
+
+        for (i, elem) in elems.iter().enumerate() {
+            let x = ElemIdx(i as u32);
+            match &elem.mode {
+                ElemMode::Active { table, offset } => {
+                    let n = elem_len(&elem.init);
+                    let offset = self.eval_const(module_addr, offset)?;
+                    let mut interpreter = Interpreter::new(self);
+                    interpreter.push_values(&[offset, Value::I32(0), Value::I32(n)]);
+                    interpreter.exec_table(module_addr, &TableInstruction::TableInit(x, *table))?;
+                    interpreter.exec_table(module_addr, &TableInstruction::ElemDrop(x))?;
+                }
+                ElemMode::Declarative => {
+                    let mut interpreter = Interpreter::new(self);
+                    interpreter.exec_table(module_addr, &TableInstruction::ElemDrop(x))?;
+                }
+                ElemMode::Passive => {}
+            }
+        }
+
+        for (i, data) in datas.iter().enumerate() {
+            if let DataMode::Active { memory, offset } = &data.mode {
+                let x = DataIdx(i as u32);
+                let offset = self.eval_const(module_addr, offset)?;
+                let mut interpreter = Interpreter::new(self);
+                interpreter.push_values(&[
+                    offset,
+                    Value::I32(0),
+                    Value::I32(data.init.len() as i32),
+                ]);
+                interpreter.exec_memory(module_addr, &MemoryInstruction::MemoryInit(x, *memory))?;
+                interpreter.exec_memory(module_addr, &MemoryInstruction::DataDrop(x))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Evaluates a constant expression.
+    fn eval_const(&mut self, module: ModuleAddr, expr: &Expr) -> Result<Value, Error> {
+        let mut interpreter = Interpreter::new(self);
+        interpreter.eval(module, expr)?;
+        Ok(interpreter.into_values().pop().unwrap())
+    }
+}
+
+fn elem_len(init: &ElemInit) -> i32 {
+    (match init {
+        ElemInit::FuncIdx(f) => f.len(),
+        ElemInit::Expr(e) => e.len(),
+    }) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exec::{HostFunc, Trap, TrapLocation},
+        parse::{
+            modules::{module::module_parser, FuncIdx},
+            types::{NumType, ValType},
+        },
+        validate::validate,
+    };
+
+    /// Builds a module with one function of type `[i32 i32] -> [i32]`,
+    /// exported as `f`, with the given body.
+    fn module(body: &[u8]) -> Vec<u8> {
+        let mut m = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        m.extend([0x01, 0x07, 0x01, 0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F]);
+        m.extend([0x03, 0x02, 0x01, 0x00]);
+        m.extend([0x07, 0x05, 0x01, 0x01, b'f', 0x00, 0x00]);
+        m.extend([0x0A, body.len() as u8 + 3, 0x01, body.len() as u8 + 1, 0x00]);
+        m.extend(body);
+        m
+    }
+
+    fn invoke(body: &[u8], args: &[Value]) -> Result<Vec<Value>, Error> {
+        let bytes = module(body);
+        let module = validate(module_parser(&bytes).unwrap()).unwrap();
+        let mut store = Store::new();
+        let addr = store.instantiate(module, &[]).unwrap();
+        let f = match store.module(addr).export("f") {
+            Some(ExternVal::Func(f)) => f,
+            _ => panic!("missing export"),
+        };
+        store.invoke(f, args)
+    }
+
+    #[test]
+    fn test_invoke() {
+        let add = [0x20, 0x00, 0x20, 0x01, 0x6A, 0x0B];
+        assert_eq!(
+            invoke(&add, &[Value::I32(3), Value::I32(4)]),
+            Ok(vec![Value::I32(7)])
+        );
+        assert_eq!(
+            invoke(&add, &[Value::I32(3)]),
+            Err(Error::ArgumentTypeMismatch)
+        );
+
+        // Sums the integers from the first argument down to 1.
+        let sum = [
+            0x02, 0x40, 0x03, 0x40, 0x20, 0x00, 0x45, 0x0D, 0x01, 0x20, 0x01, 0x20, 0x00, 0x6A,
+            0x21, 0x01, 0x20, 0x00, 0x41, 0x01, 0x6B, 0x21, 0x00, 0x0C, 0x00, 0x0B, 0x0B, 0x20,
+            0x01, 0x0B,
+        ];
+        assert_eq!(
+            invoke(&sum, &[Value::I32(4), Value::I32(0)]),
+            Ok(vec![Value::I32(10)])
+        );
+    }
+
+    /// A trap raised by the instruction at `offset` in the function `f`. The
+    /// body of `f` starts at the offset 5 of the code section.
+    fn trap(trap: Trap, offset: usize) -> Result<Vec<Value>, Error> {
+        Err(Error::Trap {
+            trap,
+            location: Some(TrapLocation {
+                module: ModuleAddr(0),
+                func: FuncIdx(0),
+                offset,
+            }),
+        })
+    }
+
+    #[test]
+    fn test_trap() {
+        let div = [0x20, 0x00, 0x20, 0x01, 0x6D, 0x0B];
+        assert_eq!(
+            invoke(&div, &[Value::I32(7), Value::I32(2)]),
+            Ok(vec![Value::I32(3)])
+        );
+        assert_eq!(
+            invoke(&div, &[Value::I32(7), Value::I32(0)]),
+            trap(Trap::IntegerDivideByZero, 9)
+        );
+        assert_eq!(
+            invoke(&div, &[Value::I32(i32::MIN), Value::I32(-1)]),
+            trap(Trap::IntegerOverflow, 9)
+        );
+
+        // Traps in the else branch of an if.
+        let branch = [0x20, 0x00, 0x04, 0x7F, 0x41, 0x01, 0x05, 0x00, 0x0B, 0x0B];
+        assert_eq!(
+            invoke(&branch, &[Value::I32(1), Value::I32(0)]),
+            Ok(vec![Value::I32(1)])
+        );
+        assert_eq!(
+            invoke(&branch, &[Value::I32(0), Value::I32(0)]),
+            trap(Trap::Unreachable, 12)
+        );
+
+        let recurse = [0x20, 0x00, 0x20, 0x01, 0x10, 0x00, 0x0B];
+        assert_eq!(
+            invoke(&recurse, &[Value::I32(0), Value::I32(0)]),
+            trap(Trap::CallStackExhausted, 9)
+        );
+    }
+
+    #[test]
+    fn test_deep_calls() {
+        // Calls itself with the first argument decremented until it is 0,
+        // then returns the second argument.
+        let countdown = [
+            0x20, 0x00, 0x04, 0x7F, 0x20, 0x00, 0x41, 0x01, 0x6B, 0x20, 0x01, 0x10, 0x00, 0x05,
+            0x20, 0x01, 0x0B, 0x0B,
+        ];
+        let bytes = module(&countdown);
+        let module = validate(module_parser(&bytes).unwrap()).unwrap();
+        let mut store = Store::new();
+        store.set_max_call_depth(200_000);
+        let addr = store.instantiate(module, &[]).unwrap();
+        let f = match store.module(addr).export("f") {
+            Some(ExternVal::Func(f)) => f,
+            _ => panic!("missing export"),
+        };
+        // Frames live on the heap, so deep recursion does not overflow the
+        // native stack.
+        assert_eq!(
+            store.invoke(f, &[Value::I32(100_000), Value::I32(7)]),
+            Ok(vec![Value::I32(7)])
+        );
+        assert_eq!(
+            store
+                .invoke(f, &[Value::I32(300_000), Value::I32(7)])
+                .unwrap_err()
+                .trap(),
+            Some(Trap::CallStackExhausted)
+        );
+    }
+
+    #[test]
+    fn test_func_name() {
+        let mut bytes = module(&[0x00, 0x0B]);
+        // Name section naming function 0 `div`.
+        bytes.extend([0x00, 0x0D, 0x04, b'n', b'a', b'm', b'e']);
+        bytes.extend([0x01, 0x06, 0x01, 0x00, 0x03, b'd', b'i', b'v']);
+        let module = validate(module_parser(&bytes).unwrap()).unwrap();
+        let mut store = Store::new();
+        let addr = store.instantiate(module, &[]).unwrap();
+        assert_eq!(store.module(addr).func_name(FuncIdx(0)), Some("div"));
+        assert_eq!(store.module(addr).func_name(FuncIdx(1)), None);
+    }
+
+    #[test]
+    fn test_host_result_mismatch() {
+        let mut store = Store::new();
+        let func_type = FuncType {
+            params: Vec::new(),
+            ret: [ValType::NumType(NumType::I32)].to_vec(),
+        };
+        let f = store.alloc_host_func(HostFunc::new(
+            func_type,
+            |_, _| Ok([Value::I64(1)].to_vec()),
+        ));
+        assert_eq!(
+            store.invoke(f, &[]).unwrap_err().trap(),
+            Some(Trap::HostResultMismatch)
+        );
+    }
+}
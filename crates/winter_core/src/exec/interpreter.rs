@@ -0,0 +1,679 @@
+use std::{rc::Rc, vec::Vec};
+
+use super::{
+    body::{FuncBody, Op},
+    Error, FuncAddr, FuncInst, ModuleAddr, Store, Trap, TrapLocation, Value,
+};
+use crate::parse::{
+    instructions::{
+        BlockType, ControlInstruction, Expr, Instr, MemArg, MemoryInstruction,
+        ParametricInstruction, ReferenceInstruction, TableInstruction, VariableInstruction,
+    },
+    modules::{FuncIdx, GlobalIdx, TableIdx, TypeIdx},
+};
+
+/// The activation of a function: its module, its locals and the position
+/// of the next instruction to execute.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#frames)
+struct Frame {
+    addr: FuncAddr,
+    func: Rc<FuncInst>,
+    module: ModuleAddr,
+    locals: Vec<Value>,
+    pc: usize,
+    /// The height of the label stack when the function was entered.
+    labels: usize,
+    /// The height of the operand stack when the function was entered,
+    /// without its arguments.
+    height: usize,
+    /// The number of results of the function.
+    arity: usize,
+}
+
+/// A label: the target of the branches out of a structured instruction.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#labels)
+#[derive(Clone, Copy)]
+struct Label {
+    /// The position of the instruction at which a branch to the label
+    /// continues.
+    target: usize,
+    /// The height of the operand stack when the structured instruction was
+    /// entered, without its parameters.
+    height: usize,
+    /// The number of values passed by a branch to the label.
+    arity: usize,
+    /// Whether the label belongs to a loop, which a branch enters again.
+    is_loop: bool,
+}
+
+/// What executing an instruction did to the stack of frames.
+enum Flow {
+    /// The current frame continues.
+    Continue,
+    /// A frame was pushed or popped.
+    Switch,
+}
+
+/// Executes instructions against a [`Store`]. Operands, labels and frames
+/// live on explicit stacks, so that the depth of nested blocks and calls is
+/// not limited by the native stack.
+pub(crate) struct Interpreter<'s> {
+    pub(super) store: &'s mut Store,
+    stack: Vec<Value>,
+    labels: Vec<Label>,
+    frames: Vec<Frame>,
+}
+
+/// Loads a value of type `$ty` from memory and pushes it as `$out`.
+macro_rules! load {
+    ($self:ident, $module:ident, $arg:ident, $ty:ty as $out:ty) => {{
+        let bytes = $self.load::<{ core::mem::size_of::<$ty>() }>($module, $arg)?;
+        $self.push(<$ty>::from_le_bytes(bytes) as $out);
+    }};
+}
+
+/// Pops a value of type `$ty` and stores it to memory wrapped to `$narrow`.
+macro_rules! store {
+    ($self:ident, $module:ident, $arg:ident, $ty:ty as $narrow:ty) => {{
+        let v: $ty = $self.pop();
+        $self.store_bytes($module, $arg, (v as $narrow).to_le_bytes())?;
+    }};
+}
+
+/// Returns true if the range `[offset, offset + n)` lies within `len`.
+pub(super) fn in_bounds(offset: u64, n: u64, len: usize) -> bool {
+    offset + n <= len as u64
+}
+
+/// Returns the body of a function defined by a module.
+fn body(func: &FuncInst) -> &FuncBody {
+    match func {
+        FuncInst::Wasm { body, .. } => body,
+        FuncInst::Host(_) => unreachable!("host functions have no frame"),
+    }
+}
+
+impl<'s> Interpreter<'s> {
+    pub fn new(store: &'s mut Store) -> Self {
+        Interpreter {
+            store,
+            stack: Vec::new(),
+            labels: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn push_values(&mut self, values: &[Value]) {
+        self.stack.extend_from_slice(values);
+    }
+
+    pub fn into_values(self) -> Vec<Value> {
+        self.stack
+    }
+
+    pub(super) fn push(&mut self, v: impl Into<Value>) {
+        self.stack.push(v.into());
+    }
+
+    pub(super) fn pop<T: From<Value>>(&mut self) -> T {
+        self.stack.pop().expect("operand stack underflow").into()
+    }
+
+    /// Evaluates a constant expression in the context of `module`, leaving
+    /// its result on the stack. Constant expressions have no structured
+    /// instructions, calls or locals.
+    pub fn eval(&mut self, module: ModuleAddr, expr: &Expr) -> Result<(), Error> {
+        for instr in &expr.instr {
+            self.exec_plain(module, instr)?;
+        }
+        Ok(())
+    }
+
+    /// Calls the function at `addr` with its arguments on top of the stack.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/exec/instructions.html#function-calls)
+    pub fn call(&mut self, addr: FuncAddr) -> Result<(), Error> {
+        let base = self.frames.len();
+        if let Flow::Switch = self.invoke(addr)? {
+            if let Err(e) = self.run(base) {
+                self.labels.truncate(self.frames[base].labels);
+                self.frames.truncate(base);
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Calls the function at `addr`. Host functions are called directly,
+    /// while calling a function defined by a module pushes its frame.
+    fn invoke(&mut self, addr: FuncAddr) -> Result<Flow, Trap> {
+        if self.frames.len() >= self.store.max_call_depth {
+            return Err(Trap::CallStackExhausted);
+        }
+        let func = Rc::clone(&self.store.funcs[addr.0]);
+        let (func_type, module, locals) = match &*func {
+            FuncInst::Wasm {
+                func_type,
+                module,
+                locals,
+                ..
+            } => (func_type, *module, locals),
+            FuncInst::Host(host) => {
+                let args = self.stack.len() - host.func_type().params.len();
+                let args = self.stack.split_off(args);
+                let results = host.call(self.store, &args)?;
+                self.stack.extend(results);
+                return Ok(Flow::Continue);
+            }
+        };
+        let height = self.stack.len() - func_type.params.len();
+        let mut values = self.stack.split_off(height);
+        for l in locals {
+            values
+                .try_reserve(l.count as usize)
+                .map_err(|_| Trap::CallStackExhausted)?;
+            let len = values.len() + l.count as usize;
+            values.resize(len, Value::default_of(l.val_type));
+        }
+        let arity = func_type.ret.len();
+        self.frames.push(Frame {
+            addr,
+            func,
+            module,
+            locals: values,
+            pc: 0,
+            labels: self.labels.len(),
+            height,
+            arity,
+        });
+        Ok(Flow::Switch)
+    }
+
+    /// Executes the frames above the first `base` ones until they return.
+    fn run(&mut self, base: usize) -> Result<(), Error> {
+        let mut func = Rc::clone(&self.frames[base].func);
+        loop {
+            let frame = self.frames.last_mut().expect("a frame is executing");
+            let (pc, module) = (frame.pc, frame.module);
+            frame.pc += 1;
+            let result = match &body(&func).ops[pc] {
+                Op::Instr(Instr::Control(c)) => self.exec_control(module, c),
+                Op::Instr(instr) => self.exec_plain(module, instr).map(|_| Flow::Continue),
+                Op::Block { block_type, end } => {
+                    self.enter_block(module, block_type, end + 1, false);
+                    Ok(Flow::Continue)
+                }
+                Op::Loop { block_type } => {
+                    self.enter_block(module, block_type, pc + 1, true);
+                    Ok(Flow::Continue)
+                }
+                Op::If {
+                    block_type,
+                    otherwise,
+                    end,
+                } => {
+                    let c: i32 = self.pop();
+                    self.enter_block(module, block_type, end + 1, false);
+                    if c == 0 {
+                        self.frame().pc = *otherwise;
+                    }
+                    Ok(Flow::Continue)
+                }
+                Op::Else { end } => {
+                    self.frame().pc = *end;
+                    Ok(Flow::Continue)
+                }
+                Op::End => match self.labels.len() > self.frame().labels {
+                    true => {
+                        self.labels.pop();
+                        Ok(Flow::Continue)
+                    }
+                    false => Ok(self.ret()),
+                },
+            };
+            match result {
+                Ok(Flow::Continue) => {}
+                Ok(Flow::Switch) if self.frames.len() <= base => return Ok(()),
+                Ok(Flow::Switch) => {
+                    func = Rc::clone(&self.frame().func);
+                }
+                Err(trap) => return Err(self.locate(trap, pc)),
+            }
+        }
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("a frame is executing")
+    }
+
+    /// Attaches the location of a trap raised by the instruction at the
+    /// position `pc` in the body of the current function.
+    fn locate(&self, trap: Trap, pc: usize) -> Error {
+        let frame = self.frames.last().expect("a frame is executing");
+        let func = self.store.modules[frame.module.0]
+            .func_addrs
+            .iter()
+            .position(|a| *a == frame.addr)
+            .expect("function is not defined by its module");
+        Error::Trap {
+            trap,
+            location: Some(TrapLocation {
+                module: frame.module,
+                func: FuncIdx(func as u32),
+                offset: body(&frame.func).offsets[pc],
+            }),
+        }
+    }
+
+    /// Pops the current frame, keeping the results of the function.
+    fn ret(&mut self) -> Flow {
+        let frame = self.frames.pop().expect("a frame is executing");
+        self.unwind(frame.height, frame.arity);
+        self.labels.truncate(frame.labels);
+        Flow::Switch
+    }
+
+    /// Branches to the label with the relative depth `l`, which is the
+    /// body of the function if there are only `l` labels in its frame.
+    fn branch(&mut self, l: u32) -> Flow {
+        let labels = self.frame().labels;
+        let index = match (self.labels.len() - labels).checked_sub(l as usize + 1) {
+            Some(index) => labels + index,
+            None => return self.ret(),
+        };
+        let label = self.labels[index];
+        self.unwind(label.height, label.arity);
+        self.labels.truncate(index + label.is_loop as usize);
+        self.frame().pc = label.target;
+        Flow::Continue
+    }
+
+    /// Drops the operands above `height` except for the top `arity` values.
+    fn unwind(&mut self, height: usize, arity: usize) {
+        let start = self.stack.len() - arity;
+        self.stack.drain(height..start);
+    }
+
+    /// Executes an instruction that does not affect control flow.
+    fn exec_plain(&mut self, module: ModuleAddr, instr: &Instr) -> Result<(), Trap> {
+        match instr {
+            Instr::Control(_) => unreachable!(),
+            Instr::Reference(r) => self.exec_reference(module, r),
+            Instr::Parametric(p) => self.exec_parametric(p),
+            Instr::Variable(v) => self.exec_variable(module, v),
+            Instr::Table(t) => self.exec_table(module, t)?,
+            Instr::Memory(m) => self.exec_memory(module, m)?,
+            Instr::Numeric(n) => self.exec_numeric(n)?,
+            Instr::Vector(v) => self.exec_vector(module, v)?,
+        }
+        Ok(())
+    }
+
+    /// Returns the number of parameters and results of a block type.
+    fn block_arity(&self, module: ModuleAddr, bt: &BlockType) -> (usize, usize) {
+        match bt {
+            BlockType::Empty => (0, 0),
+            BlockType::ValType(_) => (0, 1),
+            BlockType::TypeIndex(t) => {
+                let t = &self.store.modules[module.0].types[t.0 as usize];
+                (t.params.len(), t.ret.len())
+            }
+        }
+    }
+
+    /// Pushes the label of a structured instruction of type `bt`, which
+    /// branches to `target`.
+    fn enter_block(&mut self, module: ModuleAddr, bt: &BlockType, target: usize, is_loop: bool) {
+        let (params, results) = self.block_arity(module, bt);
+        self.labels.push(Label {
+            target,
+            height: self.stack.len() - params,
+            arity: if is_loop { params } else { results },
+            is_loop,
+        });
+    }
+
+    fn exec_control(
+        &mut self,
+        module: ModuleAddr,
+        instr: &ControlInstruction,
+    ) -> Result<Flow, Trap> {
+        match instr {
+            ControlInstruction::Unreachable => return Err(Trap::Unreachable),
+            ControlInstruction::Nop => {}
+            ControlInstruction::Block(..)
+            | ControlInstruction::Loop(..)
+            | ControlInstruction::If(..) => {
+                unreachable!("structured instructions are flattened")
+            }
+            ControlInstruction::Br(l) => return Ok(self.branch(l.0)),
+            ControlInstruction::BrIf(l) => {
+                if self.pop::<i32>() != 0 {
+                    return Ok(self.branch(l.0));
+                }
+            }
+            ControlInstruction::BrTable(labels, default) => {
+                let i: u32 = self.pop();
+                return Ok(self.branch(labels.get(i as usize).unwrap_or(default).0));
+            }
+            ControlInstruction::Return => return Ok(self.ret()),
+            ControlInstruction::Call(f) => {
+                let addr = self.store.modules[module.0].func_addrs[f.0 as usize];
+                return self.invoke(addr);
+            }
+            ControlInstruction::CallIndirect(t, x) => {
+                let addr = self.resolve_indirect(module, *t, *x)?;
+                return self.invoke(addr);
+            }
+        }
+        Ok(Flow::Continue)
+    }
+
+    /// Pops an index into table `x` and returns the function it refers to,
+    /// checking that it has type `t`.
+    fn resolve_indirect(
+        &mut self,
+        module: ModuleAddr,
+        t: TypeIdx,
+        x: TableIdx,
+    ) -> Result<FuncAddr, Trap> {
+        let i: u32 = self.pop();
+        let module = &self.store.modules[module.0];
+        let table = &self.store.tables[module.table_addrs[x.0 as usize].0];
+        let addr = match table.elements.get(i as usize) {
+            Some(Value::FuncRef(Some(addr))) => *addr,
+            Some(_) => return Err(Trap::UninitializedElement(i)),
+            None => return Err(Trap::UndefinedElement),
+        };
+        if self.store.funcs[addr.0].func_type() != &module.types[t.0 as usize] {
+            return Err(Trap::IndirectCallTypeMismatch);
+        }
+        Ok(addr)
+    }
+
+    fn exec_reference(&mut self, module: ModuleAddr, instr: &ReferenceInstruction) {
+        match instr {
+            ReferenceInstruction::RefNull(t) => self.push(Value::null(*t)),
+            ReferenceInstruction::RefIsNull => {
+                let v: Value = self.pop();
+                self.push(v.is_null());
+            }
+            ReferenceInstruction::RefFunc(x) => {
+                let addr = self.store.modules[module.0].func_addrs[x.0 as usize];
+                self.push(Value::FuncRef(Some(addr)));
+            }
+        }
+    }
+
+    fn exec_parametric(&mut self, instr: &ParametricInstruction) {
+        match instr {
+            ParametricInstruction::Drop => {
+                self.pop::<Value>();
+            }
+            ParametricInstruction::Select(_) => {
+                let c: i32 = self.pop();
+                let v2: Value = self.pop();
+                let v1: Value = self.pop();
+                self.push(if c != 0 { v1 } else { v2 });
+            }
+        }
+    }
+
+    fn global_addr(&self, module: ModuleAddr, x: GlobalIdx) -> usize {
+        self.store.modules[module.0].global_addrs[x.0 as usize].0
+    }
+
+    fn exec_variable(&mut self, module: ModuleAddr, instr: &VariableInstruction) {
+        match instr {
+            VariableInstruction::LocalGet(x) => {
+                let v = self.frame().locals[x.0 as usize];
+                self.push(v);
+            }
+            VariableInstruction::LocalSet(x) => {
+                let v = self.pop();
+                self.frame().locals[x.0 as usize] = v;
+            }
+            VariableInstruction::LocalTee(x) => {
+                let v = *self.stack.last().expect("operand stack underflow");
+                self.frame().locals[x.0 as usize] = v;
+            }
+            VariableInstruction::GlobalGet(x) => {
+                let addr = self.global_addr(module, *x);
+                self.push(self.store.globals[addr].value);
+            }
+            VariableInstruction::GlobalSet(x) => {
+                let addr = self.global_addr(module, *x);
+                self.store.globals[addr].value = self.pop();
+            }
+        }
+    }
+
+    fn table_addr(&self, module: ModuleAddr, x: TableIdx) -> usize {
+        self.store.modules[module.0].table_addrs[x.0 as usize].0
+    }
+
+    pub(super) fn exec_table(
+        &mut self,
+        module: ModuleAddr,
+        instr: &TableInstruction,
+    ) -> Result<(), Trap> {
+        match instr {
+            TableInstruction::TableGet(x) => {
+                let i: u32 = self.pop();
+                let table = &self.store.tables[self.table_addr(module, *x)];
+                let v = *table
+                    .elements
+                    .get(i as usize)
+                    .ok_or(Trap::OutOfBoundsTableAccess)?;
+                self.push(v);
+            }
+            TableInstruction::TableSet(x) => {
+                let v: Value = self.pop();
+                let i: u32 = self.pop();
+                let addr = self.table_addr(module, *x);
+                *self.store.tables[addr]
+                    .elements
+                    .get_mut(i as usize)
+                    .ok_or(Trap::OutOfBoundsTableAccess)? = v;
+            }
+            TableInstruction::TableInit(e, x) => {
+                let n: u32 = self.pop();
+                let s: u32 = self.pop();
+                let d: u32 = self.pop();
+                let elem = self.store.modules[module.0].elem_addrs[e.0 as usize];
+                let addr = self.table_addr(module, *x);
+                let elem = &self.store.elems[elem.0].elements;
+                let table = &mut self.store.tables[addr].elements;
+                if !in_bounds(s as u64, n as u64, elem.len())
+                    || !in_bounds(d as u64, n as u64, table.len())
+                {
+                    return Err(Trap::OutOfBoundsTableAccess);
+                }
+                let (s, d, n) = (s as usize, d as usize, n as usize);
+                table[d..d + n].copy_from_slice(&elem[s..s + n]);
+            }
+            TableInstruction::ElemDrop(e) => {
+                let elem = self.store.modules[module.0].elem_addrs[e.0 as usize];
+                self.store.elems[elem.0].elements = Vec::new();
+            }
+            TableInstruction::TableCopy(x, y) => {
+                let n: u32 = self.pop();
+                let s: u32 = self.pop();
+                let d: u32 = self.pop();
+                let dst = self.table_addr(module, *x);
+                let src = self.table_addr(module, *y);
+                if !in_bounds(s as u64, n as u64, self.store.tables[src].elements.len())
+                    || !in_bounds(d as u64, n as u64, self.store.tables[dst].elements.len())
+                {
+                    return Err(Trap::OutOfBoundsTableAccess);
+                }
+                let (s, d, n) = (s as usize, d as usize, n as usize);
+                if dst == src {
+                    self.store.tables[dst].elements.copy_within(s..s + n, d);
+                } else {
+                    let values = self.store.tables[src].elements[s..s + n].to_vec();
+                    self.store.tables[dst].elements[d..d + n].copy_from_slice(&values);
+                }
+            }
+            TableInstruction::TableGrow(x) => {
+                let n: u32 = self.pop();
+                let init: Value = self.pop();
+                let addr = self.table_addr(module, *x);
+                let old = self.store.tables[addr].grow(n, init);
+                self.push(old.map_or(-1, |old| old as i32));
+            }
+            TableInstruction::TableSize(x) => {
+                let addr = self.table_addr(module, *x);
+                self.push(self.store.tables[addr].elements.len() as u32);
+            }
+            TableInstruction::TableFill(x) => {
+                let n: u32 = self.pop();
+                let v: Value = self.pop();
+                let i: u32 = self.pop();
+                let addr = self.table_addr(module, *x);
+                let table = &mut self.store.tables[addr].elements;
+                if !in_bounds(i as u64, n as u64, table.len()) {
+                    return Err(Trap::OutOfBoundsTableAccess);
+                }
+                let (i, n) = (i as usize, n as usize);
+                table[i..i + n].fill(v);
+            }
+        }
+        Ok(())
+    }
+
+    fn mem_addr(&self, module: ModuleAddr) -> usize {
+        self.store.modules[module.0].mem_addrs[0].0
+    }
+
+    /// Pops an address and returns the effective address of an access of
+    /// `n` bytes, trapping if it is out of bounds.
+    fn effective_address(
+        &mut self,
+        module: ModuleAddr,
+        arg: &MemArg,
+        n: usize,
+    ) -> Result<usize, Trap> {
+        let i: u32 = self.pop();
+        let ea = i as u64 + arg.offset as u64;
+        let mem = &self.store.mems[self.mem_addr(module)];
+        if !in_bounds(ea, n as u64, mem.data.len()) {
+            return Err(Trap::OutOfBoundsMemoryAccess);
+        }
+        Ok(ea as usize)
+    }
+
+    pub(super) fn load<const N: usize>(
+        &mut self,
+        module: ModuleAddr,
+        arg: &MemArg,
+    ) -> Result<[u8; N], Trap> {
+        let ea = self.effective_address(module, arg, N)?;
+        let mem = &self.store.mems[self.mem_addr(module)];
+        Ok(mem.data[ea..ea + N].try_into().unwrap())
+    }
+
+    /// Stores `bytes` at the address on top of the stack.
+    pub(super) fn store_bytes<const N: usize>(
+        &mut self,
+        module: ModuleAddr,
+        arg: &MemArg,
+        bytes: [u8; N],
+    ) -> Result<(), Trap> {
+        let ea = self.effective_address(module, arg, N)?;
+        let addr = self.mem_addr(module);
+        self.store.mems[addr].data[ea..ea + N].copy_from_slice(&bytes);
+        Ok(())
+    }
+
+    pub(super) fn exec_memory(
+        &mut self,
+        module: ModuleAddr,
+        instr: &MemoryInstruction,
+    ) -> Result<(), Trap> {
+        match instr {
+            MemoryInstruction::I32Load(m) => load!(self, module, m, i32 as i32),
+            MemoryInstruction::I64Load(m) => load!(self, module, m, i64 as i64),
+            MemoryInstruction::F32Load(m) => load!(self, module, m, f32 as f32),
+            MemoryInstruction::F64Load(m) => load!(self, module, m, f64 as f64),
+            MemoryInstruction::I32Load8S(m) => load!(self, module, m, i8 as i32),
+            MemoryInstruction::I32Load8U(m) => load!(self, module, m, u8 as i32),
+            MemoryInstruction::I32Load16S(m) => load!(self, module, m, i16 as i32),
+            MemoryInstruction::I32Load16U(m) => load!(self, module, m, u16 as i32),
+            MemoryInstruction::I64Load8S(m) => load!(self, module, m, i8 as i64),
+            MemoryInstruction::I64Load8U(m) => load!(self, module, m, u8 as i64),
+            MemoryInstruction::I64Load16S(m) => load!(self, module, m, i16 as i64),
+            MemoryInstruction::I64Load16U(m) => load!(self, module, m, u16 as i64),
+            MemoryInstruction::I64Load32S(m) => load!(self, module, m, i32 as i64),
+            MemoryInstruction::I64Load32U(m) => load!(self, module, m, u32 as i64),
+            MemoryInstruction::I32Store(m) => store!(self, module, m, i32 as i32),
+            MemoryInstruction::I64Store(m) => store!(self, module, m, i64 as i64),
+            MemoryInstruction::F32Store(m) => store!(self, module, m, f32 as f32),
+            MemoryInstruction::F64Store(m) => store!(self, module, m, f64 as f64),
+            MemoryInstruction::I32Store8(m) => store!(self, module, m, i32 as i8),
+            MemoryInstruction::I32Store16(m) => store!(self, module, m, i32 as i16),
+            MemoryInstruction::I64Store8(m) => store!(self, module, m, i64 as i8),
+            MemoryInstruction::I64Store16(m) => store!(self, module, m, i64 as i16),
+            MemoryInstruction::I64Store32(m) => store!(self, module, m, i64 as i32),
+            MemoryInstruction::MemorySize(_) => {
+                let mem = &self.store.mems[self.mem_addr(module)];
+                self.push(mem.size());
+            }
+            MemoryInstruction::MemoryGrow(_) => {
+                let n: u32 = self.pop();
+                let addr = self.mem_addr(module);
+                let old = self.store.mems[addr].grow(n);
+                self.push(old.map_or(-1, |old| old as i32));
+            }
+            MemoryInstruction::MemoryInit(x, _) => {
+                let n: u32 = self.pop();
+                let s: u32 = self.pop();
+                let d: u32 = self.pop();
+                let data = self.store.modules[module.0].data_addrs[x.0 as usize];
+                let addr = self.mem_addr(module);
+                let data = &self.store.datas[data.0].data;
+                let mem = &mut self.store.mems[addr].data;
+                if !in_bounds(s as u64, n as u64, data.len())
+                    || !in_bounds(d as u64, n as u64, mem.len())
+                {
+                    return Err(Trap::OutOfBoundsMemoryAccess);
+                }
+                let (s, d, n) = (s as usize, d as usize, n as usize);
+                mem[d..d + n].copy_from_slice(&data[s..s + n]);
+            }
+            MemoryInstruction::DataDrop(x) => {
+                let data = self.store.modules[module.0].data_addrs[x.0 as usize];
+                self.store.datas[data.0].data = Vec::new();
+            }
+            MemoryInstruction::MemoryCopy(..) => {
+                let n: u32 = self.pop();
+                let s: u32 = self.pop();
+                let d: u32 = self.pop();
+                let addr = self.mem_addr(module);
+                let mem = &mut self.store.mems[addr].data;
+                if !in_bounds(s as u64, n as u64, mem.len())
+                    || !in_bounds(d as u64, n as u64, mem.len())
+                {
+                    return Err(Trap::OutOfBoundsMemoryAccess);
+                }
+                let (s, d, n) = (s as usize, d as usize, n as usize);
+                mem.copy_within(s..s + n, d);
+            }
+            MemoryInstruction::MemoryFill(_) => {
+                let n: u32 = self.pop();
+                let v: i32 = self.pop();
+                let d: u32 = self.pop();
+                let addr = self.mem_addr(module);
+                let mem = &mut self.store.mems[addr].data;
+                if !in_bounds(d as u64, n as u64, mem.len()) {
+                    return Err(Trap::OutOfBoundsMemoryAccess);
+                }
+                let (d, n) = (d as usize, n as usize);
+                mem[d..d + n].fill(v as u8);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+use super::{Error, ExternVal, HostFunc, IntoHostFunc, ModuleAddr, Store};
+use crate::validate::ValidatedModule;
+
+/// Resolves the imports of modules by their module and field names.
+///
+/// Definitions are extern values of a [`Store`], such as host functions or
+/// the exports of other module instances. A linker must only be used with
+/// the store its definitions were allocated in.
+#[derive(Debug, Default)]
+pub struct Linker {
+    definitions: BTreeMap<(String, String), ExternVal>,
+}
+
+impl Linker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Defines `name` in `module` as `value`, replacing any previous
+    /// definition.
+    pub fn define(&mut self, module: &str, name: &str, value: ExternVal) -> &mut Self {
+        self.definitions
+            .insert((String::from(module), String::from(name)), value);
+        self
+    }
+
+    /// Allocates `func` in `store` and defines it as `name` in `module`.
+    pub fn func(
+        &mut self,
+        store: &mut Store,
+        module: &str,
+        name: &str,
+        func: HostFunc,
+    ) -> &mut Self {
+        let addr = store.alloc_host_func(func);
+        self.define(module, name, ExternVal::Func(addr))
+    }
+
+    /// Allocates a host function for the Rust closure `func` and defines it
+    /// as `name` in `module`. See [`HostFunc::wrap`].
+    pub fn func_wrap<Params, Results>(
+        &mut self,
+        store: &mut Store,
+        module: &str,
+        name: &str,
+        func: impl IntoHostFunc<Params, Results>,
+    ) -> &mut Self {
+        self.func(store, module, name, HostFunc::wrap(func))
+    }
+
+    /// Defines every export of the module instance at `instance` in
+    /// `module`.
+    pub fn instance(&mut self, store: &Store, module: &str, instance: ModuleAddr) -> &mut Self {
+        for export in &store.module(instance).exports {
+            self.define(module, &export.name, export.value);
+        }
+        self
+    }
+
+    /// Returns the definition of `name` in `module`.
+    pub fn get(&self, module: &str, name: &str) -> Option<ExternVal> {
+        self.definitions
+            .get(&(String::from(module), String::from(name)))
+            .copied()
+    }
+
+    /// Resolves the imports of `module` and instantiates it in `store`. The
+    /// types of the definitions are checked against the types of the
+    /// imports.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/exec/modules.html#instantiation)
+    pub fn instantiate(
+        &self,
+        store: &mut Store,
+        module: ValidatedModule,
+    ) -> Result<ModuleAddr, Error> {
+        let mut imports = Vec::new();
+        if let Some(section) = &module.import {
+            for import in &section.imports {
+                let value =
+                    self.get(import.mod_name, import.name)
+                        .ok_or_else(|| Error::UnknownImport {
+                            module: String::from(import.mod_name),
+                            name: String::from(import.name),
+                        })?;
+                imports.push(value);
+            }
+        }
+        store.instantiate(module, &imports)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exec::{Trap, Value},
+        parse::{modules::module::module_parser, types::Limits},
+        validate::validate,
+    };
+
+    /// A module importing `env.add: [i32 i32] -> [i32]` and a memory with
+    /// limits `{min 1, max 2}`, and exporting a function `f` that calls
+    /// `env.add` with its arguments.
+    const MODULE: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x07, 0x01, 0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F, // type section
+        0x02, 0x17, 0x02, // import section
+        0x03, b'e', b'n', b'v', 0x03, b'a', b'd', b'd', 0x00, 0x00, // env.add
+        0x03, b'e', b'n', b'v', 0x03, b'm', b'e', b'm', 0x02, 0x01, 0x01, 0x02, // env.mem
+        0x03, 0x02, 0x01, 0x00, // function section
+        0x07, 0x05, 0x01, 0x01, b'f', 0x00, 0x01, // export section
+        0x0A, 0x0A, 0x01, 0x08, 0x00, 0x20, 0x00, 0x20, 0x01, 0x10, 0x00,
+        0x0B, // code section
+    ];
+
+    fn instantiate(store: &mut Store, linker: &Linker) -> Result<ModuleAddr, Error> {
+        let module = validate(module_parser(MODULE).unwrap()).unwrap();
+        linker.instantiate(store, module)
+    }
+
+    #[test]
+    fn test_host_func() {
+        let mut store = Store::new();
+        let mut linker = Linker::new();
+        let mem = store
+            .alloc_host_memory(Limits {
+                min: 1,
+                max: Some(1),
+            })
+            .unwrap();
+        linker
+            .func_wrap(&mut store, "env", "add", |a: i32, b: i32| {
+                a.checked_add(b).ok_or(Trap::IntegerOverflow)
+            })
+            .define("env", "mem", ExternVal::Mem(mem));
+
+        let addr = instantiate(&mut store, &linker).unwrap();
+        let f = match store.module(addr).export("f") {
+            Some(ExternVal::Func(f)) => f,
+            _ => panic!("missing export"),
+        };
+        assert_eq!(
+            store.invoke(f, &[Value::I32(1), Value::I32(2)]),
+            Ok(vec![Value::I32(3)])
+        );
+        assert_eq!(
+            store
+                .invoke(f, &[Value::I32(i32::MAX), Value::I32(1)])
+                .unwrap_err()
+                .trap(),
+            Some(Trap::IntegerOverflow)
+        );
+    }
+
+    #[test]
+    fn test_import_matching() {
+        let mut store = Store::new();
+        let mut linker = Linker::new();
+        assert_eq!(
+            instantiate(&mut store, &linker),
+            Err(Error::UnknownImport {
+                module: String::from("env"),
+                name: String::from("add"),
+            })
+        );
+
+        // The function type does not match.
+        linker.func_wrap(&mut store, "env", "add", |a: i32| a);
+        let mem = store
+            .alloc_host_memory(Limits { min: 1, max: None })
+            .unwrap();
+        linker.define("env", "mem", ExternVal::Mem(mem));
+        assert_eq!(
+            instantiate(&mut store, &linker),
+            Err(Error::ImportTypeMismatch(0))
+        );
+
+        // A memory without maximum does not match limits with a maximum.
+        linker.func_wrap(&mut store, "env", "add", |a: i32, b: i32| a + b);
+        assert_eq!(
+            instantiate(&mut store, &linker),
+            Err(Error::ImportTypeMismatch(1))
+        );
+
+        // Neither does a memory whose maximum is too large.
+        let mem = store
+            .alloc_host_memory(Limits {
+                min: 2,
+                max: Some(3),
+            })
+            .unwrap();
+        linker.define("env", "mem", ExternVal::Mem(mem));
+        assert_eq!(
+            instantiate(&mut store, &linker),
+            Err(Error::ImportTypeMismatch(1))
+        );
+
+        let mem = store
+            .alloc_host_memory(Limits {
+                min: 2,
+                max: Some(2),
+            })
+            .unwrap();
+        linker.define("env", "mem", ExternVal::Mem(mem));
+        assert!(instantiate(&mut store, &linker).is_ok());
+    }
+}
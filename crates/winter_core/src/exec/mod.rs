@@ -0,0 +1,22 @@
+//! Execution of validated modules.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/exec/index.html)
+
+mod body;
+mod host;
+mod instance;
+mod interpreter;
+mod linker;
+mod numeric;
+mod store;
+mod trap;
+mod value;
+mod vector;
+
+pub use body::*;
+pub use host::*;
+pub use instance::*;
+pub use linker::*;
+pub use store::*;
+pub use trap::*;
+pub use value::*;
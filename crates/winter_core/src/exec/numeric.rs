@@ -0,0 +1,273 @@
+use num_traits::Float;
+
+use super::{interpreter::Interpreter, Trap};
+use crate::parse::instructions::NumericInstruction;
+
+/// Pops one operand of type `$ty` and pushes the value of `$e`.
+macro_rules! unop {
+    ($self:ident, $ty:ty, $a:ident => $e:expr) => {{
+        let $a: $ty = $self.pop();
+        $self.push($e);
+    }};
+}
+
+/// Pops two operands of type `$ty` and pushes the value of `$e`.
+macro_rules! binop {
+    ($self:ident, $ty:ty, $a:ident, $b:ident => $e:expr) => {{
+        let $b: $ty = $self.pop();
+        let $a: $ty = $self.pop();
+        $self.push($e);
+    }};
+}
+
+/// Returns `b`, or traps if it is zero.
+fn nonzero<T: PartialEq + Default>(b: T) -> Result<T, Trap> {
+    if b == T::default() {
+        return Err(Trap::IntegerDivideByZero);
+    }
+    Ok(b)
+}
+
+/// Truncates `x` towards zero, trapping if it is NaN or if the result is
+/// not in the range `[min, max)`.
+fn trunc(x: f64, min: f64, max: f64) -> Result<f64, Trap> {
+    if x.is_nan() {
+        return Err(Trap::InvalidConversionToInteger);
+    }
+    let t = Float::trunc(x);
+    if t < min || t >= max {
+        return Err(Trap::IntegerOverflow);
+    }
+    Ok(t)
+}
+
+/// Minimum of two floats, propagating NaNs and ordering `-0` below `+0`.
+pub(super) fn fmin<F: Float>(a: F, b: F) -> F {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        if a.is_sign_negative() {
+            a
+        } else {
+            b
+        }
+    } else {
+        a.min(b)
+    }
+}
+
+/// Maximum of two floats, propagating NaNs and ordering `-0` below `+0`.
+pub(super) fn fmax<F: Float>(a: F, b: F) -> F {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        if a.is_sign_positive() {
+            a
+        } else {
+            b
+        }
+    } else {
+        a.max(b)
+    }
+}
+
+/// Rounds `x` with `f`, quieting it if it is a NaN.
+pub(super) fn round<F: Float>(x: F, f: fn(F) -> F) -> F {
+    if x.is_nan() {
+        x + x
+    } else {
+        f(x)
+    }
+}
+
+/// Rounds to the nearest integer, with ties to even.
+pub(super) fn nearest<F: Float>(x: F) -> F {
+    let two = F::one() + F::one();
+    let r = x.round();
+    if (r - x).abs() == F::one() / two {
+        two * (x / two).round()
+    } else {
+        r
+    }
+}
+
+const F32_SIGN: u32 = 1 << 31;
+const F64_SIGN: u64 = 1 << 63;
+
+impl Interpreter<'_> {
+    /// [Reference](https://webassembly.github.io/spec/core/exec/numerics.html)
+    pub(super) fn exec_numeric(&mut self, instr: &NumericInstruction) -> Result<(), Trap> {
+        use NumericInstruction::*;
+        match *instr {
+            I32Const(v) => self.push(v),
+            I64Const(v) => self.push(v),
+            F32Const(v) => self.push(v),
+            F64Const(v) => self.push(v),
+
+            I32Eqz => unop!(self, i32, a => a == 0),
+            I32Eq => binop!(self, i32, a, b => a == b),
+            I32Ne => binop!(self, i32, a, b => a != b),
+            I32LtS => binop!(self, i32, a, b => a < b),
+            I32LtU => binop!(self, u32, a, b => a < b),
+            I32GtS => binop!(self, i32, a, b => a > b),
+            I32GtU => binop!(self, u32, a, b => a > b),
+            I32LeS => binop!(self, i32, a, b => a <= b),
+            I32LeU => binop!(self, u32, a, b => a <= b),
+            I32GeS => binop!(self, i32, a, b => a >= b),
+            I32GeU => binop!(self, u32, a, b => a >= b),
+
+            I64Eqz => unop!(self, i64, a => a == 0),
+            I64Eq => binop!(self, i64, a, b => a == b),
+            I64Ne => binop!(self, i64, a, b => a != b),
+            I64LtS => binop!(self, i64, a, b => a < b),
+            I64LtU => binop!(self, u64, a, b => a < b),
+            I64GtS => binop!(self, i64, a, b => a > b),
+            I64GtU => binop!(self, u64, a, b => a > b),
+            I64LeS => binop!(self, i64, a, b => a <= b),
+            I64LeU => binop!(self, u64, a, b => a <= b),
+            I64GeS => binop!(self, i64, a, b => a >= b),
+            I64GeU => binop!(self, u64, a, b => a >= b),
+
+            F32Eq => binop!(self, f32, a, b => a == b),
+            F32Ne => binop!(self, f32, a, b => a != b),
+            F32Lt => binop!(self, f32, a, b => a < b),
+            F32Gt => binop!(self, f32, a, b => a > b),
+            F32Le => binop!(self, f32, a, b => a <= b),
+            F32Ge => binop!(self, f32, a, b => a >= b),
+
+            F64Eq => binop!(self, f64, a, b => a == b),
+            F64Ne => binop!(self, f64, a, b => a != b),
+            F64Lt => binop!(self, f64, a, b => a < b),
+            F64Gt => binop!(self, f64, a, b => a > b),
+            F64Le => binop!(self, f64, a, b => a <= b),
+            F64Ge => binop!(self, f64, a, b => a >= b),
+
+            I32Clz => unop!(self, i32, a => a.leading_zeros()),
+            I32Ctz => unop!(self, i32, a => a.trailing_zeros()),
+            I32Popcnt => unop!(self, i32, a => a.count_ones()),
+            I32Add => binop!(self, i32, a, b => a.wrapping_add(b)),
+            I32Sub => binop!(self, i32, a, b => a.wrapping_sub(b)),
+            I32Mul => binop!(self, i32, a, b => a.wrapping_mul(b)),
+            I32DivS => {
+                binop!(self, i32, a, b => a.checked_div(nonzero(b)?).ok_or(Trap::IntegerOverflow)?)
+            }
+            I32DivU => binop!(self, u32, a, b => a / nonzero(b)?),
+            I32RemS => binop!(self, i32, a, b => a.wrapping_rem(nonzero(b)?)),
+            I32RemU => binop!(self, u32, a, b => a % nonzero(b)?),
+            I32And => binop!(self, i32, a, b => a & b),
+            I32Or => binop!(self, i32, a, b => a | b),
+            I32Xor => binop!(self, i32, a, b => a ^ b),
+            I32Shl => binop!(self, i32, a, b => a.wrapping_shl(b as u32)),
+            I32ShrS => binop!(self, i32, a, b => a.wrapping_shr(b as u32)),
+            I32ShrU => binop!(self, u32, a, b => a.wrapping_shr(b)),
+            I32Rotl => binop!(self, u32, a, b => a.rotate_left(b)),
+            I32Rotr => binop!(self, u32, a, b => a.rotate_right(b)),
+
+            I64Clz => unop!(self, i64, a => a.leading_zeros() as i64),
+            I64Ctz => unop!(self, i64, a => a.trailing_zeros() as i64),
+            I64Popcnt => unop!(self, i64, a => a.count_ones() as i64),
+            I64Add => binop!(self, i64, a, b => a.wrapping_add(b)),
+            I64Sub => binop!(self, i64, a, b => a.wrapping_sub(b)),
+            I64Mul => binop!(self, i64, a, b => a.wrapping_mul(b)),
+            I64DivS => {
+                binop!(self, i64, a, b => a.checked_div(nonzero(b)?).ok_or(Trap::IntegerOverflow)?)
+            }
+            I64DivU => binop!(self, u64, a, b => a / nonzero(b)?),
+            I64RemS => binop!(self, i64, a, b => a.wrapping_rem(nonzero(b)?)),
+            I64RemU => binop!(self, u64, a, b => a % nonzero(b)?),
+            I64And => binop!(self, i64, a, b => a & b),
+            I64Or => binop!(self, i64, a, b => a | b),
+            I64Xor => binop!(self, i64, a, b => a ^ b),
+            I64Shl => binop!(self, i64, a, b => a.wrapping_shl(b as u32)),
+            I64ShrS => binop!(self, i64, a, b => a.wrapping_shr(b as u32)),
+            I64ShrU => binop!(self, u64, a, b => a.wrapping_shr(b as u32)),
+            I64Rotl => binop!(self, u64, a, b => a.rotate_left((b % 64) as u32)),
+            I64Rotr => binop!(self, u64, a, b => a.rotate_right((b % 64) as u32)),
+
+            F32Abs => unop!(self, f32, a => f32::from_bits(a.to_bits() & !F32_SIGN)),
+            F32Neg => unop!(self, f32, a => f32::from_bits(a.to_bits() ^ F32_SIGN)),
+            F32Ceil => unop!(self, f32, a => round(a, Float::ceil)),
+            F32Floor => unop!(self, f32, a => round(a, Float::floor)),
+            F32Trunc => unop!(self, f32, a => round(a, Float::trunc)),
+            F32Nearest => unop!(self, f32, a => nearest(a)),
+            F32Sqrt => unop!(self, f32, a => Float::sqrt(a)),
+            F32Add => binop!(self, f32, a, b => a + b),
+            F32Sub => binop!(self, f32, a, b => a - b),
+            F32Mul => binop!(self, f32, a, b => a * b),
+            F32Div => binop!(self, f32, a, b => a / b),
+            F32Min => binop!(self, f32, a, b => fmin(a, b)),
+            F32Max => binop!(self, f32, a, b => fmax(a, b)),
+            F32Copysign => binop!(self, f32, a, b => f32::from_bits(
+                (a.to_bits() & !F32_SIGN) | (b.to_bits() & F32_SIGN)
+            )),
+
+            F64Abs => unop!(self, f64, a => f64::from_bits(a.to_bits() & !F64_SIGN)),
+            F64Neg => unop!(self, f64, a => f64::from_bits(a.to_bits() ^ F64_SIGN)),
+            F64Ceil => unop!(self, f64, a => round(a, Float::ceil)),
+            F64Floor => unop!(self, f64, a => round(a, Float::floor)),
+            F64Trunc => unop!(self, f64, a => round(a, Float::trunc)),
+            F64Nearest => unop!(self, f64, a => nearest(a)),
+            F64Sqrt => unop!(self, f64, a => Float::sqrt(a)),
+            F64Add => binop!(self, f64, a, b => a + b),
+            F64Sub => binop!(self, f64, a, b => a - b),
+            F64Mul => binop!(self, f64, a, b => a * b),
+            F64Div => binop!(self, f64, a, b => a / b),
+            F64Min => binop!(self, f64, a, b => fmin(a, b)),
+            F64Max => binop!(self, f64, a, b => fmax(a, b)),
+            F64Copysign => binop!(self, f64, a, b => f64::from_bits(
+                (a.to_bits() & !F64_SIGN) | (b.to_bits() & F64_SIGN)
+            )),
+
+            I32WrapI64 => unop!(self, i64, a => a as i32),
+            I32TruncF32S => {
+                unop!(self, f32, a => trunc(a as f64, -2147483648.0, 2147483648.0)? as i32)
+            }
+            I32TruncF32U => unop!(self, f32, a => trunc(a as f64, 0.0, 4294967296.0)? as u32),
+            I32TruncF64S => unop!(self, f64, a => trunc(a, -2147483648.0, 2147483648.0)? as i32),
+            I32TruncF64U => unop!(self, f64, a => trunc(a, 0.0, 4294967296.0)? as u32),
+            I64ExtendI32S => unop!(self, i32, a => a as i64),
+            I64ExtendI32U => unop!(self, u32, a => a as i64),
+            I64TruncF32S => {
+                unop!(self, f32, a => trunc(a as f64, -9223372036854775808.0, 9223372036854775808.0)? as i64)
+            }
+            I64TruncF32U => {
+                unop!(self, f32, a => trunc(a as f64, 0.0, 18446744073709551616.0)? as u64)
+            }
+            I64TruncF64S => {
+                unop!(self, f64, a => trunc(a, -9223372036854775808.0, 9223372036854775808.0)? as i64)
+            }
+            I64TruncF64U => unop!(self, f64, a => trunc(a, 0.0, 18446744073709551616.0)? as u64),
+            F32ConvertI32S => unop!(self, i32, a => a as f32),
+            F32ConvertI32U => unop!(self, u32, a => a as f32),
+            F32ConvertI64S => unop!(self, i64, a => a as f32),
+            F32ConvertI64U => unop!(self, u64, a => a as f32),
+            F32DemoteF64 => unop!(self, f64, a => a as f32),
+            F64ConvertI32S => unop!(self, i32, a => a as f64),
+            F64ConvertI32U => unop!(self, u32, a => a as f64),
+            F64ConvertI64S => unop!(self, i64, a => a as f64),
+            F64ConvertI64U => unop!(self, u64, a => a as f64),
+            F64PromoteF32 => unop!(self, f32, a => a as f64),
+            I32ReinterpretF32 => unop!(self, f32, a => a.to_bits()),
+            I64ReinterpretF64 => unop!(self, f64, a => a.to_bits()),
+            F32ReinterpretI32 => unop!(self, u32, a => f32::from_bits(a)),
+            F64ReinterpretI64 => unop!(self, u64, a => f64::from_bits(a)),
+
+            I32Extend8S => unop!(self, i32, a => a as i8 as i32),
+            I32Extend16S => unop!(self, i32, a => a as i16 as i32),
+            I64Extend8S => unop!(self, i64, a => a as i8 as i64),
+            I64Extend16S => unop!(self, i64, a => a as i16 as i64),
+            I64Extend32S => unop!(self, i64, a => a as i32 as i64),
+
+            // Float to integer casts saturate and map NaN to zero.
+            I32TruncSatF32S => unop!(self, f32, a => a as i32),
+            I32TruncSatF32U => unop!(self, f32, a => a as u32),
+            I32TruncSatF64S => unop!(self, f64, a => a as i32),
+            I32TruncSatF64U => unop!(self, f64, a => a as u32),
+            I64TruncSatF32S => unop!(self, f32, a => a as i64),
+            I64TruncSatF32U => unop!(self, f32, a => a as u64),
+            I64TruncSatF64S => unop!(self, f64, a => a as i64),
+            I64TruncSatF64U => unop!(self, f64, a => a as u64),
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use std::{rc::Rc, vec::Vec};
+
+use super::{
+    interpreter::Interpreter, Error, ExternAddr, FuncAddr, FuncBody, HostFunc, ModuleInstance,
+    Value,
+};
+use crate::parse::{
+    modules::code_section::Locals,
+    types::{FuncType, GlobalType, Limits, MemType, RefType, TableType},
+};
+
+/// Size of a memory page in bytes.
+pub const PAGE_SIZE: usize = 65536;
+
+/// Maximum number of pages of a memory.
+pub(crate) const MAX_PAGES: u32 = 65536;
+
+/// Default limit on the number of nested function calls before execution
+/// traps with call stack exhaustion.
+pub const DEFAULT_MAX_CALL_DEPTH: usize = 1024;
+
+macro_rules! impl_addr {
+    ($(#[$meta:meta])* $typename: ident) => {
+        $(#[$meta])*
+        #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
+        pub struct $typename(pub usize);
+    };
+}
+
+impl_addr!(
+    /// Address of a module instance in a [`Store`].
+    ModuleAddr
+);
+impl_addr!(
+    /// Address of a table instance in a [`Store`].
+    TableAddr
+);
+impl_addr!(
+    /// Address of a memory instance in a [`Store`].
+    MemAddr
+);
+impl_addr!(
+    /// Address of a global instance in a [`Store`].
+    GlobalAddr
+);
+impl_addr!(
+    /// Address of an element instance in a [`Store`].
+    ElemAddr
+);
+impl_addr!(
+    /// Address of a data instance in a [`Store`].
+    DataAddr
+);
+
+/// An entity that can be imported or exported.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#external-values)
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum ExternVal {
+    Func(FuncAddr),
+    Table(TableAddr),
+    Mem(MemAddr),
+    Global(GlobalAddr),
+}
+
+/// A function instance: the closure of a function over the instance of its
+/// module.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#function-instances)
+#[derive(Debug)]
+pub enum FuncInst {
+    Wasm {
+        func_type: FuncType,
+        module: ModuleAddr,
+        locals: Vec<Locals>,
+        body: FuncBody,
+    },
+    Host(HostFunc),
+}
+
+impl FuncInst {
+    pub fn func_type(&self) -> &FuncType {
+        match self {
+            FuncInst::Wasm { func_type, .. } => func_type,
+            FuncInst::Host(func) => func.func_type(),
+        }
+    }
+}
+
+/// A table instance holds a vector of references.
+#[derive(Debug)]
+pub struct TableInst {
+    pub ref_type: RefType,
+    pub elements: Vec<Value>,
+    pub max: Option<u32>,
+}
+
+impl TableInst {
+    /// Creates a table of `min` null references, or returns `None` if it
+    /// cannot be allocated.
+    pub(crate) fn new(ref_type: RefType, min: u32, max: Option<u32>) -> Option<Self> {
+        let mut elements = Vec::new();
+        elements.try_reserve_exact(min as usize).ok()?;
+        elements.resize(min as usize, Value::null(ref_type));
+        Some(TableInst {
+            ref_type,
+            elements,
+            max,
+        })
+    }
+
+    /// Returns the current type of the table, whose minimum is its size.
+    pub fn table_type(&self) -> TableType {
+        TableType {
+            ref_type: self.ref_type,
+            limits: Limits {
+                min: self.elements.len() as u32,
+                max: self.max,
+            },
+        }
+    }
+
+    /// Grows the table by `delta` elements initialized to `init` and returns
+    /// the previous size, or `None` if the table cannot grow.
+    pub fn grow(&mut self, delta: u32, init: Value) -> Option<u32> {
+        let old = self.elements.len() as u32;
+        let new = old.checked_add(delta)?;
+        if matches!(self.max, Some(max) if new > max) {
+            return None;
+        }
+        self.elements.try_reserve_exact(delta as usize).ok()?;
+        self.elements.resize(new as usize, init);
+        Some(old)
+    }
+}
+
+/// A memory instance holds a vector of bytes whose length is a multiple of
+/// the page size.
+#[derive(Debug)]
+pub struct MemInst {
+    pub data: Vec<u8>,
+    pub max: Option<u32>,
+}
+
+impl MemInst {
+    /// Creates a zeroed memory of `min` pages, or returns `None` if it
+    /// cannot be allocated.
+    pub(crate) fn new(min: u32, max: Option<u32>) -> Option<Self> {
+        let len = (min as usize).checked_mul(PAGE_SIZE)?;
+        let mut data = Vec::new();
+        data.try_reserve_exact(len).ok()?;
+        data.resize(len, 0);
+        Some(MemInst { data, max })
+    }
+
+    /// Returns the current type of the memory, whose minimum is its size.
+    pub fn mem_type(&self) -> MemType {
+        MemType {
+            min: self.size(),
+            max: self.max,
+        }
+    }
+
+    /// Returns the size of the memory in pages.
+    pub fn size(&self) -> u32 {
+        (self.data.len() / PAGE_SIZE) as u32
+    }
+
+    /// Grows the memory by `delta` pages and returns the previous size, or
+    /// `None` if the memory cannot grow.
+    pub fn grow(&mut self, delta: u32) -> Option<u32> {
+        let old = self.size();
+        let new = old.checked_add(delta)?;
+        if new > self.max.unwrap_or(MAX_PAGES).min(MAX_PAGES) {
+            return None;
+        }
+        let additional = (delta as usize).checked_mul(PAGE_SIZE)?;
+        self.data.try_reserve_exact(additional).ok()?;
+        self.data.resize(self.data.len() + additional, 0);
+        Some(old)
+    }
+}
+
+/// A global instance holds the value of a global.
+#[derive(Debug)]
+pub struct GlobalInst {
+    pub global_type: GlobalType,
+    pub value: Value,
+}
+
+/// An element instance holds the references of an element segment.
+#[derive(Debug)]
+pub struct ElemInst {
+    pub ref_type: RefType,
+    pub elements: Vec<Value>,
+}
+
+/// A data instance holds the bytes of a data segment.
+#[derive(Debug)]
+pub struct DataInst {
+    pub data: Vec<u8>,
+}
+
+/// The store represents all global state that can be manipulated by
+/// WebAssembly programs.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#store)
+#[derive(Debug)]
+pub struct Store {
+    pub(crate) funcs: Vec<Rc<FuncInst>>,
+    pub(crate) tables: Vec<TableInst>,
+    pub(crate) mems: Vec<MemInst>,
+    pub(crate) globals: Vec<GlobalInst>,
+    pub(crate) elems: Vec<ElemInst>,
+    pub(crate) datas: Vec<DataInst>,
+    pub(crate) modules: Vec<ModuleInstance>,
+    pub(crate) max_call_depth: usize,
+    next_extern: usize,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store {
+            funcs: Vec::new(),
+            tables: Vec::new(),
+            mems: Vec::new(),
+            globals: Vec::new(),
+            elems: Vec::new(),
+            datas: Vec::new(),
+            modules: Vec::new(),
+            max_call_depth: DEFAULT_MAX_CALL_DEPTH,
+            next_extern: 0,
+        }
+    }
+}
+
+impl Store {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of nested calls before execution traps.
+    pub fn set_max_call_depth(&mut self, depth: usize) {
+        self.max_call_depth = depth;
+    }
+
+    pub fn module(&self, addr: ModuleAddr) -> &ModuleInstance {
+        &self.modules[addr.0]
+    }
+
+    pub fn func(&self, addr: FuncAddr) -> &FuncInst {
+        &self.funcs[addr.0]
+    }
+
+    pub fn table(&self, addr: TableAddr) -> &TableInst {
+        &self.tables[addr.0]
+    }
+
+    pub fn table_mut(&mut self, addr: TableAddr) -> &mut TableInst {
+        &mut self.tables[addr.0]
+    }
+
+    pub fn memory(&self, addr: MemAddr) -> &MemInst {
+        &self.mems[addr.0]
+    }
+
+    pub fn memory_mut(&mut self, addr: MemAddr) -> &mut MemInst {
+        &mut self.mems[addr.0]
+    }
+
+    pub fn global(&self, addr: GlobalAddr) -> &GlobalInst {
+        &self.globals[addr.0]
+    }
+
+    pub fn global_mut(&mut self, addr: GlobalAddr) -> &mut GlobalInst {
+        &mut self.globals[addr.0]
+    }
+
+    /// Returns a fresh address to be used with `externref` values.
+    pub fn alloc_extern(&mut self) -> ExternAddr {
+        self.next_extern += 1;
+        ExternAddr(self.next_extern - 1)
+    }
+
+    /// Allocates a host function that can be provided as an import.
+    pub fn alloc_host_func(&mut self, func: HostFunc) -> FuncAddr {
+        self.alloc_func(FuncInst::Host(func))
+    }
+
+    /// Allocates a table of type `table_type` with all elements set to
+    /// `init`, which must be a reference of the table's type.
+    pub fn alloc_host_table(
+        &mut self,
+        table_type: TableType,
+        init: Value,
+    ) -> Result<TableAddr, Error> {
+        let limits = table_type.limits;
+        let mut table = TableInst::new(table_type.ref_type, limits.min, limits.max)
+            .ok_or(Error::OutOfMemory)?;
+        table.elements.fill(init);
+        Ok(self.alloc_table(table))
+    }
+
+    /// Allocates a zeroed memory of type `mem_type`.
+    pub fn alloc_host_memory(&mut self, mem_type: MemType) -> Result<MemAddr, Error> {
+        let mem = MemInst::new(mem_type.min, mem_type.max).ok_or(Error::OutOfMemory)?;
+        Ok(self.alloc_mem(mem))
+    }
+
+    /// Allocates a global of type `global_type` holding `value`.
+    pub fn alloc_host_global(&mut self, global_type: GlobalType, value: Value) -> GlobalAddr {
+        self.alloc_global(GlobalInst { global_type, value })
+    }
+
+    pub(crate) fn alloc_func(&mut self, func: FuncInst) -> FuncAddr {
+        self.funcs.push(Rc::new(func));
+        FuncAddr(self.funcs.len() - 1)
+    }
+
+    pub(crate) fn alloc_table(&mut self, table: TableInst) -> TableAddr {
+        self.tables.push(table);
+        TableAddr(self.tables.len() - 1)
+    }
+
+    pub(crate) fn alloc_mem(&mut self, mem: MemInst) -> MemAddr {
+        self.mems.push(mem);
+        MemAddr(self.mems.len() - 1)
+    }
+
+    pub(crate) fn alloc_global(&mut self, global: GlobalInst) -> GlobalAddr {
+        self.globals.push(global);
+        GlobalAddr(self.globals.len() - 1)
+    }
+
+    /// Invokes the function at `addr` with `args` and returns its results.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/exec/modules.html#invocation)
+    pub fn invoke(&mut self, addr: FuncAddr, args: &[Value]) -> Result<Vec<Value>, Error> {
+        let func_type = self.funcs[addr.0].func_type();
+        if func_type.params.len() != args.len()
+            || func_type
+                .params
+                .iter()
+                .zip(args)
+                .any(|(t, v)| *t != v.val_type())
+        {
+            return Err(Error::ArgumentTypeMismatch);
+        }
+
+        let mut interpreter = Interpreter::new(self);
+        interpreter.push_values(args);
+        interpreter.call(addr)?;
+        Ok(interpreter.into_values())
+    }
+}
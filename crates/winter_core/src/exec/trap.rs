@@ -0,0 +1,139 @@
+use core::fmt;
+use std::string::String;
+
+use super::ModuleAddr;
+use crate::parse::modules::FuncIdx;
+
+/// The reason why execution trapped. Traps abort the current computation
+/// and cannot be handled by WebAssembly code. The [`fmt::Display`]
+/// implementation uses the messages of the specification's test suite.
+///
+/// [Reference](https://webassembly.github.io/spec/core/intro/overview.html#trap)
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Trap {
+    /// An `unreachable` instruction was executed.
+    Unreachable,
+    /// Integer division or remainder by zero.
+    IntegerDivideByZero,
+    /// Signed integer division overflowed, or a float to integer
+    /// truncation produced a value out of the range of the target type.
+    IntegerOverflow,
+    /// A float to integer truncation was applied to NaN.
+    InvalidConversionToInteger,
+    OutOfBoundsMemoryAccess,
+    OutOfBoundsTableAccess,
+    /// The function called by `call_indirect` does not have the expected
+    /// type.
+    IndirectCallTypeMismatch,
+    /// `call_indirect` on the null table element at the given index.
+    UninitializedElement(u32),
+    /// The maximum call depth of the [`Store`](super::Store) was reached.
+    CallStackExhausted,
+    /// `call_indirect` with an index past the end of the table.
+    UndefinedElement,
+    /// A host function returned values that do not match the result types
+    /// of its function type.
+    HostResultMismatch,
+}
+
+impl fmt::Display for Trap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trap::Unreachable => write!(f, "unreachable"),
+            Trap::IntegerDivideByZero => write!(f, "integer divide by zero"),
+            Trap::IntegerOverflow => write!(f, "integer overflow"),
+            Trap::InvalidConversionToInteger => write!(f, "invalid conversion to integer"),
+            Trap::OutOfBoundsMemoryAccess => write!(f, "out of bounds memory access"),
+            Trap::OutOfBoundsTableAccess => write!(f, "out of bounds table access"),
+            Trap::IndirectCallTypeMismatch => write!(f, "indirect call type mismatch"),
+            Trap::UninitializedElement(i) => write!(f, "uninitialized element {}", i),
+            Trap::CallStackExhausted => write!(f, "call stack exhausted"),
+            Trap::UndefinedElement => write!(f, "undefined element"),
+            Trap::HostResultMismatch => write!(f, "host function result type mismatch"),
+        }
+    }
+}
+
+/// The instruction that caused a trap.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub struct TrapLocation {
+    /// The module instance that defines the function.
+    pub module: ModuleAddr,
+    /// Index of the function in the function index space of its module,
+    /// which includes imported functions. Its name, if the module has a name
+    /// section, is given by
+    /// [`ModuleInstance::func_name`](super::ModuleInstance::func_name).
+    pub func: FuncIdx,
+    /// Offset of the instruction from the start of the code section of the
+    /// binary module, or 0 if the module was not decoded from a binary.
+    pub offset: usize,
+}
+
+/// Errors returned by the embedding interface of the [`Store`](super::Store).
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum Error {
+    /// Execution trapped. `location` is `None` when the trap did not occur
+    /// in a function body, such as during the initialization of segments.
+    Trap {
+        trap: Trap,
+        location: Option<TrapLocation>,
+    },
+    /// No definition was provided for an import of the module.
+    UnknownImport { module: String, name: String },
+    /// The number of provided imports does not match the number of imports
+    /// of the module.
+    ImportCountMismatch,
+    /// The extern value provided for the import at the given position does
+    /// not match the type of the import.
+    ImportTypeMismatch(usize),
+    /// The arguments of an invocation do not match the parameter types of
+    /// the function.
+    ArgumentTypeMismatch,
+    /// A table or memory of the module could not be allocated.
+    OutOfMemory,
+}
+
+impl Error {
+    /// Returns the reason of the trap if execution trapped.
+    pub fn trap(&self) -> Option<Trap> {
+        match self {
+            Error::Trap { trap, .. } => Some(*trap),
+            _ => None,
+        }
+    }
+}
+
+impl From<Trap> for Error {
+    fn from(trap: Trap) -> Self {
+        Error::Trap {
+            trap,
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Trap {
+                trap,
+                location: None,
+            } => write!(f, "trap: {}", trap),
+            Error::Trap {
+                trap,
+                location: Some(l),
+            } => write!(
+                f,
+                "trap: {} in function {} at offset {:#x}",
+                trap, l.func.0, l.offset
+            ),
+            Error::UnknownImport { module, name } => {
+                write!(f, "unknown import \"{}\" \"{}\"", module, name)
+            }
+            Error::ImportCountMismatch => write!(f, "wrong number of imports"),
+            Error::ImportTypeMismatch(i) => write!(f, "incompatible import type for import {}", i),
+            Error::ArgumentTypeMismatch => write!(f, "argument type mismatch"),
+            Error::OutOfMemory => write!(f, "out of memory"),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use crate::parse::types::{NumType, RefType, ValType, VecType};
+
+/// Address of a function instance in a [`Store`](super::Store).
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
+pub struct FuncAddr(pub usize);
+
+/// Opaque address of a host object referenced by an `externref`.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
+pub struct ExternAddr(pub usize);
+
+/// A runtime value.
+///
+/// [Reference](https://webassembly.github.io/spec/core/exec/runtime.html#values)
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    V128(u128),
+    /// A function reference, or a null reference if `None`.
+    FuncRef(Option<FuncAddr>),
+    /// An external reference, or a null reference if `None`.
+    ExternRef(Option<ExternAddr>),
+}
+
+impl Value {
+    /// Returns the default value of a type: zero for numbers and vectors and
+    /// null for references.
+    pub fn default_of(t: ValType) -> Value {
+        match t {
+            ValType::NumType(NumType::I32) => Value::I32(0),
+            ValType::NumType(NumType::I64) => Value::I64(0),
+            ValType::NumType(NumType::F32) => Value::F32(0.0),
+            ValType::NumType(NumType::F64) => Value::F64(0.0),
+            ValType::VecType(VecType::V128) => Value::V128(0),
+            ValType::RefType(RefType::FuncRef) => Value::FuncRef(None),
+            ValType::RefType(RefType::ExternRef) => Value::ExternRef(None),
+        }
+    }
+
+    /// Returns the type of the value.
+    pub fn val_type(&self) -> ValType {
+        match self {
+            Value::I32(_) => ValType::NumType(NumType::I32),
+            Value::I64(_) => ValType::NumType(NumType::I64),
+            Value::F32(_) => ValType::NumType(NumType::F32),
+            Value::F64(_) => ValType::NumType(NumType::F64),
+            Value::V128(_) => ValType::VecType(VecType::V128),
+            Value::FuncRef(_) => ValType::RefType(RefType::FuncRef),
+            Value::ExternRef(_) => ValType::RefType(RefType::ExternRef),
+        }
+    }
+
+    /// Returns a null reference of type `t`.
+    pub fn null(t: RefType) -> Value {
+        match t {
+            RefType::FuncRef => Value::FuncRef(None),
+            RefType::ExternRef => Value::ExternRef(None),
+        }
+    }
+
+    /// Returns true if the value is a null reference.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::FuncRef(None) | Value::ExternRef(None))
+    }
+}
+
+/// Conversions between [`Value`] and the Rust types that represent the
+/// WebAssembly value types. Validation guarantees that operands have the
+/// expected type, so a mismatch is a bug in the interpreter.
+macro_rules! impl_value_conversions {
+    ($ty:ty, $variant:ident) => {
+        impl From<$ty> for Value {
+            fn from(v: $ty) -> Self {
+                Value::$variant(v)
+            }
+        }
+
+        impl From<Value> for $ty {
+            fn from(v: Value) -> Self {
+                match v {
+                    Value::$variant(v) => v,
+                    _ => unreachable!(concat!("value is not of type ", stringify!($ty))),
+                }
+            }
+        }
+    };
+}
+
+impl_value_conversions!(i32, I32);
+impl_value_conversions!(i64, I64);
+impl_value_conversions!(f32, F32);
+impl_value_conversions!(f64, F64);
+impl_value_conversions!(u128, V128);
+
+impl From<u32> for Value {
+    fn from(v: u32) -> Self {
+        Value::I32(v as i32)
+    }
+}
+
+impl From<Value> for u32 {
+    fn from(v: Value) -> Self {
+        i32::from(v) as u32
+    }
+}
+
+impl From<u64> for Value {
+    fn from(v: u64) -> Self {
+        Value::I64(v as i64)
+    }
+}
+
+impl From<Value> for u64 {
+    fn from(v: Value) -> Self {
+        i64::from(v) as u64
+    }
+}
+
+impl From<bool> for Value {
+    fn from(v: bool) -> Self {
+        Value::I32(v as i32)
+    }
+}
@@ -0,0 +1,542 @@
+use core::array::from_fn;
+
+use num_traits::Float;
+
+use super::{
+    interpreter::Interpreter,
+    numeric::{fmax, fmin, nearest, round},
+    ModuleAddr, Trap,
+};
+use crate::parse::instructions::VectorInstruction;
+
+/// A scalar that can be stored in a lane of a `v128`.
+trait Lane: Copy + PartialOrd + Default {
+    const SIZE: usize;
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_lane {
+    ($($ty:ty),*) => {
+        $(impl Lane for $ty {
+            const SIZE: usize = core::mem::size_of::<$ty>();
+
+            fn read(bytes: &[u8]) -> Self {
+                <$ty>::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            fn write(self, bytes: &mut [u8]) {
+                bytes.copy_from_slice(&self.to_le_bytes());
+            }
+        })*
+    };
+}
+
+impl_lane!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+/// Splits a vector into `N` lanes of type `T`, lane 0 being the least
+/// significant.
+fn lanes<T: Lane, const N: usize>(v: u128) -> [T; N] {
+    let bytes = v.to_le_bytes();
+    from_fn(|i| T::read(&bytes[i * T::SIZE..(i + 1) * T::SIZE]))
+}
+
+fn from_lanes<T: Lane, const N: usize>(lanes: [T; N]) -> u128 {
+    let mut bytes = [0; 16];
+    for (i, lane) in lanes.into_iter().enumerate() {
+        lane.write(&mut bytes[i * T::SIZE..(i + 1) * T::SIZE]);
+    }
+    u128::from_le_bytes(bytes)
+}
+
+fn all_true<T: Lane, const N: usize>(v: u128) -> bool {
+    lanes::<T, N>(v).iter().all(|l| *l != T::default())
+}
+
+/// Collects the sign bit of every lane.
+fn bitmask<T: Lane, const N: usize>(v: u128) -> u32 {
+    lanes::<T, N>(v)
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| **l < T::default())
+        .fold(0, |m, (i, _)| m | 1 << i)
+}
+
+/// Applies `$e` to every lane of one or two operands.
+macro_rules! lanewise {
+    ($self:ident, [$ty:ty; $n:literal], $a:ident => $e:expr) => {{
+        let a = lanes::<$ty, $n>($self.pop());
+        $self.push(from_lanes::<_, $n>(a.map(|$a| $e)));
+    }};
+    ($self:ident, [$ty:ty; $n:literal], $a:ident, $b:ident => $e:expr) => {{
+        let b = lanes::<$ty, $n>($self.pop());
+        let a = lanes::<$ty, $n>($self.pop());
+        $self.push(from_lanes::<_, $n>(from_fn(|i| {
+            let ($a, $b) = (a[i], b[i]);
+            $e
+        })));
+    }};
+}
+
+/// Compares lanes, producing all ones lanes of type `$m` where `$e` holds.
+macro_rules! compare {
+    ($self:ident, [$ty:ty; $n:literal] -> $m:ty, $a:ident, $b:ident => $e:expr) => {
+        lanewise!($self, [$ty; $n], $a, $b => if $e { -1 as $m } else { 0 })
+    };
+}
+
+/// Shifts every lane by a scalar amount taken modulo the lane width.
+macro_rules! shift {
+    ($self:ident, [$ty:ty; $n:literal], $a:ident, $s:ident => $e:expr) => {{
+        let $s: u32 = $self.pop();
+        lanewise!($self, [$ty; $n], $a => $e)
+    }};
+}
+
+/// Builds a vector of `$m` lanes from the lane arrays of one or two
+/// operands, with `$i` the index of the lane being computed.
+macro_rules! reshape {
+    ($self:ident, [$ty:ty; $n:literal] -> $m:literal, $i:ident, $a:ident => $e:expr) => {{
+        let $a = lanes::<$ty, $n>($self.pop());
+        $self.push(from_lanes::<_, $m>(from_fn(|$i| $e)));
+    }};
+    ($self:ident, [$ty:ty; $n:literal] -> $m:literal, $i:ident, $a:ident, $b:ident => $e:expr) => {{
+        let $b = lanes::<$ty, $n>($self.pop());
+        let $a = lanes::<$ty, $n>($self.pop());
+        $self.push(from_lanes::<_, $m>(from_fn(|$i| $e)));
+    }};
+}
+
+/// Loads `$n` lanes of type `$ty` and extends each to `$wide`.
+macro_rules! load_extend {
+    ($self:ident, $module:ident, $arg:ident, [$ty:ty; $n:literal] as $wide:ty) => {{
+        let bytes = $self.load::<8>($module, $arg)?;
+        let lanes: [$wide; $n] = from_fn(|i| {
+            <$ty as Lane>::read(&bytes[i * <$ty>::SIZE..(i + 1) * <$ty>::SIZE]) as $wide
+        });
+        $self.push(from_lanes(lanes));
+    }};
+}
+
+/// Loads a scalar of type `$ty` and replicates it to all lanes.
+macro_rules! load_splat {
+    ($self:ident, $module:ident, $arg:ident, [$ty:ty; $n:literal]) => {{
+        let bytes = $self.load::<{ core::mem::size_of::<$ty>() }>($module, $arg)?;
+        $self.push(from_lanes([<$ty>::from_le_bytes(bytes); $n]));
+    }};
+}
+
+/// Replaces lane `$l` of the vector on top of the stack by a scalar loaded
+/// from memory.
+macro_rules! load_lane {
+    ($self:ident, $module:ident, $arg:ident, $l:ident, [$ty:ty; $n:literal]) => {{
+        let mut lanes = lanes::<$ty, $n>($self.pop());
+        let bytes = $self.load::<{ core::mem::size_of::<$ty>() }>($module, $arg)?;
+        lanes[*$l as usize] = <$ty>::from_le_bytes(bytes);
+        $self.push(from_lanes(lanes));
+    }};
+}
+
+/// Stores lane `$l` of the vector on top of the stack to memory.
+macro_rules! store_lane {
+    ($self:ident, $module:ident, $arg:ident, $l:ident, [$ty:ty; $n:literal]) => {{
+        let lanes = lanes::<$ty, $n>($self.pop());
+        $self.store_bytes($module, $arg, lanes[*$l as usize].to_le_bytes())?;
+    }};
+}
+
+/// Replicates a scalar of type `$s` to all lanes.
+macro_rules! splat {
+    ($self:ident, $s:ty as [$ty:ty; $n:literal]) => {{
+        let a: $s = $self.pop();
+        $self.push(from_lanes([a as $ty; $n]));
+    }};
+}
+
+macro_rules! extract_lane {
+    ($self:ident, $l:ident, [$ty:ty; $n:literal] as $s:ty) => {{
+        let lanes = lanes::<$ty, $n>($self.pop());
+        $self.push(lanes[*$l as usize] as $s);
+    }};
+}
+
+macro_rules! replace_lane {
+    ($self:ident, $l:ident, $s:ty as [$ty:ty; $n:literal]) => {{
+        let a: $s = $self.pop();
+        let mut lanes = lanes::<$ty, $n>($self.pop());
+        lanes[*$l as usize] = a as $ty;
+        $self.push(from_lanes(lanes));
+    }};
+}
+
+impl Interpreter<'_> {
+    /// [Reference](https://webassembly.github.io/spec/core/exec/instructions.html#vector-instructions)
+    pub(super) fn exec_vector(
+        &mut self,
+        module: ModuleAddr,
+        instr: &VectorInstruction,
+    ) -> Result<(), Trap> {
+        use VectorInstruction::*;
+        match instr {
+            V128Load(m) => {
+                let bytes = self.load::<16>(module, m)?;
+                self.push(u128::from_le_bytes(bytes));
+            }
+            V128Load8x8S(m) => load_extend!(self, module, m, [i8; 8] as i16),
+            V128Load8x8U(m) => load_extend!(self, module, m, [u8; 8] as u16),
+            V128Load16x4S(m) => load_extend!(self, module, m, [i16; 4] as i32),
+            V128Load16x4U(m) => load_extend!(self, module, m, [u16; 4] as u32),
+            V128Load32x2S(m) => load_extend!(self, module, m, [i32; 2] as i64),
+            V128Load32x2U(m) => load_extend!(self, module, m, [u32; 2] as u64),
+            V128Load8Splat(m) => load_splat!(self, module, m, [u8; 16]),
+            V128Load16Splat(m) => load_splat!(self, module, m, [u16; 8]),
+            V128Load32Splat(m) => load_splat!(self, module, m, [u32; 4]),
+            V128Load64Splat(m) => load_splat!(self, module, m, [u64; 2]),
+            V128Store(m) => {
+                let v: u128 = self.pop();
+                self.store_bytes(module, m, v.to_le_bytes())?;
+            }
+            V128Load32Zero(m) => {
+                let bytes = self.load::<4>(module, m)?;
+                self.push(u32::from_le_bytes(bytes) as u128);
+            }
+            V128Load64Zero(m) => {
+                let bytes = self.load::<8>(module, m)?;
+                self.push(u64::from_le_bytes(bytes) as u128);
+            }
+            V128Load8Lane(m, l) => load_lane!(self, module, m, l, [u8; 16]),
+            V128Load16Lane(m, l) => load_lane!(self, module, m, l, [u16; 8]),
+            V128Load32Lane(m, l) => load_lane!(self, module, m, l, [u32; 4]),
+            V128Load64Lane(m, l) => load_lane!(self, module, m, l, [u64; 2]),
+            V128Store8Lane(m, l) => store_lane!(self, module, m, l, [u8; 16]),
+            V128Store16Lane(m, l) => store_lane!(self, module, m, l, [u16; 8]),
+            V128Store32Lane(m, l) => store_lane!(self, module, m, l, [u32; 4]),
+            V128Store64Lane(m, l) => store_lane!(self, module, m, l, [u64; 2]),
+
+            V128Const(v) => self.push(*v),
+            I8x16Shuffle(s) => reshape!(self, [u8; 16] -> 16, i, a, b => {
+                let l = s[i] as usize;
+                if l < 16 { a[l] } else { b[l - 16] }
+            }),
+            I8x16Swizzle => reshape!(self, [u8; 16] -> 16, i, a, b => {
+                a.get(b[i] as usize).copied().unwrap_or(0)
+            }),
+            I8x16Splat => splat!(self, i32 as [i8; 16]),
+            I16x8Splat => splat!(self, i32 as [i16; 8]),
+            I32x4Splat => splat!(self, i32 as [i32; 4]),
+            I64x2Splat => splat!(self, i64 as [i64; 2]),
+            F32x4Splat => splat!(self, f32 as [f32; 4]),
+            F64x2Splat => splat!(self, f64 as [f64; 2]),
+            I8x16ExtractLaneS(l) => extract_lane!(self, l, [i8; 16] as i32),
+            I8x16ExtractLaneU(l) => extract_lane!(self, l, [u8; 16] as i32),
+            I16x8ExtractLaneS(l) => extract_lane!(self, l, [i16; 8] as i32),
+            I16x8ExtractLaneU(l) => extract_lane!(self, l, [u16; 8] as i32),
+            I32x4ExtractLane(l) => extract_lane!(self, l, [i32; 4] as i32),
+            I64x2ExtractLane(l) => extract_lane!(self, l, [i64; 2] as i64),
+            F32x4ExtractLane(l) => extract_lane!(self, l, [f32; 4] as f32),
+            F64x2ExtractLane(l) => extract_lane!(self, l, [f64; 2] as f64),
+            I8x16ReplaceLane(l) => replace_lane!(self, l, i32 as [i8; 16]),
+            I16x8ReplaceLane(l) => replace_lane!(self, l, i32 as [i16; 8]),
+            I32x4ReplaceLane(l) => replace_lane!(self, l, i32 as [i32; 4]),
+            I64x2ReplaceLane(l) => replace_lane!(self, l, i64 as [i64; 2]),
+            F32x4ReplaceLane(l) => replace_lane!(self, l, f32 as [f32; 4]),
+            F64x2ReplaceLane(l) => replace_lane!(self, l, f64 as [f64; 2]),
+
+            I8x16Eq => compare!(self, [i8; 16] -> i8, a, b => a == b),
+            I8x16Ne => compare!(self, [i8; 16] -> i8, a, b => a != b),
+            I8x16LtS => compare!(self, [i8; 16] -> i8, a, b => a < b),
+            I8x16LtU => compare!(self, [u8; 16] -> i8, a, b => a < b),
+            I8x16GtS => compare!(self, [i8; 16] -> i8, a, b => a > b),
+            I8x16GtU => compare!(self, [u8; 16] -> i8, a, b => a > b),
+            I8x16LeS => compare!(self, [i8; 16] -> i8, a, b => a <= b),
+            I8x16LeU => compare!(self, [u8; 16] -> i8, a, b => a <= b),
+            I8x16GeS => compare!(self, [i8; 16] -> i8, a, b => a >= b),
+            I8x16GeU => compare!(self, [u8; 16] -> i8, a, b => a >= b),
+            I16x8Eq => compare!(self, [i16; 8] -> i16, a, b => a == b),
+            I16x8Ne => compare!(self, [i16; 8] -> i16, a, b => a != b),
+            I16x8LtS => compare!(self, [i16; 8] -> i16, a, b => a < b),
+            I16x8LtU => compare!(self, [u16; 8] -> i16, a, b => a < b),
+            I16x8GtS => compare!(self, [i16; 8] -> i16, a, b => a > b),
+            I16x8GtU => compare!(self, [u16; 8] -> i16, a, b => a > b),
+            I16x8LeS => compare!(self, [i16; 8] -> i16, a, b => a <= b),
+            I16x8LeU => compare!(self, [u16; 8] -> i16, a, b => a <= b),
+            I16x8GeS => compare!(self, [i16; 8] -> i16, a, b => a >= b),
+            I16x8GeU => compare!(self, [u16; 8] -> i16, a, b => a >= b),
+            I32x4Eq => compare!(self, [i32; 4] -> i32, a, b => a == b),
+            I32x4Ne => compare!(self, [i32; 4] -> i32, a, b => a != b),
+            I32x4LtS => compare!(self, [i32; 4] -> i32, a, b => a < b),
+            I32x4LtU => compare!(self, [u32; 4] -> i32, a, b => a < b),
+            I32x4GtS => compare!(self, [i32; 4] -> i32, a, b => a > b),
+            I32x4GtU => compare!(self, [u32; 4] -> i32, a, b => a > b),
+            I32x4LeS => compare!(self, [i32; 4] -> i32, a, b => a <= b),
+            I32x4LeU => compare!(self, [u32; 4] -> i32, a, b => a <= b),
+            I32x4GeS => compare!(self, [i32; 4] -> i32, a, b => a >= b),
+            I32x4GeU => compare!(self, [u32; 4] -> i32, a, b => a >= b),
+            I64x2Eq => compare!(self, [i64; 2] -> i64, a, b => a == b),
+            I64x2Ne => compare!(self, [i64; 2] -> i64, a, b => a != b),
+            I64x2LtS => compare!(self, [i64; 2] -> i64, a, b => a < b),
+            I64x2GtS => compare!(self, [i64; 2] -> i64, a, b => a > b),
+            I64x2LeS => compare!(self, [i64; 2] -> i64, a, b => a <= b),
+            I64x2GeS => compare!(self, [i64; 2] -> i64, a, b => a >= b),
+            F32x4Eq => compare!(self, [f32; 4] -> i32, a, b => a == b),
+            F32x4Ne => compare!(self, [f32; 4] -> i32, a, b => a != b),
+            F32x4Lt => compare!(self, [f32; 4] -> i32, a, b => a < b),
+            F32x4Gt => compare!(self, [f32; 4] -> i32, a, b => a > b),
+            F32x4Le => compare!(self, [f32; 4] -> i32, a, b => a <= b),
+            F32x4Ge => compare!(self, [f32; 4] -> i32, a, b => a >= b),
+            F64x2Eq => compare!(self, [f64; 2] -> i64, a, b => a == b),
+            F64x2Ne => compare!(self, [f64; 2] -> i64, a, b => a != b),
+            F64x2Lt => compare!(self, [f64; 2] -> i64, a, b => a < b),
+            F64x2Gt => compare!(self, [f64; 2] -> i64, a, b => a > b),
+            F64x2Le => compare!(self, [f64; 2] -> i64, a, b => a <= b),
+            F64x2Ge => compare!(self, [f64; 2] -> i64, a, b => a >= b),
+
+            V128Not => {
+                let a: u128 = self.pop();
+                self.push(!a);
+            }
+            V128And => lanewise!(self, [u64; 2], a, b => a & b),
+            V128Andnot => lanewise!(self, [u64; 2], a, b => a & !b),
+            V128Or => lanewise!(self, [u64; 2], a, b => a | b),
+            V128Xor => lanewise!(self, [u64; 2], a, b => a ^ b),
+            V128Bitselect => {
+                let c: u128 = self.pop();
+                let b: u128 = self.pop();
+                let a: u128 = self.pop();
+                self.push((a & c) | (b & !c));
+            }
+            V128AnyTrue => {
+                let a: u128 = self.pop();
+                self.push(a != 0);
+            }
+
+            I8x16Abs => lanewise!(self, [i8; 16], a => a.wrapping_abs()),
+            I8x16Neg => lanewise!(self, [i8; 16], a => a.wrapping_neg()),
+            I8x16Popcnt => lanewise!(self, [u8; 16], a => a.count_ones() as u8),
+            I8x16AllTrue => {
+                let a: u128 = self.pop();
+                self.push(all_true::<u8, 16>(a));
+            }
+            I8x16Bitmask => {
+                let a: u128 = self.pop();
+                self.push(bitmask::<i8, 16>(a));
+            }
+            I8x16NarrowI16x8S => reshape!(self, [i16; 8] -> 16, i, a, b => {
+                let l = if i < 8 { a[i] } else { b[i - 8] };
+                l.clamp(i8::MIN as i16, i8::MAX as i16) as i8
+            }),
+            I8x16NarrowI16x8U => reshape!(self, [i16; 8] -> 16, i, a, b => {
+                let l = if i < 8 { a[i] } else { b[i - 8] };
+                l.clamp(0, u8::MAX as i16) as u8
+            }),
+            I8x16Shl => shift!(self, [i8; 16], a, s => a.wrapping_shl(s)),
+            I8x16ShrS => shift!(self, [i8; 16], a, s => a.wrapping_shr(s)),
+            I8x16ShrU => shift!(self, [u8; 16], a, s => a.wrapping_shr(s)),
+            I8x16Add => lanewise!(self, [i8; 16], a, b => a.wrapping_add(b)),
+            I8x16AddSatS => lanewise!(self, [i8; 16], a, b => a.saturating_add(b)),
+            I8x16AddSatU => lanewise!(self, [u8; 16], a, b => a.saturating_add(b)),
+            I8x16Sub => lanewise!(self, [i8; 16], a, b => a.wrapping_sub(b)),
+            I8x16SubSatS => lanewise!(self, [i8; 16], a, b => a.saturating_sub(b)),
+            I8x16SubSatU => lanewise!(self, [u8; 16], a, b => a.saturating_sub(b)),
+            I8x16MinS => lanewise!(self, [i8; 16], a, b => a.min(b)),
+            I8x16MinU => lanewise!(self, [u8; 16], a, b => a.min(b)),
+            I8x16MaxS => lanewise!(self, [i8; 16], a, b => a.max(b)),
+            I8x16MaxU => lanewise!(self, [u8; 16], a, b => a.max(b)),
+            I8x16AvgrU => {
+                lanewise!(self, [u8; 16], a, b => ((a as u16 + b as u16).div_ceil(2)) as u8)
+            }
+
+            I16x8ExtaddPairwiseI8x16S => reshape!(self, [i8; 16] -> 8, i, a => {
+                a[2 * i] as i16 + a[2 * i + 1] as i16
+            }),
+            I16x8ExtaddPairwiseI8x16U => reshape!(self, [u8; 16] -> 8, i, a => {
+                a[2 * i] as u16 + a[2 * i + 1] as u16
+            }),
+            I32x4ExtaddPairwiseI16x8S => reshape!(self, [i16; 8] -> 4, i, a => {
+                a[2 * i] as i32 + a[2 * i + 1] as i32
+            }),
+            I32x4ExtaddPairwiseI16x8U => reshape!(self, [u16; 8] -> 4, i, a => {
+                a[2 * i] as u32 + a[2 * i + 1] as u32
+            }),
+
+            I16x8Abs => lanewise!(self, [i16; 8], a => a.wrapping_abs()),
+            I16x8Neg => lanewise!(self, [i16; 8], a => a.wrapping_neg()),
+            I16x8Q15mulrSatS => lanewise!(self, [i16; 8], a, b => {
+                ((a as i32 * b as i32 + 0x4000) >> 15).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+            }),
+            I16x8AllTrue => {
+                let a: u128 = self.pop();
+                self.push(all_true::<u16, 8>(a));
+            }
+            I16x8Bitmask => {
+                let a: u128 = self.pop();
+                self.push(bitmask::<i16, 8>(a));
+            }
+            I16x8NarrowI32x4S => reshape!(self, [i32; 4] -> 8, i, a, b => {
+                let l = if i < 4 { a[i] } else { b[i - 4] };
+                l.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+            }),
+            I16x8NarrowI32x4U => reshape!(self, [i32; 4] -> 8, i, a, b => {
+                let l = if i < 4 { a[i] } else { b[i - 4] };
+                l.clamp(0, u16::MAX as i32) as u16
+            }),
+            I16x8ExtendLowI8x16S => reshape!(self, [i8; 16] -> 8, i, a => a[i] as i16),
+            I16x8ExtendHighI8x16S => reshape!(self, [i8; 16] -> 8, i, a => a[i + 8] as i16),
+            I16x8ExtendLowI8x16U => reshape!(self, [u8; 16] -> 8, i, a => a[i] as u16),
+            I16x8ExtendHighI8x16U => reshape!(self, [u8; 16] -> 8, i, a => a[i + 8] as u16),
+            I16x8Shl => shift!(self, [i16; 8], a, s => a.wrapping_shl(s)),
+            I16x8ShrS => shift!(self, [i16; 8], a, s => a.wrapping_shr(s)),
+            I16x8ShrU => shift!(self, [u16; 8], a, s => a.wrapping_shr(s)),
+            I16x8Add => lanewise!(self, [i16; 8], a, b => a.wrapping_add(b)),
+            I16x8AddSatS => lanewise!(self, [i16; 8], a, b => a.saturating_add(b)),
+            I16x8AddSatU => lanewise!(self, [u16; 8], a, b => a.saturating_add(b)),
+            I16x8Sub => lanewise!(self, [i16; 8], a, b => a.wrapping_sub(b)),
+            I16x8SubSatS => lanewise!(self, [i16; 8], a, b => a.saturating_sub(b)),
+            I16x8SubSatU => lanewise!(self, [u16; 8], a, b => a.saturating_sub(b)),
+            I16x8Mul => lanewise!(self, [i16; 8], a, b => a.wrapping_mul(b)),
+            I16x8MinS => lanewise!(self, [i16; 8], a, b => a.min(b)),
+            I16x8MinU => lanewise!(self, [u16; 8], a, b => a.min(b)),
+            I16x8MaxS => lanewise!(self, [i16; 8], a, b => a.max(b)),
+            I16x8MaxU => lanewise!(self, [u16; 8], a, b => a.max(b)),
+            I16x8AvgrU => {
+                lanewise!(self, [u16; 8], a, b => ((a as u32 + b as u32).div_ceil(2)) as u16)
+            }
+            I16x8ExtmulLowI8x16S => {
+                reshape!(self, [i8; 16] -> 8, i, a, b => a[i] as i16 * b[i] as i16)
+            }
+            I16x8ExtmulHighI8x16S => reshape!(self, [i8; 16] -> 8, i, a, b => {
+                a[i + 8] as i16 * b[i + 8] as i16
+            }),
+            I16x8ExtmulLowI8x16U => {
+                reshape!(self, [u8; 16] -> 8, i, a, b => a[i] as u16 * b[i] as u16)
+            }
+            I16x8ExtmulHighI8x16U => reshape!(self, [u8; 16] -> 8, i, a, b => {
+                a[i + 8] as u16 * b[i + 8] as u16
+            }),
+
+            I32x4Abs => lanewise!(self, [i32; 4], a => a.wrapping_abs()),
+            I32x4Neg => lanewise!(self, [i32; 4], a => a.wrapping_neg()),
+            I32x4AllTrue => {
+                let a: u128 = self.pop();
+                self.push(all_true::<u32, 4>(a));
+            }
+            I32x4Bitmask => {
+                let a: u128 = self.pop();
+                self.push(bitmask::<i32, 4>(a));
+            }
+            I32x4ExtendLowI16x8S => reshape!(self, [i16; 8] -> 4, i, a => a[i] as i32),
+            I32x4ExtendHighI16x8S => reshape!(self, [i16; 8] -> 4, i, a => a[i + 4] as i32),
+            I32x4ExtendLowI16x8U => reshape!(self, [u16; 8] -> 4, i, a => a[i] as u32),
+            I32x4ExtendHighI16x8U => reshape!(self, [u16; 8] -> 4, i, a => a[i + 4] as u32),
+            I32x4Shl => shift!(self, [i32; 4], a, s => a.wrapping_shl(s)),
+            I32x4ShrS => shift!(self, [i32; 4], a, s => a.wrapping_shr(s)),
+            I32x4ShrU => shift!(self, [u32; 4], a, s => a.wrapping_shr(s)),
+            I32x4Add => lanewise!(self, [i32; 4], a, b => a.wrapping_add(b)),
+            I32x4Sub => lanewise!(self, [i32; 4], a, b => a.wrapping_sub(b)),
+            I32x4Mul => lanewise!(self, [i32; 4], a, b => a.wrapping_mul(b)),
+            I32x4MinS => lanewise!(self, [i32; 4], a, b => a.min(b)),
+            I32x4MinU => lanewise!(self, [u32; 4], a, b => a.min(b)),
+            I32x4MaxS => lanewise!(self, [i32; 4], a, b => a.max(b)),
+            I32x4MaxU => lanewise!(self, [u32; 4], a, b => a.max(b)),
+            I32x4DotI16x8S => reshape!(self, [i16; 8] -> 4, i, a, b => {
+                (a[2 * i] as i32 * b[2 * i] as i32)
+                    .wrapping_add(a[2 * i + 1] as i32 * b[2 * i + 1] as i32)
+            }),
+            I32x4ExtmulLowI16x8S => {
+                reshape!(self, [i16; 8] -> 4, i, a, b => a[i] as i32 * b[i] as i32)
+            }
+            I32x4ExtmulHighI16x8S => reshape!(self, [i16; 8] -> 4, i, a, b => {
+                a[i + 4] as i32 * b[i + 4] as i32
+            }),
+            I32x4ExtmulLowI16x8U => {
+                reshape!(self, [u16; 8] -> 4, i, a, b => a[i] as u32 * b[i] as u32)
+            }
+            I32x4ExtmulHighI16x8U => reshape!(self, [u16; 8] -> 4, i, a, b => {
+                a[i + 4] as u32 * b[i + 4] as u32
+            }),
+
+            I64x2Abs => lanewise!(self, [i64; 2], a => a.wrapping_abs()),
+            I64x2Neg => lanewise!(self, [i64; 2], a => a.wrapping_neg()),
+            I64x2AllTrue => {
+                let a: u128 = self.pop();
+                self.push(all_true::<u64, 2>(a));
+            }
+            I64x2Bitmask => {
+                let a: u128 = self.pop();
+                self.push(bitmask::<i64, 2>(a));
+            }
+            I64x2ExtendLowI32x4S => reshape!(self, [i32; 4] -> 2, i, a => a[i] as i64),
+            I64x2ExtendHighI32x4S => reshape!(self, [i32; 4] -> 2, i, a => a[i + 2] as i64),
+            I64x2ExtendLowI32x4U => reshape!(self, [u32; 4] -> 2, i, a => a[i] as u64),
+            I64x2ExtendHighI32x4U => reshape!(self, [u32; 4] -> 2, i, a => a[i + 2] as u64),
+            I64x2Shl => shift!(self, [i64; 2], a, s => a.wrapping_shl(s)),
+            I64x2ShrS => shift!(self, [i64; 2], a, s => a.wrapping_shr(s)),
+            I64x2ShrU => shift!(self, [u64; 2], a, s => a.wrapping_shr(s)),
+            I64x2Add => lanewise!(self, [i64; 2], a, b => a.wrapping_add(b)),
+            I64x2Sub => lanewise!(self, [i64; 2], a, b => a.wrapping_sub(b)),
+            I64x2Mul => lanewise!(self, [i64; 2], a, b => a.wrapping_mul(b)),
+            I64x2ExtmulLowI32x4S => {
+                reshape!(self, [i32; 4] -> 2, i, a, b => a[i] as i64 * b[i] as i64)
+            }
+            I64x2ExtmulHighI32x4S => reshape!(self, [i32; 4] -> 2, i, a, b => {
+                a[i + 2] as i64 * b[i + 2] as i64
+            }),
+            I64x2ExtmulLowI32x4U => {
+                reshape!(self, [u32; 4] -> 2, i, a, b => a[i] as u64 * b[i] as u64)
+            }
+            I64x2ExtmulHighI32x4U => reshape!(self, [u32; 4] -> 2, i, a, b => {
+                a[i + 2] as u64 * b[i + 2] as u64
+            }),
+
+            F32x4Abs => lanewise!(self, [u32; 4], a => a & !(1 << 31)),
+            F32x4Neg => lanewise!(self, [u32; 4], a => a ^ (1 << 31)),
+            F32x4Ceil => lanewise!(self, [f32; 4], a => round(a, Float::ceil)),
+            F32x4Floor => lanewise!(self, [f32; 4], a => round(a, Float::floor)),
+            F32x4Trunc => lanewise!(self, [f32; 4], a => round(a, Float::trunc)),
+            F32x4Nearest => lanewise!(self, [f32; 4], a => nearest(a)),
+            F32x4Sqrt => lanewise!(self, [f32; 4], a => Float::sqrt(a)),
+            F32x4Add => lanewise!(self, [f32; 4], a, b => a + b),
+            F32x4Sub => lanewise!(self, [f32; 4], a, b => a - b),
+            F32x4Mul => lanewise!(self, [f32; 4], a, b => a * b),
+            F32x4Div => lanewise!(self, [f32; 4], a, b => a / b),
+            F32x4Min => lanewise!(self, [f32; 4], a, b => fmin(a, b)),
+            F32x4Max => lanewise!(self, [f32; 4], a, b => fmax(a, b)),
+            F32x4Pmin => lanewise!(self, [f32; 4], a, b => if b < a { b } else { a }),
+            F32x4Pmax => lanewise!(self, [f32; 4], a, b => if a < b { b } else { a }),
+
+            F64x2Abs => lanewise!(self, [u64; 2], a => a & !(1 << 63)),
+            F64x2Neg => lanewise!(self, [u64; 2], a => a ^ (1 << 63)),
+            F64x2Ceil => lanewise!(self, [f64; 2], a => round(a, Float::ceil)),
+            F64x2Floor => lanewise!(self, [f64; 2], a => round(a, Float::floor)),
+            F64x2Trunc => lanewise!(self, [f64; 2], a => round(a, Float::trunc)),
+            F64x2Nearest => lanewise!(self, [f64; 2], a => nearest(a)),
+            F64x2Sqrt => lanewise!(self, [f64; 2], a => Float::sqrt(a)),
+            F64x2Add => lanewise!(self, [f64; 2], a, b => a + b),
+            F64x2Sub => lanewise!(self, [f64; 2], a, b => a - b),
+            F64x2Mul => lanewise!(self, [f64; 2], a, b => a * b),
+            F64x2Div => lanewise!(self, [f64; 2], a, b => a / b),
+            F64x2Min => lanewise!(self, [f64; 2], a, b => fmin(a, b)),
+            F64x2Max => lanewise!(self, [f64; 2], a, b => fmax(a, b)),
+            F64x2Pmin => lanewise!(self, [f64; 2], a, b => if b < a { b } else { a }),
+            F64x2Pmax => lanewise!(self, [f64; 2], a, b => if a < b { b } else { a }),
+
+            I32x4TruncSatF32x4S => lanewise!(self, [f32; 4], a => a as i32),
+            I32x4TruncSatF32x4U => lanewise!(self, [f32; 4], a => a as u32),
+            F32x4ConvertI32x4S => lanewise!(self, [i32; 4], a => a as f32),
+            F32x4ConvertI32x4U => lanewise!(self, [u32; 4], a => a as f32),
+            I32x4TruncSatF64x2SZero => reshape!(self, [f64; 2] -> 4, i, a => {
+                if i < 2 { a[i] as i32 } else { 0 }
+            }),
+            I32x4TruncSatF64x2UZero => reshape!(self, [f64; 2] -> 4, i, a => {
+                if i < 2 { a[i] as u32 } else { 0 }
+            }),
+            F64x2ConvertLowI32x4S => reshape!(self, [i32; 4] -> 2, i, a => a[i] as f64),
+            F64x2ConvertLowI32x4U => reshape!(self, [u32; 4] -> 2, i, a => a[i] as f64),
+            F32x4DemoteF64x2Zero => reshape!(self, [f64; 2] -> 4, i, a => {
+                if i < 2 { a[i] as f32 } else { 0.0 }
+            }),
+            F64x2PromoteLowF32x4 => reshape!(self, [f32; 4] -> 2, i, a => a[i] as f64),
+        }
+        Ok(())
+    }
+}
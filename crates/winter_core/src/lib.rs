@@ -1,6 +1,12 @@
 #![cfg_attr(not(test), no_std)]
-#![feature(const_option)]
 
 extern crate no_std_compat as std;
 
+pub mod encode;
+pub mod exec;
 pub mod parse;
+pub mod print;
+pub mod text;
+pub mod validate;
+#[cfg(feature = "wast")]
+pub mod wast;
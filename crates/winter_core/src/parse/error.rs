@@ -0,0 +1,475 @@
+use core::fmt;
+use std::vec::Vec;
+
+use nom::{
+    error::{ContextError, ErrorKind, FromExternalError, ParseError as NomParseError},
+    Offset,
+};
+
+/// A step of an [`ErrorTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceKind {
+    /// A nom combinator failed.
+    Nom(ErrorKind),
+    /// An expected character was not found.
+    Char(char),
+    /// A parser wrapped in `context` failed.
+    Context(&'static str),
+    /// The entry of a section at this index failed to parse.
+    Entry(u32),
+}
+
+/// The error of the binary parsers. Like nom's `VerboseError`, it records
+/// the input at each step of a failure, from the innermost one; it can be
+/// turned into a [`ParseError`] that locates the failure in the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorTrace<'a> {
+    pub errors: Vec<(&'a [u8], TraceKind)>,
+}
+
+impl<'a> ErrorTrace<'a> {
+    /// Records that the entry at `index`, starting at `input`, failed to
+    /// parse.
+    pub fn entry(mut self, input: &'a [u8], index: u32) -> Self {
+        self.errors.push((input, TraceKind::Entry(index)));
+        self
+    }
+
+    /// Returns a failure of kind `kind` at the start of `input` in the
+    /// contexts `labels`, outermost first.
+    pub(crate) fn with_context(input: &'a [u8], kind: ErrorKind, labels: &[&'static str]) -> Self {
+        labels
+            .iter()
+            .rev()
+            .fold(Self::from_error_kind(input, kind), |e, label| {
+                Self::add_context(input, label, e)
+            })
+    }
+
+    /// Turns `err` into a failure at the end of `input` in the contexts
+    /// `labels` if the parser applied to `input` needed more bytes. The
+    /// decoders of integers return [`nom::Err::Incomplete`] when their input
+    /// ends, and callers that know `input` to be complete report where it
+    /// ends.
+    pub(crate) fn complete(
+        input: &'a [u8],
+        labels: &[&'static str],
+        err: nom::Err<Self>,
+    ) -> nom::Err<Self> {
+        match err {
+            nom::Err::Incomplete(_) => nom::Err::Error(Self::with_context(
+                &input[input.len()..],
+                ErrorKind::Eof,
+                labels,
+            )),
+            err => err,
+        }
+    }
+}
+
+impl<'a> NomParseError<&'a [u8]> for ErrorTrace<'a> {
+    fn from_error_kind(input: &'a [u8], kind: ErrorKind) -> Self {
+        ErrorTrace {
+            errors: [(input, TraceKind::Nom(kind))].into_iter().collect(),
+        }
+    }
+
+    fn append(input: &'a [u8], kind: ErrorKind, mut other: Self) -> Self {
+        other.errors.push((input, TraceKind::Nom(kind)));
+        other
+    }
+
+    fn from_char(input: &'a [u8], c: char) -> Self {
+        ErrorTrace {
+            errors: [(input, TraceKind::Char(c))].into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> ContextError<&'a [u8]> for ErrorTrace<'a> {
+    fn add_context(input: &'a [u8], ctx: &'static str, mut other: Self) -> Self {
+        other.errors.push((input, TraceKind::Context(ctx)));
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<&'a [u8], E> for ErrorTrace<'a> {
+    fn from_external_error(input: &'a [u8], kind: ErrorKind, _e: E) -> Self {
+        Self::from_error_kind(input, kind)
+    }
+}
+
+/// Context labels of the section parsers, with the ids of the sections.
+pub(crate) const SECTIONS: [(&str, u8); 13] = [
+    ("custom_section", 0),
+    ("type_section", 1),
+    ("import_section", 2),
+    ("func_section", 3),
+    ("table_section", 4),
+    ("memory_section", 5),
+    ("global_section", 6),
+    ("export_section", 7),
+    ("start_section", 8),
+    ("element_section", 9),
+    ("code_section", 10),
+    ("data_section", 11),
+    ("data_count", 12),
+];
+
+/// The category of a failure to decode a binary module, as named by the
+/// messages of the `assert_malformed` assertions of the specification test
+/// suite.
+///
+/// [Reference](https://github.com/WebAssembly/spec/tree/main/interpreter#errors)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MalformedKind {
+    /// The binary does not start with `\0asm`.
+    MagicHeaderNotDetected,
+    /// The version of the binary format is not 1.
+    UnknownBinaryVersion,
+    /// The binary ends in the middle of a construct.
+    UnexpectedEnd,
+    /// A section or a function body ends in the middle of a construct.
+    UnexpectedEndOfSection,
+    /// An LEB128 integer has bits set beyond the width of its type.
+    IntegerTooLarge,
+    /// An LEB128 integer is encoded with more bytes than its type allows.
+    IntegerRepresentationTooLong,
+    /// A name is not valid UTF-8.
+    MalformedUtf8,
+    /// A section has an unknown id.
+    MalformedSectionId,
+    /// The content of a section does not match its size.
+    SectionSizeMismatch,
+    /// Bytes follow the last section, or a section is out of order.
+    JunkAfterLastSection,
+    /// The function and code sections have a different number of entries.
+    InconsistentFunctionCount,
+    /// The data count and data sections have a different number of entries.
+    InconsistentDataCount,
+    /// Structured instructions are nested deeper than the parser allows.
+    NestingTooDeep,
+    /// A byte reserved for the index of a memory is not 0x00.
+    ZeroFlagExpected,
+    /// The mutability of a global type is neither 0x00 nor 0x01.
+    MalformedMutability,
+    /// A function declares more than `u32::MAX` locals.
+    TooManyLocals,
+    /// A function uses `memory.init` or `data.drop` in a module without a
+    /// data count section.
+    DataCountRequired,
+    /// Any other failure.
+    Other,
+}
+
+impl MalformedKind {
+    /// Returns the message of the specification test suite for failures of
+    /// this kind.
+    pub fn message(self) -> &'static str {
+        match self {
+            MalformedKind::MagicHeaderNotDetected => "magic header not detected",
+            MalformedKind::UnknownBinaryVersion => "unknown binary version",
+            MalformedKind::UnexpectedEnd => "unexpected end",
+            MalformedKind::UnexpectedEndOfSection => "unexpected end of section or function",
+            MalformedKind::IntegerTooLarge => "integer too large",
+            MalformedKind::IntegerRepresentationTooLong => "integer representation too long",
+            MalformedKind::MalformedUtf8 => "malformed UTF-8 encoding",
+            MalformedKind::MalformedSectionId => "malformed section id",
+            MalformedKind::SectionSizeMismatch => "section size mismatch",
+            MalformedKind::JunkAfterLastSection => "junk after last section",
+            MalformedKind::InconsistentFunctionCount => {
+                "function and code section have inconsistent lengths"
+            }
+            MalformedKind::InconsistentDataCount => {
+                "data count and data section have inconsistent lengths"
+            }
+            MalformedKind::NestingTooDeep => "nesting too deep",
+            MalformedKind::ZeroFlagExpected => "zero flag expected",
+            MalformedKind::MalformedMutability => "malformed mutability",
+            MalformedKind::TooManyLocals => "too many locals",
+            MalformedKind::DataCountRequired => "data count section required",
+            MalformedKind::Other => "malformed",
+        }
+    }
+
+    /// Returns the kind of the failures of parsers wrapped in the context
+    /// `label`. Parsers that detect a kind of failure use its message as
+    /// label, and the header parsers use `magic` and `version`.
+    fn from_context(label: &str) -> Option<Self> {
+        const KINDS: [MalformedKind; 13] = [
+            MalformedKind::IntegerTooLarge,
+            MalformedKind::IntegerRepresentationTooLong,
+            MalformedKind::MalformedUtf8,
+            MalformedKind::MalformedSectionId,
+            MalformedKind::SectionSizeMismatch,
+            MalformedKind::JunkAfterLastSection,
+            MalformedKind::InconsistentFunctionCount,
+            MalformedKind::InconsistentDataCount,
+            MalformedKind::NestingTooDeep,
+            MalformedKind::ZeroFlagExpected,
+            MalformedKind::MalformedMutability,
+            MalformedKind::TooManyLocals,
+            MalformedKind::DataCountRequired,
+        ];
+        match label {
+            "magic" => Some(MalformedKind::MagicHeaderNotDetected),
+            "version" => Some(MalformedKind::UnknownBinaryVersion),
+            _ => KINDS.into_iter().find(|kind| kind.message() == label),
+        }
+    }
+}
+
+impl fmt::Display for MalformedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Number of bytes of the binary shown on each side of a failure.
+const EXCERPT_RADIUS: usize = 8;
+
+/// Error returned when a binary module is malformed.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/index.html)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Offset of the failure from the start of the binary.
+    pub offset: usize,
+    /// Id of the section in which the failure occurred.
+    pub section: Option<u8>,
+    /// Index of the failing entry in the vector of the section.
+    pub entry: Option<u32>,
+    /// Context labels of the parsers that failed, outermost first.
+    pub context: Vec<&'static str>,
+    /// Kind of the innermost failure.
+    pub kind: ErrorKind,
+    /// Category of the failure.
+    pub malformed: MalformedKind,
+    /// Offset of the first byte of `excerpt`.
+    excerpt_offset: usize,
+    /// The bytes around the failure.
+    excerpt: Vec<u8>,
+}
+
+impl ParseError {
+    /// Locates the failure `err` of a parser applied to `input`, the whole
+    /// binary. A parser that needs more than `input` fails at its end.
+    pub fn new(input: &[u8], err: nom::Err<ErrorTrace>) -> Self {
+        let errors = match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => e.errors,
+            nom::Err::Incomplete(_) => Vec::new(),
+        };
+        let offset = errors
+            .first()
+            .map_or(input.len(), |(rest, _)| input.offset(rest));
+        let kind = match errors.first() {
+            Some((_, TraceKind::Nom(kind))) => *kind,
+            Some((_, TraceKind::Char(_))) => ErrorKind::Char,
+            _ => ErrorKind::Eof,
+        };
+        let mut section = None;
+        let mut entry = None;
+        let mut context = Vec::new();
+        for (_, kind) in errors.iter().rev() {
+            match *kind {
+                TraceKind::Context(label) => {
+                    section = section.or_else(|| {
+                        SECTIONS
+                            .iter()
+                            .find(|(l, _)| *l == label)
+                            .map(|(_, id)| *id)
+                    });
+                    context.push(label);
+                }
+                TraceKind::Entry(index) => entry = entry.or(Some(index)),
+                _ => {}
+            }
+        }
+        ParseError {
+            offset,
+            section,
+            entry,
+            context,
+            kind,
+            malformed: Self::classify(input, &errors),
+            excerpt_offset: 0,
+            excerpt: Vec::new(),
+        }
+        .with_excerpt(input)
+    }
+
+    /// Takes the bytes around the failure from `binary`, the bytes from the
+    /// offset 0.
+    pub(crate) fn with_excerpt(mut self, binary: &[u8]) -> Self {
+        let start = self.offset.saturating_sub(EXCERPT_RADIUS);
+        let end = binary.len().min(self.offset + EXCERPT_RADIUS);
+        self.excerpt_offset = start;
+        self.excerpt = binary[start..end].to_vec();
+        self
+    }
+
+    /// Moves the failure by `base` bytes, for a failure located in a part
+    /// of a binary that starts at the offset `base`.
+    pub(crate) fn offset_by(mut self, base: usize) -> Self {
+        self.offset += base;
+        self.excerpt_offset += base;
+        self
+    }
+
+    /// Returns the category of the failure traced by `errors` in `input`.
+    /// Failures on truncated input are unexpected ends of the section or
+    /// function that contains them, or else of the binary. Otherwise, the
+    /// innermost context with a known category gives it.
+    fn classify(input: &[u8], errors: &[(&[u8], TraceKind)]) -> MalformedKind {
+        let (rest, kind) = match errors.first() {
+            Some(error) => error,
+            None => return MalformedKind::UnexpectedEnd,
+        };
+        if rest.is_empty() || *kind == TraceKind::Nom(ErrorKind::Eof) {
+            let in_section = errors.iter().any(|(_, kind)| match *kind {
+                TraceKind::Context(label) => {
+                    label == "func" || SECTIONS.iter().any(|(l, _)| *l == label)
+                }
+                _ => false,
+            });
+            return match in_section || input.offset(rest) + rest.len() < input.len() {
+                true => MalformedKind::UnexpectedEndOfSection,
+                false => MalformedKind::UnexpectedEnd,
+            };
+        }
+        errors
+            .iter()
+            .find_map(|(_, kind)| match *kind {
+                TraceKind::Context(label) => MalformedKind::from_context(label),
+                _ => None,
+            })
+            .unwrap_or(MalformedKind::Other)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self.malformed {
+            MalformedKind::Other => self.kind.description(),
+            malformed => malformed.message(),
+        };
+        write!(f, "{} at offset {:#x}", message, self.offset)?;
+        if let Some(section) = self.section {
+            write!(f, " in section {}", section)?;
+        }
+        if let Some(entry) = self.entry {
+            write!(f, ", entry {}", entry)?;
+        }
+        for (i, label) in self.context.iter().enumerate() {
+            let separator = if i == 0 { " (" } else { " > " };
+            write!(f, "{}{}", separator, label)?;
+        }
+        if !self.context.is_empty() {
+            write!(f, ")")?;
+        }
+        write!(f, ":")?;
+        for (i, byte) in self.excerpt.iter().enumerate() {
+            match self.excerpt_offset + i == self.offset {
+                true => write!(f, " [{:02x}]", byte)?,
+                false => write!(f, " {:02x}", byte)?,
+            }
+        }
+        if self.offset == self.excerpt_offset + self.excerpt.len() {
+            write!(f, " [end]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::modules::module::module_parser;
+
+    #[test]
+    fn test_parse_error() {
+        let binary = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x07, 0x02, // type section with two entries
+            0x60, 0x00, 0x00, // [] -> []
+            0x61, 0x00, 0x00, // malformed functype
+        ];
+        let error = module_parser(&binary).unwrap_err();
+        assert_eq!(error.offset, 14);
+        assert_eq!(error.section, Some(1));
+        assert_eq!(error.entry, Some(1));
+        assert_eq!(error.context, ["type_section", "functype"]);
+        assert_eq!(error.kind, ErrorKind::Tag);
+        assert_eq!(
+            error.to_string(),
+            "Tag at offset 0xe in section 1, entry 1 (type_section > functype): 00 00 01 07 02 60 \
+             00 00 [61] 00 00"
+        );
+
+        let error = module_parser(&binary[..9]).unwrap_err();
+        assert_eq!(error.offset, 9);
+        assert_eq!(error.section, Some(1));
+        assert_eq!(error.malformed, MalformedKind::UnexpectedEndOfSection);
+        assert!(error
+            .to_string()
+            .starts_with("unexpected end of section or function at offset 0x9 in section 1"));
+        assert!(error.to_string().ends_with("00 00 01 [end]"));
+    }
+
+    #[test]
+    fn test_malformed_kind() {
+        let malformed = |sections: &[u8]| {
+            let binary: Vec<u8> = [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00]
+                .into_iter()
+                .chain(sections.iter().copied())
+                .collect();
+            module_parser(&binary).unwrap_err().malformed
+        };
+        assert_eq!(
+            malformed(&[0x01, 0x06, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            MalformedKind::IntegerRepresentationTooLong
+        );
+        assert_eq!(
+            malformed(&[0x01, 0x05, 0x80, 0x80, 0x80, 0x80, 0x10]),
+            MalformedKind::IntegerTooLarge
+        );
+        assert_eq!(
+            malformed(&[0x02, 0x03, 0x01, 0x01, 0xFF]),
+            MalformedKind::MalformedUtf8
+        );
+        assert_eq!(
+            malformed(&[0x01, 0x01, 0x01, 0x00, 0x01, 0x00]),
+            MalformedKind::UnexpectedEndOfSection
+        );
+        assert_eq!(
+            malformed(&[0x01, 0x02, 0x00, 0x00]),
+            MalformedKind::SectionSizeMismatch
+        );
+        assert_eq!(malformed(&[0x0D, 0x00]), MalformedKind::MalformedSectionId);
+        assert_eq!(
+            malformed(&[0x06, 0x06, 0x01, 0x7F, 0x02, 0x41, 0x00, 0x0B]),
+            MalformedKind::MalformedMutability
+        );
+        assert_eq!(
+            malformed(&[0x01, 0x01, 0x00, 0x01, 0x01, 0x00]),
+            MalformedKind::JunkAfterLastSection
+        );
+        assert_eq!(
+            malformed(&[0x03, 0x01, 0x00, 0x01, 0x01, 0x00]),
+            MalformedKind::JunkAfterLastSection
+        );
+        assert_eq!(
+            malformed(&[0x03, 0x02, 0x01, 0x00]),
+            MalformedKind::InconsistentFunctionCount
+        );
+        assert_eq!(
+            malformed(&[0x0C, 0x01, 0x01]),
+            MalformedKind::InconsistentDataCount
+        );
+
+        let error = module_parser(&[0x00, 0x61, 0x73, 0x6E, 0x01, 0x00, 0x00, 0x00]).unwrap_err();
+        assert_eq!(error.malformed, MalformedKind::MagicHeaderNotDetected);
+        let error = module_parser(&[0x00, 0x61, 0x73, 0x6D, 0x02, 0x00, 0x00, 0x00]).unwrap_err();
+        assert_eq!(error.malformed, MalformedKind::UnknownBinaryVersion);
+    }
+}
@@ -3,20 +3,15 @@ use std::vec::Vec;
 use nom::{
     branch::alt,
     bytes::complete::tag,
-    combinator::map,
+    combinator::{map, verify},
     error::context,
-    multi::{length_count, many0},
-    sequence::{delimited, preceded, tuple},
 };
 
-use super::{instr_parser, Instr};
+use super::Instr;
 use crate::parse::{
-    modules::{
-        funcidx_parser, labelidx_parser, tableidx_parser, typeidx_parser, FuncIdx, LabelIdx,
-        TableIdx, TypeIdx,
-    },
+    modules::{FuncIdx, LabelIdx, TableIdx, TypeIdx},
     types::{valtype_parser, ValType},
-    values::{leb128_i33, vector_count_parser},
+    values::leb128_i33,
     Res,
 };
 
@@ -27,14 +22,16 @@ pub enum BlockType {
     TypeIndex(TypeIdx),
 }
 
-pub fn blocktype_parser(input: &[u8]) -> Res<BlockType> {
+pub fn blocktype_parser(input: &[u8]) -> Res<'_, BlockType> {
     context(
         "blocktype",
         alt((
             map(tag([0x40]), |_| BlockType::Empty),
             map(valtype_parser, BlockType::ValType),
-            map(leb128_i33, |t| BlockType::TypeIndex((t as u32).into())), /* TODO: check if this
-                                                                           * is correct */
+            // A type index is a positive signed 33-bit integer.
+            map(verify(leb128_i33, |t| *t >= 0), |t| {
+                BlockType::TypeIndex((t as u32).into())
+            }),
         )),
     )(input)
 }
@@ -78,107 +75,3 @@ pub enum ControlInstruction {
     /// the call is aborted with a trap if it does not match.
     CallIndirect(TypeIdx, TableIdx),
 }
-
-pub fn control_instr_parser(input: &[u8]) -> Res<ControlInstruction> {
-    context(
-        "conditional_instr",
-        alt((
-            context(
-                "unreachable",
-                map(tag([0x00]), |_| ControlInstruction::Unreachable),
-            ),
-            context("nop", map(tag([0x01]), |_| ControlInstruction::Nop)),
-            context(
-                "block",
-                map(
-                    delimited(
-                        tag([0x02]),
-                        tuple((blocktype_parser, many0(instr_parser))),
-                        tag([0x0B]),
-                    ),
-                    |(b, v)| ControlInstruction::Block(b, v),
-                ),
-            ),
-            context(
-                "loop",
-                map(
-                    delimited(
-                        tag([0x03]),
-                        tuple((blocktype_parser, many0(instr_parser))),
-                        tag([0x0B]),
-                    ),
-                    |(b, v)| ControlInstruction::Loop(b, v),
-                ),
-            ),
-            context(
-                "if_without_else",
-                map(
-                    delimited(
-                        tag([0x04]),
-                        tuple((blocktype_parser, many0(instr_parser))),
-                        tag([0x0B]),
-                    ),
-                    |(b, v)| ControlInstruction::If(b, v, Vec::with_capacity(0)),
-                ),
-            ),
-            context(
-                "if_with_else",
-                map(
-                    delimited(
-                        tag([0x04]),
-                        tuple((
-                            blocktype_parser,
-                            many0(instr_parser),
-                            tag([0x05]),
-                            many0(instr_parser),
-                        )),
-                        tag([0x0B]),
-                    ),
-                    |(b, v, _, e)| ControlInstruction::If(b, v, e),
-                ),
-            ),
-            context(
-                "br",
-                map(
-                    preceded(tag([0x0C]), labelidx_parser),
-                    ControlInstruction::Br,
-                ),
-            ),
-            context(
-                "br_if",
-                map(
-                    preceded(tag([0x0D]), labelidx_parser),
-                    ControlInstruction::BrIf,
-                ),
-            ),
-            context(
-                "br_table",
-                map(
-                    preceded(
-                        tag([0x0E]),
-                        tuple((
-                            length_count(vector_count_parser, labelidx_parser),
-                            labelidx_parser,
-                        )),
-                    ),
-                    |(v, d)| ControlInstruction::BrTable(v, d),
-                ),
-            ),
-            context("return", map(tag([0x0F]), |_| ControlInstruction::Return)),
-            context(
-                "call",
-                map(
-                    preceded(tag([0x10]), funcidx_parser),
-                    ControlInstruction::Call,
-                ),
-            ),
-            context(
-                "call_indirect",
-                map(
-                    preceded(tag([0x11]), tuple((typeidx_parser, tableidx_parser))),
-                    |(t, tbl)| ControlInstruction::CallIndirect(t, tbl),
-                ),
-            ),
-        )),
-    )(input)
-}
@@ -1,22 +1,250 @@
-use std::vec::Vec;
+use std::{boxed::Box, vec::Vec};
 
-use nom::{
-    bytes::complete::tag, combinator::map, error::context, multi::many0, sequence::terminated,
+use nom::{combinator::fail, error::context};
+
+use super::{
+    operator_parser, BlockType, ControlInstruction, Instr, MemoryInstruction, Operator,
+    ParametricInstruction,
 };
+use crate::parse::{Locate, Res, Span};
 
-use super::{instr_parser, Instr};
-use crate::parse::Res;
+/// The maximum number of structured instructions nested in one another in
+/// an expression, unless a parser is given another limit.
+pub const DEFAULT_MAX_NESTING_DEPTH: u32 = 1024;
 
 #[derive(Debug)]
 pub struct Expr {
     pub instr: Vec<Instr>,
+    /// The spans of the instructions, in the order in which they appear in
+    /// the binary: a structured instruction comes before the instructions
+    /// that it contains, and its span contains theirs. Empty if the
+    /// expression was not decoded from a binary.
+    pub spans: Vec<Span>,
+}
+
+impl Locate for Expr {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+    }
+}
+
+/// How a sequence of instructions parsed by [`instrs_parser`] ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Terminator {
+    Else,
+    End,
+}
+
+/// A sequence of instructions parsed by [`instrs_parser`].
+pub(super) struct Instrs {
+    pub instrs: Vec<Instr>,
+    pub spans: Vec<Span>,
+    pub terminator: Terminator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    Block,
+    Loop,
+    If,
+}
+
+/// A structured instruction whose instructions are being parsed.
+struct Frame {
+    kind: FrameKind,
+    block_type: BlockType,
+    /// The instructions that precede the structured instruction.
+    outer: Vec<Instr>,
+    /// The instructions of the first branch of an `if` with an `else`.
+    then: Option<Vec<Instr>>,
+    /// The index of the span of the structured instruction.
+    span: usize,
+}
+
+impl Frame {
+    /// Returns the structured instruction, made of `instrs`, the last of
+    /// its instructions.
+    fn instr(self, instrs: Vec<Instr>) -> ControlInstruction {
+        match (self.kind, self.then) {
+            (FrameKind::Block, _) => ControlInstruction::Block(self.block_type, instrs),
+            (FrameKind::Loop, _) => ControlInstruction::Loop(self.block_type, instrs),
+            (FrameKind::If, Some(then)) => ControlInstruction::If(self.block_type, then, instrs),
+            (FrameKind::If, None) => {
+                ControlInstruction::If(self.block_type, instrs, Vec::with_capacity(0))
+            }
+        }
+    }
+}
+
+/// Returns the instruction `operator`, which is not a structured
+/// instruction, `else` or `end`.
+fn plain_instr(operator: Operator) -> Instr {
+    match operator {
+        Operator::Unreachable => control(ControlInstruction::Unreachable),
+        Operator::Nop => control(ControlInstruction::Nop),
+        Operator::Br(l) => control(ControlInstruction::Br(l)),
+        Operator::BrIf(l) => control(ControlInstruction::BrIf(l)),
+        Operator::BrTable(labels, default) => control(ControlInstruction::BrTable(
+            labels.iter().collect(),
+            default,
+        )),
+        Operator::Return => control(ControlInstruction::Return),
+        Operator::Call(f) => control(ControlInstruction::Call(f)),
+        Operator::CallIndirect(t, tbl) => control(ControlInstruction::CallIndirect(t, tbl)),
+        Operator::Drop => Instr::Parametric(Box::new(ParametricInstruction::Drop)),
+        Operator::Select => Instr::Parametric(Box::new(ParametricInstruction::Select(None))),
+        Operator::SelectTyped(types) => Instr::Parametric(Box::new(ParametricInstruction::Select(
+            Some(types.iter().collect()),
+        ))),
+        Operator::Reference(i) => Instr::Reference(Box::new(i)),
+        Operator::Variable(i) => Instr::Variable(Box::new(i)),
+        Operator::Table(i) => Instr::Table(Box::new(i)),
+        Operator::Memory(i) => Instr::Memory(Box::new(i)),
+        Operator::Numeric(i) => Instr::Numeric(Box::new(i)),
+        Operator::Vector(i) => Instr::Vector(Box::new(i)),
+        Operator::Block(_)
+        | Operator::Loop(_)
+        | Operator::If(_)
+        | Operator::Else
+        | Operator::End => {
+            unreachable!("structured instructions are parsed by instrs_parser")
+        }
+    }
+}
+
+fn control(instr: ControlInstruction) -> Instr {
+    Instr::Control(Box::new(instr))
+}
+
+/// Parses a sequence of instructions up to the `else` or `end` that
+/// terminates it, and returns them with their spans and the terminator.
+///
+/// Structured instructions are parsed with a stack of the instructions
+/// that contain them rather than by recursion, so that deeply nested
+/// instructions cannot exhaust the native stack. At most `max_depth`
+/// structured instructions may be nested in the sequence, and `memory.init`
+/// and `data.drop` are only allowed if `data_count`, if the module has a
+/// data count section.
+pub(super) fn instrs_parser<'a>(
+    max_depth: u32,
+    data_count: bool,
+) -> impl FnMut(&'a [u8]) -> Res<'a, Instrs> {
+    move |input: &'a [u8]| {
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut instrs = Vec::new();
+        let mut spans = Vec::new();
+        let mut input = input;
+        loop {
+            let (rest, operator) = context("instr", operator_parser)(input)?;
+            let span = Span {
+                start: input.len(),
+                end: rest.len(),
+            };
+            if frames.len() as u32 >= max_depth
+                && matches!(
+                    operator,
+                    Operator::Block(_) | Operator::Loop(_) | Operator::If(_)
+                )
+            {
+                return context("nesting too deep", fail)(input);
+            }
+            if !data_count
+                && matches!(
+                    operator,
+                    Operator::Memory(
+                        MemoryInstruction::MemoryInit(..) | MemoryInstruction::DataDrop(_)
+                    )
+                )
+            {
+                return context("data count section required", fail)(input);
+            }
+            let mut open = |kind, block_type| {
+                frames.push(Frame {
+                    kind,
+                    block_type,
+                    outer: core::mem::take(&mut instrs),
+                    then: None,
+                    span: spans.len(),
+                });
+                spans.push(span);
+            };
+            match operator {
+                Operator::Block(block_type) => open(FrameKind::Block, block_type),
+                Operator::Loop(block_type) => open(FrameKind::Loop, block_type),
+                Operator::If(block_type) => open(FrameKind::If, block_type),
+                Operator::Else => match frames.last_mut() {
+                    None => {
+                        let terminator = Terminator::Else;
+                        return Ok((
+                            rest,
+                            Instrs {
+                                instrs,
+                                spans,
+                                terminator,
+                            },
+                        ));
+                    }
+                    Some(frame) if frame.kind == FrameKind::If && frame.then.is_none() => {
+                        frame.then = Some(core::mem::take(&mut instrs));
+                    }
+                    Some(_) => return context("else", fail)(input),
+                },
+                Operator::End => match frames.pop() {
+                    None => {
+                        let terminator = Terminator::End;
+                        return Ok((
+                            rest,
+                            Instrs {
+                                instrs,
+                                spans,
+                                terminator,
+                            },
+                        ));
+                    }
+                    Some(mut frame) => {
+                        spans[frame.span].end = span.end;
+                        let outer = core::mem::take(&mut frame.outer);
+                        let instr = frame.instr(core::mem::replace(&mut instrs, outer));
+                        instrs.push(control(instr));
+                    }
+                },
+                operator => {
+                    instrs.push(plain_instr(operator));
+                    spans.push(span);
+                }
+            }
+            input = rest;
+        }
+    }
+}
+
+/// Parses an expression, an instruction sequence terminated by `end`, in
+/// which at most `max_depth` structured instructions are nested. The
+/// expression of a function body may only use `memory.init` and `data.drop`
+/// if `data_count`, if the module has a data count section.
+pub(crate) fn nested_expr_parser<'a>(
+    max_depth: u32,
+    data_count: bool,
+) -> impl FnMut(&'a [u8]) -> Res<'a, Expr> {
+    let mut parser = instrs_parser(max_depth, data_count);
+    move |input: &'a [u8]| {
+        let (rest, instrs) = context("expr", &mut parser)(input)?;
+        match instrs.terminator {
+            Terminator::End => Ok((
+                rest,
+                Expr {
+                    instr: instrs.instrs,
+                    spans: instrs.spans,
+                },
+            )),
+            // The `else` is the last byte consumed.
+            Terminator::Else => {
+                context("expr", context("else", fail))(&input[input.len() - rest.len() - 1..])
+            }
+        }
+    }
 }
 
-pub fn expr_parser(input: &[u8]) -> Res<Expr> {
-    context(
-        "expr",
-        map(terminated(many0(instr_parser), tag([0x0B])), |instr| Expr {
-            instr,
-        }),
-    )(input)
+pub(crate) fn expr_parser(input: &[u8]) -> Res<'_, Expr> {
+    nested_expr_parser(DEFAULT_MAX_NESTING_DEPTH, true)(input)
 }
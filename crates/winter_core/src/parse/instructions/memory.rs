@@ -0,0 +1,188 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{cut, map},
+    error::context,
+    sequence::{preceded, tuple},
+};
+
+use super::prefixed_opcode;
+use crate::parse::{
+    modules::{dataidx_parser, DataIdx, MemIdx},
+    values::leb128_u32,
+    Res,
+};
+
+/// Immediate of memory access instructions. `offset` is added to the
+/// address operand to get the effective address and `align` is the
+/// alignment hint, expressed as the exponent of a power of 2.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemArg {
+    pub align: u32,
+    pub offset: u32,
+}
+
+/// Memory arguments are encoded as the alignment followed by the offset.
+pub fn memarg_parser(input: &[u8]) -> Res<'_, MemArg> {
+    context(
+        "memarg",
+        map(tuple((leb128_u32, leb128_u32)), |(align, offset)| MemArg {
+            align,
+            offset,
+        }),
+    )(input)
+}
+
+/// Instructions in this group are concerned with linear memory.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#memory-instructions)
+#[derive(Debug)]
+pub enum MemoryInstruction {
+    I32Load(MemArg),
+    I64Load(MemArg),
+    F32Load(MemArg),
+    F64Load(MemArg),
+    I32Load8S(MemArg),
+    I32Load8U(MemArg),
+    I32Load16S(MemArg),
+    I32Load16U(MemArg),
+    I64Load8S(MemArg),
+    I64Load8U(MemArg),
+    I64Load16S(MemArg),
+    I64Load16U(MemArg),
+    I64Load32S(MemArg),
+    I64Load32U(MemArg),
+
+    I32Store(MemArg),
+    I64Store(MemArg),
+    F32Store(MemArg),
+    F64Store(MemArg),
+    I32Store8(MemArg),
+    I32Store16(MemArg),
+    I64Store8(MemArg),
+    I64Store16(MemArg),
+    I64Store32(MemArg),
+
+    /// The memory.size instruction returns the current size of a memory,
+    /// in units of page size.
+    MemorySize(MemIdx),
+    /// The memory.grow instruction grows memory by a given delta and returns
+    /// the previous size, or -1 if enough memory cannot be allocated.
+    MemoryGrow(MemIdx),
+    /// The memory.init instruction copies data from a passive data segment
+    /// into a memory.
+    MemoryInit(DataIdx, MemIdx),
+    /// The data.drop instruction prevents further use of a passive data
+    /// segment.
+    DataDrop(DataIdx),
+    /// The memory.copy instruction copies data from a source memory region
+    /// (second operand) to a possibly overlapping destination region (first
+    /// operand).
+    MemoryCopy(MemIdx, MemIdx),
+    /// The memory.fill instruction sets all values in a region to a given
+    /// byte.
+    MemoryFill(MemIdx),
+}
+
+fn memarg_instr<'a>(
+    name: &'static str,
+    opcode: u8,
+    f: fn(MemArg) -> MemoryInstruction,
+) -> impl FnMut(&'a [u8]) -> Res<'a, MemoryInstruction> {
+    context(name, map(preceded(tag([opcode]), memarg_parser), f))
+}
+
+/// Memory instructions refer to the only memory of the module with a 0x00
+/// byte, reserved for the index of a memory. Any other byte is a failure
+/// rather than an error, so that it is not taken for another instruction.
+fn memory_zero_parser(input: &[u8]) -> Res<'_, MemIdx> {
+    cut(context(
+        "zero flag expected",
+        map(tag([0x00]), |_| MemIdx(0)),
+    ))(input)
+}
+
+pub fn memory_instr_parser(input: &[u8]) -> Res<'_, MemoryInstruction> {
+    context(
+        "memory_instr",
+        alt((
+            alt((
+                memarg_instr("i32.load", 0x28, MemoryInstruction::I32Load),
+                memarg_instr("i64.load", 0x29, MemoryInstruction::I64Load),
+                memarg_instr("f32.load", 0x2A, MemoryInstruction::F32Load),
+                memarg_instr("f64.load", 0x2B, MemoryInstruction::F64Load),
+                memarg_instr("i32.load8_s", 0x2C, MemoryInstruction::I32Load8S),
+                memarg_instr("i32.load8_u", 0x2D, MemoryInstruction::I32Load8U),
+                memarg_instr("i32.load16_s", 0x2E, MemoryInstruction::I32Load16S),
+                memarg_instr("i32.load16_u", 0x2F, MemoryInstruction::I32Load16U),
+                memarg_instr("i64.load8_s", 0x30, MemoryInstruction::I64Load8S),
+                memarg_instr("i64.load8_u", 0x31, MemoryInstruction::I64Load8U),
+                memarg_instr("i64.load16_s", 0x32, MemoryInstruction::I64Load16S),
+                memarg_instr("i64.load16_u", 0x33, MemoryInstruction::I64Load16U),
+                memarg_instr("i64.load32_s", 0x34, MemoryInstruction::I64Load32S),
+                memarg_instr("i64.load32_u", 0x35, MemoryInstruction::I64Load32U),
+            )),
+            alt((
+                memarg_instr("i32.store", 0x36, MemoryInstruction::I32Store),
+                memarg_instr("i64.store", 0x37, MemoryInstruction::I64Store),
+                memarg_instr("f32.store", 0x38, MemoryInstruction::F32Store),
+                memarg_instr("f64.store", 0x39, MemoryInstruction::F64Store),
+                memarg_instr("i32.store8", 0x3A, MemoryInstruction::I32Store8),
+                memarg_instr("i32.store16", 0x3B, MemoryInstruction::I32Store16),
+                memarg_instr("i64.store8", 0x3C, MemoryInstruction::I64Store8),
+                memarg_instr("i64.store16", 0x3D, MemoryInstruction::I64Store16),
+                memarg_instr("i64.store32", 0x3E, MemoryInstruction::I64Store32),
+            )),
+            context(
+                "memory.size",
+                map(
+                    preceded(tag([0x3F]), memory_zero_parser),
+                    MemoryInstruction::MemorySize,
+                ),
+            ),
+            context(
+                "memory.grow",
+                map(
+                    preceded(tag([0x40]), memory_zero_parser),
+                    MemoryInstruction::MemoryGrow,
+                ),
+            ),
+            context(
+                "memory.init",
+                map(
+                    preceded(
+                        prefixed_opcode(0xFC, 8),
+                        tuple((dataidx_parser, memory_zero_parser)),
+                    ),
+                    |(x, m)| MemoryInstruction::MemoryInit(x, m),
+                ),
+            ),
+            context(
+                "data.drop",
+                map(
+                    preceded(prefixed_opcode(0xFC, 9), dataidx_parser),
+                    MemoryInstruction::DataDrop,
+                ),
+            ),
+            context(
+                "memory.copy",
+                map(
+                    preceded(
+                        prefixed_opcode(0xFC, 10),
+                        tuple((memory_zero_parser, memory_zero_parser)),
+                    ),
+                    |(d, s)| MemoryInstruction::MemoryCopy(d, s),
+                ),
+            ),
+            context(
+                "memory.fill",
+                map(
+                    preceded(prefixed_opcode(0xFC, 11), memory_zero_parser),
+                    MemoryInstruction::MemoryFill,
+                ),
+            ),
+        )),
+    )(input)
+}
@@ -1,25 +1,271 @@
+/// Generates a decoder for the instructions of a group that take no
+/// immediates, mapping each opcode to its variant, and the encoder mapping
+/// each variant back to its opcode.
+macro_rules! impl_opcode_table {
+    ($ty:ident, $fn_name:ident($op_ty:ty), $rev_name:ident { $($opcode:literal => $variant:ident,)* }) => {
+        impl $ty {
+            fn $fn_name(opcode: $op_ty) -> Option<Self> {
+                match opcode {
+                    $($opcode => Some($ty::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub(crate) fn $rev_name(&self) -> Option<$op_ty> {
+                match self {
+                    $($ty::$variant => Some($opcode),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
 mod control;
 mod expr;
+mod memory;
+mod numeric;
+mod operators;
+mod parametric;
+mod reference;
+mod table;
+mod variable;
+mod vector;
 
 use std::boxed::Box;
 
 pub use control::*;
 pub use expr::*;
-use nom::{branch::alt, combinator::map, error::context};
+pub use memory::*;
+use nom::{bytes::complete::tag, combinator::verify, sequence::preceded};
+pub use numeric::*;
+pub use operators::*;
+pub use parametric::*;
+pub use reference::*;
+pub use table::*;
+pub use variable::*;
+pub use vector::*;
 
-use super::Res;
+use super::{values::leb128_u32, Res};
 
 #[derive(Debug)]
 pub enum Instr {
     Control(Box<ControlInstruction>),
+    Reference(Box<ReferenceInstruction>),
+    Parametric(Box<ParametricInstruction>),
+    Variable(Box<VariableInstruction>),
+    Table(Box<TableInstruction>),
+    Memory(Box<MemoryInstruction>),
+    Numeric(Box<NumericInstruction>),
+    Vector(Box<VectorInstruction>),
 }
 
-pub fn instr_parser(input: &[u8]) -> Res<Instr> {
-    context(
-        "instr",
-        alt((
-            map(control_instr_parser, |f| Instr::Control(Box::new(f))),
-            map(control_instr_parser, |f| Instr::Control(Box::new(f))),
-        )),
-    )(input)
+/// Parses the `u32` sub-opcode of an instruction whose opcode is made of a
+/// `prefix` byte followed by an LEB128-encoded sub-opcode.
+fn prefixed_opcode_parser<'a>(prefix: u8) -> impl FnMut(&'a [u8]) -> Res<'a, u32> {
+    preceded(tag([prefix]), leb128_u32)
+}
+
+/// Recognizes the prefixed opcode made of `prefix` and the sub-opcode
+/// `opcode`.
+fn prefixed_opcode<'a>(prefix: u8, opcode: u32) -> impl FnMut(&'a [u8]) -> Res<'a, u32> {
+    verify(prefixed_opcode_parser(prefix), move |op| *op == opcode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{
+        modules::{DataIdx, ElemIdx, LocalIdx, MemIdx, TableIdx, TypeIdx},
+        MalformedKind, ParseError,
+    };
+
+    #[test]
+    fn test_parametric_and_variable() {
+        // local.get 0, local.get 1, select (result i32), local.tee 2, drop, end
+        let input = [
+            0x20, 0x00, 0x20, 0x01, 0x1C, 0x01, 0x7F, 0x22, 0x02, 0x1A, 0x0B,
+        ];
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(expr.instr.len(), 5);
+
+        match &expr.instr[2] {
+            Instr::Parametric(p) => match &**p {
+                ParametricInstruction::Select(Some(t)) => assert_eq!(t.len(), 1),
+                other => panic!("unexpected instruction {:?}", other),
+            },
+            other => panic!("unexpected instruction {:?}", other),
+        }
+        assert!(matches!(
+            &expr.instr[3],
+            Instr::Variable(v) if matches!(**v, VariableInstruction::LocalTee(LocalIdx(2)))
+        ));
+        assert!(
+            matches!(&expr.instr[4], Instr::Parametric(p) if matches!(**p, ParametricInstruction::Drop))
+        );
+    }
+
+    #[test]
+    fn test_memory() {
+        // i64.store8 align=0 offset=16, memory.grow 0, end
+        let input = [0x3C, 0x00, 0x10, 0x40, 0x00, 0x0B];
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert!(matches!(
+            &expr.instr[0],
+            Instr::Memory(m) if matches!(**m, MemoryInstruction::I64Store8(MemArg { align: 0, offset: 16 }))
+        ));
+        assert!(matches!(
+            &expr.instr[1],
+            Instr::Memory(m) if matches!(**m, MemoryInstruction::MemoryGrow(MemIdx(0)))
+        ));
+
+        // memory.size with its reserved byte encoded in two bytes
+        let input = [0x3F, 0x80, 0x00, 0x0B];
+        let error = ParseError::new(&input, expr_parser(&input).unwrap_err());
+        assert_eq!(error.offset, 1);
+        assert_eq!(error.malformed, MalformedKind::ZeroFlagExpected);
+    }
+
+    #[test]
+    fn test_numeric() {
+        // i32.const -2, f32.const 1.5, f32.convert_i32_s, i64.extend32_s, end
+        let input = [0x41, 0x7E, 0x43, 0x00, 0x00, 0xC0, 0x3F, 0xB2, 0xC4, 0x0B];
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert!(matches!(
+            &expr.instr[0],
+            Instr::Numeric(n) if matches!(**n, NumericInstruction::I32Const(-2))
+        ));
+        assert!(matches!(
+            &expr.instr[1],
+            Instr::Numeric(n) if matches!(**n, NumericInstruction::F32Const(f) if f == 1.5)
+        ));
+        assert!(matches!(
+            &expr.instr[2],
+            Instr::Numeric(n) if matches!(**n, NumericInstruction::F32ConvertI32S)
+        ));
+        assert!(matches!(
+            &expr.instr[3],
+            Instr::Numeric(n) if matches!(**n, NumericInstruction::I64Extend32S)
+        ));
+
+        // 0x0B (end) is not a numeric instruction.
+        assert!(numeric_instr_parser(&[0x0B]).is_err());
+    }
+
+    #[test]
+    fn test_prefixed() {
+        // i64.trunc_sat_f64_u, memory.init 3 0, table.copy 1 2, elem.drop 4
+        // (with a padded sub-opcode), end
+        let input = [
+            0xFC, 0x07, 0xFC, 0x08, 0x03, 0x00, 0xFC, 0x0E, 0x01, 0x02, 0xFC, 0x8D, 0x00, 0x04,
+            0x0B,
+        ];
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert!(matches!(
+            &expr.instr[0],
+            Instr::Numeric(n) if matches!(**n, NumericInstruction::I64TruncSatF64U)
+        ));
+        assert!(matches!(
+            &expr.instr[1],
+            Instr::Memory(m) if matches!(**m, MemoryInstruction::MemoryInit(DataIdx(3), MemIdx(0)))
+        ));
+        assert!(matches!(
+            &expr.instr[2],
+            Instr::Table(t) if matches!(**t, TableInstruction::TableCopy(TableIdx(1), TableIdx(2)))
+        ));
+        assert!(matches!(
+            &expr.instr[3],
+            Instr::Table(t) if matches!(**t, TableInstruction::ElemDrop(ElemIdx(4)))
+        ));
+
+        // Unknown sub-opcode.
+        assert!(operator_parser(&[0xFC, 0x7F]).is_err());
+    }
+
+    #[test]
+    fn test_vector() {
+        let mut input = vec![0xFD, 0x0C];
+        input.extend(1u128.to_le_bytes());
+        // i32x4.extract_lane 3, v128.load32_lane align=2 offset=0 lane=1,
+        // f64x2.convert_low_i32x4_u (two-byte sub-opcode), end
+        input.extend([
+            0xFD, 0x1B, 0x03, 0xFD, 0x56, 0x02, 0x00, 0x01, 0xFD, 0xFF, 0x01, 0x0B,
+        ]);
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert!(matches!(
+            &expr.instr[0],
+            Instr::Vector(v) if matches!(**v, VectorInstruction::V128Const(1))
+        ));
+        assert!(matches!(
+            &expr.instr[1],
+            Instr::Vector(v) if matches!(**v, VectorInstruction::I32x4ExtractLane(3))
+        ));
+        assert!(matches!(
+            &expr.instr[2],
+            Instr::Vector(v) if matches!(**v, VectorInstruction::V128Load32Lane(MemArg { align: 2, offset: 0 }, 1))
+        ));
+        assert!(matches!(
+            &expr.instr[3],
+            Instr::Vector(v) if matches!(**v, VectorInstruction::F64x2ConvertLowI32x4U)
+        ));
+
+        // 154 is not an assigned opcode.
+        assert!(operator_parser(&[0xFD, 0x9A, 0x01]).is_err());
+    }
+
+    #[test]
+    fn test_nesting() {
+        // block, if (result i32), i32.const 1, else, i32.const 2, end, drop,
+        // end, end
+        let input = [
+            0x02, 0x40, 0x04, 0x7F, 0x41, 0x01, 0x05, 0x41, 0x02, 0x0B, 0x1A, 0x0B, 0x0B,
+        ];
+        let (rest, expr) = expr_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        match &expr.instr[0] {
+            Instr::Control(c) => match &**c {
+                ControlInstruction::Block(BlockType::Empty, instrs) => {
+                    assert_eq!(instrs.len(), 2);
+                    assert!(matches!(
+                        &instrs[0],
+                        Instr::Control(c) if matches!(&**c, ControlInstruction::If(_, t, e) if t.len() == 1 && e.len() == 1)
+                    ));
+                }
+                other => panic!("unexpected instruction {:?}", other),
+            },
+            other => panic!("unexpected instruction {:?}", other),
+        }
+        assert_eq!(expr.spans.len(), 5);
+
+        // `else` outside of an `if`.
+        assert!(expr_parser(&[0x02, 0x40, 0x05, 0x0B, 0x0B]).is_err());
+        assert!(expr_parser(&[0x05, 0x0B]).is_err());
+
+        // Block types are type indices or single bytes, never negative.
+        let (_, expr) = expr_parser(&[0x02, 0x80, 0x01, 0x0B, 0x0B]).unwrap();
+        assert!(matches!(
+            &expr.instr[0],
+            Instr::Control(c) if matches!(&**c, ControlInstruction::Block(BlockType::TypeIndex(TypeIdx(128)), _))
+        ));
+        let input = [0x02, 0x41, 0x0B, 0x0B];
+        let error = ParseError::new(&input, expr_parser(&input).unwrap_err());
+        assert_eq!(error.offset, 1);
+        assert_eq!(error.context, ["expr", "instr", "blocktype"]);
+
+        // Nesting is limited without recursion.
+        let depth = 100_000;
+        let mut input = [0x02, 0x40].repeat(depth);
+        input.extend([0x0B].repeat(depth + 1));
+        let error = nested_expr_parser(8, true)(&input).unwrap_err();
+        let error = ParseError::new(&input, error);
+        assert_eq!(error.offset, 16);
+        assert_eq!(error.malformed, MalformedKind::NestingTooDeep);
+        assert!(expr_parser(&input).is_err());
+        assert!(expr_parser(&input[depth * 2 - 2048..]).is_ok());
+    }
 }
@@ -0,0 +1,361 @@
+use core::{fmt, marker::PhantomData};
+
+use nom::{
+    bytes::complete::{tag, take},
+    combinator::{fail, map, peek},
+    error::context,
+    multi::fold_many_m_n,
+    sequence::{pair, preceded, tuple},
+};
+
+use super::{
+    blocktype_parser, memory_instr_parser, numeric_instr_parser, prefixed_opcode_parser,
+    reference_instr_parser, table_instr_parser, variable_instr_parser, vector_instr_parser,
+    BlockType, MemoryInstruction, NumericInstruction, ReferenceInstruction, TableInstruction,
+    VariableInstruction, VectorInstruction,
+};
+use crate::parse::{
+    modules::{
+        funcidx_parser, labelidx_parser, tableidx_parser, typeidx_parser, FuncIdx, LabelIdx,
+        TableIdx, TypeIdx,
+    },
+    types::{valtype_parser, ValType},
+    values::vector_count_parser,
+    ParseError, Res,
+};
+
+/// A vector immediate of an [`Operator`]. Its elements are borrowed from
+/// the encoding of the operator and decoded when iterated.
+pub struct Immediates<'a, T> {
+    count: u32,
+    data: &'a [u8],
+    parser: fn(&'a [u8]) -> Res<'a, T>,
+}
+
+impl<'a, T> Immediates<'a, T> {
+    /// Returns the number of elements.
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    /// Returns whether there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// Returns an iterator over the elements.
+    pub fn iter(&self) -> ImmediatesIter<'a, T> {
+        ImmediatesIter {
+            remaining: self.count,
+            data: self.data,
+            parser: self.parser,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Immediates<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Immediates<'_, T> {}
+
+impl<T: fmt::Debug> fmt::Debug for Immediates<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &Immediates<'a, T> {
+    type IntoIter = ImmediatesIter<'a, T>;
+    type Item = T;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of [`Immediates`].
+pub struct ImmediatesIter<'a, T> {
+    remaining: u32,
+    data: &'a [u8],
+    parser: fn(&'a [u8]) -> Res<'a, T>,
+    item: PhantomData<T>,
+}
+
+impl<T> Iterator for ImmediatesIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        // The elements were decoded when the operator was read.
+        let (rest, item) = (self.parser)(self.data).expect("immediates are well-formed");
+        self.remaining -= 1;
+        self.data = rest;
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+/// Parses a vector immediate whose elements are parsed by `parser`, without
+/// collecting them.
+fn immediates<'a, T>(
+    parser: fn(&'a [u8]) -> Res<'a, T>,
+) -> impl FnMut(&'a [u8]) -> Res<'a, Immediates<'a, T>> {
+    move |input: &'a [u8]| {
+        let (data, count) = vector_count_parser(input)?;
+        let (rest, ()) =
+            fold_many_m_n(count as usize, count as usize, parser, || (), |(), _| ())(data)?;
+        let data = &data[..data.len() - rest.len()];
+        Ok((
+            rest,
+            Immediates {
+                count,
+                data,
+                parser,
+            },
+        ))
+    }
+}
+
+/// An instruction of a function body, as read by an [`OperatorsReader`].
+/// Unlike [`Instr`](super::Instr), structured instructions are not nested:
+/// their instructions follow them and are terminated by [`Operator::End`],
+/// with [`Operator::Else`] separating the branches of an `if`.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html)
+#[derive(Debug)]
+pub enum Operator<'a> {
+    Unreachable,
+    Nop,
+    Block(BlockType),
+    Loop(BlockType),
+    If(BlockType),
+    Else,
+    /// Terminates a structured instruction, or the expression itself.
+    End,
+    Br(LabelIdx),
+    BrIf(LabelIdx),
+    BrTable(Immediates<'a, LabelIdx>, LabelIdx),
+    Return,
+    Call(FuncIdx),
+    CallIndirect(TypeIdx, TableIdx),
+    Drop,
+    Select,
+    SelectTyped(Immediates<'a, ValType>),
+    Reference(ReferenceInstruction),
+    Variable(VariableInstruction),
+    Table(TableInstruction),
+    Memory(MemoryInstruction),
+    Numeric(NumericInstruction),
+    Vector(VectorInstruction),
+}
+
+/// Parses an operator, choosing the parser of its group from its opcode.
+pub fn operator_parser(input: &[u8]) -> Res<'_, Operator<'_>> {
+    let (_, opcode) = context("operator", peek(take(1usize)))(input)?;
+    match opcode[0] {
+        0x00 => map(tag([0x00]), |_| Operator::Unreachable)(input),
+        0x01 => map(tag([0x01]), |_| Operator::Nop)(input),
+        0x02 => map(preceded(tag([0x02]), blocktype_parser), Operator::Block)(input),
+        0x03 => map(preceded(tag([0x03]), blocktype_parser), Operator::Loop)(input),
+        0x04 => map(preceded(tag([0x04]), blocktype_parser), Operator::If)(input),
+        0x05 => map(tag([0x05]), |_| Operator::Else)(input),
+        0x0B => map(tag([0x0B]), |_| Operator::End)(input),
+        0x0C => map(preceded(tag([0x0C]), labelidx_parser), Operator::Br)(input),
+        0x0D => map(preceded(tag([0x0D]), labelidx_parser), Operator::BrIf)(input),
+        0x0E => context(
+            "br_table",
+            map(
+                preceded(
+                    tag([0x0E]),
+                    pair(immediates(labelidx_parser), labelidx_parser),
+                ),
+                |(labels, default)| Operator::BrTable(labels, default),
+            ),
+        )(input),
+        0x0F => map(tag([0x0F]), |_| Operator::Return)(input),
+        0x10 => map(preceded(tag([0x10]), funcidx_parser), Operator::Call)(input),
+        0x11 => map(
+            preceded(tag([0x11]), tuple((typeidx_parser, tableidx_parser))),
+            |(t, tbl)| Operator::CallIndirect(t, tbl),
+        )(input),
+        0x1A => map(tag([0x1A]), |_| Operator::Drop)(input),
+        0x1B => map(tag([0x1B]), |_| Operator::Select)(input),
+        0x1C => context(
+            "select_typed",
+            map(
+                preceded(tag([0x1C]), immediates(valtype_parser)),
+                Operator::SelectTyped,
+            ),
+        )(input),
+        0x20..=0x24 => map(variable_instr_parser, Operator::Variable)(input),
+        0x25 | 0x26 => map(table_instr_parser, Operator::Table)(input),
+        0x28..=0x40 => map(memory_instr_parser, Operator::Memory)(input),
+        0x41..=0xC4 => map(numeric_instr_parser, Operator::Numeric)(input),
+        0xD0..=0xD2 => map(reference_instr_parser, Operator::Reference)(input),
+        0xFC => match peek(prefixed_opcode_parser(0xFC))(input)?.1 {
+            8..=11 => map(memory_instr_parser, Operator::Memory)(input),
+            12..=17 => map(table_instr_parser, Operator::Table)(input),
+            _ => map(numeric_instr_parser, Operator::Numeric)(input),
+        },
+        0xFD => map(vector_instr_parser, Operator::Vector)(input),
+        _ => context("unknown operator", fail)(input),
+    }
+}
+
+/// Reads the instructions of an expression as a flat sequence of
+/// [`Operator`]s, each with its offset in the binary, without building the
+/// tree of [`Instr`](super::Instr)s.
+///
+/// The reader checks that each structured instruction is terminated and
+/// that nothing follows the end of the expression. That `else` only occurs
+/// in an `if` is left to the consumer of the operators.
+#[derive(Debug, Clone)]
+pub struct OperatorsReader<'a> {
+    input: &'a [u8],
+    position: usize,
+    base: usize,
+    /// The number of structured instructions that are not terminated yet,
+    /// including the expression itself.
+    depth: u32,
+}
+
+impl<'a> OperatorsReader<'a> {
+    /// Creates a reader of the expression encoded in `input`, which starts
+    /// at the offset `base` in the binary.
+    pub fn new(input: &'a [u8], base: usize) -> Self {
+        OperatorsReader {
+            input,
+            position: 0,
+            base,
+            depth: 1,
+        }
+    }
+
+    /// Returns the offset of the next operator in the binary.
+    pub fn offset(&self) -> usize {
+        self.base + self.position
+    }
+
+    /// Returns whether the end of the expression was read.
+    pub fn is_end(&self) -> bool {
+        self.depth == 0
+    }
+
+    /// Reads the next operator and returns it with its offset.
+    pub fn read(&mut self) -> Result<(Operator<'a>, usize), ParseError> {
+        let input = &self.input[self.position..];
+        let result = match self.depth {
+            0 => context("section size mismatch", fail)(input),
+            _ => operator_parser(input),
+        };
+        let (rest, operator) = result.map_err(|e| {
+            // Nothing is read after a failure.
+            self.depth = 0;
+            self.position = self.input.len();
+            ParseError::new(self.input, e).offset_by(self.base)
+        })?;
+        match operator {
+            Operator::Block(_) | Operator::Loop(_) | Operator::If(_) => self.depth += 1,
+            Operator::End => self.depth -= 1,
+            _ => {}
+        }
+        let offset = self.offset();
+        self.position = self.input.len() - rest.len();
+        Ok((operator, offset))
+    }
+}
+
+impl<'a> Iterator for OperatorsReader<'a> {
+    type Item = Result<(Operator<'a>, usize), ParseError>;
+
+    /// Returns the next operator, until the end of the expression or the
+    /// first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.depth == 0 && self.position == self.input.len() {
+            true => None,
+            false => Some(self.read()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{modules::DataIdx, types::NumType, MalformedKind};
+
+    #[test]
+    fn test_operators_reader() {
+        let input = [
+            0x02, 0x40, // block
+            0x41, 0x01, // i32.const 1
+            0x04, 0x7F, // if (result i32)
+            0x41, 0x02, // i32.const 2
+            0x05, // else
+            0x41, 0x03, // i32.const 3
+            0x0B, // end
+            0x0E, 0x02, 0x00, 0x01, 0x00, // br_table 0 1 0
+            0x0B, // end
+            0x1C, 0x01, 0x7F, // select (result i32)
+            0xFC, 0x08, 0x03, 0x00, // memory.init 3 0
+            0x0B, // end
+        ];
+        let operators: Vec<_> = OperatorsReader::new(&input, 10)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let offsets: Vec<_> = operators.iter().map(|(_, offset)| *offset).collect();
+        assert_eq!(offsets, [10, 12, 14, 16, 18, 19, 21, 22, 27, 28, 31, 35]);
+        assert!(matches!(
+            operators[2].0,
+            Operator::If(BlockType::ValType(ValType::NumType(NumType::I32)))
+        ));
+        assert!(matches!(operators[4].0, Operator::Else));
+        match &operators[7].0 {
+            Operator::BrTable(labels, default) => {
+                assert_eq!(
+                    labels.iter().collect::<Vec<_>>(),
+                    [LabelIdx(0), LabelIdx(1)]
+                );
+                assert_eq!(*default, LabelIdx(0));
+            }
+            other => panic!("unexpected operator {:?}", other),
+        }
+        match &operators[9].0 {
+            Operator::SelectTyped(types) => {
+                assert_eq!(
+                    types.iter().collect::<Vec<_>>(),
+                    [ValType::NumType(NumType::I32)]
+                )
+            }
+            other => panic!("unexpected operator {:?}", other),
+        }
+        assert!(matches!(
+            operators[10].0,
+            Operator::Memory(MemoryInstruction::MemoryInit(DataIdx(3), _))
+        ));
+        assert!(matches!(operators[11].0, Operator::End));
+
+        // The expression is not terminated.
+        let mut reader = OperatorsReader::new(&input[..17], 10);
+        let error = reader.find_map(Result::err).unwrap();
+        assert_eq!(error.offset, 27);
+        assert_eq!(error.malformed, MalformedKind::UnexpectedEnd);
+        assert!(reader.next().is_none());
+
+        // An instruction follows the end of the expression.
+        let mut reader = OperatorsReader::new(&[0x0B, 0x01], 0);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.is_end());
+        let error = reader.next().unwrap().unwrap_err();
+        assert_eq!(error.offset, 1);
+        assert_eq!(error.malformed, MalformedKind::SectionSizeMismatch);
+    }
+}
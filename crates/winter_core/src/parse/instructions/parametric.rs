@@ -0,0 +1,50 @@
+use std::vec::Vec;
+
+use nom::{
+    branch::alt, bytes::complete::tag, combinator::map, error::context, multi::length_count,
+    sequence::preceded,
+};
+
+use crate::parse::{
+    types::{valtype_parser, ValType},
+    values::vector_count_parser,
+    Res,
+};
+
+/// Instructions in this group can operate on operands of any value type.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#parametric-instructions)
+#[derive(Debug)]
+pub enum ParametricInstruction {
+    /// The drop instruction simply throws away a single operand.
+    Drop,
+
+    /// The select instruction selects one of its first two operands based
+    /// on whether its third operand is zero or not. It may include a value
+    /// type determining the type of these operands. If missing, the
+    /// operands must be of numeric type.
+    Select(Option<Vec<ValType>>),
+}
+
+pub fn parametric_instr_parser(input: &[u8]) -> Res<'_, ParametricInstruction> {
+    context(
+        "parametric_instr",
+        alt((
+            context("drop", map(tag([0x1A]), |_| ParametricInstruction::Drop)),
+            context(
+                "select",
+                map(tag([0x1B]), |_| ParametricInstruction::Select(None)),
+            ),
+            context(
+                "select_typed",
+                map(
+                    preceded(
+                        tag([0x1C]),
+                        length_count(vector_count_parser, valtype_parser),
+                    ),
+                    |t| ParametricInstruction::Select(Some(t)),
+                ),
+            ),
+        )),
+    )(input)
+}
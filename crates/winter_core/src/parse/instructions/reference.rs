@@ -0,0 +1,46 @@
+use nom::{branch::alt, bytes::complete::tag, combinator::map, error::context, sequence::preceded};
+
+use crate::parse::{
+    modules::{funcidx_parser, FuncIdx},
+    types::{reftype_parser, RefType},
+    Res,
+};
+
+/// Instructions in this group are concerned with accessing references.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#reference-instructions)
+#[derive(Debug)]
+pub enum ReferenceInstruction {
+    /// Produces a null value of the given reference type.
+    RefNull(RefType),
+    /// Checks for null.
+    RefIsNull,
+    /// Produces a reference to a given function.
+    RefFunc(FuncIdx),
+}
+
+pub fn reference_instr_parser(input: &[u8]) -> Res<'_, ReferenceInstruction> {
+    context(
+        "reference_instr",
+        alt((
+            context(
+                "ref.null",
+                map(
+                    preceded(tag([0xD0]), reftype_parser),
+                    ReferenceInstruction::RefNull,
+                ),
+            ),
+            context(
+                "ref.is_null",
+                map(tag([0xD1]), |_| ReferenceInstruction::RefIsNull),
+            ),
+            context(
+                "ref.func",
+                map(
+                    preceded(tag([0xD2]), funcidx_parser),
+                    ReferenceInstruction::RefFunc,
+                ),
+            ),
+        )),
+    )(input)
+}
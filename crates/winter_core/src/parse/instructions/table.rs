@@ -0,0 +1,109 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::map,
+    error::context,
+    sequence::{preceded, tuple},
+};
+
+use super::prefixed_opcode;
+use crate::parse::{
+    modules::{elemidx_parser, tableidx_parser, ElemIdx, TableIdx},
+    Res,
+};
+
+/// Instructions in this group are concerned with tables.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#table-instructions)
+#[derive(Debug)]
+pub enum TableInstruction {
+    /// Loads an element in a table.
+    TableGet(TableIdx),
+    /// Stores an element in a table.
+    TableSet(TableIdx),
+    /// Initializes a table (second operand) from a passive element segment
+    /// (first operand).
+    TableInit(ElemIdx, TableIdx),
+    /// Prevents further use of a passive element segment.
+    ElemDrop(ElemIdx),
+    /// Copies elements from the source table (second operand) to the
+    /// destination table (first operand).
+    TableCopy(TableIdx, TableIdx),
+    /// Grows a table by a given delta and returns the previous size, or -1
+    /// if enough space cannot be allocated.
+    TableGrow(TableIdx),
+    /// Returns the current size of a table.
+    TableSize(TableIdx),
+    /// Sets all entries in a range to a given value.
+    TableFill(TableIdx),
+}
+
+pub fn table_instr_parser(input: &[u8]) -> Res<'_, TableInstruction> {
+    context(
+        "table_instr",
+        alt((
+            context(
+                "table.get",
+                map(
+                    preceded(tag([0x25]), tableidx_parser),
+                    TableInstruction::TableGet,
+                ),
+            ),
+            context(
+                "table.set",
+                map(
+                    preceded(tag([0x26]), tableidx_parser),
+                    TableInstruction::TableSet,
+                ),
+            ),
+            context(
+                "table.init",
+                map(
+                    preceded(
+                        prefixed_opcode(0xFC, 12),
+                        tuple((elemidx_parser, tableidx_parser)),
+                    ),
+                    |(y, x)| TableInstruction::TableInit(y, x),
+                ),
+            ),
+            context(
+                "elem.drop",
+                map(
+                    preceded(prefixed_opcode(0xFC, 13), elemidx_parser),
+                    TableInstruction::ElemDrop,
+                ),
+            ),
+            context(
+                "table.copy",
+                map(
+                    preceded(
+                        prefixed_opcode(0xFC, 14),
+                        tuple((tableidx_parser, tableidx_parser)),
+                    ),
+                    |(x, y)| TableInstruction::TableCopy(x, y),
+                ),
+            ),
+            context(
+                "table.grow",
+                map(
+                    preceded(prefixed_opcode(0xFC, 15), tableidx_parser),
+                    TableInstruction::TableGrow,
+                ),
+            ),
+            context(
+                "table.size",
+                map(
+                    preceded(prefixed_opcode(0xFC, 16), tableidx_parser),
+                    TableInstruction::TableSize,
+                ),
+            ),
+            context(
+                "table.fill",
+                map(
+                    preceded(prefixed_opcode(0xFC, 17), tableidx_parser),
+                    TableInstruction::TableFill,
+                ),
+            ),
+        )),
+    )(input)
+}
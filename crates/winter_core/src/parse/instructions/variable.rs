@@ -0,0 +1,67 @@
+use nom::{branch::alt, bytes::complete::tag, combinator::map, error::context, sequence::preceded};
+
+use crate::parse::{
+    modules::{globalidx_parser, localidx_parser, GlobalIdx, LocalIdx},
+    Res,
+};
+
+/// Variable instructions are concerned with access to local or global
+/// variables.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#variable-instructions)
+#[derive(Debug)]
+pub enum VariableInstruction {
+    /// Get the value of a local variable.
+    LocalGet(LocalIdx),
+    /// Set the value of a local variable.
+    LocalSet(LocalIdx),
+    /// Like local.set but also returns its argument.
+    LocalTee(LocalIdx),
+    /// Get the value of a global variable.
+    GlobalGet(GlobalIdx),
+    /// Set the value of a global variable.
+    GlobalSet(GlobalIdx),
+}
+
+pub fn variable_instr_parser(input: &[u8]) -> Res<'_, VariableInstruction> {
+    context(
+        "variable_instr",
+        alt((
+            context(
+                "local.get",
+                map(
+                    preceded(tag([0x20]), localidx_parser),
+                    VariableInstruction::LocalGet,
+                ),
+            ),
+            context(
+                "local.set",
+                map(
+                    preceded(tag([0x21]), localidx_parser),
+                    VariableInstruction::LocalSet,
+                ),
+            ),
+            context(
+                "local.tee",
+                map(
+                    preceded(tag([0x22]), localidx_parser),
+                    VariableInstruction::LocalTee,
+                ),
+            ),
+            context(
+                "global.get",
+                map(
+                    preceded(tag([0x23]), globalidx_parser),
+                    VariableInstruction::GlobalGet,
+                ),
+            ),
+            context(
+                "global.set",
+                map(
+                    preceded(tag([0x24]), globalidx_parser),
+                    VariableInstruction::GlobalSet,
+                ),
+            ),
+        )),
+    )(input)
+}
@@ -0,0 +1,625 @@
+use nom::{
+    branch::alt,
+    bytes::complete::take,
+    combinator::{map, map_opt},
+    error::context,
+    number::complete::u8 as laneidx_parser,
+    sequence::{preceded, tuple},
+};
+
+use super::{memarg_parser, prefixed_opcode, prefixed_opcode_parser, MemArg};
+use crate::parse::Res;
+
+/// Lane indices are encoded as a single byte.
+pub type LaneIdx = u8;
+
+/// Vector instructions (also known as SIMD instructions, single instruction
+/// multiple data) provide basic operations over values of vector type. All
+/// of them are encoded with the 0xFD prefix followed by a `u32` sub-opcode.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions)
+#[derive(Debug)]
+pub enum VectorInstruction {
+    V128Load(MemArg),
+    V128Load8x8S(MemArg),
+    V128Load8x8U(MemArg),
+    V128Load16x4S(MemArg),
+    V128Load16x4U(MemArg),
+    V128Load32x2S(MemArg),
+    V128Load32x2U(MemArg),
+    V128Load8Splat(MemArg),
+    V128Load16Splat(MemArg),
+    V128Load32Splat(MemArg),
+    V128Load64Splat(MemArg),
+    V128Store(MemArg),
+    V128Const(u128),
+    I8x16Shuffle([LaneIdx; 16]),
+    I8x16Swizzle,
+    I8x16Splat,
+    I16x8Splat,
+    I32x4Splat,
+    I64x2Splat,
+    F32x4Splat,
+    F64x2Splat,
+    I8x16ExtractLaneS(LaneIdx),
+    I8x16ExtractLaneU(LaneIdx),
+    I8x16ReplaceLane(LaneIdx),
+    I16x8ExtractLaneS(LaneIdx),
+    I16x8ExtractLaneU(LaneIdx),
+    I16x8ReplaceLane(LaneIdx),
+    I32x4ExtractLane(LaneIdx),
+    I32x4ReplaceLane(LaneIdx),
+    I64x2ExtractLane(LaneIdx),
+    I64x2ReplaceLane(LaneIdx),
+    F32x4ExtractLane(LaneIdx),
+    F32x4ReplaceLane(LaneIdx),
+    F64x2ExtractLane(LaneIdx),
+    F64x2ReplaceLane(LaneIdx),
+    I8x16Eq,
+    I8x16Ne,
+    I8x16LtS,
+    I8x16LtU,
+    I8x16GtS,
+    I8x16GtU,
+    I8x16LeS,
+    I8x16LeU,
+    I8x16GeS,
+    I8x16GeU,
+    I16x8Eq,
+    I16x8Ne,
+    I16x8LtS,
+    I16x8LtU,
+    I16x8GtS,
+    I16x8GtU,
+    I16x8LeS,
+    I16x8LeU,
+    I16x8GeS,
+    I16x8GeU,
+    I32x4Eq,
+    I32x4Ne,
+    I32x4LtS,
+    I32x4LtU,
+    I32x4GtS,
+    I32x4GtU,
+    I32x4LeS,
+    I32x4LeU,
+    I32x4GeS,
+    I32x4GeU,
+    F32x4Eq,
+    F32x4Ne,
+    F32x4Lt,
+    F32x4Gt,
+    F32x4Le,
+    F32x4Ge,
+    F64x2Eq,
+    F64x2Ne,
+    F64x2Lt,
+    F64x2Gt,
+    F64x2Le,
+    F64x2Ge,
+    V128Not,
+    V128And,
+    V128Andnot,
+    V128Or,
+    V128Xor,
+    V128Bitselect,
+    V128AnyTrue,
+    V128Load8Lane(MemArg, LaneIdx),
+    V128Load16Lane(MemArg, LaneIdx),
+    V128Load32Lane(MemArg, LaneIdx),
+    V128Load64Lane(MemArg, LaneIdx),
+    V128Store8Lane(MemArg, LaneIdx),
+    V128Store16Lane(MemArg, LaneIdx),
+    V128Store32Lane(MemArg, LaneIdx),
+    V128Store64Lane(MemArg, LaneIdx),
+    V128Load32Zero(MemArg),
+    V128Load64Zero(MemArg),
+    F32x4DemoteF64x2Zero,
+    F64x2PromoteLowF32x4,
+    I8x16Abs,
+    I8x16Neg,
+    I8x16Popcnt,
+    I8x16AllTrue,
+    I8x16Bitmask,
+    I8x16NarrowI16x8S,
+    I8x16NarrowI16x8U,
+    F32x4Ceil,
+    F32x4Floor,
+    F32x4Trunc,
+    F32x4Nearest,
+    I8x16Shl,
+    I8x16ShrS,
+    I8x16ShrU,
+    I8x16Add,
+    I8x16AddSatS,
+    I8x16AddSatU,
+    I8x16Sub,
+    I8x16SubSatS,
+    I8x16SubSatU,
+    F64x2Ceil,
+    F64x2Floor,
+    I8x16MinS,
+    I8x16MinU,
+    I8x16MaxS,
+    I8x16MaxU,
+    F64x2Trunc,
+    I8x16AvgrU,
+    I16x8ExtaddPairwiseI8x16S,
+    I16x8ExtaddPairwiseI8x16U,
+    I32x4ExtaddPairwiseI16x8S,
+    I32x4ExtaddPairwiseI16x8U,
+    I16x8Abs,
+    I16x8Neg,
+    I16x8Q15mulrSatS,
+    I16x8AllTrue,
+    I16x8Bitmask,
+    I16x8NarrowI32x4S,
+    I16x8NarrowI32x4U,
+    I16x8ExtendLowI8x16S,
+    I16x8ExtendHighI8x16S,
+    I16x8ExtendLowI8x16U,
+    I16x8ExtendHighI8x16U,
+    I16x8Shl,
+    I16x8ShrS,
+    I16x8ShrU,
+    I16x8Add,
+    I16x8AddSatS,
+    I16x8AddSatU,
+    I16x8Sub,
+    I16x8SubSatS,
+    I16x8SubSatU,
+    F64x2Nearest,
+    I16x8Mul,
+    I16x8MinS,
+    I16x8MinU,
+    I16x8MaxS,
+    I16x8MaxU,
+    I16x8AvgrU,
+    I16x8ExtmulLowI8x16S,
+    I16x8ExtmulHighI8x16S,
+    I16x8ExtmulLowI8x16U,
+    I16x8ExtmulHighI8x16U,
+    I32x4Abs,
+    I32x4Neg,
+    I32x4AllTrue,
+    I32x4Bitmask,
+    I32x4ExtendLowI16x8S,
+    I32x4ExtendHighI16x8S,
+    I32x4ExtendLowI16x8U,
+    I32x4ExtendHighI16x8U,
+    I32x4Shl,
+    I32x4ShrS,
+    I32x4ShrU,
+    I32x4Add,
+    I32x4Sub,
+    I32x4Mul,
+    I32x4MinS,
+    I32x4MinU,
+    I32x4MaxS,
+    I32x4MaxU,
+    I32x4DotI16x8S,
+    I32x4ExtmulLowI16x8S,
+    I32x4ExtmulHighI16x8S,
+    I32x4ExtmulLowI16x8U,
+    I32x4ExtmulHighI16x8U,
+    I64x2Abs,
+    I64x2Neg,
+    I64x2AllTrue,
+    I64x2Bitmask,
+    I64x2ExtendLowI32x4S,
+    I64x2ExtendHighI32x4S,
+    I64x2ExtendLowI32x4U,
+    I64x2ExtendHighI32x4U,
+    I64x2Shl,
+    I64x2ShrS,
+    I64x2ShrU,
+    I64x2Add,
+    I64x2Sub,
+    I64x2Mul,
+    I64x2Eq,
+    I64x2Ne,
+    I64x2LtS,
+    I64x2GtS,
+    I64x2LeS,
+    I64x2GeS,
+    I64x2ExtmulLowI32x4S,
+    I64x2ExtmulHighI32x4S,
+    I64x2ExtmulLowI32x4U,
+    I64x2ExtmulHighI32x4U,
+    F32x4Abs,
+    F32x4Neg,
+    F32x4Sqrt,
+    F32x4Add,
+    F32x4Sub,
+    F32x4Mul,
+    F32x4Div,
+    F32x4Min,
+    F32x4Max,
+    F32x4Pmin,
+    F32x4Pmax,
+    F64x2Abs,
+    F64x2Neg,
+    F64x2Sqrt,
+    F64x2Add,
+    F64x2Sub,
+    F64x2Mul,
+    F64x2Div,
+    F64x2Min,
+    F64x2Max,
+    F64x2Pmin,
+    F64x2Pmax,
+    I32x4TruncSatF32x4S,
+    I32x4TruncSatF32x4U,
+    F32x4ConvertI32x4S,
+    F32x4ConvertI32x4U,
+    I32x4TruncSatF64x2SZero,
+    I32x4TruncSatF64x2UZero,
+    F64x2ConvertLowI32x4S,
+    F64x2ConvertLowI32x4U,
+}
+
+impl_opcode_table!(VectorInstruction, from_prefixed_opcode(u32), prefixed_opcode {
+    14 => I8x16Swizzle,
+    15 => I8x16Splat,
+    16 => I16x8Splat,
+    17 => I32x4Splat,
+    18 => I64x2Splat,
+    19 => F32x4Splat,
+    20 => F64x2Splat,
+    35 => I8x16Eq,
+    36 => I8x16Ne,
+    37 => I8x16LtS,
+    38 => I8x16LtU,
+    39 => I8x16GtS,
+    40 => I8x16GtU,
+    41 => I8x16LeS,
+    42 => I8x16LeU,
+    43 => I8x16GeS,
+    44 => I8x16GeU,
+    45 => I16x8Eq,
+    46 => I16x8Ne,
+    47 => I16x8LtS,
+    48 => I16x8LtU,
+    49 => I16x8GtS,
+    50 => I16x8GtU,
+    51 => I16x8LeS,
+    52 => I16x8LeU,
+    53 => I16x8GeS,
+    54 => I16x8GeU,
+    55 => I32x4Eq,
+    56 => I32x4Ne,
+    57 => I32x4LtS,
+    58 => I32x4LtU,
+    59 => I32x4GtS,
+    60 => I32x4GtU,
+    61 => I32x4LeS,
+    62 => I32x4LeU,
+    63 => I32x4GeS,
+    64 => I32x4GeU,
+    65 => F32x4Eq,
+    66 => F32x4Ne,
+    67 => F32x4Lt,
+    68 => F32x4Gt,
+    69 => F32x4Le,
+    70 => F32x4Ge,
+    71 => F64x2Eq,
+    72 => F64x2Ne,
+    73 => F64x2Lt,
+    74 => F64x2Gt,
+    75 => F64x2Le,
+    76 => F64x2Ge,
+    77 => V128Not,
+    78 => V128And,
+    79 => V128Andnot,
+    80 => V128Or,
+    81 => V128Xor,
+    82 => V128Bitselect,
+    83 => V128AnyTrue,
+    94 => F32x4DemoteF64x2Zero,
+    95 => F64x2PromoteLowF32x4,
+    96 => I8x16Abs,
+    97 => I8x16Neg,
+    98 => I8x16Popcnt,
+    99 => I8x16AllTrue,
+    100 => I8x16Bitmask,
+    101 => I8x16NarrowI16x8S,
+    102 => I8x16NarrowI16x8U,
+    103 => F32x4Ceil,
+    104 => F32x4Floor,
+    105 => F32x4Trunc,
+    106 => F32x4Nearest,
+    107 => I8x16Shl,
+    108 => I8x16ShrS,
+    109 => I8x16ShrU,
+    110 => I8x16Add,
+    111 => I8x16AddSatS,
+    112 => I8x16AddSatU,
+    113 => I8x16Sub,
+    114 => I8x16SubSatS,
+    115 => I8x16SubSatU,
+    116 => F64x2Ceil,
+    117 => F64x2Floor,
+    118 => I8x16MinS,
+    119 => I8x16MinU,
+    120 => I8x16MaxS,
+    121 => I8x16MaxU,
+    122 => F64x2Trunc,
+    123 => I8x16AvgrU,
+    124 => I16x8ExtaddPairwiseI8x16S,
+    125 => I16x8ExtaddPairwiseI8x16U,
+    126 => I32x4ExtaddPairwiseI16x8S,
+    127 => I32x4ExtaddPairwiseI16x8U,
+    128 => I16x8Abs,
+    129 => I16x8Neg,
+    130 => I16x8Q15mulrSatS,
+    131 => I16x8AllTrue,
+    132 => I16x8Bitmask,
+    133 => I16x8NarrowI32x4S,
+    134 => I16x8NarrowI32x4U,
+    135 => I16x8ExtendLowI8x16S,
+    136 => I16x8ExtendHighI8x16S,
+    137 => I16x8ExtendLowI8x16U,
+    138 => I16x8ExtendHighI8x16U,
+    139 => I16x8Shl,
+    140 => I16x8ShrS,
+    141 => I16x8ShrU,
+    142 => I16x8Add,
+    143 => I16x8AddSatS,
+    144 => I16x8AddSatU,
+    145 => I16x8Sub,
+    146 => I16x8SubSatS,
+    147 => I16x8SubSatU,
+    148 => F64x2Nearest,
+    149 => I16x8Mul,
+    150 => I16x8MinS,
+    151 => I16x8MinU,
+    152 => I16x8MaxS,
+    153 => I16x8MaxU,
+    155 => I16x8AvgrU,
+    156 => I16x8ExtmulLowI8x16S,
+    157 => I16x8ExtmulHighI8x16S,
+    158 => I16x8ExtmulLowI8x16U,
+    159 => I16x8ExtmulHighI8x16U,
+    160 => I32x4Abs,
+    161 => I32x4Neg,
+    163 => I32x4AllTrue,
+    164 => I32x4Bitmask,
+    167 => I32x4ExtendLowI16x8S,
+    168 => I32x4ExtendHighI16x8S,
+    169 => I32x4ExtendLowI16x8U,
+    170 => I32x4ExtendHighI16x8U,
+    171 => I32x4Shl,
+    172 => I32x4ShrS,
+    173 => I32x4ShrU,
+    174 => I32x4Add,
+    177 => I32x4Sub,
+    181 => I32x4Mul,
+    182 => I32x4MinS,
+    183 => I32x4MinU,
+    184 => I32x4MaxS,
+    185 => I32x4MaxU,
+    186 => I32x4DotI16x8S,
+    188 => I32x4ExtmulLowI16x8S,
+    189 => I32x4ExtmulHighI16x8S,
+    190 => I32x4ExtmulLowI16x8U,
+    191 => I32x4ExtmulHighI16x8U,
+    192 => I64x2Abs,
+    193 => I64x2Neg,
+    195 => I64x2AllTrue,
+    196 => I64x2Bitmask,
+    199 => I64x2ExtendLowI32x4S,
+    200 => I64x2ExtendHighI32x4S,
+    201 => I64x2ExtendLowI32x4U,
+    202 => I64x2ExtendHighI32x4U,
+    203 => I64x2Shl,
+    204 => I64x2ShrS,
+    205 => I64x2ShrU,
+    206 => I64x2Add,
+    209 => I64x2Sub,
+    213 => I64x2Mul,
+    214 => I64x2Eq,
+    215 => I64x2Ne,
+    216 => I64x2LtS,
+    217 => I64x2GtS,
+    218 => I64x2LeS,
+    219 => I64x2GeS,
+    220 => I64x2ExtmulLowI32x4S,
+    221 => I64x2ExtmulHighI32x4S,
+    222 => I64x2ExtmulLowI32x4U,
+    223 => I64x2ExtmulHighI32x4U,
+    224 => F32x4Abs,
+    225 => F32x4Neg,
+    227 => F32x4Sqrt,
+    228 => F32x4Add,
+    229 => F32x4Sub,
+    230 => F32x4Mul,
+    231 => F32x4Div,
+    232 => F32x4Min,
+    233 => F32x4Max,
+    234 => F32x4Pmin,
+    235 => F32x4Pmax,
+    236 => F64x2Abs,
+    237 => F64x2Neg,
+    239 => F64x2Sqrt,
+    240 => F64x2Add,
+    241 => F64x2Sub,
+    242 => F64x2Mul,
+    243 => F64x2Div,
+    244 => F64x2Min,
+    245 => F64x2Max,
+    246 => F64x2Pmin,
+    247 => F64x2Pmax,
+    248 => I32x4TruncSatF32x4S,
+    249 => I32x4TruncSatF32x4U,
+    250 => F32x4ConvertI32x4S,
+    251 => F32x4ConvertI32x4U,
+    252 => I32x4TruncSatF64x2SZero,
+    253 => I32x4TruncSatF64x2UZero,
+    254 => F64x2ConvertLowI32x4S,
+    255 => F64x2ConvertLowI32x4U,
+});
+
+fn memarg_instr<'a>(
+    name: &'static str,
+    opcode: u32,
+    f: fn(MemArg) -> VectorInstruction,
+) -> impl FnMut(&'a [u8]) -> Res<'a, VectorInstruction> {
+    context(
+        name,
+        map(preceded(prefixed_opcode(0xFD, opcode), memarg_parser), f),
+    )
+}
+
+fn lane_instr<'a>(
+    name: &'static str,
+    opcode: u32,
+    f: fn(LaneIdx) -> VectorInstruction,
+) -> impl FnMut(&'a [u8]) -> Res<'a, VectorInstruction> {
+    context(
+        name,
+        map(preceded(prefixed_opcode(0xFD, opcode), laneidx_parser), f),
+    )
+}
+
+fn memarg_lane_instr<'a>(
+    name: &'static str,
+    opcode: u32,
+    f: fn(MemArg, LaneIdx) -> VectorInstruction,
+) -> impl FnMut(&'a [u8]) -> Res<'a, VectorInstruction> {
+    context(
+        name,
+        map(
+            preceded(
+                prefixed_opcode(0xFD, opcode),
+                tuple((memarg_parser, laneidx_parser)),
+            ),
+            move |(m, l)| f(m, l),
+        ),
+    )
+}
+
+pub fn vector_instr_parser(input: &[u8]) -> Res<'_, VectorInstruction> {
+    context(
+        "vector_instr",
+        alt((
+            alt((
+                memarg_instr("v128.load", 0, VectorInstruction::V128Load),
+                memarg_instr("v128.load8x8_s", 1, VectorInstruction::V128Load8x8S),
+                memarg_instr("v128.load8x8_u", 2, VectorInstruction::V128Load8x8U),
+                memarg_instr("v128.load16x4_s", 3, VectorInstruction::V128Load16x4S),
+                memarg_instr("v128.load16x4_u", 4, VectorInstruction::V128Load16x4U),
+                memarg_instr("v128.load32x2_s", 5, VectorInstruction::V128Load32x2S),
+                memarg_instr("v128.load32x2_u", 6, VectorInstruction::V128Load32x2U),
+                memarg_instr("v128.load8_splat", 7, VectorInstruction::V128Load8Splat),
+                memarg_instr("v128.load16_splat", 8, VectorInstruction::V128Load16Splat),
+                memarg_instr("v128.load32_splat", 9, VectorInstruction::V128Load32Splat),
+                memarg_instr("v128.load64_splat", 10, VectorInstruction::V128Load64Splat),
+                memarg_instr("v128.store", 11, VectorInstruction::V128Store),
+                memarg_instr("v128.load32_zero", 92, VectorInstruction::V128Load32Zero),
+                memarg_instr("v128.load64_zero", 93, VectorInstruction::V128Load64Zero),
+            )),
+            alt((
+                lane_instr(
+                    "i8x16.extract_lane_s",
+                    21,
+                    VectorInstruction::I8x16ExtractLaneS,
+                ),
+                lane_instr(
+                    "i8x16.extract_lane_u",
+                    22,
+                    VectorInstruction::I8x16ExtractLaneU,
+                ),
+                lane_instr(
+                    "i8x16.replace_lane",
+                    23,
+                    VectorInstruction::I8x16ReplaceLane,
+                ),
+                lane_instr(
+                    "i16x8.extract_lane_s",
+                    24,
+                    VectorInstruction::I16x8ExtractLaneS,
+                ),
+                lane_instr(
+                    "i16x8.extract_lane_u",
+                    25,
+                    VectorInstruction::I16x8ExtractLaneU,
+                ),
+                lane_instr(
+                    "i16x8.replace_lane",
+                    26,
+                    VectorInstruction::I16x8ReplaceLane,
+                ),
+                lane_instr(
+                    "i32x4.extract_lane",
+                    27,
+                    VectorInstruction::I32x4ExtractLane,
+                ),
+                lane_instr(
+                    "i32x4.replace_lane",
+                    28,
+                    VectorInstruction::I32x4ReplaceLane,
+                ),
+                lane_instr(
+                    "i64x2.extract_lane",
+                    29,
+                    VectorInstruction::I64x2ExtractLane,
+                ),
+                lane_instr(
+                    "i64x2.replace_lane",
+                    30,
+                    VectorInstruction::I64x2ReplaceLane,
+                ),
+                lane_instr(
+                    "f32x4.extract_lane",
+                    31,
+                    VectorInstruction::F32x4ExtractLane,
+                ),
+                lane_instr(
+                    "f32x4.replace_lane",
+                    32,
+                    VectorInstruction::F32x4ReplaceLane,
+                ),
+                lane_instr(
+                    "f64x2.extract_lane",
+                    33,
+                    VectorInstruction::F64x2ExtractLane,
+                ),
+                lane_instr(
+                    "f64x2.replace_lane",
+                    34,
+                    VectorInstruction::F64x2ReplaceLane,
+                ),
+            )),
+            alt((
+                memarg_lane_instr("v128.load8_lane", 84, VectorInstruction::V128Load8Lane),
+                memarg_lane_instr("v128.load16_lane", 85, VectorInstruction::V128Load16Lane),
+                memarg_lane_instr("v128.load32_lane", 86, VectorInstruction::V128Load32Lane),
+                memarg_lane_instr("v128.load64_lane", 87, VectorInstruction::V128Load64Lane),
+                memarg_lane_instr("v128.store8_lane", 88, VectorInstruction::V128Store8Lane),
+                memarg_lane_instr("v128.store16_lane", 89, VectorInstruction::V128Store16Lane),
+                memarg_lane_instr("v128.store32_lane", 90, VectorInstruction::V128Store32Lane),
+                memarg_lane_instr("v128.store64_lane", 91, VectorInstruction::V128Store64Lane),
+            )),
+            context(
+                "v128.const",
+                map(
+                    preceded(prefixed_opcode(0xFD, 12), take(16usize)),
+                    |b: &[u8]| {
+                        VectorInstruction::V128Const(u128::from_le_bytes(b.try_into().unwrap()))
+                    },
+                ),
+            ),
+            context(
+                "i8x16.shuffle",
+                map(
+                    preceded(prefixed_opcode(0xFD, 13), take(16usize)),
+                    |b: &[u8]| VectorInstruction::I8x16Shuffle(b.try_into().unwrap()),
+                ),
+            ),
+            map_opt(
+                prefixed_opcode_parser(0xFD),
+                VectorInstruction::from_prefixed_opcode,
+            ),
+        )),
+    )(input)
+}
@@ -1,8 +1,15 @@
-use nom::{error::VerboseError, IResult};
+use nom::IResult;
 
+mod error;
 pub mod instructions;
 pub mod modules;
+mod span;
 pub mod types;
 pub mod values;
 
-pub type Res<'a, U> = IResult<&'a [u8], U, VerboseError<&'a [u8]>>;
+pub(crate) use error::SECTIONS;
+pub use error::{ErrorTrace, MalformedKind, ParseError, TraceKind};
+pub use span::Span;
+pub(crate) use span::{spanned, Locate};
+
+pub type Res<'a, U> = IResult<&'a [u8], U, ErrorTrace<'a>>;
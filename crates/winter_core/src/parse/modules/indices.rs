@@ -9,10 +9,10 @@ use crate::parse::{values::leb128_u32, Res};
 
 macro_rules! impl_idx {
     ($typename: ident, $parse_fn: ident) => {
-        #[derive(Debug)]
+        #[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy)]
         pub struct $typename(pub u32);
 
-        pub fn $parse_fn(input: &[u8]) -> Res<$typename> {
+        pub fn $parse_fn(input: &[u8]) -> Res<'_, $typename> {
             map(leb128_u32, |f| $typename(f))(input)
         }
 
@@ -1,6 +1,17 @@
-use nom::{bytes::complete::tag, sequence::preceded};
+use std::vec::Vec;
 
-use crate::parse::{values::vector_count_parser, Res};
+use nom::{
+    bytes::complete::tag,
+    combinator::{cut, fail, verify},
+    error::context,
+    sequence::preceded,
+};
+
+use crate::parse::{
+    spanned,
+    values::{leb128_u32, vector_count_parser, vector_parser},
+    Res, Span,
+};
 
 pub mod code_section;
 pub mod custom_section;
@@ -13,12 +24,77 @@ pub mod global_section;
 pub mod import_section;
 pub mod memory_section;
 pub mod module;
+pub mod name_section;
+pub mod parser;
+pub mod producers_section;
 pub mod start_section;
 pub mod table_section;
+pub mod target_features_section;
 pub mod type_section;
 
-/// Parser a section. Validates the section number and returns
-/// the number of bytes for the section.
-fn section_length_parser(section_id: u8) -> impl FnMut(&[u8]) -> Res<u32> {
-    move |inp: &[u8]| preceded(tag([section_id]), vector_count_parser)(inp)
+/// Parses a section with id `section_id` whose content is parsed by
+/// `parser`. Once the id is matched, failures are not recoverable, so that
+/// a malformed section is reported instead of being skipped.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#sections)
+fn section<'a, O>(
+    section_id: u8,
+    parser: impl FnMut(&'a [u8]) -> Res<'a, O>,
+) -> impl FnMut(&'a [u8]) -> Res<'a, O> {
+    preceded(tag([section_id]), cut(sized(parser)))
+}
+
+/// Parses content prefixed with its `u32` size with `parser`, which must
+/// consume all of it.
+fn sized<'a, O>(parser: impl FnMut(&'a [u8]) -> Res<'a, O>) -> impl FnMut(&'a [u8]) -> Res<'a, O> {
+    let mut parser = exact(parser);
+    move |input: &'a [u8]| {
+        let (rest, content) = vector_parser(input)?;
+        let (_, output) = parser(content)?;
+        Ok((rest, output))
+    }
+}
+
+/// Parses all of the input with `parser`.
+fn exact<'a, O>(
+    mut parser: impl FnMut(&'a [u8]) -> Res<'a, O>,
+) -> impl FnMut(&'a [u8]) -> Res<'a, O> {
+    move |input: &'a [u8]| {
+        let (rest, output) = parser(input)?;
+        match rest.is_empty() {
+            true => Ok((rest, output)),
+            false => context("section size mismatch", fail)(rest),
+        }
+    }
+}
+
+/// Parses the `u32` flags of a segment, which select its mode and
+/// encoding, if they are `value`. Flags may be encoded with more bytes than
+/// needed like any `u32`.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#element-section)
+fn flags<'a>(value: u32) -> impl FnMut(&'a [u8]) -> Res<'a, u32> {
+    verify(leb128_u32, move |flags| *flags == value)
+}
+
+/// Parses the vector of entries of a section with `parser`. Like
+/// `length_count`, but the index of an entry that fails to parse is added
+/// to the error, and the spans of the entries are returned with them.
+fn entries<'a, O>(
+    parser: impl FnMut(&'a [u8]) -> Res<'a, O>,
+) -> impl FnMut(&'a [u8]) -> Res<'a, (Vec<O>, Vec<Span>)> {
+    let mut parser = spanned(parser);
+    move |input: &'a [u8]| {
+        let (mut input, count) = vector_count_parser(input)?;
+        let mut items = Vec::new();
+        let mut spans = Vec::new();
+        for index in 0..count {
+            let (rest, (item, span)) =
+                parser(input).map_err(|e| e.map(|e| e.entry(input, index)))?;
+            items.push(item);
+            spans.push(span);
+            input = rest;
+        }
+        Ok((input, (items, spans)))
+    }
 }
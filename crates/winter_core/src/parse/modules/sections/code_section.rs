@@ -1,18 +1,20 @@
+use core::cell::OnceCell;
 use std::vec::Vec;
 
 use nom::{
-    combinator::map,
+    combinator::{fail, map, peek},
     error::context,
-    multi::{length_count, length_value},
+    multi::{fold_many_m_n, length_count},
     sequence::tuple,
 };
 
-use super::section_length_parser;
+use super::exact;
 use crate::parse::{
-    instructions::{expr_parser, Expr},
+    instructions::{nested_expr_parser, Expr, OperatorsReader, DEFAULT_MAX_NESTING_DEPTH},
+    spanned,
     types::{valtype_parser, ValType},
-    values::{leb128_u32, vector_count_parser},
-    Res,
+    values::{leb128_u32, vector_count_parser, vector_parser},
+    Locate, ParseError, Res, Span,
 };
 
 #[derive(Debug)]
@@ -27,18 +29,118 @@ pub struct Func {
     pub expr: Expr,
 }
 
+/// An entry of the code section. The body of a function decoded from a
+/// binary module is only decoded when it is first used, so that modules can
+/// be inspected without decoding every function.
 #[derive(Debug)]
-pub struct Code {
+pub struct Code<'a> {
     pub size: u32,
-    pub code: Func,
+    /// The encoding of the locals and expression of the function, if it was
+    /// decoded from a binary module.
+    pub body: Option<&'a [u8]>,
+    /// The offset of the end of the body in the module.
+    end: usize,
+    /// The maximum nesting depth of the structured instructions of the
+    /// body.
+    pub(super) max_nesting_depth: u32,
+    /// Whether the module has a data count section, which `memory.init` and
+    /// `data.drop` require.
+    pub(super) data_count: bool,
+    func: OnceCell<Func>,
 }
 
-#[derive(Debug)]
-pub struct CodeSection {
-    pub code: Vec<Code>,
+impl<'a> Code<'a> {
+    /// Creates the code of the function `func`, which has no encoding.
+    pub fn new(func: Func) -> Self {
+        Code {
+            size: 0,
+            body: None,
+            end: 0,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+            data_count: true,
+            func: OnceCell::from(func),
+        }
+    }
+
+    /// Returns the locals and expression of the function, decoding them
+    /// from its body on first use.
+    pub fn func(&self) -> Result<&Func, ParseError> {
+        if let Some(func) = self.func.get() {
+            return Ok(func);
+        }
+        let func = self.decode_func()?;
+        Ok(self.func.get_or_init(|| func))
+    }
+
+    /// Returns the locals and expression of the function, like
+    /// [`Code::func`].
+    pub fn into_func(mut self) -> Result<Func, ParseError> {
+        match self.func.take() {
+            Some(func) => Ok(func),
+            None => self.decode_func(),
+        }
+    }
+
+    /// Replaces the locals and expression of the function with `func`. The
+    /// body is cleared, so the function is encoded from `func`.
+    pub fn set_func(&mut self, func: Func) {
+        self.size = 0;
+        self.body = None;
+        self.func = OnceCell::from(func);
+    }
+
+    /// Returns a reader of the instructions of the body, after its locals.
+    pub fn operators(&self) -> Result<OperatorsReader<'a>, ParseError> {
+        let body = self.body.unwrap_or_default();
+        let (expr, ()) =
+            skip_locals(body).map_err(|e| ParseError::new(body, e).offset_by(self.body_start()))?;
+        Ok(OperatorsReader::new(expr, self.end - expr.len()))
+    }
+
+    /// Returns the offset of the body in the module, after its size, if it
+    /// was decoded from a binary module.
+    pub fn body_offset(&self) -> Option<usize> {
+        self.body.map(|_| self.body_start())
+    }
+
+    fn body_start(&self) -> usize {
+        self.end - self.body.map_or(0, <[u8]>::len)
+    }
+
+    /// Decodes the body, the locals and expression of the function.
+    fn decode_func(&self) -> Result<Func, ParseError> {
+        let body = self.body.unwrap_or_default();
+        let (_, mut func) = exact(func_parser(self.max_nesting_depth, self.data_count))(body)
+            .map_err(|e| ParseError::new(body, e).offset_by(self.body_start()))?;
+        func.expr.locate(self.end);
+        Ok(func)
+    }
 }
 
-fn locals_parser(input: &[u8]) -> Res<Locals> {
+impl Locate for Code<'_> {
+    fn locate(&mut self, end: usize) {
+        self.end = end - self.end;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CodeSection<'a> {
+    pub code: Vec<Code<'a>>,
+    /// The spans of the entries.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for CodeSection<'_> {
+    fn locate(&mut self, end: usize) {
+        self.code.locate(end);
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
+}
+
+fn locals_parser(input: &[u8]) -> Res<'_, Locals> {
     context(
         "locals",
         map(tuple((leb128_u32, valtype_parser)), |(count, val_type)| {
@@ -47,38 +149,133 @@ fn locals_parser(input: &[u8]) -> Res<Locals> {
     )(input)
 }
 
-fn func_parser(input: &[u8]) -> Res<Func> {
+/// Fails at `input`, the start of the locals of a function, if they
+/// declare more than `u32::MAX` locals in total.
+fn check_total_locals(input: &[u8], total: u64) -> Res<'_, ()> {
+    match total > u64::from(u32::MAX) {
+        true => context("too many locals", fail)(input),
+        false => Ok((input, ())),
+    }
+}
+
+/// Parses the locals of a function body.
+fn locals_vec_parser(input: &[u8]) -> Res<'_, Vec<Locals>> {
+    let (rest, locals) = length_count(vector_count_parser, locals_parser)(input)?;
+    let total = locals.iter().map(|l| u64::from(l.count)).sum();
+    check_total_locals(input, total)?;
+    Ok((rest, locals))
+}
+
+/// Skips the locals of a function body without collecting them.
+fn skip_locals(input: &[u8]) -> Res<'_, ()> {
+    let (rest, count) = vector_count_parser(input)?;
+    let (rest, total) = fold_many_m_n(
+        count as usize,
+        count as usize,
+        locals_parser,
+        || 0,
+        |total, locals| total + u64::from(locals.count),
+    )(rest)?;
+    check_total_locals(input, total)?;
+    Ok((rest, ()))
+}
+
+/// Parses the locals and expression of a function, in which at most
+/// `max_depth` structured instructions are nested. The expression may only
+/// use `memory.init` and `data.drop` if `data_count`.
+fn func_parser<'a>(max_depth: u32, data_count: bool) -> impl FnMut(&'a [u8]) -> Res<'a, Func> {
     context(
         "func",
         map(
-            tuple((
-                length_count(vector_count_parser, locals_parser),
-                expr_parser,
-            )),
+            tuple((locals_vec_parser, nested_expr_parser(max_depth, data_count))),
             |(locals, expr)| Func { locals, expr },
         ),
-    )(input)
+    )
 }
 
-fn code_parser(input: &[u8]) -> Res<Code> {
+/// Parses an entry of the code section, without decoding its body.
+pub(super) fn code_parser(input: &[u8]) -> Res<'_, Code<'_>> {
     context(
         "code",
-        map(tuple((leb128_u32, func_parser)), |(size, code)| Code {
-            size,
-            code,
-        }),
-    )(input)
-}
-
-pub fn code_section_parser(input: &[u8]) -> Res<CodeSection> {
-    context(
-        "code_section",
         map(
-            length_value(
-                section_length_parser(10),
-                length_count(vector_count_parser, code_parser),
-            ),
-            |code| CodeSection { code },
+            spanned(tuple((peek(leb128_u32), vector_parser))),
+            |((size, body), span)| Code {
+                size,
+                body: Some(body),
+                end: span.end,
+                max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+                data_count: true,
+                func: OnceCell::new(),
+            },
         ),
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::{instructions::Operator, modules::module::module_parser, MalformedKind};
+
+    #[test]
+    fn test_lazy_func() {
+        let binary = [
+            0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+            0x03, 0x03, 0x02, 0x00, 0x00, // function section
+            0x0A, 0x08, 0x02, // code section with two entries
+            0x02, 0x00, 0x0B, // no locals, end
+            0x03, 0x00, 0x0B, 0x0B, // no locals, end, end
+        ];
+        let module = module_parser(&binary).unwrap();
+        let section = module.code.as_ref().unwrap();
+        assert_eq!(section.span, Span { start: 19, end: 29 });
+        assert_eq!(
+            section.spans,
+            [Span { start: 22, end: 25 }, Span { start: 25, end: 29 }]
+        );
+        let code = &section.code;
+        assert_eq!(code[0].body, Some(&[0x00, 0x0B][..]));
+        assert!(code[0].func().unwrap().locals.is_empty());
+        let mut operators = code[0].operators().unwrap();
+        assert!(matches!(operators.next(), Some(Ok((Operator::End, 24)))));
+        assert!(operators.next().is_none());
+
+        let error = code[1].func().unwrap_err();
+        assert_eq!(error.offset, 28);
+        assert_eq!(error.malformed, MalformedKind::SectionSizeMismatch);
+        assert_eq!(module.decode_funcs(), Err(error));
+    }
+
+    #[test]
+    fn test_malformed_body() {
+        let malformed = |sections: &[u8], body: &[u8]| {
+            let mut binary = [
+                0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+                0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type section
+                0x03, 0x02, 0x01, 0x00, // function section
+            ]
+            .to_vec();
+            binary.extend(sections);
+            binary.extend([0x0A, body.len() as u8 + 2, 0x01, body.len() as u8]);
+            binary.extend(body);
+            let module = module_parser(&binary).unwrap();
+            module.decode_funcs().err().map(|e| e.malformed)
+        };
+
+        // Two entries of u32::MAX locals.
+        let body = [
+            0x02, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x7F, 0x0B,
+        ];
+        assert_eq!(malformed(&[], &body), Some(MalformedKind::TooManyLocals));
+        let body = [0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F, 0x7F, 0x0B];
+        assert_eq!(malformed(&[], &body), None);
+
+        // data.drop 0, with and without a data count section.
+        let body = [0x00, 0xFC, 0x09, 0x00, 0x0B];
+        assert_eq!(
+            malformed(&[], &body),
+            Some(MalformedKind::DataCountRequired)
+        );
+        assert_eq!(malformed(&[0x0C, 0x01, 0x00], &body), None);
+    }
+}
@@ -1,26 +1,38 @@
-use nom::{
-    bytes::complete::take_while, combinator::map, error::context, multi::length_value,
-    sequence::tuple,
-};
+use nom::{bytes::complete::take_while, combinator::map, error::context, sequence::tuple};
 
-use super::section_length_parser;
-use crate::parse::{values::name_parser, Res};
+use super::section;
+use crate::parse::{spanned, values::name_parser, Locate, Res, Span};
 
 #[derive(Debug)]
 pub struct CustomSection<'a> {
     pub name: &'a str,
     pub data: &'a [u8],
+    /// Id of the section that this custom section follows in the module, or
+    /// 0 if it precedes all other sections. Custom sections found between
+    /// two known sections follow the id of the first one, whether or not it
+    /// is present.
+    pub after: u8,
+    /// The span of the section, from its id.
+    pub span: Span,
 }
 
-pub fn custom_section_parser(input: &[u8]) -> Res<CustomSection> {
+impl Locate for CustomSection<'_> {
+    fn locate(&mut self, end: usize) {
+        self.span.locate(end);
+    }
+}
+
+pub(crate) fn custom_section_parser(input: &[u8]) -> Res<'_, CustomSection<'_>> {
     context(
         "custom_section",
         map(
-            length_value(
-                section_length_parser(0),
-                tuple((name_parser, take_while(|_| true))),
-            ),
-            |(name, data)| CustomSection { name, data },
+            spanned(section(0, tuple((name_parser, take_while(|_| true))))),
+            |((name, data), span)| CustomSection {
+                name,
+                data,
+                after: 0,
+                span,
+            },
         ),
     )(input)
 }
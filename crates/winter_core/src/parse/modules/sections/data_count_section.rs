@@ -1,19 +1,26 @@
-use nom::{combinator::map, error::context, multi::length_value};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
-use crate::parse::{values::leb128_u32, Res};
+use super::section;
+use crate::parse::{spanned, values::leb128_u32, Locate, Res, Span};
 
 #[derive(Debug)]
 pub struct DataCountSection {
     pub count: u32,
+    /// The span of the section, from its id.
+    pub span: Span,
 }
 
-pub fn data_count_section_parser(input: &[u8]) -> Res<DataCountSection> {
+impl Locate for DataCountSection {
+    fn locate(&mut self, end: usize) {
+        self.span.locate(end);
+    }
+}
+
+pub(crate) fn data_count_section_parser(input: &[u8]) -> Res<'_, DataCountSection> {
     context(
         "data_count",
-        map(
-            length_value(section_length_parser(12), leb128_u32),
-            |count| DataCountSection { count },
-        ),
+        map(spanned(section(12, leb128_u32)), |(count, span)| {
+            DataCountSection { count, span }
+        }),
     )(input)
 }
@@ -2,19 +2,18 @@ use std::vec::Vec;
 
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     combinator::map,
     error::context,
-    multi::{length_count, length_value},
     sequence::{preceded, tuple},
 };
 
-use super::section_length_parser;
+use super::{entries, flags, section};
 use crate::parse::{
     instructions::{expr_parser, Expr},
     modules::{memidx_parser, MemIdx},
-    values::{vector_count_parser, vector_parser},
-    Res,
+    spanned,
+    values::vector_parser,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -29,17 +28,37 @@ pub struct Data<'a> {
     pub mode: DataMode,
 }
 
-#[derive(Debug)]
+impl Locate for Data<'_> {
+    fn locate(&mut self, end: usize) {
+        if let DataMode::Active { offset, .. } = &mut self.mode {
+            offset.locate(end);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct DataSection<'a> {
     pub data: Vec<Data<'a>>,
+    /// The spans of the data segments.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
 }
 
-fn data_parser(input: &[u8]) -> Res<Data> {
+impl Locate for DataSection<'_> {
+    fn locate(&mut self, end: usize) {
+        self.data.locate(end);
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
+}
+
+fn data_parser(input: &[u8]) -> Res<'_, Data<'_>> {
     context(
         "data",
         alt((
             map(
-                preceded(tag([0x0]), tuple((expr_parser, vector_parser))),
+                preceded(flags(0), tuple((expr_parser, vector_parser))),
                 |(offset, init)| Data {
                     init,
                     mode: DataMode::Active {
@@ -48,15 +67,12 @@ fn data_parser(input: &[u8]) -> Res<Data> {
                     },
                 },
             ),
-            map(preceded(tag([0x1]), vector_parser), |vec| Data {
+            map(preceded(flags(1), vector_parser), |vec| Data {
                 init: vec,
                 mode: DataMode::Passive,
             }),
             map(
-                preceded(
-                    tag([0x2]),
-                    tuple((memidx_parser, expr_parser, vector_parser)),
-                ),
+                preceded(flags(2), tuple((memidx_parser, expr_parser, vector_parser))),
                 |(memory, offset, init)| Data {
                     init,
                     mode: DataMode::Active { offset, memory },
@@ -66,15 +82,12 @@ fn data_parser(input: &[u8]) -> Res<Data> {
     )(input)
 }
 
-pub fn data_section_parser(input: &[u8]) -> Res<DataSection> {
+pub(crate) fn data_section_parser(input: &[u8]) -> Res<'_, DataSection<'_>> {
     context(
         "data_section",
         map(
-            length_value(
-                section_length_parser(11),
-                length_count(vector_count_parser, data_parser),
-            ),
-            |data| DataSection { data },
+            spanned(section(11, entries(data_parser))),
+            |((data, spans), span)| DataSection { data, spans, span },
         ),
     )(input)
 }
@@ -5,17 +5,18 @@ use nom::{
     bytes::complete::tag,
     combinator::map,
     error::context,
-    multi::{length_count, length_value},
+    multi::length_count,
     sequence::{preceded, tuple},
 };
 
-use super::section_length_parser;
+use super::{entries, flags, section};
 use crate::parse::{
     instructions::{expr_parser, Expr},
     modules::{funcidx_parser, tableidx_parser, FuncIdx, TableIdx},
+    spanned,
     types::{reftype_parser, RefType},
     values::vector_count_parser,
-    Res,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -44,12 +45,45 @@ pub struct Elem {
     pub mode: ElemMode,
 }
 
-#[derive(Debug)]
+impl Elem {
+    /// Returns the type of the references in the segment.
+    pub fn ref_type(&self) -> RefType {
+        match self.elem_type {
+            ElemKind::FuncRef => RefType::FuncRef,
+            ElemKind::RefType(t) => t,
+        }
+    }
+}
+
+impl Locate for Elem {
+    fn locate(&mut self, end: usize) {
+        if let ElemMode::Active { offset, .. } = &mut self.mode {
+            offset.locate(end);
+        }
+        if let ElemInit::Expr(exprs) = &mut self.init {
+            exprs.locate(end);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct ElementSection {
     pub elems: Vec<Elem>,
+    /// The spans of the element segments.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for ElementSection {
+    fn locate(&mut self, end: usize) {
+        self.elems.locate(end);
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-fn elemkind_parser(input: &[u8]) -> Res<ElemKind> {
+fn elemkind_parser(input: &[u8]) -> Res<'_, ElemKind> {
     context(
         "elemkind",
         alt((
@@ -59,13 +93,13 @@ fn elemkind_parser(input: &[u8]) -> Res<ElemKind> {
     )(input)
 }
 
-fn elem_parser(input: &[u8]) -> Res<Elem> {
+fn elem_parser(input: &[u8]) -> Res<'_, Elem> {
     context(
         "elem",
         alt((
             map(
                 preceded(
-                    tag([0x00]),
+                    flags(0),
                     tuple((
                         expr_parser,
                         length_count(vector_count_parser, funcidx_parser),
@@ -82,7 +116,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x01]),
+                    flags(1),
                     tuple((
                         elemkind_parser,
                         length_count(vector_count_parser, funcidx_parser),
@@ -96,7 +130,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x02]),
+                    flags(2),
                     tuple((
                         tableidx_parser,
                         expr_parser,
@@ -115,7 +149,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x03]),
+                    flags(3),
                     tuple((
                         elemkind_parser,
                         length_count(vector_count_parser, funcidx_parser),
@@ -129,7 +163,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x04]),
+                    flags(4),
                     tuple((expr_parser, length_count(vector_count_parser, expr_parser))),
                 ),
                 |(e, el)| Elem {
@@ -143,7 +177,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x05]),
+                    flags(5),
                     tuple((
                         reftype_parser,
                         length_count(vector_count_parser, expr_parser),
@@ -157,7 +191,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x06]),
+                    flags(6),
                     tuple((
                         tableidx_parser,
                         expr_parser,
@@ -176,7 +210,7 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
             ),
             map(
                 preceded(
-                    tag([0x07]),
+                    flags(7),
                     tuple((
                         reftype_parser,
                         length_count(vector_count_parser, expr_parser),
@@ -192,15 +226,53 @@ fn elem_parser(input: &[u8]) -> Res<Elem> {
     )(input)
 }
 
-pub fn element_section_parser(input: &[u8]) -> Res<ElementSection> {
+pub(crate) fn element_section_parser(input: &[u8]) -> Res<'_, ElementSection> {
     context(
         "element_section",
         map(
-            length_value(
-                section_length_parser(9),
-                length_count(vector_count_parser, elem_parser),
-            ),
-            |elems| ElementSection { elems },
+            spanned(section(9, entries(elem_parser))),
+            |((elems, spans), span)| ElementSection { elems, spans, span },
         ),
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::instructions::{Instr, ReferenceInstruction};
+
+    #[test]
+    fn test_element_section_exprs() {
+        let input = [
+            0x09, 0x12, // section id and size
+            0x02, // two segments
+            // Active segment for table 0 at offset `i32.const 0` containing
+            // `ref.func 0` and `ref.func 1`.
+            0x04, 0x41, 0x00, 0x0B, 0x02, 0xD2, 0x00, 0x0B, 0xD2, 0x01, 0x0B,
+            // Declarative funcref segment containing `ref.null func`.
+            0x07, 0x70, 0x01, 0xD0, 0x70, 0x0B,
+        ];
+        let (rest, section) = element_section_parser(&input).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(section.elems.len(), 2);
+
+        match &section.elems[0].init {
+            ElemInit::Expr(e) => {
+                assert_eq!(e.len(), 2);
+                assert!(matches!(
+                    &e[1].instr[0],
+                    Instr::Reference(r) if matches!(**r, ReferenceInstruction::RefFunc(FuncIdx(1)))
+                ));
+            }
+            other => panic!("unexpected init {:?}", other),
+        }
+        assert!(matches!(section.elems[1].mode, ElemMode::Declarative));
+        match &section.elems[1].init {
+            ElemInit::Expr(e) => assert!(matches!(
+                &e[0].instr[0],
+                Instr::Reference(r) if matches!(**r, ReferenceInstruction::RefNull(RefType::FuncRef))
+            )),
+            other => panic!("unexpected init {:?}", other),
+        }
+    }
+}
@@ -5,18 +5,18 @@ use nom::{
     bytes::complete::tag,
     combinator::map,
     error::context,
-    multi::{length_count, length_value},
     sequence::{preceded, tuple},
 };
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
     modules::{
         funcidx_parser, globalidx_parser, memidx_parser, tableidx_parser, FuncIdx, GlobalIdx,
         MemIdx, TableIdx,
     },
-    values::{name_parser, vector_count_parser},
-    Res,
+    spanned,
+    values::name_parser,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -33,12 +33,23 @@ pub struct Export<'a> {
     pub desc: ExportDesc,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ExportSection<'a> {
     pub exports: Vec<Export<'a>>,
+    /// The spans of the exports.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for ExportSection<'_> {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-fn export_desc_parser(input: &[u8]) -> Res<ExportDesc> {
+fn export_desc_parser(input: &[u8]) -> Res<'_, ExportDesc> {
     context(
         "export_desc",
         alt((
@@ -50,7 +61,7 @@ fn export_desc_parser(input: &[u8]) -> Res<ExportDesc> {
     )(input)
 }
 
-fn export_parser(input: &[u8]) -> Res<Export> {
+fn export_parser(input: &[u8]) -> Res<'_, Export<'_>> {
     context(
         "export",
         map(tuple((name_parser, export_desc_parser)), |(name, desc)| {
@@ -59,15 +70,16 @@ fn export_parser(input: &[u8]) -> Res<Export> {
     )(input)
 }
 
-pub fn export_section_parser(input: &[u8]) -> Res<ExportSection> {
+pub(crate) fn export_section_parser(input: &[u8]) -> Res<'_, ExportSection<'_>> {
     context(
         "export_section",
         map(
-            length_value(
-                section_length_parser(7),
-                length_count(vector_count_parser, export_parser),
-            ),
-            |exports| ExportSection { exports },
+            spanned(section(7, entries(export_parser))),
+            |((exports, spans), span)| ExportSection {
+                exports,
+                spans,
+                span,
+            },
         ),
     )(input)
 }
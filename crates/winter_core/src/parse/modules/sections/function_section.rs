@@ -1,32 +1,39 @@
 use std::vec::Vec;
 
-use nom::{
-    combinator::map,
-    error::context,
-    multi::{length_count, length_value},
-};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
     modules::{typeidx_parser, TypeIdx},
-    values::vector_count_parser,
-    Res,
+    spanned, Locate, Res, Span,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct FuncSection {
     pub functions: Vec<TypeIdx>,
+    /// The spans of the type indices of the functions.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for FuncSection {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-pub fn func_section_parser(input: &[u8]) -> Res<FuncSection> {
+pub(crate) fn func_section_parser(input: &[u8]) -> Res<'_, FuncSection> {
     context(
         "func_section",
         map(
-            length_value(
-                section_length_parser(3),
-                length_count(vector_count_parser, typeidx_parser),
-            ),
-            |functions| FuncSection { functions },
+            spanned(section(3, entries(typeidx_parser))),
+            |((functions, spans), span)| FuncSection {
+                functions,
+                spans,
+                span,
+            },
         ),
     )(input)
 }
@@ -1,18 +1,13 @@
 use std::vec::Vec;
 
-use nom::{
-    combinator::map,
-    error::context,
-    multi::{length_count, length_value},
-    sequence::tuple,
-};
+use nom::{combinator::map, error::context, sequence::tuple};
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
     instructions::{expr_parser, Expr},
+    spanned,
     types::{globaltype_parser, GlobalType},
-    values::vector_count_parser,
-    Res,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -21,12 +16,30 @@ pub struct Global {
     pub expr: Expr,
 }
 
-#[derive(Debug)]
+impl Locate for Global {
+    fn locate(&mut self, end: usize) {
+        self.expr.locate(end);
+    }
+}
+
+#[derive(Debug, Default)]
 pub struct GlobalSection {
     pub globals: Vec<Global>,
+    /// The spans of the globals.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for GlobalSection {
+    fn locate(&mut self, end: usize) {
+        self.globals.locate(end);
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-fn global_parser(input: &[u8]) -> Res<Global> {
+fn global_parser(input: &[u8]) -> Res<'_, Global> {
     context(
         "global",
         map(
@@ -36,15 +49,16 @@ fn global_parser(input: &[u8]) -> Res<Global> {
     )(input)
 }
 
-pub fn global_section_parser(input: &[u8]) -> Res<GlobalSection> {
+pub(crate) fn global_section_parser(input: &[u8]) -> Res<'_, GlobalSection> {
     context(
         "global_section",
         map(
-            length_value(
-                section_length_parser(6),
-                length_count(vector_count_parser, global_parser),
-            ),
-            |globals| GlobalSection { globals },
+            spanned(section(6, entries(global_parser))),
+            |((globals, spans), span)| GlobalSection {
+                globals,
+                spans,
+                span,
+            },
         ),
     )(input)
 }
@@ -5,16 +5,16 @@ use nom::{
     bytes::complete::tag,
     combinator::map,
     error::context,
-    multi::{length_count, length_value},
     sequence::{preceded, tuple},
 };
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
     modules::{typeidx_parser, TypeIdx},
+    spanned,
     types::{globaltype_parser, memtype_parser, tabletype_parser, GlobalType, MemType, TableType},
-    values::{name_parser, vector_count_parser},
-    Res,
+    values::name_parser,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -32,12 +32,23 @@ pub struct Import<'a> {
     pub desc: ImportDesc,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct ImportSection<'a> {
     pub imports: Vec<Import<'a>>,
+    /// The spans of the imports.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for ImportSection<'_> {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-fn import_desc_parser(input: &[u8]) -> Res<ImportDesc> {
+fn import_desc_parser(input: &[u8]) -> Res<'_, ImportDesc> {
     context(
         "import_desc",
         alt((
@@ -49,7 +60,7 @@ fn import_desc_parser(input: &[u8]) -> Res<ImportDesc> {
     )(input)
 }
 
-fn import_parser(input: &[u8]) -> Res<Import> {
+fn import_parser(input: &[u8]) -> Res<'_, Import<'_>> {
     context(
         "import",
         map(
@@ -63,15 +74,16 @@ fn import_parser(input: &[u8]) -> Res<Import> {
     )(input)
 }
 
-pub fn import_section_parser(input: &[u8]) -> Res<ImportSection> {
+pub(crate) fn import_section_parser(input: &[u8]) -> Res<'_, ImportSection<'_>> {
     context(
         "import_section",
         map(
-            length_value(
-                section_length_parser(2),
-                length_count(vector_count_parser, import_parser),
-            ),
-            |imports| ImportSection { imports },
+            spanned(section(2, entries(import_parser))),
+            |((imports, spans), span)| ImportSection {
+                imports,
+                spans,
+                span,
+            },
         ),
     )(input)
 }
@@ -1,16 +1,12 @@
 use std::vec::Vec;
 
-use nom::{
-    combinator::map,
-    error::context,
-    multi::{length_count, length_value},
-};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
+    spanned,
     types::{memtype_parser, MemType},
-    values::vector_count_parser,
-    Res,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -18,21 +14,31 @@ pub struct Mem {
     pub mem_type: MemType,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct MemSection {
     pub tables: Vec<Mem>,
+    /// The spans of the memories.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for MemSection {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-pub fn memory_section_parser(input: &[u8]) -> Res<MemSection> {
+pub(crate) fn memory_section_parser(input: &[u8]) -> Res<'_, MemSection> {
     context(
         "memory_section",
         map(
-            length_value(
-                section_length_parser(5),
-                length_count(vector_count_parser, memtype_parser),
-            ),
-            |f| MemSection {
+            spanned(section(5, entries(memtype_parser))),
+            |((f, spans), span)| MemSection {
                 tables: f.into_iter().map(|mem_type| Mem { mem_type }).collect(),
+                spans,
+                span,
             },
         ),
     )(input)
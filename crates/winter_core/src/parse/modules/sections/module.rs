@@ -1,32 +1,42 @@
 use std::vec::Vec;
 
-use nom::{
-    bytes::complete::tag,
-    combinator::opt,
-    error::{context, VerboseError},
-    multi::many0,
-    sequence::tuple,
-};
+use nom::error::ErrorKind;
 
 use super::{
-    code_section::{code_section_parser, CodeSection},
-    custom_section::{custom_section_parser, CustomSection},
-    data_count_section::{data_count_section_parser, DataCountSection},
-    data_section::{data_section_parser, DataSection},
-    element_section::{element_section_parser, ElementSection},
-    export_section::{export_section_parser, ExportSection},
-    function_section::{func_section_parser, FuncSection},
-    global_section::{global_section_parser, GlobalSection},
-    import_section::{import_section_parser, ImportSection},
-    memory_section::{memory_section_parser, MemSection},
-    start_section::{start_section_parser, StartSection},
-    table_section::{table_section_parser, TableSection},
-    type_section::{type_section_parser, TypeSection},
+    code_section::CodeSection,
+    custom_section::CustomSection,
+    data_count_section::DataCountSection,
+    data_section::DataSection,
+    element_section::ElementSection,
+    export_section::ExportSection,
+    function_section::FuncSection,
+    global_section::GlobalSection,
+    import_section::ImportSection,
+    memory_section::MemSection,
+    name_section::{name_section_parser, NameSection},
+    parser::{Chunk, Parser, Payload},
+    producers_section::{producers_section_parser, ProducersSection},
+    start_section::StartSection,
+    table_section::TableSection,
+    target_features_section::{target_features_section_parser, TargetFeaturesSection},
+    type_section::TypeSection,
+};
+use crate::parse::{
+    instructions::DEFAULT_MAX_NESTING_DEPTH, ErrorTrace, MalformedKind, ParseError, Res, Span,
 };
 
 #[derive(Default, Debug)]
 pub struct Module<'a> {
     pub custom_sections: Vec<CustomSection<'a>>,
+    /// The decoded `name` custom section, if the module has one that can be
+    /// decoded.
+    pub names: Option<NameSection<'a>>,
+    /// The decoded `producers` custom section, if the module has one that
+    /// can be decoded.
+    pub producers: Option<ProducersSection<'a>>,
+    /// The decoded `target_features` custom section, if the module has one
+    /// that can be decoded.
+    pub target_features: Option<TargetFeaturesSection<'a>>,
     pub func_type: Option<TypeSection>,
     pub import: Option<ImportSection<'a>>,
     pub type_idx: Option<FuncSection>,
@@ -37,73 +47,142 @@ pub struct Module<'a> {
     pub start: Option<StartSection>,
     pub elem: Option<ElementSection>,
     pub m: Option<DataCountSection>,
-    pub code: Option<CodeSection>,
+    pub code: Option<CodeSection<'a>>,
     pub data: Option<DataSection<'a>>,
 }
 
-pub fn module_parser(input: &[u8]) -> Result<Module, nom::Err<VerboseError<&[u8]>>> {
-    let result = tuple((
-        context("magic", tag([0x00, 0x61, 0x73, 0x6D])),
-        context("version", tag([0x01, 0x00, 0x00, 0x00])),
-        many0(custom_section_parser),
-        opt(type_section_parser),
-        many0(custom_section_parser),
-        opt(import_section_parser),
-        many0(custom_section_parser),
-        opt(func_section_parser),
-        many0(custom_section_parser),
-        opt(table_section_parser),
-        many0(custom_section_parser),
-        opt(memory_section_parser),
-        many0(custom_section_parser),
-        opt(global_section_parser),
-        many0(custom_section_parser),
-        opt(export_section_parser),
-        many0(custom_section_parser),
-        opt(start_section_parser),
-        tuple((
-            many0(custom_section_parser),
-            opt(element_section_parser),
-            many0(custom_section_parser),
-            opt(data_count_section_parser),
-            many0(custom_section_parser),
-            opt(code_section_parser),
-            many0(custom_section_parser),
-            opt(data_section_parser),
-            many0(custom_section_parser),
-        )),
-    ))(input)?
-    .1;
+/// Decodes the first custom section named `name` with `parser`. Custom
+/// sections that cannot be decoded are ignored.
+fn decode<'a, T>(
+    sections: &[CustomSection<'a>],
+    name: &str,
+    parser: impl Fn(&'a [u8]) -> Res<'a, T>,
+) -> Option<T> {
+    let section = sections.iter().find(|s| s.name == name)?;
+    parser(section.data).ok().map(|(_, value)| value)
+}
+
+impl<'a> Module<'a> {
+    /// Returns the features that the module declares in its
+    /// `target_features` section and that `winter_core` does not support.
+    /// Modules without the section declare no features.
+    pub fn unsupported_features(&self) -> Vec<&'a str> {
+        self.target_features
+            .as_ref()
+            .map(TargetFeaturesSection::unsupported)
+            .unwrap_or_default()
+    }
+
+    /// Decodes the body of every function, which is otherwise decoded on
+    /// first use, and returns the first error.
+    pub fn decode_funcs(&self) -> Result<(), ParseError> {
+        let code = self.code.as_ref().map(|c| c.code.as_slice());
+        code.unwrap_or_default()
+            .iter()
+            .try_for_each(|code| code.func().map(|_| ()))
+    }
+}
+
+/// Returns the error of the section with the context `label` at `span` in
+/// `input`, whose number of entries disagrees with another section.
+fn inconsistent(input: &[u8], span: Span, label: &'static str, kind: MalformedKind) -> ParseError {
+    let labels = [label, kind.message()];
+    let error = ErrorTrace::with_context(&input[span.start..], ErrorKind::Verify, &labels);
+    ParseError::new(input, nom::Err::Error(error))
+}
+
+/// Parses the binary module `input` with a [`Parser`].
+///
+/// The module must consist only of its known sections, each at most once
+/// and in order, with custom sections anywhere between them. The content of
+/// each section must match its size. The code section must have a body for
+/// each function of the function section, and the data section as many
+/// segments as the data count section declares, if present.
+///
+/// Returns a [`ParseError`] locating the failure in `input` if the module
+/// is malformed.
+pub fn module_parser(input: &[u8]) -> Result<Module<'_>, ParseError> {
+    nested_module_parser(input, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// Parses the binary module `input` like [`module_parser`], with at most
+/// `max_depth` structured instructions nested in one another in a function
+/// body. Deeper function bodies fail to decode with
+/// [`MalformedKind::NestingTooDeep`].
+pub fn nested_module_parser(input: &[u8], max_depth: u32) -> Result<Module<'_>, ParseError> {
+    let mut parser = Parser::new();
+    parser.set_max_nesting_depth(max_depth);
+    let mut m = Module::default();
+    let mut data = input;
+    loop {
+        let (consumed, payload) = match parser
+            .parse(data, true)
+            .map_err(|e| e.with_excerpt(input))?
+        {
+            Chunk::Parsed { consumed, payload } => (consumed, payload),
+            Chunk::NeedMoreData(_) => unreachable!("the whole module is parsed at once"),
+        };
+        data = &data[consumed..];
+        match payload {
+            Payload::Version(_) => {}
+            Payload::TypeSection(section) => m.func_type = Some(section),
+            Payload::ImportSection(section) => m.import = Some(section),
+            Payload::FuncSection(section) => m.type_idx = Some(section),
+            Payload::TableSection(section) => m.table = Some(section),
+            Payload::MemSection(section) => m.mem = Some(section),
+            Payload::GlobalSection(section) => m.global = Some(section),
+            Payload::ExportSection(section) => m.export = Some(section),
+            Payload::StartSection(section) => m.start = Some(section),
+            Payload::ElementSection(section) => m.elem = Some(section),
+            Payload::DataCountSection(section) => m.m = Some(section),
+            Payload::CodeSectionStart { span, .. } => {
+                m.code = Some(CodeSection {
+                    span,
+                    ..CodeSection::default()
+                })
+            }
+            Payload::CodeSectionEntry { code, span } => {
+                if let Some(section) = &mut m.code {
+                    section.spans.push(span);
+                    section.code.push(code);
+                }
+            }
+            Payload::DataSection(section) => m.data = Some(section),
+            Payload::CustomSection(section) => m.custom_sections.push(section),
+            Payload::End => break,
+        }
+    }
 
-    let mut m = Module {
-        func_type: result.3,
-        import: result.5,
-        type_idx: result.7,
-        table: result.9,
-        mem: result.11,
-        global: result.13,
-        export: result.15,
-        start: result.17,
-        elem: result.18 .1,
-        m: result.18 .3,
-        code: result.18 .5,
-        data: result.18 .7,
-        ..Default::default()
-    };
+    let funcs = m.type_idx.as_ref().map_or(0, |s| s.functions.len());
+    let codes = m.code.as_ref().map_or(0, |s| s.code.len());
+    if funcs != codes {
+        let (label, span) = match (&m.code, &m.type_idx) {
+            (Some(code), _) => ("code_section", code.span),
+            (None, Some(funcs)) => ("func_section", funcs.span),
+            (None, None) => unreachable!("a function without a section"),
+        };
+        let kind = MalformedKind::InconsistentFunctionCount;
+        return Err(inconsistent(input, span, label, kind));
+    }
+    if let Some(count) = &m.m {
+        let segments = m.data.as_ref().map_or(0, |s| s.data.len());
+        if count.count as usize != segments {
+            let (label, span) = match &m.data {
+                Some(data) => ("data_section", data.span),
+                None => ("data_count", count.span),
+            };
+            let kind = MalformedKind::InconsistentDataCount;
+            return Err(inconsistent(input, span, label, kind));
+        }
+    }
 
-    m.custom_sections.extend(result.2.into_iter());
-    m.custom_sections.extend(result.4.into_iter());
-    m.custom_sections.extend(result.6.into_iter());
-    m.custom_sections.extend(result.8.into_iter());
-    m.custom_sections.extend(result.10.into_iter());
-    m.custom_sections.extend(result.12.into_iter());
-    m.custom_sections.extend(result.14.into_iter());
-    m.custom_sections.extend(result.16.into_iter());
-    m.custom_sections.extend(result.18 .0.into_iter());
-    m.custom_sections.extend(result.18 .2.into_iter());
-    m.custom_sections.extend(result.18 .4.into_iter());
-    m.custom_sections.extend(result.18 .6.into_iter());
-    m.custom_sections.extend(result.18 .8.into_iter());
+    m.names = decode(&m.custom_sections, "name", name_section_parser);
+    m.producers = decode(&m.custom_sections, "producers", producers_section_parser);
+    m.target_features = decode(
+        &m.custom_sections,
+        "target_features",
+        target_features_section_parser,
+    );
 
     Ok(m)
 }
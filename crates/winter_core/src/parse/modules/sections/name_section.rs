@@ -0,0 +1,179 @@
+use std::vec::Vec;
+
+use nom::{
+    combinator::{map, verify},
+    error::context,
+    multi::length_count,
+    number::complete::u8 as byte,
+    sequence::tuple,
+};
+
+use crate::parse::{
+    values::{leb128_u32, name_parser, vector_count_parser, vector_parser},
+    Res,
+};
+
+/// Names of the definitions of an index space, by increasing index. Maps
+/// whose indices are not increasing are not decoded.
+///
+/// [Reference](https://webassembly.github.io/spec/core/appendix/custom.html#name-maps)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NameMap<'a> {
+    pub names: Vec<(u32, &'a str)>,
+}
+
+impl<'a> NameMap<'a> {
+    /// Returns the name of the definition at `index`.
+    pub fn get(&self, index: u32) -> Option<&'a str> {
+        let i = self.names.binary_search_by_key(&index, |(i, _)| *i).ok()?;
+        Some(self.names[i].1)
+    }
+}
+
+/// Names of the definitions of index spaces local to another definition,
+/// such as the locals of each function, by increasing index of the outer
+/// definition.
+///
+/// [Reference](https://webassembly.github.io/spec/core/appendix/custom.html#name-maps)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IndirectNameMap<'a> {
+    pub maps: Vec<(u32, NameMap<'a>)>,
+}
+
+impl<'a> IndirectNameMap<'a> {
+    /// Returns the names local to the definition at `index`.
+    pub fn map(&self, index: u32) -> Option<&NameMap<'a>> {
+        let i = self.maps.binary_search_by_key(&index, |(i, _)| *i).ok()?;
+        Some(&self.maps[i].1)
+    }
+
+    /// Returns the name of the definition at `inner` local to the
+    /// definition at `outer`.
+    pub fn get(&self, outer: u32, inner: u32) -> Option<&'a str> {
+        self.map(outer)?.get(inner)
+    }
+}
+
+/// The decoded `name` custom section. It includes the subsections of the
+/// extended name section proposal, which names labels, types, tables,
+/// memories, globals and segments.
+///
+/// The section is decoded in addition to being kept as a
+/// [`CustomSection`](super::custom_section::CustomSection), which is the
+/// one that is encoded.
+///
+/// [Reference](https://webassembly.github.io/spec/core/appendix/custom.html#name-section)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NameSection<'a> {
+    pub module: Option<&'a str>,
+    pub funcs: NameMap<'a>,
+    /// Names of the locals of each function, parameters included.
+    pub locals: IndirectNameMap<'a>,
+    /// Names of the labels of each function, indexed by the order in which
+    /// the blocks are introduced in the body.
+    pub labels: IndirectNameMap<'a>,
+    pub types: NameMap<'a>,
+    pub tables: NameMap<'a>,
+    pub mems: NameMap<'a>,
+    pub globals: NameMap<'a>,
+    pub elems: NameMap<'a>,
+    pub datas: NameMap<'a>,
+}
+
+/// Returns whether the indices of `entries` are strictly increasing.
+fn is_increasing<T>(entries: &[(u32, T)]) -> bool {
+    entries.windows(2).all(|w| w[0].0 < w[1].0)
+}
+
+fn name_map_parser(input: &[u8]) -> Res<'_, NameMap<'_>> {
+    context(
+        "name_map",
+        map(
+            verify(
+                length_count(vector_count_parser, tuple((leb128_u32, name_parser))),
+                |names: &Vec<_>| is_increasing(names),
+            ),
+            |names| NameMap { names },
+        ),
+    )(input)
+}
+
+fn indirect_name_map_parser(input: &[u8]) -> Res<'_, IndirectNameMap<'_>> {
+    context(
+        "indirect_name_map",
+        map(
+            verify(
+                length_count(vector_count_parser, tuple((leb128_u32, name_map_parser))),
+                |maps: &Vec<_>| is_increasing(maps),
+            ),
+            |maps| IndirectNameMap { maps },
+        ),
+    )(input)
+}
+
+/// Replaces `field` with the value decoded from `data` by `parser`, unless
+/// it fails.
+fn decode<'a, T>(data: &'a [u8], mut parser: impl FnMut(&'a [u8]) -> Res<'a, T>, field: &mut T) {
+    if let Ok((_, value)) = parser(data) {
+        *field = value;
+    }
+}
+
+/// Parses the content of the `name` custom section, after its name.
+///
+/// Errors in custom sections do not make a module malformed, so
+/// subsections that cannot be decoded or that have an unknown id are
+/// skipped. Only an error in the framing of the subsections is returned.
+pub fn name_section_parser(mut input: &[u8]) -> Res<'_, NameSection<'_>> {
+    let mut section = NameSection::default();
+    while !input.is_empty() {
+        let (rest, (id, data)) = context("name_subsection", tuple((byte, vector_parser)))(input)?;
+        match id {
+            0 => decode(data, map(name_parser, Some), &mut section.module),
+            1 => decode(data, name_map_parser, &mut section.funcs),
+            2 => decode(data, indirect_name_map_parser, &mut section.locals),
+            3 => decode(data, indirect_name_map_parser, &mut section.labels),
+            4 => decode(data, name_map_parser, &mut section.types),
+            5 => decode(data, name_map_parser, &mut section.tables),
+            6 => decode(data, name_map_parser, &mut section.mems),
+            7 => decode(data, name_map_parser, &mut section.globals),
+            8 => decode(data, name_map_parser, &mut section.elems),
+            9 => decode(data, name_map_parser, &mut section.datas),
+            _ => {}
+        }
+        input = rest;
+    }
+    Ok((input, section))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_section_parser() {
+        let data = [
+            0x00, 0x02, 0x01, b'm', // module name
+            0x01, 0x05, 0x01, 0x00, 0x02, b'f', b'0', // function 0
+            0x02, 0x09, 0x01, 0x00, 0x02, 0x00, 0x01, b'a', 0x01, 0x01, b'b', // locals
+            0x03, 0x01, 0xFF, // labels, undecodable
+            0x0C, 0x01, 0x00, // unknown subsection
+            0x07, 0x04, 0x01, 0x03, 0x01, b'g', // global 3
+        ];
+        let (rest, section) = name_section_parser(&data).unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(section.module, Some("m"));
+        assert_eq!(section.funcs.get(0), Some("f0"));
+        assert_eq!(section.funcs.get(1), None);
+        assert_eq!(section.locals.get(0, 1), Some("b"));
+        assert_eq!(section.labels, IndirectNameMap::default());
+        assert_eq!(section.globals.get(3), Some("g"));
+
+        assert!(name_section_parser(&[0x01, 0x05, 0x00]).is_err());
+
+        // Function names whose indices are not increasing are skipped.
+        let data = [0x01, 0x07, 0x02, 0x01, 0x01, b'a', 0x00, 0x01, b'b'];
+        let (_, section) = name_section_parser(&data).unwrap();
+        assert_eq!(section.funcs, NameMap::default());
+    }
+}
@@ -0,0 +1,555 @@
+use nom::{
+    bytes::complete::{tag, take},
+    combinator::{cut, fail, map, verify},
+    error::{context, ErrorKind},
+    number::complete::{le_u32, u8 as byte},
+    sequence::{pair, preceded},
+};
+
+use super::{
+    code_section::{code_parser, Code},
+    custom_section::{custom_section_parser, CustomSection},
+    data_count_section::{data_count_section_parser, DataCountSection},
+    data_section::{data_section_parser, DataSection},
+    element_section::{element_section_parser, ElementSection},
+    export_section::{export_section_parser, ExportSection},
+    function_section::{func_section_parser, FuncSection},
+    global_section::{global_section_parser, GlobalSection},
+    import_section::{import_section_parser, ImportSection},
+    memory_section::{memory_section_parser, MemSection},
+    start_section::{start_section_parser, StartSection},
+    table_section::{table_section_parser, TableSection},
+    type_section::{type_section_parser, TypeSection},
+};
+use crate::parse::{
+    instructions::DEFAULT_MAX_NESTING_DEPTH,
+    values::{leb128_u32, vector_count_parser},
+    ErrorTrace, Locate, ParseError, Res, Span, SECTIONS,
+};
+
+/// Size of the preamble of a module, its magic number and version.
+const HEADER_SIZE: usize = 8;
+
+/// Id of the last known section.
+const MAX_SECTION_ID: u8 = 12;
+
+/// Id of the code section, whose entries are parsed one at a time.
+const CODE_SECTION_ID: u8 = 10;
+
+/// A part of a binary module, yielded by a [`Parser`] as soon as all its
+/// bytes are available.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#binary-module)
+#[derive(Debug)]
+pub enum Payload<'a> {
+    /// The preamble of the module, with the version of the binary format.
+    Version(u32),
+    TypeSection(TypeSection),
+    ImportSection(ImportSection<'a>),
+    FuncSection(FuncSection),
+    TableSection(TableSection),
+    MemSection(MemSection),
+    GlobalSection(GlobalSection),
+    ExportSection(ExportSection<'a>),
+    StartSection(StartSection),
+    ElementSection(ElementSection),
+    DataCountSection(DataCountSection),
+    /// The start of the code section, of `size` bytes, followed by a
+    /// [`Payload::CodeSectionEntry`] for each of its `count` entries.
+    /// `span` is the span of the whole section.
+    CodeSectionStart {
+        count: u32,
+        size: u32,
+        span: Span,
+    },
+    /// An entry of the code section. `span` is the span of the entry, from
+    /// the size of the function.
+    CodeSectionEntry {
+        code: Code<'a>,
+        span: Span,
+    },
+    DataSection(DataSection<'a>),
+    CustomSection(CustomSection<'a>),
+    /// The end of the module.
+    End,
+}
+
+impl Locate for Payload<'_> {
+    fn locate(&mut self, end: usize) {
+        match self {
+            Payload::TypeSection(section) => section.locate(end),
+            Payload::ImportSection(section) => section.locate(end),
+            Payload::FuncSection(section) => section.locate(end),
+            Payload::TableSection(section) => section.locate(end),
+            Payload::MemSection(section) => section.locate(end),
+            Payload::GlobalSection(section) => section.locate(end),
+            Payload::ExportSection(section) => section.locate(end),
+            Payload::StartSection(section) => section.locate(end),
+            Payload::ElementSection(section) => section.locate(end),
+            Payload::DataCountSection(section) => section.locate(end),
+            Payload::CodeSectionEntry { code, span } => {
+                code.locate(end);
+                span.locate(end);
+            }
+            Payload::DataSection(section) => section.locate(end),
+            Payload::CustomSection(section) => section.locate(end),
+            Payload::Version(_) | Payload::CodeSectionStart { .. } | Payload::End => {}
+        }
+    }
+}
+
+/// The result of [`Parser::parse`].
+#[derive(Debug)]
+pub enum Chunk<'a> {
+    /// At least `hint` more bytes are needed to parse the next payload.
+    NeedMoreData(usize),
+    /// `payload` was parsed from the first `consumed` bytes of the data.
+    Parsed {
+        consumed: usize,
+        payload: Payload<'a>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Header,
+    Sections,
+    /// In the code section, which ends at the offset `end`, with
+    /// `remaining` entries left from the entry at `index`.
+    Code {
+        remaining: u32,
+        index: u32,
+        end: usize,
+    },
+    End,
+}
+
+/// An incremental parser of binary modules, which yields the parts of a
+/// module as the module is received.
+///
+/// The data given to [`Parser::parse`] must start where the previous
+/// payload ended: the caller drops the `consumed` bytes of each parsed
+/// payload, and gives the same bytes with more appended when more data is
+/// needed.
+#[derive(Debug)]
+pub struct Parser {
+    state: State,
+    /// Offset in the module of the data given to `parse`.
+    offset: usize,
+    /// Id of the last known section parsed, or 0 before the first one.
+    last: u8,
+    /// Whether the module has a data count section.
+    data_count: bool,
+    max_nesting_depth: u32,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the position of the known section with id `id` in a module.
+/// The data count section comes before the code section.
+fn order(id: u8) -> u8 {
+    match id {
+        12 => 10,
+        10 | 11 => id + 1,
+        _ => id,
+    }
+}
+
+/// The magic number that starts every module, `\0asm`.
+const MAGIC: [u8; 4] = [0x00, 0x61, 0x73, 0x6D];
+
+/// Parses the magic number and the version of a module. A module shorter
+/// than the magic number ends unexpectedly.
+///
+/// [Reference](https://webassembly.github.io/spec/core/binary/modules.html#binary-module)
+fn header_parser(input: &[u8]) -> Res<'_, u32> {
+    preceded(
+        context("magic", verify(take(4usize), |magic: &[u8]| magic == MAGIC)),
+        context("version", verify(le_u32, |version| *version == 1)),
+    )(input)
+}
+
+/// Parses the size and number of entries of the code section. Returns the
+/// size of the section, the number of entries and the size of the entries.
+fn code_section_size_parser(input: &[u8]) -> Res<'_, (u32, u32, u32)> {
+    let (rest, size) = leb128_u32(input)?;
+    let content = &rest[..rest.len().min(size as usize)];
+    let (remaining, count) = vector_count_parser(content)?;
+    let count_size = content.len() - remaining.len();
+    Ok((&rest[count_size..], (size, count, size - count_size as u32)))
+}
+
+/// Parses the start of the code section, up to its first entry.
+fn code_section_start_parser(input: &[u8]) -> Res<'_, (u32, u32, u32)> {
+    context(
+        "code_section",
+        preceded(tag([CODE_SECTION_ID]), cut(code_section_size_parser)),
+    )(input)
+}
+
+/// Parses the known section or custom section at the start of `input`.
+fn section_parser(id: u8, input: &[u8]) -> Res<'_, Payload<'_>> {
+    match id {
+        0 => map(custom_section_parser, Payload::CustomSection)(input),
+        1 => map(type_section_parser, Payload::TypeSection)(input),
+        2 => map(import_section_parser, Payload::ImportSection)(input),
+        3 => map(func_section_parser, Payload::FuncSection)(input),
+        4 => map(table_section_parser, Payload::TableSection)(input),
+        5 => map(memory_section_parser, Payload::MemSection)(input),
+        6 => map(global_section_parser, Payload::GlobalSection)(input),
+        7 => map(export_section_parser, Payload::ExportSection)(input),
+        8 => map(start_section_parser, Payload::StartSection)(input),
+        9 => map(element_section_parser, Payload::ElementSection)(input),
+        11 => map(data_section_parser, Payload::DataSection)(input),
+        12 => map(data_count_section_parser, Payload::DataCountSection)(input),
+        _ => context("malformed section id", fail)(input),
+    }
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Parser {
+            state: State::Header,
+            offset: 0,
+            last: 0,
+            data_count: false,
+            max_nesting_depth: DEFAULT_MAX_NESTING_DEPTH,
+        }
+    }
+
+    /// Sets the maximum number of structured instructions nested in one
+    /// another in a function body, [`DEFAULT_MAX_NESTING_DEPTH`] by
+    /// default. Deeper function bodies fail to decode with
+    /// [`MalformedKind::NestingTooDeep`]. Constant expressions, which have
+    /// no structured instructions when valid, keep the default limit.
+    pub fn set_max_nesting_depth(&mut self, depth: u32) {
+        self.max_nesting_depth = depth;
+    }
+
+    /// Parses the next payload from `data`, the bytes of the module
+    /// received since the end of the previous payload. `eof` tells whether
+    /// the module ends with `data`.
+    ///
+    /// Returns [`Chunk::NeedMoreData`] if `data` ends before the payload
+    /// and `eof` is false, and a [`ParseError`] located in the whole module
+    /// if the module is malformed.
+    pub fn parse<'a>(&mut self, data: &'a [u8], eof: bool) -> Result<Chunk<'a>, ParseError> {
+        let chunk = match self.state {
+            State::Header if data.len() < HEADER_SIZE && !eof => {
+                Chunk::NeedMoreData(HEADER_SIZE - data.len())
+            }
+            State::Header => {
+                let parsed = self.run(data, usize::MAX, eof, &[], header_parser)?;
+                if parsed.is_some() {
+                    self.state = State::Sections;
+                }
+                chunk(parsed.map(|(consumed, version)| (consumed, Payload::Version(version))))
+            }
+            State::Sections => match data.first() {
+                None if eof => {
+                    self.state = State::End;
+                    chunk(Some((0, Payload::End)))
+                }
+                None => Chunk::NeedMoreData(1),
+                Some(&CODE_SECTION_ID) => self.code_section_start(data, eof)?,
+                Some(&id) => self.section(id, data, eof)?,
+            },
+            State::Code {
+                remaining: 0, end, ..
+            } => {
+                if end != self.offset {
+                    return Err(self.fail(data, &["code_section", "section size mismatch"]));
+                }
+                self.state = State::Sections;
+                return self.parse(data, eof);
+            }
+            State::Code {
+                remaining,
+                index,
+                end,
+            } => self.code_section_entry(data, eof, remaining, index, end)?,
+            State::End => chunk(Some((0, Payload::End))),
+        };
+        if let Chunk::Parsed { consumed, .. } = chunk {
+            self.offset += consumed;
+        }
+        Ok(chunk)
+    }
+
+    /// Parses the known section with id `id` or the custom section at the
+    /// start of `data`, once all its bytes are available.
+    fn section<'a>(&mut self, id: u8, data: &'a [u8], eof: bool) -> Result<Chunk<'a>, ParseError> {
+        if id != 0 && id <= MAX_SECTION_ID && order(id) <= order(self.last) {
+            return Err(self.fail(data, &["junk after last section"]));
+        }
+        // If the size cannot be read, the section parser reports why.
+        let header: Res<(u8, u32)> = pair(byte, leb128_u32)(data);
+        let limit = match header {
+            Ok((rest, (_, size))) => data.len() - rest.len() + size as usize,
+            Err(nom::Err::Incomplete(_)) if !eof => return Ok(Chunk::NeedMoreData(1)),
+            Err(_) => usize::MAX,
+        };
+        if limit != usize::MAX && limit > data.len() && !eof {
+            return Ok(Chunk::NeedMoreData(limit - data.len()));
+        }
+        let label = SECTIONS
+            .iter()
+            .find(|(_, i)| *i == id)
+            .map(|(label, _)| *label);
+        let parsed = self.run(data, limit, eof, label.as_slice(), |input| {
+            section_parser(id, input)
+        })?;
+        Ok(chunk(parsed.map(|(consumed, mut payload)| {
+            // The section parser consumes the whole section.
+            payload.locate(self.offset + consumed);
+            match payload {
+                Payload::CustomSection(section) => (
+                    consumed,
+                    Payload::CustomSection(CustomSection {
+                        after: self.last,
+                        ..section
+                    }),
+                ),
+                payload => {
+                    self.last = id;
+                    self.data_count |= id == 12;
+                    (consumed, payload)
+                }
+            }
+        })))
+    }
+
+    /// Parses the start of the code section at the start of `data`, up to
+    /// its first entry.
+    fn code_section_start<'a>(
+        &mut self,
+        data: &'a [u8],
+        eof: bool,
+    ) -> Result<Chunk<'a>, ParseError> {
+        if order(CODE_SECTION_ID) <= order(self.last) {
+            return Err(self.fail(data, &["junk after last section"]));
+        }
+        let parsed = self.run(
+            data,
+            usize::MAX,
+            eof,
+            &["code_section"],
+            code_section_start_parser,
+        )?;
+        Ok(chunk(parsed.map(
+            |(consumed, (size, count, entries_size))| {
+                self.last = CODE_SECTION_ID;
+                let end = self.offset + consumed + entries_size as usize;
+                self.state = State::Code {
+                    remaining: count,
+                    index: 0,
+                    end,
+                };
+                let span = Span {
+                    start: self.offset,
+                    end,
+                };
+                (consumed, Payload::CodeSectionStart { count, size, span })
+            },
+        )))
+    }
+
+    /// Parses the entry at `index` of the code section, which ends at the
+    /// offset `end` with `remaining` entries left.
+    fn code_section_entry<'a>(
+        &mut self,
+        data: &'a [u8],
+        eof: bool,
+        remaining: u32,
+        index: u32,
+        end: usize,
+    ) -> Result<Chunk<'a>, ParseError> {
+        // Once its size is read, the entry is parsed when all its bytes are
+        // available. An entry larger than the section fails at once.
+        let limit = end - self.offset;
+        let size: Res<u32> = leb128_u32(&data[..data.len().min(limit)]);
+        if let Ok((rest, size)) = size {
+            let entry_end = data.len().min(limit) - rest.len() + size as usize;
+            if entry_end <= limit && entry_end > data.len() && !eof {
+                return Ok(Chunk::NeedMoreData(entry_end - data.len()));
+            }
+        }
+        let entry = |input| {
+            context("code_section", |input| {
+                code_parser(input).map_err(|e| e.map(|e| e.entry(input, index)))
+            })(input)
+        };
+        let parsed = self.run(data, limit, eof, &["code_section"], entry)?;
+        if parsed.is_some() {
+            self.state = State::Code {
+                remaining: remaining - 1,
+                index: index + 1,
+                end,
+            };
+        }
+        Ok(chunk(parsed.map(|(consumed, mut code)| {
+            // The code section ends at `end`, where the input of the entry
+            // parser ends.
+            code.locate(end);
+            code.max_nesting_depth = self.max_nesting_depth;
+            code.data_count = self.data_count;
+            let span = Span {
+                start: self.offset,
+                end: self.offset + consumed,
+            };
+            (consumed, Payload::CodeSectionEntry { code, span })
+        })))
+    }
+
+    /// Applies `parser` to the first `limit` bytes of `data`. Returns the
+    /// number of bytes consumed and the output of the parser, or `None` if
+    /// the parser needs more bytes, `data` ends before `limit` and `eof` is
+    /// false. A parser that needs more bytes than there are fails at the
+    /// end of its input in the contexts `labels`.
+    fn run<'a, O>(
+        &self,
+        data: &'a [u8],
+        limit: usize,
+        eof: bool,
+        labels: &[&'static str],
+        mut parser: impl FnMut(&'a [u8]) -> Res<'a, O>,
+    ) -> Result<Option<(usize, O)>, ParseError> {
+        let input = &data[..data.len().min(limit)];
+        match parser(input) {
+            Ok((rest, output)) => Ok(Some((input.len() - rest.len(), output))),
+            Err(nom::Err::Incomplete(_)) if limit > data.len() && !eof => Ok(None),
+            Err(e) => Err(self.error(data, ErrorTrace::complete(input, labels, e))),
+        }
+    }
+
+    /// Locates the failure `err` of a parser applied to `data` in the
+    /// module.
+    fn error(&self, data: &[u8], err: nom::Err<ErrorTrace>) -> ParseError {
+        ParseError::new(data, err).offset_by(self.offset)
+    }
+
+    /// Returns the error of a failure at the start of `data` in the
+    /// contexts `labels`, outermost first.
+    fn fail(&self, data: &[u8], labels: &[&'static str]) -> ParseError {
+        let error = ErrorTrace::with_context(data, ErrorKind::Fail, labels);
+        self.error(data, nom::Err::Error(error))
+    }
+}
+
+/// Returns the chunk of the payload `parsed`, if it could be parsed.
+fn chunk(parsed: Option<(usize, Payload)>) -> Chunk {
+    match parsed {
+        Some((consumed, payload)) => Chunk::Parsed { consumed, payload },
+        None => Chunk::NeedMoreData(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encode::encode,
+        parse::{modules::module::nested_module_parser, MalformedKind},
+        text::{module_parser, ParseBuffer},
+    };
+
+    /// Parses `binary` received `step` bytes at a time, and returns the
+    /// names of the payloads.
+    fn payloads(binary: &[u8], step: usize) -> Result<Vec<&'static str>, ParseError> {
+        let mut parser = Parser::new();
+        let (mut start, mut received) = (0, 0);
+        let mut names = Vec::new();
+        loop {
+            let eof = received == binary.len();
+            let (consumed, payload) = match parser.parse(&binary[start..received], eof)? {
+                Chunk::NeedMoreData(_) => {
+                    assert!(!eof);
+                    received = binary.len().min(received + step);
+                    continue;
+                }
+                Chunk::Parsed { consumed, payload } => (consumed, payload),
+            };
+            start += consumed;
+            names.push(match payload {
+                Payload::Version(_) => "version",
+                Payload::TypeSection(_) => "type",
+                Payload::FuncSection(_) => "func",
+                Payload::MemSection(_) => "memory",
+                Payload::CodeSectionStart { count: 2, .. } => "code",
+                Payload::CodeSectionEntry { .. } => "code entry",
+                Payload::DataSection(_) => "data",
+                Payload::CustomSection(_) => "custom",
+                Payload::End => break,
+                _ => "other",
+            });
+        }
+        Ok(names)
+    }
+
+    #[test]
+    fn test_parser() {
+        let source = r#"(module (func) (func nop) (memory 1) (data (i32.const 0) "hi"))"#;
+        let buffer = ParseBuffer::new(source).unwrap();
+        let mut binary = encode(&module_parser(&buffer).unwrap());
+        for step in [1, 3, binary.len()] {
+            assert_eq!(
+                payloads(&binary, step).unwrap(),
+                [
+                    "version",
+                    "type",
+                    "func",
+                    "memory",
+                    "code",
+                    "code entry",
+                    "code entry",
+                    "data"
+                ]
+            );
+        }
+
+        binary.extend([0x00, 0x02, 0x01]);
+        let error = payloads(&binary, 1).unwrap_err();
+        assert_eq!(error.offset, binary.len() - 1);
+        assert_eq!(error.malformed, MalformedKind::UnexpectedEndOfSection);
+        binary.truncate(binary.len() - 3);
+        binary.extend([0x0D, 0x00]);
+        let error = payloads(&binary, 1).unwrap_err();
+        assert_eq!(error.offset, binary.len() - 2);
+        assert_eq!(error.malformed, MalformedKind::MalformedSectionId);
+    }
+
+    #[test]
+    fn test_max_nesting_depth() {
+        let source = r#"(module (func (block (block))))"#;
+        let buffer = ParseBuffer::new(source).unwrap();
+        let binary = encode(&module_parser(&buffer).unwrap());
+        for (depth, ok) in [(1, false), (2, true)] {
+            let mut parser = Parser::new();
+            parser.set_max_nesting_depth(depth);
+            let mut data = &binary[..];
+            let code = loop {
+                match parser.parse(data, true).unwrap() {
+                    Chunk::Parsed {
+                        payload: Payload::CodeSectionEntry { code, .. },
+                        ..
+                    } => break code,
+                    Chunk::Parsed { consumed, .. } => data = &data[consumed..],
+                    Chunk::NeedMoreData(_) => unreachable!(),
+                }
+            };
+            match code.func() {
+                Ok(_) => assert!(ok),
+                Err(e) => {
+                    assert!(!ok);
+                    assert_eq!(e.malformed, MalformedKind::NestingTooDeep);
+                }
+            }
+            let module = nested_module_parser(&binary, depth).unwrap();
+            assert_eq!(module.decode_funcs().is_ok(), ok);
+        }
+    }
+}
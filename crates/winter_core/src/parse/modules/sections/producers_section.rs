@@ -0,0 +1,120 @@
+use std::vec::Vec;
+
+use nom::{
+    combinator::{all_consuming, map},
+    error::context,
+    multi::length_count,
+    sequence::tuple,
+};
+
+use crate::parse::{
+    values::{name_parser, vector_count_parser},
+    Res,
+};
+
+/// A tool or language with its version, such as `rustc` `1.70.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProducerValue<'a> {
+    pub name: &'a str,
+    pub version: &'a str,
+}
+
+/// A field of the producers section, such as `language`, `processed-by` or
+/// `sdk`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProducersField<'a> {
+    pub name: &'a str,
+    pub values: Vec<ProducerValue<'a>>,
+}
+
+/// The decoded `producers` custom section, which records the toolchain that
+/// produced the module.
+///
+/// [Reference](https://github.com/WebAssembly/tool-conventions/blob/main/ProducersSection.md)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProducersSection<'a> {
+    pub fields: Vec<ProducersField<'a>>,
+}
+
+impl<'a> ProducersSection<'a> {
+    /// Returns the values of the field `name`.
+    pub fn field(&self, name: &str) -> &[ProducerValue<'a>] {
+        self.fields
+            .iter()
+            .find(|f| f.name == name)
+            .map_or(&[], |f| &f.values)
+    }
+
+    /// Returns the source languages of the module.
+    pub fn language(&self) -> &[ProducerValue<'a>] {
+        self.field("language")
+    }
+
+    /// Returns the tools that produced or transformed the module.
+    pub fn processed_by(&self) -> &[ProducerValue<'a>] {
+        self.field("processed-by")
+    }
+
+    /// Returns the SDKs the module was built with.
+    pub fn sdk(&self) -> &[ProducerValue<'a>] {
+        self.field("sdk")
+    }
+}
+
+fn producer_value_parser(input: &[u8]) -> Res<'_, ProducerValue<'_>> {
+    context(
+        "producer_value",
+        map(tuple((name_parser, name_parser)), |(name, version)| {
+            ProducerValue { name, version }
+        }),
+    )(input)
+}
+
+fn producers_field_parser(input: &[u8]) -> Res<'_, ProducersField<'_>> {
+    context(
+        "producers_field",
+        map(
+            tuple((
+                name_parser,
+                length_count(vector_count_parser, producer_value_parser),
+            )),
+            |(name, values)| ProducersField { name, values },
+        ),
+    )(input)
+}
+
+/// Parses the content of the `producers` custom section, after its name.
+pub fn producers_section_parser(input: &[u8]) -> Res<'_, ProducersSection<'_>> {
+    context(
+        "producers_section",
+        map(
+            all_consuming(length_count(vector_count_parser, producers_field_parser)),
+            |fields| ProducersSection { fields },
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_producers_section_parser() {
+        let mut data = vec![0x02];
+        data.extend(b"\x08language\x01\x04Rust\x00");
+        data.extend(b"\x0Cprocessed-by\x01\x05rustc\x061.70.0");
+        let (_, section) = producers_section_parser(&data).unwrap();
+        assert_eq!(
+            section.language(),
+            [ProducerValue {
+                name: "Rust",
+                version: ""
+            }]
+        );
+        assert_eq!(section.processed_by()[0].version, "1.70.0");
+        assert!(section.sdk().is_empty());
+
+        data.push(0x00);
+        assert!(producers_section_parser(&data).is_err());
+    }
+}
@@ -1,22 +1,29 @@
-use nom::{combinator::map, error::context, multi::length_value};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
+use super::section;
 use crate::parse::{
     modules::{funcidx_parser, FuncIdx},
-    Res,
+    spanned, Locate, Res, Span,
 };
 
 #[derive(Debug)]
 pub struct StartSection {
     pub start: FuncIdx,
+    /// The span of the section, from its id.
+    pub span: Span,
 }
 
-pub fn start_section_parser(input: &[u8]) -> Res<StartSection> {
+impl Locate for StartSection {
+    fn locate(&mut self, end: usize) {
+        self.span.locate(end);
+    }
+}
+
+pub(crate) fn start_section_parser(input: &[u8]) -> Res<'_, StartSection> {
     context(
         "start_section",
-        map(
-            length_value(section_length_parser(8), funcidx_parser),
-            |start| StartSection { start },
-        ),
+        map(spanned(section(8, funcidx_parser)), |(start, span)| {
+            StartSection { start, span }
+        }),
     )(input)
 }
@@ -1,16 +1,12 @@
 use std::vec::Vec;
 
-use nom::{
-    combinator::map,
-    error::context,
-    multi::{length_count, length_value},
-};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
+    spanned,
     types::{tabletype_parser, TableType},
-    values::vector_count_parser,
-    Res,
+    Locate, Res, Span,
 };
 
 #[derive(Debug)]
@@ -18,24 +14,34 @@ pub struct Table {
     pub table_type: TableType,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct TableSection {
     pub tables: Vec<Table>,
+    /// The spans of the tables.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for TableSection {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-pub fn table_section_parser(input: &[u8]) -> Res<TableSection> {
+pub(crate) fn table_section_parser(input: &[u8]) -> Res<'_, TableSection> {
     context(
         "table_section",
         map(
-            length_value(
-                section_length_parser(4),
-                length_count(vector_count_parser, tabletype_parser),
-            ),
-            |f| TableSection {
+            spanned(section(4, entries(tabletype_parser))),
+            |((f, spans), span)| TableSection {
                 tables: f
                     .into_iter()
                     .map(|table_type| Table { table_type })
                     .collect(),
+                spans,
+                span,
             },
         ),
     )(input)
@@ -0,0 +1,132 @@
+use std::vec::Vec;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{all_consuming, map, value},
+    error::context,
+    multi::length_count,
+    sequence::tuple,
+};
+
+use crate::parse::{
+    values::{name_parser, vector_count_parser},
+    Res,
+};
+
+/// Features of the tool conventions that `winter_core` supports, in
+/// addition to the MVP.
+pub const SUPPORTED_FEATURES: &[&str] = &[
+    "bulk-memory",
+    "multivalue",
+    "mutable-globals",
+    "nontrapping-fptoint",
+    "reference-types",
+    "sign-ext",
+    "simd128",
+];
+
+/// How a module relates to a feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeaturePolicy {
+    /// `+`: the module uses the feature.
+    Used,
+    /// `-`: the module does not use the feature and must not be linked with
+    /// modules that do.
+    Disallowed,
+    /// `=`: the module requires the feature, and so must be linked with
+    /// modules that use it.
+    Required,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetFeature<'a> {
+    pub policy: FeaturePolicy,
+    pub name: &'a str,
+}
+
+impl TargetFeature<'_> {
+    /// Returns true if the feature is supported, or if the module declares
+    /// that it does not use it.
+    pub fn is_supported(&self) -> bool {
+        self.policy == FeaturePolicy::Disallowed || SUPPORTED_FEATURES.contains(&self.name)
+    }
+}
+
+/// The decoded `target_features` custom section, which lists the features
+/// the module was compiled with.
+///
+/// [Reference](https://github.com/WebAssembly/tool-conventions/blob/main/Linking.md#target-features-section)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TargetFeaturesSection<'a> {
+    pub features: Vec<TargetFeature<'a>>,
+}
+
+impl<'a> TargetFeaturesSection<'a> {
+    /// Returns the names of the features used or required by the module
+    /// that `winter_core` does not support.
+    pub fn unsupported(&self) -> Vec<&'a str> {
+        self.features
+            .iter()
+            .filter(|f| !f.is_supported())
+            .map(|f| f.name)
+            .collect()
+    }
+}
+
+fn feature_policy_parser(input: &[u8]) -> Res<'_, FeaturePolicy> {
+    context(
+        "feature_policy",
+        alt((
+            value(FeaturePolicy::Used, tag(b"+")),
+            value(FeaturePolicy::Disallowed, tag(b"-")),
+            value(FeaturePolicy::Required, tag(b"=")),
+        )),
+    )(input)
+}
+
+fn target_feature_parser(input: &[u8]) -> Res<'_, TargetFeature<'_>> {
+    context(
+        "target_feature",
+        map(
+            tuple((feature_policy_parser, name_parser)),
+            |(policy, name)| TargetFeature { policy, name },
+        ),
+    )(input)
+}
+
+/// Parses the content of the `target_features` custom section, after its
+/// name.
+pub fn target_features_section_parser(input: &[u8]) -> Res<'_, TargetFeaturesSection<'_>> {
+    context(
+        "target_features_section",
+        map(
+            all_consuming(length_count(vector_count_parser, target_feature_parser)),
+            |features| TargetFeaturesSection { features },
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_features_section_parser() {
+        let mut data = vec![0x03];
+        data.extend(b"\x2B\x08sign-ext");
+        data.extend(b"\x2D\x07atomics");
+        data.extend(b"\x3D\x09tail-call");
+        let (_, section) = target_features_section_parser(&data).unwrap();
+        assert_eq!(
+            section.features[1],
+            TargetFeature {
+                policy: FeaturePolicy::Disallowed,
+                name: "atomics"
+            }
+        );
+        assert_eq!(section.unsupported(), ["tail-call"]);
+
+        assert!(target_features_section_parser(b"\x01\x2A\x01a").is_err());
+    }
+}
@@ -1,32 +1,40 @@
 use std::vec::Vec;
 
-use nom::{
-    combinator::map,
-    error::context,
-    multi::{length_count, length_value},
-};
+use nom::{combinator::map, error::context};
 
-use super::section_length_parser;
+use super::{entries, section};
 use crate::parse::{
+    spanned,
     types::{functype_parser, FuncType},
-    values::vector_count_parser,
-    Res,
+    Locate, Res, Span,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct TypeSection {
     pub function_types: Vec<FuncType>,
+    /// The spans of the function types.
+    pub spans: Vec<Span>,
+    /// The span of the section, from its id.
+    pub span: Span,
+}
+
+impl Locate for TypeSection {
+    fn locate(&mut self, end: usize) {
+        self.spans.locate(end);
+        self.span.locate(end);
+    }
 }
 
-pub fn type_section_parser(input: &[u8]) -> Res<TypeSection> {
+pub(crate) fn type_section_parser(input: &[u8]) -> Res<'_, TypeSection> {
     context(
         "type_section",
         map(
-            length_value(
-                section_length_parser(1),
-                length_count(vector_count_parser, functype_parser),
-            ),
-            |function_types| TypeSection { function_types },
+            spanned(section(1, entries(functype_parser))),
+            |((function_types, spans), span)| TypeSection {
+                function_types,
+                spans,
+                span,
+            },
         ),
     )(input)
 }
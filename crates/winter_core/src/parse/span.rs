@@ -0,0 +1,122 @@
+use std::vec::Vec;
+
+use crate::parse::Res;
+
+/// A range of bytes of a binary module, as offsets from the start of the
+/// module. Items of modules that were not decoded from a binary have empty
+/// spans.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Returns the number of bytes in the span.
+    pub fn len(&self) -> usize {
+        debug_assert!(self.start <= self.end, "unlocated span {:?}", self);
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Returns whether the span has no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Items whose spans are recorded by the parsers before the offset of their
+/// input in the module is known.
+///
+/// Parsers do not see where their input starts in the module, but they see
+/// where it ends: the input of the parser of a section ends with the
+/// section, and every part of the section is parsed from the rest of that
+/// input. The parsers record spans as the number of bytes left until that
+/// end, and [`Locate::locate`] turns them into offsets once `end`, the
+/// offset of the end of the input, is known. These parsers are private to
+/// the crate, whose public parsers only return located spans.
+pub(crate) trait Locate {
+    fn locate(&mut self, end: usize);
+}
+
+impl Locate for Span {
+    fn locate(&mut self, end: usize) {
+        *self = Span {
+            start: end - self.start,
+            end: end - self.end,
+        };
+    }
+}
+
+impl<T: Locate> Locate for Vec<T> {
+    fn locate(&mut self, end: usize) {
+        self.iter_mut().for_each(|item| item.locate(end));
+    }
+}
+
+impl<T: Locate> Locate for Option<T> {
+    fn locate(&mut self, end: usize) {
+        if let Some(item) = self {
+            item.locate(end);
+        }
+    }
+}
+
+/// Applies `parser` and records the span of what it consumed, to be
+/// located with [`Locate`].
+pub(crate) fn spanned<'a, O>(
+    mut parser: impl FnMut(&'a [u8]) -> Res<'a, O>,
+) -> impl FnMut(&'a [u8]) -> Res<'a, (O, Span)> {
+    move |input: &'a [u8]| {
+        let (rest, output) = parser(input)?;
+        let span = Span {
+            start: input.len(),
+            end: rest.len(),
+        };
+        Ok((rest, (output, span)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encode::encode,
+        parse::modules::module::module_parser,
+        text::{self, ParseBuffer},
+    };
+
+    #[test]
+    fn test_spans() {
+        let source = r#"(module
+            (global i32 (i32.const 7))
+            (export "f" (func 0))
+            (func (result i32) (block (result i32) (i32.const 2)) (drop) (i32.const 3)))"#;
+        let buffer = ParseBuffer::new(source).unwrap();
+        let binary = encode(&text::module_parser(&buffer).unwrap());
+        let module = module_parser(&binary).unwrap();
+        let bytes = |span: Span| &binary[span.start..span.end];
+
+        let global = module.global.as_ref().unwrap();
+        assert_eq!(bytes(global.span)[0], 6);
+        assert_eq!(bytes(global.spans[0]), [0x7F, 0x00, 0x41, 0x07, 0x0B]);
+        assert_eq!(bytes(global.globals[0].expr.spans[0]), [0x41, 0x07]);
+
+        let export = module.export.as_ref().unwrap();
+        assert_eq!(bytes(export.spans[0]), [0x01, b'f', 0x00, 0x00]);
+
+        let code = module.code.as_ref().unwrap();
+        assert_eq!(bytes(code.span)[0], 10);
+        assert_eq!(code.span.end, binary.len());
+        let func = code.code[0].func().unwrap();
+        let spans: Vec<_> = func.expr.spans.iter().map(|s| bytes(*s)).collect();
+        assert_eq!(
+            spans,
+            [
+                &[0x02, 0x7F, 0x41, 0x02, 0x0B][..],
+                &[0x41, 0x02],
+                &[0x1A],
+                &[0x41, 0x03],
+            ]
+        );
+    }
+}
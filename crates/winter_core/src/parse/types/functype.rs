@@ -4,7 +4,7 @@ use super::{resulttype_parser, ResultType};
 use crate::parse::Res;
 
 /// Function type info.
-#[derive(Debug)]
+#[derive(Eq, PartialEq, Debug, Clone)]
 pub struct FuncType {
     pub params: ResultType,
     pub ret: ResultType,
@@ -14,7 +14,7 @@ pub struct FuncType {
 /// vectors of parameter and result types.
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#function-types)
-pub fn functype_parser(input: &[u8]) -> Res<FuncType> {
+pub fn functype_parser(input: &[u8]) -> Res<'_, FuncType> {
     let r = context(
         "functype",
         tuple((tag([0x60]), resulttype_parser, resulttype_parser)),
@@ -1,10 +1,16 @@
-use nom::{bytes::complete::take, combinator::map, error::context, sequence::pair};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{map, value},
+    error::context,
+    sequence::pair,
+};
 
 use super::{valtype_parser, ValType};
 use crate::parse::Res;
 
 /// GlobalType.
-#[derive(Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub struct GlobalType {
     pub value_type: ValType,
 
@@ -13,17 +19,24 @@ pub struct GlobalType {
 }
 
 /// Global types are encoded by their value type and a flag for their
-/// mutability.
+/// mutability, 0x00 for constants and 0x01 for variables.
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#global-types)
-pub fn globaltype_parser(input: &[u8]) -> Res<GlobalType> {
+pub fn globaltype_parser(input: &[u8]) -> Res<'_, GlobalType> {
     context(
         "globaltype",
-        map(pair(valtype_parser, take(1usize)), |(value_type, b)| {
-            GlobalType {
+        map(
+            pair(
+                valtype_parser,
+                context(
+                    "malformed mutability",
+                    alt((value(false, tag([0x00])), value(true, tag([0x01])))),
+                ),
+            ),
+            |(value_type, mutable)| GlobalType {
                 value_type,
-                mutable: b[0] == 0x01,
-            }
-        }),
+                mutable,
+            },
+        ),
     )(input)
 }
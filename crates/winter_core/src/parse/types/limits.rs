@@ -4,17 +4,33 @@ use super::super::values::leb128_u32;
 use crate::parse::Res;
 
 /// Limits with a required min value and an optional max value.
-#[derive(Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub struct Limits {
     pub min: u32,
     pub max: Option<u32>,
 }
 
+impl Limits {
+    /// Returns true if these limits match `expected`, that is if they are at
+    /// least as strict: the minimum is not lower and, if `expected` has a
+    /// maximum, the maximum is present and not greater.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/valid/types.html#import-subtyping)
+    pub fn matches(&self, expected: &Limits) -> bool {
+        self.min >= expected.min
+            && match (self.max, expected.max) {
+                (_, None) => true,
+                (Some(max), Some(expected)) => max <= expected,
+                (None, Some(_)) => false,
+            }
+    }
+}
+
 /// Limits are encoded with a preceding flag indicating whether a maximum is
 /// present.
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#limits)
-pub fn limits_parser(input: &[u8]) -> Res<Limits> {
+pub fn limits_parser(input: &[u8]) -> Res<'_, Limits> {
     context(
         "limits",
         alt((
@@ -23,7 +39,7 @@ pub fn limits_parser(input: &[u8]) -> Res<Limits> {
                 max: None,
             }),
             map(
-                tuple((tag([0x0]), leb128_u32, leb128_u32)),
+                tuple((tag([0x1]), leb128_u32, leb128_u32)),
                 |(_, min, max)| Limits {
                     min,
                     max: Some(max),
@@ -32,3 +48,26 @@ pub fn limits_parser(input: &[u8]) -> Res<Limits> {
         )),
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let value = limits_parser(&[0x00, 0x02]);
+        assert_eq!(value, Ok((&[][..], Limits { min: 2, max: None })));
+        let value = limits_parser(&[0x01, 0x02, 0x80, 0x01]);
+        assert_eq!(
+            value,
+            Ok((
+                &[][..],
+                Limits {
+                    min: 2,
+                    max: Some(128)
+                }
+            ))
+        );
+        assert!(limits_parser(&[0x02, 0x02]).is_err());
+    }
+}
@@ -6,6 +6,7 @@ mod reftype;
 mod resulttype;
 mod tabletype;
 mod valtype;
+mod vectype;
 
 pub use functype::*;
 pub use globaltype::*;
@@ -15,6 +16,7 @@ pub use reftype::*;
 pub use resulttype::*;
 pub use tabletype::*;
 pub use valtype::*;
+pub use vectype::*;
 
 pub type MemType = Limits;
 pub use self::limits_parser as memtype_parser;
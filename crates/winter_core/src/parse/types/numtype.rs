@@ -4,7 +4,7 @@ use crate::parse::Res;
 
 /// Number types are encoded by a single byte.
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#number-types)
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum NumType {
     I32,
     I64,
@@ -29,7 +29,7 @@ impl TryFrom<u8> for NumType {
 /// Number types are encoded by a single byte.
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#number-types)
 /// Returns a struct `NumType` which contains the type of the number.
-pub fn numtype_parser(input: &[u8]) -> Res<NumType> {
+pub fn numtype_parser(input: &[u8]) -> Res<'_, NumType> {
     context(
         "numtype",
         map_res(take(1usize), |f: &[u8]| (f[0]).try_into()),
@@ -4,17 +4,26 @@ use super::{limits_parser, reftype_parser, Limits, RefType};
 use crate::parse::Res;
 
 /// Table type info.
-#[derive(Debug)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub struct TableType {
     pub ref_type: RefType,
     pub limits: Limits,
 }
 
+impl TableType {
+    /// Returns true if a table of this type can be imported as `expected`.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/valid/types.html#import-subtyping)
+    pub fn matches(&self, expected: &TableType) -> bool {
+        self.ref_type == expected.ref_type && self.limits.matches(&expected.limits)
+    }
+}
+
 /// Table types are encoded with their limits and the encoding of their element
 /// reference type.
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#table-types)
-pub fn tabletype_parser(input: &[u8]) -> Res<TableType> {
+pub fn tabletype_parser(input: &[u8]) -> Res<'_, TableType> {
     map(
         context("tabletype", pair(reftype_parser, limits_parser)),
         |(reftype, limits)| TableType {
@@ -1,13 +1,14 @@
 use nom::{branch::alt, combinator::map, error::context};
 
-use super::{numtype_parser, reftype_parser, NumType, RefType};
+use super::{numtype_parser, reftype_parser, vectype_parser, NumType, RefType, VecType};
 use crate::parse::Res;
 
-/// Value types are either a [`NumType`] or [`RefType`].
+/// Value types are either a [`NumType`], [`VecType`] or [`RefType`].
 /// See [`valtype`] for more information.
-#[derive(Eq, PartialEq, Debug, Clone)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
 pub enum ValType {
     NumType(NumType),
+    VecType(VecType),
     RefType(RefType),
 }
 
@@ -17,21 +18,28 @@ impl From<NumType> for ValType {
     }
 }
 
+impl From<VecType> for ValType {
+    fn from(vec_type: VecType) -> Self {
+        ValType::VecType(vec_type)
+    }
+}
+
 impl From<RefType> for ValType {
     fn from(ref_type: RefType) -> Self {
         ValType::RefType(ref_type)
     }
 }
 
-/// Value types are encoded with their respective encoding as a [`NumType`] or
-/// [`RefType`].
+/// Value types are encoded with their respective encoding as a [`NumType`],
+/// [`VecType`] or [`RefType`].
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/types.html#value-types)
-pub fn valtype_parser(input: &[u8]) -> Res<ValType> {
+pub fn valtype_parser(input: &[u8]) -> Res<'_, ValType> {
     context(
         "valtype",
         alt((
             map(numtype_parser, ValType::from),
+            map(vectype_parser, ValType::from),
             map(reftype_parser, ValType::from),
         )),
     )(input)
@@ -45,5 +53,7 @@ mod tests {
     fn test_valtype() {
         let value: Res<ValType> = valtype_parser(&[0x7C]);
         assert_eq!(value, Ok((&[][..], ValType::NumType(NumType::F64))));
+        let value: Res<ValType> = valtype_parser(&[0x7B]);
+        assert_eq!(value, Ok((&[][..], ValType::VecType(VecType::V128))));
     }
 }
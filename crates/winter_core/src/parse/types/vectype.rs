@@ -0,0 +1,31 @@
+use nom::{bytes::complete::take, combinator::map_res, error::context};
+
+use crate::parse::Res;
+
+/// Vector types are encoded by a single byte.
+/// [Reference](https://webassembly.github.io/spec/core/binary/types.html#vector-types)
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum VecType {
+    V128,
+}
+
+impl TryFrom<u8> for VecType {
+    type Error = &'static str;
+
+    fn try_from(input: u8) -> Result<Self, Self::Error> {
+        match input {
+            0x7B => Ok(VecType::V128),
+            _ => Err("Invalid vec type"),
+        }
+    }
+}
+
+/// Vector types are encoded by a single byte.
+/// [Reference](https://webassembly.github.io/spec/core/binary/types.html#vector-types)
+/// Returns a struct `VecType` which contains the type of the vector.
+pub fn vectype_parser(input: &[u8]) -> Res<'_, VecType> {
+    context(
+        "vectype",
+        map_res(take(1usize), |f: &[u8]| (f[0]).try_into()),
+    )(input)
+}
@@ -2,8 +2,8 @@
 
 use std::{
     mem::size_of,
-    num::NonZeroUsize,
     ops::{BitOrAssign, RangeFrom},
+    vec::Vec,
 };
 
 use nom::{
@@ -12,16 +12,35 @@ use nom::{
 };
 use num_traits::{PrimInt, Signed, WrappingNeg};
 
-const NEED_ONE: Needed = Needed::Size(NonZeroUsize::new(1).unwrap());
-
 /// Maximum LEB128-encoded size of an integer type
 /// T is bit count.
 const fn leb128_size<const T: usize>() -> usize {
-    (T + 6) / 7 // equivalent to ceil(bits/7) w/o floats
+    T.div_ceil(7)
+}
+
+/// Number of bits of an integer type of `T` bits encoded in the last byte
+/// of its longest LEB128 encoding.
+const fn last_byte_bits<const T: usize>() -> usize {
+    T - 7 * (leb128_size::<T>() - 1)
+}
+
+/// Whether the last `byte` of the longest encoding of an unsigned integer
+/// has all the bits above the `bits` used ones unset.
+#[inline]
+fn unsigned_fits(byte: u8, bits: usize) -> bool {
+    byte >> bits == 0
+}
+
+/// Whether the last `byte` of the longest encoding of a signed integer has
+/// all the bits above the `bits` used ones equal to its sign bit.
+#[inline]
+fn signed_fits(byte: u8, bits: usize) -> bool {
+    let sign_extended = ((byte << 1) as i8) >> 1;
+    matches!(sign_extended >> (bits - 1), 0 | -1)
 }
 
 macro_rules! impl_generic_leb128 {
-    ($fn_name:ident, $int_ty:ident, $post:tt, $int_name:expr, $leb_size:expr) => {
+    ($fn_name:ident, $int_ty:ident, $post:tt, $fits:tt, $int_name:expr, $leb_size:expr) => {
         #[doc = "Recognizes an LEB128-encoded number that fits in a `"]
         #[doc=$int_name]
         #[doc = "`."]
@@ -35,14 +54,22 @@ macro_rules! impl_generic_leb128 {
             let mut shift = 0;
 
             for (pos, byte) in input.iter_indices() {
+                let last = pos == leb128_size::<$leb_size>() - 1;
                 if (byte & 0x80) == 0 {
+                    if last && !$fits(byte, last_byte_bits::<$leb_size>()) {
+                        return Err(nom::Err::Failure(E::add_context(
+                            input.clone(),
+                            "integer too large",
+                            make_error(input, ErrorKind::TooLarge),
+                        )));
+                    }
                     res |= (byte as $int_ty) << shift;
                     $post(&mut res, shift, byte);
                     return Ok((input.slice(pos + 1..), res));
-                } else if pos == leb128_size::<$leb_size>() - 1 {
-                    return Err(nom::Err::Error(E::add_context(
+                } else if last {
+                    return Err(nom::Err::Failure(E::add_context(
                         input.clone(),
-                        concat!("LEB128 integer is too big to fit in ", $int_name),
+                        "integer representation too long",
                         make_error(input, ErrorKind::TooLarge),
                     )));
                 } else {
@@ -51,11 +78,18 @@ macro_rules! impl_generic_leb128 {
                 shift += 7;
             }
 
-            Err(nom::Err::Incomplete(NEED_ONE))
+            Err(nom::Err::Incomplete(Needed::new(1)))
         }
     };
-    ($fn_name:ident, $int_ty:ident, $post:tt, $leb_size:tt) => {
-        impl_generic_leb128!($fn_name, $int_ty, $post, stringify!($int_ty), $leb_size);
+    ($fn_name:ident, $int_ty:ident, $post:tt, $fits:tt, $leb_size:tt) => {
+        impl_generic_leb128!(
+            $fn_name,
+            $int_ty,
+            $post,
+            $fits,
+            stringify!($int_ty),
+            $leb_size
+        );
     };
 }
 
@@ -64,7 +98,7 @@ fn ignore<T: BitOrAssign + PrimInt + WrappingNeg>(_res: &mut T, _shift: usize, _
 
 macro_rules! impl_unsigned_leb128 {
     ($fn_name:ident, $int_ty:ident, $leb_size:expr) => {
-        impl_generic_leb128!($fn_name, $int_ty, ignore, $leb_size);
+        impl_generic_leb128!($fn_name, $int_ty, ignore, unsigned_fits, $leb_size);
     };
 }
 
@@ -90,7 +124,7 @@ fn sign_extend<T: BitOrAssign + PrimInt + Signed + WrappingNeg>(
 
 macro_rules! impl_signed_leb128 {
     ($fn_name:ident, $int_ty:ident, $leb_size:expr) => {
-        impl_generic_leb128!($fn_name, $int_ty, sign_extend, $leb_size);
+        impl_generic_leb128!($fn_name, $int_ty, sign_extend, signed_fits, $leb_size);
     };
 }
 
@@ -101,9 +135,130 @@ impl_signed_leb128!(leb128_i33, i64, 33);
 impl_signed_leb128!(leb128_i64, i64, 64);
 impl_signed_leb128!(leb128_i128, i128, 128);
 
+macro_rules! impl_leb128_encoder {
+    ($fn_name:ident, $padded_fn_name:ident, $int_ty:ident, $done:expr, $leb_size:expr) => {
+        #[doc = concat!("Writes the shortest LEB128 encoding of a `", stringify!($int_ty), "`.")]
+        pub fn $fn_name(sink: &mut Vec<u8>, mut value: $int_ty) {
+            loop {
+                let byte = (value & 0x7F) as u8;
+                value >>= 7;
+                if $done(value, byte) {
+                    sink.push(byte);
+                    return;
+                }
+                sink.push(byte | 0x80);
+            }
+        }
+
+        #[doc = concat!("Writes the LEB128 encoding of a `", stringify!($int_ty), "` padded to ")]
+        #[doc = concat!("the maximum size of a ", stringify!($leb_size), "-bit integer, so that it")]
+        #[doc = "can be patched in place with any other value."]
+        pub fn $padded_fn_name(sink: &mut Vec<u8>, mut value: $int_ty) {
+            for _ in 1..leb128_size::<$leb_size>() {
+                sink.push((value & 0x7F) as u8 | 0x80);
+                value >>= 7;
+            }
+            sink.push((value & 0x7F) as u8);
+        }
+    };
+}
+
+/// Unsigned encodings end once the remaining bits are all zero.
+fn unsigned_done<T: PrimInt>(value: T, _byte: u8) -> bool {
+    value.is_zero()
+}
+
+/// Signed encodings end once the remaining bits are all copies of the sign
+/// bit of the last byte.
+fn signed_done<T: PrimInt + Signed>(value: T, byte: u8) -> bool {
+    match byte & 0x40 {
+        0 => value.is_zero(),
+        _ => value == T::one().neg(),
+    }
+}
+
+impl_leb128_encoder!(
+    encode_leb128_u8,
+    encode_leb128_u8_padded,
+    u8,
+    unsigned_done,
+    8
+);
+impl_leb128_encoder!(
+    encode_leb128_u16,
+    encode_leb128_u16_padded,
+    u16,
+    unsigned_done,
+    16
+);
+impl_leb128_encoder!(
+    encode_leb128_u32,
+    encode_leb128_u32_padded,
+    u32,
+    unsigned_done,
+    32
+);
+impl_leb128_encoder!(
+    encode_leb128_u64,
+    encode_leb128_u64_padded,
+    u64,
+    unsigned_done,
+    64
+);
+impl_leb128_encoder!(
+    encode_leb128_u128,
+    encode_leb128_u128_padded,
+    u128,
+    unsigned_done,
+    128
+);
+impl_leb128_encoder!(
+    encode_leb128_i8,
+    encode_leb128_i8_padded,
+    i8,
+    signed_done,
+    8
+);
+impl_leb128_encoder!(
+    encode_leb128_i16,
+    encode_leb128_i16_padded,
+    i16,
+    signed_done,
+    16
+);
+impl_leb128_encoder!(
+    encode_leb128_i32,
+    encode_leb128_i32_padded,
+    i32,
+    signed_done,
+    32
+);
+impl_leb128_encoder!(
+    encode_leb128_i33,
+    encode_leb128_i33_padded,
+    i64,
+    signed_done,
+    33
+);
+impl_leb128_encoder!(
+    encode_leb128_i64,
+    encode_leb128_i64_padded,
+    i64,
+    signed_done,
+    64
+);
+impl_leb128_encoder!(
+    encode_leb128_i128,
+    encode_leb128_i128_padded,
+    i128,
+    signed_done,
+    128
+);
+
 #[cfg(test)]
 mod tests {
     use nom::error::VerboseError;
+    use proptest::prelude::*;
 
     use super::*;
 
@@ -120,6 +275,8 @@ mod tests {
         assert_eq!(value, Ok((&[0x00][..], 3)));
         let value: ResType = leb128_u8(&[0x83, 0x80, 0x00]);
         assert!(value.is_err(), "Should fail on too large input");
+        let value: ResType = leb128_u8(&[0x83]);
+        assert_eq!(value, Err(nom::Err::Incomplete(Needed::new(1))));
 
         assert_eq!(leb128_size::<16>(), 3);
         type ResType2<'a> = Result<(&'a [u8], u16), nom::Err<VerboseError<&'a [u8]>>>;
@@ -160,4 +317,137 @@ mod tests {
         let value: ResType3 = leb128_i16(&[0xFE, 0xFF, 0xFF, 0x7F, 0x00]);
         assert!(value.is_err(), "Should fail on too large input");
     }
+
+    #[test]
+    fn test_encode_leb128() {
+        let mut sink = Vec::new();
+        encode_leb128_u32(&mut sink, 624485);
+        assert_eq!(sink, [0xE5, 0x8E, 0x26]);
+        sink.clear();
+        encode_leb128_u32_padded(&mut sink, 3);
+        assert_eq!(sink, [0x83, 0x80, 0x80, 0x80, 0x00]);
+        sink.clear();
+        encode_leb128_i64(&mut sink, -123456);
+        assert_eq!(sink, [0xC0, 0xBB, 0x78]);
+        sink.clear();
+        encode_leb128_i32(&mut sink, 64);
+        assert_eq!(sink, [0xC0, 0x00]);
+        sink.clear();
+        encode_leb128_i33_padded(&mut sink, -2);
+        assert_eq!(sink, [0xFE, 0xFF, 0xFF, 0xFF, 0x7F]);
+    }
+
+    /// Generates property tests checking that both encodings of a value
+    /// decode back to it, and that the padded encoding has the maximum
+    /// size.
+    macro_rules! leb128_round_trip {
+        ($test_name:ident, $strategy:expr, $encode:ident, $encode_padded:ident, $decode:ident, $leb_size:expr) => {
+            proptest! {
+                #[test]
+                fn $test_name(value in $strategy) {
+                    for padded in [false, true] {
+                        let mut sink = Vec::new();
+                        match padded {
+                            false => $encode(&mut sink, value),
+                            true => $encode_padded(&mut sink, value),
+                        }
+                        if padded {
+                            prop_assert_eq!(sink.len(), leb128_size::<$leb_size>());
+                        }
+                        let decoded = $decode::<_, VerboseError<&[u8]>>(&sink[..]);
+                        prop_assert_eq!(decoded, Ok((&[][..], value)));
+                    }
+                }
+            }
+        };
+    }
+
+    leb128_round_trip!(
+        test_u8,
+        any::<u8>(),
+        encode_leb128_u8,
+        encode_leb128_u8_padded,
+        leb128_u8,
+        8
+    );
+    leb128_round_trip!(
+        test_u16,
+        any::<u16>(),
+        encode_leb128_u16,
+        encode_leb128_u16_padded,
+        leb128_u16,
+        16
+    );
+    leb128_round_trip!(
+        test_u32,
+        any::<u32>(),
+        encode_leb128_u32,
+        encode_leb128_u32_padded,
+        leb128_u32,
+        32
+    );
+    leb128_round_trip!(
+        test_u64,
+        any::<u64>(),
+        encode_leb128_u64,
+        encode_leb128_u64_padded,
+        leb128_u64,
+        64
+    );
+    leb128_round_trip!(
+        test_u128,
+        any::<u128>(),
+        encode_leb128_u128,
+        encode_leb128_u128_padded,
+        leb128_u128,
+        128
+    );
+    leb128_round_trip!(
+        test_i8,
+        any::<i8>(),
+        encode_leb128_i8,
+        encode_leb128_i8_padded,
+        leb128_i8,
+        8
+    );
+    leb128_round_trip!(
+        test_i16,
+        any::<i16>(),
+        encode_leb128_i16,
+        encode_leb128_i16_padded,
+        leb128_i16,
+        16
+    );
+    leb128_round_trip!(
+        test_i32,
+        any::<i32>(),
+        encode_leb128_i32,
+        encode_leb128_i32_padded,
+        leb128_i32,
+        32
+    );
+    leb128_round_trip!(
+        test_i33,
+        -(1i64 << 32)..(1i64 << 32),
+        encode_leb128_i33,
+        encode_leb128_i33_padded,
+        leb128_i33,
+        33
+    );
+    leb128_round_trip!(
+        test_i64,
+        any::<i64>(),
+        encode_leb128_i64,
+        encode_leb128_i64_padded,
+        leb128_i64,
+        64
+    );
+    leb128_round_trip!(
+        test_i128,
+        any::<i128>(),
+        encode_leb128_i128,
+        encode_leb128_i128_padded,
+        leb128_i128,
+        128
+    );
 }
@@ -1,4 +1,7 @@
-use nom::{combinator::map_res, error::context};
+use nom::{
+    combinator::{map_parser, map_res, rest},
+    error::context,
+};
 
 use super::vector_parser;
 use crate::parse::Res;
@@ -7,8 +10,17 @@ use crate::parse::Res;
 /// UTF-8 encoding of the name’s character sequence.
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/values.html#names)
-pub fn name_parser(input: &[u8]) -> Res<&str> {
-    context("name", map_res(vector_parser, core::str::from_utf8))(input)
+pub fn name_parser(input: &[u8]) -> Res<'_, &str> {
+    context(
+        "name",
+        map_parser(
+            vector_parser,
+            context(
+                "malformed UTF-8 encoding",
+                map_res(rest, core::str::from_utf8),
+            ),
+        ),
+    )(input)
 }
 
 #[cfg(test)]
@@ -1,7 +1,9 @@
 use std::ops::RangeFrom;
 
 use nom::{
-    bytes::complete::take, error::VerboseError, IResult, InputIter, InputLength, InputTake, Slice,
+    bytes::complete::take,
+    error::{ContextError, ParseError},
+    IResult, InputIter, InputLength, InputTake, Slice,
 };
 
 use super::leb128_u32;
@@ -11,8 +13,9 @@ use super::leb128_u32;
 ///
 /// [Reference](https://webassembly.github.io/spec/core/binary/conventions.html#vectors).
 /// `u32` is decoded by [`leb128_u32`].
-pub fn vector_parser<I>(input: I) -> IResult<I, I, VerboseError<I>>
+pub fn vector_parser<I, E>(input: I) -> IResult<I, I, E>
 where
+    E: ParseError<I> + ContextError<I>,
     I: Clone + Slice<RangeFrom<usize>> + InputIter<Item = u8> + InputLength + InputTake,
 {
     leb128_u32(input).and_then(|(remaining, length)| take(length)(remaining))
@@ -25,8 +28,9 @@ where
 ///
 /// `u32` is decoded by [`leb128_u32`].
 /// This method returns the length of the vector.
-pub fn vector_count_parser<I>(input: I) -> IResult<I, u32, VerboseError<I>>
+pub fn vector_count_parser<I, E>(input: I) -> IResult<I, u32, E>
 where
+    E: ParseError<I> + ContextError<I>,
     I: Clone + Slice<RangeFrom<usize>> + InputIter<Item = u8> + InputLength + InputTake,
 {
     leb128_u32(input)
@@ -0,0 +1,262 @@
+use core::fmt;
+
+use super::{val_type, Printer};
+use crate::{
+    parse::{
+        instructions::{
+            BlockType, ControlInstruction, Instr, MemArg, MemoryInstruction, NumericInstruction,
+            ParametricInstruction, ReferenceInstruction, TableInstruction, VariableInstruction,
+            VectorInstruction,
+        },
+        types::RefType,
+    },
+    text::{lane_name, memarg_lane_name, memarg_name, plain_name},
+};
+
+/// Writes a float given its bit pattern `bits`, `width` bits wide with a
+/// mantissa of `mantissa_bits`. Finite floats are written by `value`'s
+/// [`fmt::Display`] implementation, as the shortest decimal number that
+/// converts back to the same float.
+fn float(
+    f: &mut fmt::Formatter<'_>,
+    bits: u64,
+    width: u32,
+    mantissa_bits: u32,
+    value: &dyn fmt::Display,
+) -> fmt::Result {
+    let exponent_mask = (1 << (width - 1 - mantissa_bits)) - 1;
+    let exponent = (bits >> mantissa_bits) & exponent_mask;
+    let mantissa = bits & ((1 << mantissa_bits) - 1);
+    if exponent != exponent_mask {
+        return write!(f, "{}", value);
+    }
+    if bits >> (width - 1) != 0 {
+        write!(f, "-")?;
+    }
+    match mantissa {
+        0 => write!(f, "inf"),
+        m if m == 1 << (mantissa_bits - 1) => write!(f, "nan"),
+        m => write!(f, "nan:{:#x}", m),
+    }
+}
+
+impl<'p> Printer<'p, '_, '_> {
+    /// Writes `instrs` one per line, indenting the bodies of blocks.
+    pub(super) fn instrs(&mut self, instrs: &[Instr]) -> fmt::Result {
+        for instr in instrs {
+            self.newline()?;
+            self.instr(instr)?;
+            if let Some(span) = self.instr_spans.next() {
+                write!(self.f, "  ;; @{:#x}", span.start)?;
+            }
+            if let Instr::Control(c) = instr {
+                match &**c {
+                    ControlInstruction::Block(_, body) | ControlInstruction::Loop(_, body) => {
+                        self.block(body)?;
+                        self.newline()?;
+                        write!(self.f, "end")?;
+                    }
+                    ControlInstruction::If(_, then, otherwise) => {
+                        self.block(then)?;
+                        if !otherwise.is_empty() {
+                            self.newline()?;
+                            write!(self.f, "else")?;
+                            self.block(otherwise)?;
+                        }
+                        self.newline()?;
+                        write!(self.f, "end")?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, body: &[Instr]) -> fmt::Result {
+        self.indent += 1;
+        self.instrs(body)?;
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn block_type(&mut self, block_type: &BlockType) -> fmt::Result {
+        match block_type {
+            BlockType::Empty => Ok(()),
+            BlockType::ValType(t) => write!(self.f, " (result {})", val_type(*t)),
+            BlockType::TypeIndex(x) => write!(self.f, " (type {})", x.0),
+        }
+    }
+
+    fn memarg(&mut self, memarg: MemArg, natural: u32) -> fmt::Result {
+        if memarg.offset != 0 {
+            write!(self.f, " offset={}", memarg.offset)?;
+        }
+        if memarg.align != natural {
+            write!(self.f, " align={}", 1u64 << memarg.align.min(63))?;
+        }
+        Ok(())
+    }
+
+    /// Writes an instruction and its immediates, without the bodies of
+    /// blocks.
+    pub(super) fn instr(&mut self, instr: &Instr) -> fmt::Result {
+        if let Some(name) = plain_name(instr) {
+            return write!(self.f, "{}", name);
+        }
+        if let Some((name, memarg, natural)) = memarg_name(instr) {
+            write!(self.f, "{}", name)?;
+            return self.memarg(memarg, natural);
+        }
+        match instr {
+            Instr::Control(c) => self.control(c),
+            Instr::Reference(r) => match **r {
+                ReferenceInstruction::RefNull(RefType::FuncRef) => write!(self.f, "ref.null func"),
+                ReferenceInstruction::RefNull(RefType::ExternRef) => {
+                    write!(self.f, "ref.null extern")
+                }
+                ReferenceInstruction::RefIsNull => write!(self.f, "ref.is_null"),
+                ReferenceInstruction::RefFunc(x) => {
+                    write!(self.f, "ref.func ")?;
+                    self.func_ref(x.0)
+                }
+            },
+            Instr::Parametric(p) => match &**p {
+                ParametricInstruction::Drop => write!(self.f, "drop"),
+                ParametricInstruction::Select(None) => write!(self.f, "select"),
+                ParametricInstruction::Select(Some(types)) => {
+                    write!(self.f, "select (result")?;
+                    for t in types {
+                        write!(self.f, " {}", val_type(*t))?;
+                    }
+                    write!(self.f, ")")
+                }
+            },
+            Instr::Variable(v) => match **v {
+                VariableInstruction::LocalGet(x) => {
+                    write!(self.f, "local.get ")?;
+                    self.local_ref(x.0)
+                }
+                VariableInstruction::LocalSet(x) => {
+                    write!(self.f, "local.set ")?;
+                    self.local_ref(x.0)
+                }
+                VariableInstruction::LocalTee(x) => {
+                    write!(self.f, "local.tee ")?;
+                    self.local_ref(x.0)
+                }
+                VariableInstruction::GlobalGet(x) => write!(self.f, "global.get {}", x.0),
+                VariableInstruction::GlobalSet(x) => write!(self.f, "global.set {}", x.0),
+            },
+            Instr::Table(t) => match **t {
+                TableInstruction::TableGet(x) => write!(self.f, "table.get {}", x.0),
+                TableInstruction::TableSet(x) => write!(self.f, "table.set {}", x.0),
+                TableInstruction::TableInit(e, x) => write!(self.f, "table.init {} {}", x.0, e.0),
+                TableInstruction::ElemDrop(e) => write!(self.f, "elem.drop {}", e.0),
+                TableInstruction::TableCopy(x, y) => write!(self.f, "table.copy {} {}", x.0, y.0),
+                TableInstruction::TableGrow(x) => write!(self.f, "table.grow {}", x.0),
+                TableInstruction::TableSize(x) => write!(self.f, "table.size {}", x.0),
+                TableInstruction::TableFill(x) => write!(self.f, "table.fill {}", x.0),
+            },
+            Instr::Memory(m) => self.memory(m),
+            Instr::Numeric(n) => match **n {
+                NumericInstruction::I32Const(v) => write!(self.f, "i32.const {}", v),
+                NumericInstruction::I64Const(v) => write!(self.f, "i64.const {}", v),
+                NumericInstruction::F32Const(v) => {
+                    write!(self.f, "f32.const ")?;
+                    float(self.f, v.to_bits().into(), 32, 23, &v)
+                }
+                NumericInstruction::F64Const(v) => {
+                    write!(self.f, "f64.const ")?;
+                    float(self.f, v.to_bits(), 64, 52, &v)
+                }
+                // All other numeric instructions are plain.
+                _ => unreachable!("numeric instruction without a name"),
+            },
+            Instr::Vector(v) => self.vector(v),
+        }
+    }
+
+    fn control(&mut self, instr: &ControlInstruction) -> fmt::Result {
+        match instr {
+            ControlInstruction::Unreachable => write!(self.f, "unreachable"),
+            ControlInstruction::Nop => write!(self.f, "nop"),
+            ControlInstruction::Block(bt, _) => {
+                write!(self.f, "block")?;
+                self.block_type(bt)
+            }
+            ControlInstruction::Loop(bt, _) => {
+                write!(self.f, "loop")?;
+                self.block_type(bt)
+            }
+            ControlInstruction::If(bt, ..) => {
+                write!(self.f, "if")?;
+                self.block_type(bt)
+            }
+            ControlInstruction::Br(l) => write!(self.f, "br {}", l.0),
+            ControlInstruction::BrIf(l) => write!(self.f, "br_if {}", l.0),
+            ControlInstruction::BrTable(labels, default) => {
+                write!(self.f, "br_table")?;
+                for l in labels.iter().chain([default]) {
+                    write!(self.f, " {}", l.0)?;
+                }
+                Ok(())
+            }
+            ControlInstruction::Return => write!(self.f, "return"),
+            ControlInstruction::Call(x) => {
+                write!(self.f, "call ")?;
+                self.func_ref(x.0)
+            }
+            ControlInstruction::CallIndirect(t, x) => {
+                write!(self.f, "call_indirect {} (type {})", x.0, t.0)
+            }
+        }
+    }
+
+    fn memory(&mut self, instr: &MemoryInstruction) -> fmt::Result {
+        match *instr {
+            MemoryInstruction::MemorySize(x) => write!(self.f, "memory.size {}", x.0),
+            MemoryInstruction::MemoryGrow(x) => write!(self.f, "memory.grow {}", x.0),
+            MemoryInstruction::MemoryInit(d, x) => {
+                write!(self.f, "memory.init {} {}", x.0, d.0)
+            }
+            MemoryInstruction::DataDrop(d) => write!(self.f, "data.drop {}", d.0),
+            MemoryInstruction::MemoryCopy(x, y) => {
+                write!(self.f, "memory.copy {} {}", x.0, y.0)
+            }
+            MemoryInstruction::MemoryFill(x) => write!(self.f, "memory.fill {}", x.0),
+            // Loads and stores take a memory argument.
+            _ => unreachable!("memory instruction without a name"),
+        }
+    }
+
+    fn vector(&mut self, instr: &VectorInstruction) -> fmt::Result {
+        if let Some((name, lane)) = lane_name(instr) {
+            return write!(self.f, "{} {}", name, lane);
+        }
+        if let Some((name, memarg, natural, lane)) = memarg_lane_name(instr) {
+            write!(self.f, "{}", name)?;
+            self.memarg(memarg, natural)?;
+            return write!(self.f, " {}", lane);
+        }
+        match instr {
+            VectorInstruction::V128Const(v) => {
+                write!(self.f, "v128.const i32x4")?;
+                for i in 0..4 {
+                    write!(self.f, " {:#010x}", (v >> (32 * i)) as u32)?;
+                }
+                Ok(())
+            }
+            VectorInstruction::I8x16Shuffle(lanes) => {
+                write!(self.f, "i8x16.shuffle")?;
+                for lane in lanes {
+                    write!(self.f, " {}", lane)?;
+                }
+                Ok(())
+            }
+            // All other vector instructions are plain or take a memory
+            // argument.
+            _ => unreachable!("vector instruction without a name"),
+        }
+    }
+}
@@ -0,0 +1,588 @@
+//! Printing of modules in the WebAssembly text format.
+//!
+//! The printed text uses flat instructions, with the bodies of blocks
+//! indented, and can be parsed again by [`text::module_parser`].
+//! Functions and locals are given the names of the `name` custom section
+//! when it is present.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/text/index.html)
+//!
+//! [`text::module_parser`]: crate::text::module_parser
+
+mod instructions;
+mod names;
+
+use core::fmt;
+use std::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use names::Names;
+
+use crate::parse::{
+    instructions::Expr,
+    modules::{
+        code_section::Code,
+        data_section::DataMode,
+        element_section::{Elem, ElemInit, ElemKind, ElemMode},
+        export_section::ExportDesc,
+        import_section::ImportDesc,
+        module::Module,
+    },
+    types::{FuncType, GlobalType, Limits, NumType, RefType, TableType, ValType, VecType},
+    Span,
+};
+
+/// Returns the text of `module`.
+pub fn print(module: &Module) -> String {
+    Wat::new(module).to_string()
+}
+
+/// Returns the text of `module`, with the byte offsets of its functions and
+/// instructions in the binary it was decoded from in comments.
+pub fn print_with_offsets(module: &Module) -> String {
+    Wat::new(module).offsets().to_string()
+}
+
+/// A module displayed in the text format.
+pub struct Wat<'m, 'a> {
+    module: &'m Module<'a>,
+    offsets: bool,
+}
+
+impl<'m, 'a> Wat<'m, 'a> {
+    pub fn new(module: &'m Module<'a>) -> Self {
+        Wat {
+            module,
+            offsets: false,
+        }
+    }
+
+    /// Annotates the functions and instructions with their byte offsets in
+    /// the binary the module was decoded from, taken from their spans.
+    /// Functions that were not decoded from a binary have no offsets.
+    pub fn offsets(mut self) -> Self {
+        self.offsets = true;
+        self
+    }
+}
+
+impl fmt::Display for Wat<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = Names::new(self.module);
+        Printer {
+            f,
+            module: self.module,
+            names: &names,
+            offsets: self.offsets,
+            instr_spans: [].iter(),
+            locals: None,
+            indent: 0,
+        }
+        .module()
+    }
+}
+
+fn num_type(t: NumType) -> &'static str {
+    match t {
+        NumType::I32 => "i32",
+        NumType::I64 => "i64",
+        NumType::F32 => "f32",
+        NumType::F64 => "f64",
+    }
+}
+
+fn ref_type(t: RefType) -> &'static str {
+    match t {
+        RefType::FuncRef => "funcref",
+        RefType::ExternRef => "externref",
+    }
+}
+
+fn val_type(t: ValType) -> &'static str {
+    match t {
+        ValType::NumType(t) => num_type(t),
+        ValType::VecType(VecType::V128) => "v128",
+        ValType::RefType(t) => ref_type(t),
+    }
+}
+
+struct Printer<'p, 'w, 'a> {
+    f: &'p mut fmt::Formatter<'w>,
+    module: &'p Module<'a>,
+    names: &'p Names<'a>,
+    offsets: bool,
+    /// Spans of the remaining instructions of the current function, if
+    /// offsets are printed.
+    instr_spans: core::slice::Iter<'p, Span>,
+    /// Names of the locals of the current function.
+    locals: Option<&'p BTreeMap<u32, &'a str>>,
+    indent: usize,
+}
+
+impl<'p, 'a> Printer<'p, '_, 'a> {
+    /// Starts a new line at the current indentation.
+    fn newline(&mut self) -> fmt::Result {
+        writeln!(self.f)?;
+        for _ in 0..self.indent {
+            write!(self.f, "  ")?;
+        }
+        Ok(())
+    }
+
+    /// Writes a string literal, escaping the bytes that are not printable
+    /// ASCII characters.
+    fn string(&mut self, bytes: &[u8]) -> fmt::Result {
+        write!(self.f, "\"")?;
+        for &b in bytes {
+            match b {
+                b'"' | b'\\' => write!(self.f, "\\{}", b as char)?,
+                0x20..=0x7E => write!(self.f, "{}", b as char)?,
+                _ => write!(self.f, "\\{:02x}", b)?,
+            }
+        }
+        write!(self.f, "\"")
+    }
+
+    /// Writes the identifier `name` if there is one, followed by the index
+    /// in a comment.
+    fn id(&mut self, name: Option<&str>, index: usize) -> fmt::Result {
+        if let Some(name) = name {
+            write!(self.f, " ${}", name)?;
+        }
+        write!(self.f, " (;{};)", index)
+    }
+
+    /// Writes a reference to a function, by name if it has one.
+    fn func_ref(&mut self, index: u32) -> fmt::Result {
+        match self.names.funcs.get(&index) {
+            Some(name) => write!(self.f, "${}", name),
+            None => write!(self.f, "{}", index),
+        }
+    }
+
+    /// Writes a reference to a local of the current function.
+    fn local_ref(&mut self, index: u32) -> fmt::Result {
+        match self.locals.and_then(|l| l.get(&index)) {
+            Some(name) => write!(self.f, "${}", name),
+            None => write!(self.f, "{}", index),
+        }
+    }
+
+    /// Writes the types of `types` as `(keyword t*)` fields, with a field
+    /// of its own for each named value. `first` is the index of the first
+    /// value. Each field is preceded by a space, except the first one if
+    /// `line_start` is true.
+    fn values(
+        &mut self,
+        keyword: &str,
+        types: &[ValType],
+        first: u32,
+        line_start: bool,
+    ) -> fmt::Result {
+        let mut open = false;
+        let mut separator = if line_start { "" } else { " " };
+        for (i, t) in (first..).zip(types) {
+            match self.locals.and_then(|l| l.get(&i)) {
+                Some(name) => {
+                    if open {
+                        write!(self.f, ")")?;
+                        open = false;
+                    }
+                    write!(
+                        self.f,
+                        "{}({} ${} {})",
+                        separator,
+                        keyword,
+                        name,
+                        val_type(*t)
+                    )?;
+                }
+                None if open => write!(self.f, " {}", val_type(*t))?,
+                None => {
+                    write!(self.f, "{}({} {}", separator, keyword, val_type(*t))?;
+                    open = true;
+                }
+            }
+            separator = " ";
+        }
+        if open {
+            write!(self.f, ")")?;
+        }
+        Ok(())
+    }
+
+    fn func_type(&mut self, t: &FuncType) -> fmt::Result {
+        self.values("param", &t.params, 0, false)?;
+        if !t.ret.is_empty() {
+            write!(self.f, " (result")?;
+            for r in &t.ret {
+                write!(self.f, " {}", val_type(*r))?;
+            }
+            write!(self.f, ")")?;
+        }
+        Ok(())
+    }
+
+    /// Writes a type use: the type index followed by the parameters and
+    /// results of the type.
+    fn type_use(&mut self, index: u32) -> fmt::Result {
+        write!(self.f, " (type {})", index)?;
+        let types = self.module.func_type.as_ref();
+        match types.and_then(|s| s.function_types.get(index as usize)) {
+            Some(t) => self.func_type(t),
+            None => Ok(()),
+        }
+    }
+
+    fn limits(&mut self, limits: &Limits) -> fmt::Result {
+        write!(self.f, " {}", limits.min)?;
+        match limits.max {
+            Some(max) => write!(self.f, " {}", max),
+            None => Ok(()),
+        }
+    }
+
+    fn table_type(&mut self, t: &TableType) -> fmt::Result {
+        self.limits(&t.limits)?;
+        write!(self.f, " {}", ref_type(t.ref_type))
+    }
+
+    fn global_type(&mut self, t: &GlobalType) -> fmt::Result {
+        match t.mutable {
+            true => write!(self.f, " (mut {})", val_type(t.value_type)),
+            false => write!(self.f, " {}", val_type(t.value_type)),
+        }
+    }
+
+    /// Writes a constant expression on the current line.
+    fn const_expr(&mut self, expr: &Expr) -> fmt::Result {
+        for instr in &expr.instr {
+            write!(self.f, " ")?;
+            self.instr(instr)?;
+        }
+        Ok(())
+    }
+
+    fn module(&mut self) -> fmt::Result {
+        write!(self.f, "(module")?;
+        if let Some(name) = self.names.module {
+            write!(self.f, " ${}", name)?;
+        }
+        self.indent += 1;
+        let module = self.module;
+
+        let types = module.func_type.iter().flat_map(|s| &s.function_types);
+        for (i, t) in types.enumerate() {
+            self.newline()?;
+            write!(self.f, "(type (;{};) (func", i)?;
+            self.func_type(t)?;
+            write!(self.f, "))")?;
+        }
+
+        let mut counts = [0; 4];
+        for import in module.import.iter().flat_map(|s| &s.imports) {
+            self.newline()?;
+            write!(self.f, "(import ")?;
+            self.string(import.mod_name.as_bytes())?;
+            write!(self.f, " ")?;
+            self.string(import.name.as_bytes())?;
+            match &import.desc {
+                ImportDesc::Func(x) => {
+                    write!(self.f, " (func")?;
+                    let name = self.names.funcs.get(&counts[0]).copied();
+                    self.id(name, counts[0] as usize)?;
+                    self.type_use(x.0)?;
+                    counts[0] += 1;
+                }
+                ImportDesc::Table(t) => {
+                    write!(self.f, " (table (;{};)", counts[1])?;
+                    self.table_type(t)?;
+                    counts[1] += 1;
+                }
+                ImportDesc::Mem(m) => {
+                    write!(self.f, " (memory (;{};)", counts[2])?;
+                    self.limits(m)?;
+                    counts[2] += 1;
+                }
+                ImportDesc::Global(g) => {
+                    write!(self.f, " (global (;{};)", counts[3])?;
+                    self.global_type(g)?;
+                    counts[3] += 1;
+                }
+            }
+            write!(self.f, "))")?;
+        }
+        let [funcs, tables, mems, globals] = counts;
+
+        let types = module.type_idx.iter().flat_map(|s| &s.functions);
+        let code = module.code.iter().flat_map(|s| &s.code);
+        for (i, (t, code)) in types.zip(code).enumerate() {
+            self.func(funcs + i as u32, t.0, code)?;
+        }
+
+        let table_types = module.table.iter().flat_map(|s| &s.tables);
+        for (i, table) in table_types.enumerate() {
+            self.newline()?;
+            write!(self.f, "(table (;{};)", tables as usize + i)?;
+            self.table_type(&table.table_type)?;
+            write!(self.f, ")")?;
+        }
+
+        for (i, mem) in module.mem.iter().flat_map(|s| &s.tables).enumerate() {
+            self.newline()?;
+            write!(self.f, "(memory (;{};)", mems as usize + i)?;
+            self.limits(&mem.mem_type)?;
+            write!(self.f, ")")?;
+        }
+
+        for (i, global) in module.global.iter().flat_map(|s| &s.globals).enumerate() {
+            self.newline()?;
+            write!(self.f, "(global (;{};)", globals as usize + i)?;
+            self.global_type(&global.global_type)?;
+            self.const_expr(&global.expr)?;
+            write!(self.f, ")")?;
+        }
+
+        for export in module.export.iter().flat_map(|s| &s.exports) {
+            self.newline()?;
+            write!(self.f, "(export ")?;
+            self.string(export.name.as_bytes())?;
+            match export.desc {
+                ExportDesc::Func(x) => {
+                    write!(self.f, " (func ")?;
+                    self.func_ref(x.0)?;
+                }
+                ExportDesc::Table(x) => write!(self.f, " (table {}", x.0)?,
+                ExportDesc::Mem(x) => write!(self.f, " (memory {}", x.0)?,
+                ExportDesc::Global(x) => write!(self.f, " (global {}", x.0)?,
+            }
+            write!(self.f, "))")?;
+        }
+
+        if let Some(start) = &module.start {
+            self.newline()?;
+            write!(self.f, "(start ")?;
+            self.func_ref(start.start.0)?;
+            write!(self.f, ")")?;
+        }
+
+        for (i, elem) in module.elem.iter().flat_map(|s| &s.elems).enumerate() {
+            self.elem(elem, i)?;
+        }
+
+        for (i, data) in module.data.iter().flat_map(|s| &s.data).enumerate() {
+            self.newline()?;
+            write!(self.f, "(data (;{};)", i)?;
+            if let DataMode::Active { memory, offset } = &data.mode {
+                if memory.0 != 0 {
+                    write!(self.f, " (memory {})", memory.0)?;
+                }
+                write!(self.f, " (offset")?;
+                self.const_expr(offset)?;
+                write!(self.f, ")")?;
+            }
+            write!(self.f, " ")?;
+            self.string(data.init)?;
+            write!(self.f, ")")?;
+        }
+
+        for custom in &module.custom_sections {
+            if custom.name != "name" {
+                self.newline()?;
+                write!(self.f, ";; custom section ")?;
+                self.string(custom.name.as_bytes())?;
+                write!(self.f, ", size {}", custom.data.len())?;
+            }
+        }
+
+        self.indent -= 1;
+        self.newline()?;
+        write!(self.f, ")")
+    }
+
+    /// Writes the function `index` of the function index space, whose type
+    /// is `type_idx`.
+    fn func(&mut self, index: u32, type_idx: u32, code: &'p Code) -> fmt::Result {
+        self.locals = self.names.locals.get(&index);
+
+        self.newline()?;
+        write!(self.f, "(func")?;
+        let name = self.names.funcs.get(&index).copied();
+        self.id(name, index as usize)?;
+        self.type_use(type_idx)?;
+        let func = match code.func() {
+            Ok(func) => func,
+            // A body that cannot be decoded is replaced by the error.
+            Err(e) => return write!(self.f, " (; {} ;))", e),
+        };
+        if let Some(offset) = code.body_offset().filter(|_| self.offsets) {
+            write!(self.f, "  ;; @{:#x}", offset)?;
+            self.instr_spans = func.expr.spans.iter();
+        }
+
+        self.indent += 1;
+        let params = self
+            .module
+            .func_type
+            .as_ref()
+            .and_then(|s| s.function_types.get(type_idx as usize))
+            .map_or(0, |t| t.params.len() as u32);
+        let locals: Vec<ValType> = func
+            .locals
+            .iter()
+            .flat_map(|l| core::iter::repeat_n(l.val_type, l.count as usize))
+            .collect();
+        if !locals.is_empty() {
+            self.newline()?;
+            self.values("local", &locals, params, true)?;
+        }
+        self.instrs(&func.expr.instr)?;
+        self.indent -= 1;
+        self.newline()?;
+        write!(self.f, ")")?;
+        self.locals = None;
+        self.instr_spans = [].iter();
+        Ok(())
+    }
+
+    fn elem(&mut self, elem: &Elem, index: usize) -> fmt::Result {
+        self.newline()?;
+        write!(self.f, "(elem (;{};)", index)?;
+        match &elem.mode {
+            ElemMode::Passive => {}
+            ElemMode::Declarative => write!(self.f, " declare")?,
+            ElemMode::Active { table, offset } => {
+                if table.0 != 0 {
+                    write!(self.f, " (table {})", table.0)?;
+                }
+                write!(self.f, " (offset")?;
+                self.const_expr(offset)?;
+                write!(self.f, ")")?;
+            }
+        }
+        match &elem.init {
+            ElemInit::FuncIdx(funcs) => {
+                write!(self.f, " func")?;
+                for x in funcs {
+                    write!(self.f, " ")?;
+                    self.func_ref(x.0)?;
+                }
+            }
+            ElemInit::Expr(exprs) => {
+                let t = match elem.elem_type {
+                    ElemKind::FuncRef => RefType::FuncRef,
+                    ElemKind::RefType(t) => t,
+                };
+                write!(self.f, " {}", ref_type(t))?;
+                for expr in exprs {
+                    write!(self.f, " (item")?;
+                    self.const_expr(expr)?;
+                    write!(self.f, ")")?;
+                }
+            }
+        }
+        write!(self.f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse::modules::module::module_parser,
+        text::{self, ParseBuffer},
+    };
+
+    /// A module with a function `inc` whose parameter is named `x`.
+    const INC: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x06, 0x01, 0x60, 0x01, 0x7F, 0x01, 0x7F, // type section
+        0x03, 0x02, 0x01, 0x00, // function section
+        0x0A, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x41, 0x01, 0x6A, 0x0B, // code section
+        0x00, 0x15, 0x04, b'n', b'a', b'm', b'e', // name section
+        0x01, 0x06, 0x01, 0x00, 0x03, b'i', b'n', b'c', // function names
+        0x02, 0x06, 0x01, 0x00, 0x01, 0x00, 0x01, b'x', // local names
+    ];
+
+    #[test]
+    fn test_names_and_offsets() {
+        let module = module_parser(INC).unwrap();
+        assert_eq!(
+            print_with_offsets(&module),
+            "(module
+  (type (;0;) (func (param i32) (result i32)))
+  (func $inc (;0;) (type 0) (param $x i32) (result i32)  ;; @0x18
+    local.get $x  ;; @0x19
+    i32.const 1  ;; @0x1b
+    i32.add  ;; @0x1d
+  )
+)"
+        );
+    }
+
+    #[test]
+    fn test_malformed_body() {
+        // `i32.add` is replaced by an unknown opcode.
+        let mut binary = INC.to_vec();
+        binary[29] = 0xC7;
+        let module = module_parser(&binary).unwrap();
+        assert_eq!(
+            print(&module),
+            "(module
+  (type (;0;) (func (param i32) (result i32)))
+  (func $inc (;0;) (type 0) (param $x i32) (result i32) (; Fail at offset 0x1d (func > expr > \
+             instr > unknown operator): 00 20 00 41 01 [c7] 0b ;))
+)"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = r#"(module
+  (type (;0;) (func (param i32 i32) (result i32)))
+  (type (;1;) (func (param f32) (result f64)))
+  (import "env" "\00\"" (func (;0;) (type 0) (param i32 i32) (result i32)))
+  (import "env" "g" (global (;0;) (mut i64)))
+  (func (;1;) (type 1) (param f32) (result f64)
+    (local i32 i32 v128)
+    block (result i32)
+      loop
+        local.get 1
+        br_if 1
+        local.get 0
+        i32.load16_u offset=2 align=1
+        if (type 0)
+          br_table 0 1 0
+        else
+          call_indirect 0 (type 0)
+        end
+      end
+      unreachable
+    end
+    drop
+    v128.const i32x4 0x00000001 0xffffffff 0x00000000 0x80000000
+    i8x16.extract_lane_s 15
+    drop
+    f32.const -nan:0x200000
+    f32.const 0.1
+    select (result f32)
+    f64.promote_f32
+  )
+  (table (;0;) 2 funcref)
+  (memory (;0;) 1 2)
+  (global (;1;) f64 f64.const -inf)
+  (export "f" (func 1))
+  (start 0)
+  (elem (;0;) (offset i32.const 0) func 1 0)
+  (elem (;1;) declare funcref (item ref.func 1) (item ref.null func))
+  (data (;0;) (memory 0) (offset i32.const 8) "a\0a")
+  (data (;1;) "")
+)"#;
+        let buffer = ParseBuffer::new(source).unwrap();
+        let module = text::module_parser(&buffer).unwrap();
+        assert_eq!(print(&module), source.replace("(memory 0) ", ""));
+    }
+}
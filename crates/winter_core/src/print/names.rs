@@ -0,0 +1,53 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    parse::modules::{module::Module, name_section::NameMap},
+    text::is_idchar,
+};
+
+/// Names of a module that can be used as symbolic identifiers, taken from
+/// its [`NameSection`](crate::parse::modules::name_section::NameSection).
+///
+/// Names that are not valid identifiers, and names already given to an
+/// earlier definition of the same index space, are dropped so that the
+/// printed module can be parsed again.
+///
+/// [Reference](https://webassembly.github.io/spec/core/appendix/custom.html#name-section)
+#[derive(Default)]
+pub(super) struct Names<'a> {
+    pub module: Option<&'a str>,
+    pub funcs: BTreeMap<u32, &'a str>,
+    pub locals: BTreeMap<u32, BTreeMap<u32, &'a str>>,
+}
+
+fn is_id(name: &str) -> bool {
+    !name.is_empty() && name.bytes().all(is_idchar)
+}
+
+/// Keeps the names of `map` that are valid and unique identifiers.
+fn unique<'a>(map: &NameMap<'a>) -> BTreeMap<u32, &'a str> {
+    let mut seen = BTreeSet::new();
+    map.names
+        .iter()
+        .filter(|&&(_, name)| is_id(name) && seen.insert(name))
+        .copied()
+        .collect()
+}
+
+impl<'a> Names<'a> {
+    pub fn new(module: &Module<'a>) -> Self {
+        let Some(section) = &module.names else {
+            return Names::default();
+        };
+        Names {
+            module: section.module.filter(|n| is_id(n)),
+            funcs: unique(&section.funcs),
+            locals: section
+                .locals
+                .maps
+                .iter()
+                .map(|(f, map)| (*f, unique(map)))
+                .collect(),
+        }
+    }
+}
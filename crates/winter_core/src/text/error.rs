@@ -0,0 +1,129 @@
+use core::fmt;
+
+/// The index spaces of a module in which symbolic identifiers are bound.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Namespace {
+    Type,
+    Func,
+    Table,
+    Memory,
+    Global,
+    Elem,
+    Data,
+    Local,
+    Label,
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Namespace::Type => write!(f, "type"),
+            Namespace::Func => write!(f, "function"),
+            Namespace::Table => write!(f, "table"),
+            Namespace::Memory => write!(f, "memory"),
+            Namespace::Global => write!(f, "global"),
+            Namespace::Elem => write!(f, "elem segment"),
+            Namespace::Data => write!(f, "data segment"),
+            Namespace::Local => write!(f, "local"),
+            Namespace::Label => write!(f, "label"),
+        }
+    }
+}
+
+/// The reason why a text module is malformed. The [`fmt::Display`]
+/// implementation uses the messages of the specification's test suite
+/// where there is one.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum TextErrorKind {
+    /// A character that cannot start a token.
+    UnexpectedCharacter,
+    UnterminatedString,
+    UnterminatedComment,
+    /// An unknown escape sequence or a `\u{...}` escape that is not a
+    /// Unicode scalar value.
+    InvalidEscape,
+    /// A name is not valid UTF-8.
+    MalformedUtf8,
+    UnexpectedToken,
+    UnexpectedEnd,
+    UnknownOperator,
+    /// A number literal does not fit in its type.
+    ConstantOutOfRange,
+    /// An unsigned integer, such as an index or an offset, does not fit in
+    /// 32 bits.
+    U32OutOfRange,
+    UnknownId(Namespace),
+    DuplicateId(Namespace),
+    /// The label after `else` or `end` is not the label of the block.
+    MismatchingLabel,
+    /// A type use with both a type index and inline parameters or results
+    /// that do not match the type.
+    InlineFunctionType,
+    /// A `param` field follows a `result` field in a type definition.
+    ResultBeforeParameter,
+    /// An import follows a definition in the same index space.
+    ImportAfterDefinition(Namespace),
+    MultipleStart,
+    /// The alignment of a memory access is not a power of two.
+    InvalidAlignment,
+    /// A lane index that does not fit in a byte.
+    MalformedLaneIndex,
+    /// A `v128.const` has more or fewer literals than its shape has lanes.
+    WrongLaneCount,
+    /// Blocks or folded instructions are nested deeper than
+    /// [`DEFAULT_MAX_NESTING_DEPTH`](crate::parse::instructions::DEFAULT_MAX_NESTING_DEPTH).
+    NestingTooDeep,
+}
+
+impl fmt::Display for TextErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TextErrorKind::UnexpectedCharacter => write!(f, "unexpected character"),
+            TextErrorKind::UnterminatedString => write!(f, "unclosed string"),
+            TextErrorKind::UnterminatedComment => write!(f, "unclosed comment"),
+            TextErrorKind::InvalidEscape => write!(f, "malformed escape"),
+            TextErrorKind::MalformedUtf8 => write!(f, "malformed UTF-8 encoding"),
+            TextErrorKind::UnexpectedToken => write!(f, "unexpected token"),
+            TextErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            TextErrorKind::UnknownOperator => write!(f, "unknown operator"),
+            TextErrorKind::ConstantOutOfRange => write!(f, "constant out of range"),
+            TextErrorKind::U32OutOfRange => write!(f, "i32 constant out of range"),
+            TextErrorKind::UnknownId(n) => write!(f, "unknown {}", n),
+            TextErrorKind::DuplicateId(n) => write!(f, "duplicate {}", n),
+            TextErrorKind::MismatchingLabel => write!(f, "mismatching label"),
+            TextErrorKind::InlineFunctionType => write!(f, "inline function type"),
+            TextErrorKind::ResultBeforeParameter => write!(f, "result before parameter"),
+            TextErrorKind::ImportAfterDefinition(n) => write!(f, "import after {}", n),
+            TextErrorKind::MultipleStart => write!(f, "multiple start sections"),
+            TextErrorKind::InvalidAlignment => write!(f, "alignment"),
+            TextErrorKind::MalformedLaneIndex => write!(f, "malformed lane index"),
+            TextErrorKind::WrongLaneCount => write!(f, "wrong number of lane literals"),
+            TextErrorKind::NestingTooDeep => write!(f, "nesting too deep"),
+        }
+    }
+}
+
+/// Error returned when a text module is malformed. `offset` is the byte
+/// offset in the source of the token at which the error was found.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct TextError {
+    pub kind: TextErrorKind,
+    pub offset: usize,
+}
+
+impl TextError {
+    /// Returns the one-based line and column of the error in `source`, the
+    /// text the module was parsed from.
+    pub fn line_col(&self, source: &str) -> (usize, usize) {
+        let before = &source[..self.offset.min(source.len())];
+        let line = before.matches('\n').count() + 1;
+        let column = before.chars().rev().take_while(|&c| c != '\n').count() + 1;
+        (line, column)
+    }
+}
+
+impl fmt::Display for TextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
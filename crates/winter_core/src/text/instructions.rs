@@ -0,0 +1,981 @@
+//! Instructions of the text format, in their flat and folded forms.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/text/instructions.html)
+
+use std::{boxed::Box, vec::Vec};
+
+use super::{module::ModuleParser, parser::Result, Namespace, TextErrorKind};
+use crate::parse::{
+    instructions::{
+        BlockType, ControlInstruction, Instr, MemArg, MemoryInstruction, NumericInstruction,
+        ParametricInstruction, ReferenceInstruction, TableInstruction, VariableInstruction,
+        VectorInstruction, DEFAULT_MAX_NESTING_DEPTH,
+    },
+    modules::{DataIdx, ElemIdx, FuncIdx, GlobalIdx, LabelIdx, LocalIdx, MemIdx, TableIdx},
+};
+
+/// The keywords of the text format that are not instructions. They are
+/// unexpected where an instruction is expected, while other keywords are
+/// unknown operators.
+const KEYWORDS: &[&str] = &[
+    "module",
+    "type",
+    "func",
+    "param",
+    "result",
+    "local",
+    "global",
+    "table",
+    "memory",
+    "elem",
+    "data",
+    "start",
+    "import",
+    "export",
+    "mut",
+    "offset",
+    "item",
+    "declare",
+    "then",
+    "funcref",
+    "externref",
+    "i32",
+    "i64",
+    "f32",
+    "f64",
+    "v128",
+];
+
+/// Generates the lookup of the instructions that take no immediates, by
+/// name and by value.
+macro_rules! plain_instructions {
+    ($($name:literal => $group:ident($ty:ident::$variant:ident),)*) => {
+        fn plain_instruction(name: &str) -> Option<Instr> {
+            Some(match name {
+                $($name => Instr::$group(Box::new($ty::$variant)),)*
+                _ => return None,
+            })
+        }
+
+        /// Returns the name of `instr` if it is a numeric or vector
+        /// instruction without immediates.
+        pub(crate) fn plain_name(instr: &Instr) -> Option<&'static str> {
+            $(if let Instr::$group(i) = instr {
+                if matches!(**i, $ty::$variant) {
+                    return Some($name);
+                }
+            })*
+            None
+        }
+    };
+}
+
+/// Generates the lookup of the instructions that take a memory argument,
+/// along with the exponent of their natural alignment.
+macro_rules! memarg_instructions {
+    ($($name:literal, $align:literal => $group:ident($ty:ident::$variant:ident),)*) => {
+        fn memarg_instruction(name: &str) -> Option<(fn(MemArg) -> Instr, u32)> {
+            Some(match name {
+                $($name => {
+                    let f: fn(MemArg) -> Instr = |m| Instr::$group(Box::new($ty::$variant(m)));
+                    (f, $align)
+                })*
+                _ => return None,
+            })
+        }
+
+        /// Returns the name of `instr` if it takes a memory argument, along
+        /// with the argument and the exponent of the natural alignment.
+        pub(crate) fn memarg_name(instr: &Instr) -> Option<(&'static str, MemArg, u32)> {
+            $(if let Instr::$group(i) = instr {
+                if let $ty::$variant(m) = **i {
+                    return Some(($name, m, $align));
+                }
+            })*
+            None
+        }
+    };
+}
+
+/// Generates the lookup of the vector instructions that take a lane index,
+/// optionally preceded by a memory argument.
+macro_rules! lane_instructions {
+    (
+        $($name:literal => $variant:ident,)*;
+        $($mem_name:literal, $align:literal => $mem_variant:ident,)*
+    ) => {
+        fn lane_instruction(name: &str) -> Option<fn(u8) -> VectorInstruction> {
+            Some(match name {
+                $($name => VectorInstruction::$variant,)*
+                _ => return None,
+            })
+        }
+
+        fn memarg_lane_instruction(
+            name: &str,
+        ) -> Option<(fn(MemArg, u8) -> VectorInstruction, u32)> {
+            Some(match name {
+                $($mem_name => (VectorInstruction::$mem_variant, $align),)*
+                _ => return None,
+            })
+        }
+
+        /// Returns the name and the lane index of `instr` if it takes a
+        /// lane index.
+        pub(crate) fn lane_name(instr: &VectorInstruction) -> Option<(&'static str, u8)> {
+            match *instr {
+                $(VectorInstruction::$variant(lane) => Some(($name, lane)),)*
+                _ => None,
+            }
+        }
+
+        /// Returns the name, the memory argument, the exponent of the
+        /// natural alignment and the lane index of `instr` if it takes a
+        /// memory argument and a lane index.
+        pub(crate) fn memarg_lane_name(
+            instr: &VectorInstruction,
+        ) -> Option<(&'static str, MemArg, u32, u8)> {
+            match *instr {
+                $(VectorInstruction::$mem_variant(m, lane) => Some(($mem_name, m, $align, lane)),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+fn control(instr: ControlInstruction) -> Instr {
+    Instr::Control(Box::new(instr))
+}
+
+fn table(instr: TableInstruction) -> Instr {
+    Instr::Table(Box::new(instr))
+}
+
+fn memory(instr: MemoryInstruction) -> Instr {
+    Instr::Memory(Box::new(instr))
+}
+
+fn numeric(instr: NumericInstruction) -> Instr {
+    Instr::Numeric(Box::new(instr))
+}
+
+fn vector(instr: VectorInstruction) -> Instr {
+    Instr::Vector(Box::new(instr))
+}
+
+/// A structured instruction whose instructions are being parsed.
+struct Block<'a> {
+    /// `block`, `loop` or `if`.
+    keyword: &'a str,
+    label: Option<&'a str>,
+    block_type: BlockType,
+    /// The instructions that precede the structured instruction.
+    outer: Vec<Instr>,
+    /// The instructions of the first branch of an `if` whose second branch
+    /// is being parsed.
+    then: Option<Vec<Instr>>,
+}
+
+impl Block<'_> {
+    /// Replaces `out`, the last instructions of the structured instruction,
+    /// with the instructions that precede it followed by the structured
+    /// instruction.
+    fn close(mut self, out: &mut Vec<Instr>) {
+        let instrs = core::mem::replace(out, core::mem::take(&mut self.outer));
+        let instr = match (self.keyword, self.then) {
+            ("block", _) => ControlInstruction::Block(self.block_type, instrs),
+            ("loop", _) => ControlInstruction::Loop(self.block_type, instrs),
+            (_, Some(then)) => ControlInstruction::If(self.block_type, then, instrs),
+            (_, None) => ControlInstruction::If(self.block_type, instrs, Vec::new()),
+        };
+        out.push(control(instr));
+    }
+}
+
+/// An instruction whose nested instructions are being parsed.
+enum Frame<'a> {
+    /// A flat structured instruction, which ends with `end`.
+    Flat(Block<'a>),
+    /// A folded `block` or `loop`, or a branch of a folded `if`, which ends
+    /// with a closing parenthesis.
+    Folded(Block<'a>),
+    /// The condition of a folded `if`, which ends with its `then` branch.
+    Condition(Block<'a>),
+    /// The operands of a folded instruction that is not structured, which
+    /// end with a closing parenthesis.
+    Operands(Instr),
+}
+
+impl<'a> ModuleParser<'a> {
+    /// Parses flat and folded instructions up to the end of the current
+    /// field or block.
+    pub(super) fn instrs(&mut self, out: &mut Vec<Instr>) -> Result<()> {
+        self.nested_instrs(Vec::new(), out)
+    }
+
+    /// Parses a folded instruction and appends it to `out`, after the
+    /// instructions computing its operands.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/text/instructions.html#folded-instructions)
+    pub(super) fn folded_instr(&mut self, out: &mut Vec<Instr>) -> Result<()> {
+        let mut frames = Vec::new();
+        self.open_folded(&mut frames, out)?;
+        self.nested_instrs(frames, out)
+    }
+
+    /// Parses instructions and appends them to `out`, inside the open
+    /// instructions of `frames`. Returns once they are all closed, or at
+    /// the end of the current field or block if there are none.
+    ///
+    /// Nested instructions are parsed with a stack of the instructions that
+    /// contain them rather than by recursion, so that deeply nested
+    /// instructions cannot exhaust the native stack. At most
+    /// [`DEFAULT_MAX_NESTING_DEPTH`] instructions may be open at once.
+    fn nested_instrs(&mut self, mut frames: Vec<Frame<'a>>, out: &mut Vec<Instr>) -> Result<()> {
+        let single = !frames.is_empty();
+        loop {
+            match frames.last() {
+                Some(Frame::Condition(_)) if self.p.take_field("then") => {
+                    if let Some(Frame::Condition(mut block)) = frames.pop() {
+                        // The condition precedes the `if`.
+                        block.outer = core::mem::take(out);
+                        self.labels.push(block.label);
+                        frames.push(Frame::Folded(block));
+                    }
+                    continue;
+                }
+                // The condition is made of folded instructions only.
+                Some(Frame::Condition(_)) => {
+                    self.open_folded(&mut frames, out)?;
+                    continue;
+                }
+                _ if self.p.peek_lparen() => {
+                    self.open_folded(&mut frames, out)?;
+                    continue;
+                }
+                _ => {}
+            }
+            match self.p.peek_keyword() {
+                None | Some("end" | "else") => {}
+                Some(_) => {
+                    self.flat_instr(&mut frames, out)?;
+                    continue;
+                }
+            }
+
+            match frames.pop() {
+                None => return Ok(()),
+                Some(Frame::Flat(mut block)) => {
+                    if block.keyword == "if" && block.then.is_none() && self.p.take_keyword("else")
+                    {
+                        self.end_label(block.label)?;
+                        block.then = Some(core::mem::take(out));
+                        frames.push(Frame::Flat(block));
+                        continue;
+                    }
+                    self.p.expect_keyword("end")?;
+                    self.end_label(block.label)?;
+                    self.labels.pop();
+                    block.close(out);
+                }
+                Some(Frame::Folded(mut block)) => {
+                    self.p.rparen()?;
+                    if block.keyword == "if" {
+                        if block.then.is_none() && self.p.take_field("else") {
+                            block.then = Some(core::mem::take(out));
+                            frames.push(Frame::Folded(block));
+                            continue;
+                        }
+                        // The branches are followed by the end of the `if`.
+                        self.p.rparen()?;
+                    }
+                    self.labels.pop();
+                    block.close(out);
+                }
+                Some(Frame::Operands(instr)) => {
+                    self.p.rparen()?;
+                    out.push(instr);
+                }
+                Some(Frame::Condition(_)) => unreachable!("conditions end with their branches"),
+            }
+            if single && frames.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Opens `frame`, unless [`DEFAULT_MAX_NESTING_DEPTH`] instructions are
+    /// already open.
+    fn push_frame(&mut self, frames: &mut Vec<Frame<'a>>, frame: Frame<'a>) -> Result<()> {
+        if frames.len() as u32 >= DEFAULT_MAX_NESTING_DEPTH {
+            return Err(self.p.error(TextErrorKind::NestingTooDeep));
+        }
+        if let Frame::Flat(block) | Frame::Folded(block) = &frame {
+            self.labels.push(block.label);
+        }
+        frames.push(frame);
+        Ok(())
+    }
+
+    /// Parses the optional label after `else` or `end`, which must repeat
+    /// the label of the block.
+    fn end_label(&mut self, label: Option<&'a str>) -> Result<()> {
+        let offset = self.p.offset();
+        match self.p.id() {
+            Some(id) if Some(id) != label => {
+                Err(self.p.error_at(TextErrorKind::MismatchingLabel, offset))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parses a flat instruction. A structured instruction is opened, and
+    /// other instructions are appended to `out`.
+    fn flat_instr(&mut self, frames: &mut Vec<Frame<'a>>, out: &mut Vec<Instr>) -> Result<()> {
+        let offset = self.p.offset();
+        let keyword = self.p.keyword()?;
+        match keyword {
+            "block" | "loop" | "if" => {
+                let label = self.p.id();
+                let block = Block {
+                    keyword,
+                    label,
+                    block_type: self.block_type()?,
+                    outer: core::mem::take(out),
+                    then: None,
+                };
+                self.push_frame(frames, Frame::Flat(block))
+            }
+            _ => {
+                out.push(self.plain_instr(keyword, offset)?);
+                Ok(())
+            }
+        }
+    }
+
+    /// Opens a folded instruction, whose operands or instructions follow.
+    fn open_folded(&mut self, frames: &mut Vec<Frame<'a>>, out: &mut Vec<Instr>) -> Result<()> {
+        self.p.lparen()?;
+        let offset = self.p.offset();
+        let keyword = self.p.keyword()?;
+        let frame = match keyword {
+            "block" | "loop" | "if" => {
+                let label = self.p.id();
+                let mut block = Block {
+                    keyword,
+                    label,
+                    block_type: self.block_type()?,
+                    outer: Vec::new(),
+                    then: None,
+                };
+                match keyword {
+                    // The condition of an `if` precedes it, so its
+                    // instructions are set aside at its `then` branch.
+                    "if" => Frame::Condition(block),
+                    _ => {
+                        block.outer = core::mem::take(out);
+                        Frame::Folded(block)
+                    }
+                }
+            }
+            _ => Frame::Operands(self.plain_instr(keyword, offset)?),
+        };
+        self.push_frame(frames, frame)
+    }
+
+    /// Parses an optional index, which defaults to 0.
+    fn opt_index(&mut self, ns: Namespace) -> Result<u32> {
+        match self.p.peek_index() {
+            true => self.index(ns),
+            false => Ok(0),
+        }
+    }
+
+    /// Parses the two optional indices of `table.copy` and `memory.copy`.
+    fn opt_index_pair(&mut self, ns: Namespace) -> Result<(u32, u32)> {
+        match self.p.peek_index() {
+            true => Ok((self.index(ns)?, self.index(ns)?)),
+            false => Ok((0, 0)),
+        }
+    }
+
+    /// Parses the immediates of `table.init` and `memory.init`: a segment
+    /// index, optionally preceded by the index of the table or memory.
+    fn init_indices(&mut self, ns: Namespace, segment: Namespace) -> Result<(u32, u32)> {
+        let index = match self.p.peek_index_at(1) {
+            true => self.index(ns)?,
+            false => 0,
+        };
+        Ok((self.index(segment)?, index))
+    }
+
+    /// Parses a memory argument, whose alignment defaults to the natural
+    /// alignment `2^natural`.
+    fn memarg(&mut self, natural: u32) -> Result<MemArg> {
+        let offset = self.p.key_value("offset=")?.unwrap_or(0);
+        let position = self.p.offset();
+        let align = match self.p.key_value("align=")? {
+            Some(align) if align.is_power_of_two() => align.trailing_zeros(),
+            Some(_) => return Err(self.p.error_at(TextErrorKind::InvalidAlignment, position)),
+            None => natural,
+        };
+        Ok(MemArg { align, offset })
+    }
+
+    fn lane(&mut self) -> Result<u8> {
+        let offset = self.p.offset();
+        let lane = self.p.u32()?;
+        u8::try_from(lane).map_err(|_| self.p.error_at(TextErrorKind::MalformedLaneIndex, offset))
+    }
+
+    /// Parses the immediates of the instruction `keyword`, which started at
+    /// `offset`.
+    fn plain_instr(&mut self, keyword: &'a str, offset: usize) -> Result<Instr> {
+        if let Some(instr) = plain_instruction(keyword) {
+            return Ok(instr);
+        }
+        if let Some((f, natural)) = memarg_instruction(keyword) {
+            return Ok(f(self.memarg(natural)?));
+        }
+        if let Some(f) = lane_instruction(keyword) {
+            return Ok(vector(f(self.lane()?)));
+        }
+        if let Some((f, natural)) = memarg_lane_instruction(keyword) {
+            let memarg = self.memarg(natural)?;
+            return Ok(vector(f(memarg, self.lane()?)));
+        }
+
+        let instr = match keyword {
+            "unreachable" => control(ControlInstruction::Unreachable),
+            "nop" => control(ControlInstruction::Nop),
+            "br" => control(ControlInstruction::Br(LabelIdx(
+                self.index(Namespace::Label)?,
+            ))),
+            "br_if" => control(ControlInstruction::BrIf(LabelIdx(
+                self.index(Namespace::Label)?,
+            ))),
+            "br_table" => {
+                let mut labels = Vec::new();
+                while self.p.peek_index() {
+                    labels.push(LabelIdx(self.index(Namespace::Label)?));
+                }
+                let default = labels.pop().ok_or_else(|| self.p.unexpected())?;
+                control(ControlInstruction::BrTable(labels, default))
+            }
+            "return" => control(ControlInstruction::Return),
+            "call" => control(ControlInstruction::Call(FuncIdx(
+                self.index(Namespace::Func)?,
+            ))),
+            "call_indirect" => {
+                let table = self.opt_index(Namespace::Table)?;
+                let type_idx = self.anonymous_type_use()?;
+                control(ControlInstruction::CallIndirect(type_idx, TableIdx(table)))
+            }
+
+            "ref.null" => {
+                Instr::Reference(Box::new(ReferenceInstruction::RefNull(self.p.heaptype()?)))
+            }
+            "ref.is_null" => Instr::Reference(Box::new(ReferenceInstruction::RefIsNull)),
+            "ref.func" => Instr::Reference(Box::new(ReferenceInstruction::RefFunc(FuncIdx(
+                self.index(Namespace::Func)?,
+            )))),
+
+            "drop" => Instr::Parametric(Box::new(ParametricInstruction::Drop)),
+            "select" => {
+                let mut types: Option<Vec<_>> = None;
+                while self.p.take_field("result") {
+                    types
+                        .get_or_insert_with(Vec::new)
+                        .extend(self.p.valtypes()?);
+                    self.p.rparen()?;
+                }
+                Instr::Parametric(Box::new(ParametricInstruction::Select(types)))
+            }
+
+            "local.get" | "local.set" | "local.tee" => {
+                let index = LocalIdx(self.index(Namespace::Local)?);
+                Instr::Variable(Box::new(match keyword {
+                    "local.get" => VariableInstruction::LocalGet(index),
+                    "local.set" => VariableInstruction::LocalSet(index),
+                    _ => VariableInstruction::LocalTee(index),
+                }))
+            }
+            "global.get" | "global.set" => {
+                let index = GlobalIdx(self.index(Namespace::Global)?);
+                Instr::Variable(Box::new(match keyword {
+                    "global.get" => VariableInstruction::GlobalGet(index),
+                    _ => VariableInstruction::GlobalSet(index),
+                }))
+            }
+
+            "table.get" | "table.set" | "table.size" | "table.grow" | "table.fill" => {
+                let index = TableIdx(self.opt_index(Namespace::Table)?);
+                table(match keyword {
+                    "table.get" => TableInstruction::TableGet(index),
+                    "table.set" => TableInstruction::TableSet(index),
+                    "table.size" => TableInstruction::TableSize(index),
+                    "table.grow" => TableInstruction::TableGrow(index),
+                    _ => TableInstruction::TableFill(index),
+                })
+            }
+            "table.copy" => {
+                let (x, y) = self.opt_index_pair(Namespace::Table)?;
+                table(TableInstruction::TableCopy(TableIdx(x), TableIdx(y)))
+            }
+            "table.init" => {
+                let (elem, index) = self.init_indices(Namespace::Table, Namespace::Elem)?;
+                table(TableInstruction::TableInit(ElemIdx(elem), TableIdx(index)))
+            }
+            "elem.drop" => table(TableInstruction::ElemDrop(ElemIdx(
+                self.index(Namespace::Elem)?,
+            ))),
+
+            "memory.size" | "memory.grow" | "memory.fill" => {
+                let index = MemIdx(self.opt_index(Namespace::Memory)?);
+                memory(match keyword {
+                    "memory.size" => MemoryInstruction::MemorySize(index),
+                    "memory.grow" => MemoryInstruction::MemoryGrow(index),
+                    _ => MemoryInstruction::MemoryFill(index),
+                })
+            }
+            "memory.copy" => {
+                let (x, y) = self.opt_index_pair(Namespace::Memory)?;
+                memory(MemoryInstruction::MemoryCopy(MemIdx(x), MemIdx(y)))
+            }
+            "memory.init" => {
+                self.uses_data_count = true;
+                let (data, index) = self.init_indices(Namespace::Memory, Namespace::Data)?;
+                memory(MemoryInstruction::MemoryInit(DataIdx(data), MemIdx(index)))
+            }
+            "data.drop" => {
+                self.uses_data_count = true;
+                memory(MemoryInstruction::DataDrop(DataIdx(
+                    self.index(Namespace::Data)?,
+                )))
+            }
+
+            "i32.const" => numeric(NumericInstruction::I32Const(self.p.int(32)? as u32 as i32)),
+            "i64.const" => numeric(NumericInstruction::I64Const(self.p.int(64)? as i64)),
+            "f32.const" => numeric(NumericInstruction::F32Const(self.p.f32()?)),
+            "f64.const" => numeric(NumericInstruction::F64Const(self.p.f64()?)),
+
+            "v128.const" => vector(VectorInstruction::V128Const(self.p.v128_const()?)),
+            "i8x16.shuffle" => {
+                let mut lanes = [0; 16];
+                for lane in &mut lanes {
+                    *lane = self.lane()?;
+                }
+                vector(VectorInstruction::I8x16Shuffle(lanes))
+            }
+
+            _ if KEYWORDS.contains(&keyword) => {
+                return Err(self.p.error_at(TextErrorKind::UnexpectedToken, offset))
+            }
+            _ => return Err(self.p.error_at(TextErrorKind::UnknownOperator, offset)),
+        };
+        Ok(instr)
+    }
+}
+
+lane_instructions! {
+    "i8x16.extract_lane_s" => I8x16ExtractLaneS,
+    "i8x16.extract_lane_u" => I8x16ExtractLaneU,
+    "i8x16.replace_lane" => I8x16ReplaceLane,
+    "i16x8.extract_lane_s" => I16x8ExtractLaneS,
+    "i16x8.extract_lane_u" => I16x8ExtractLaneU,
+    "i16x8.replace_lane" => I16x8ReplaceLane,
+    "i32x4.extract_lane" => I32x4ExtractLane,
+    "i32x4.replace_lane" => I32x4ReplaceLane,
+    "i64x2.extract_lane" => I64x2ExtractLane,
+    "i64x2.replace_lane" => I64x2ReplaceLane,
+    "f32x4.extract_lane" => F32x4ExtractLane,
+    "f32x4.replace_lane" => F32x4ReplaceLane,
+    "f64x2.extract_lane" => F64x2ExtractLane,
+    "f64x2.replace_lane" => F64x2ReplaceLane,
+    ;
+    "v128.load8_lane", 0 => V128Load8Lane,
+    "v128.load16_lane", 1 => V128Load16Lane,
+    "v128.load32_lane", 2 => V128Load32Lane,
+    "v128.load64_lane", 3 => V128Load64Lane,
+    "v128.store8_lane", 0 => V128Store8Lane,
+    "v128.store16_lane", 1 => V128Store16Lane,
+    "v128.store32_lane", 2 => V128Store32Lane,
+    "v128.store64_lane", 3 => V128Store64Lane,
+}
+
+memarg_instructions! {
+    "i32.load", 2 => Memory(MemoryInstruction::I32Load),
+    "i64.load", 3 => Memory(MemoryInstruction::I64Load),
+    "f32.load", 2 => Memory(MemoryInstruction::F32Load),
+    "f64.load", 3 => Memory(MemoryInstruction::F64Load),
+    "i32.load8_s", 0 => Memory(MemoryInstruction::I32Load8S),
+    "i32.load8_u", 0 => Memory(MemoryInstruction::I32Load8U),
+    "i32.load16_s", 1 => Memory(MemoryInstruction::I32Load16S),
+    "i32.load16_u", 1 => Memory(MemoryInstruction::I32Load16U),
+    "i64.load8_s", 0 => Memory(MemoryInstruction::I64Load8S),
+    "i64.load8_u", 0 => Memory(MemoryInstruction::I64Load8U),
+    "i64.load16_s", 1 => Memory(MemoryInstruction::I64Load16S),
+    "i64.load16_u", 1 => Memory(MemoryInstruction::I64Load16U),
+    "i64.load32_s", 2 => Memory(MemoryInstruction::I64Load32S),
+    "i64.load32_u", 2 => Memory(MemoryInstruction::I64Load32U),
+    "i32.store", 2 => Memory(MemoryInstruction::I32Store),
+    "i64.store", 3 => Memory(MemoryInstruction::I64Store),
+    "f32.store", 2 => Memory(MemoryInstruction::F32Store),
+    "f64.store", 3 => Memory(MemoryInstruction::F64Store),
+    "i32.store8", 0 => Memory(MemoryInstruction::I32Store8),
+    "i32.store16", 1 => Memory(MemoryInstruction::I32Store16),
+    "i64.store8", 0 => Memory(MemoryInstruction::I64Store8),
+    "i64.store16", 1 => Memory(MemoryInstruction::I64Store16),
+    "i64.store32", 2 => Memory(MemoryInstruction::I64Store32),
+    "v128.load", 4 => Vector(VectorInstruction::V128Load),
+    "v128.load8x8_s", 3 => Vector(VectorInstruction::V128Load8x8S),
+    "v128.load8x8_u", 3 => Vector(VectorInstruction::V128Load8x8U),
+    "v128.load16x4_s", 3 => Vector(VectorInstruction::V128Load16x4S),
+    "v128.load16x4_u", 3 => Vector(VectorInstruction::V128Load16x4U),
+    "v128.load32x2_s", 3 => Vector(VectorInstruction::V128Load32x2S),
+    "v128.load32x2_u", 3 => Vector(VectorInstruction::V128Load32x2U),
+    "v128.load8_splat", 0 => Vector(VectorInstruction::V128Load8Splat),
+    "v128.load16_splat", 1 => Vector(VectorInstruction::V128Load16Splat),
+    "v128.load32_splat", 2 => Vector(VectorInstruction::V128Load32Splat),
+    "v128.load64_splat", 3 => Vector(VectorInstruction::V128Load64Splat),
+    "v128.load32_zero", 2 => Vector(VectorInstruction::V128Load32Zero),
+    "v128.load64_zero", 3 => Vector(VectorInstruction::V128Load64Zero),
+    "v128.store", 4 => Vector(VectorInstruction::V128Store),
+}
+
+plain_instructions! {
+    "i32.eqz" => Numeric(NumericInstruction::I32Eqz),
+    "i32.eq" => Numeric(NumericInstruction::I32Eq),
+    "i32.ne" => Numeric(NumericInstruction::I32Ne),
+    "i32.lt_s" => Numeric(NumericInstruction::I32LtS),
+    "i32.lt_u" => Numeric(NumericInstruction::I32LtU),
+    "i32.gt_s" => Numeric(NumericInstruction::I32GtS),
+    "i32.gt_u" => Numeric(NumericInstruction::I32GtU),
+    "i32.le_s" => Numeric(NumericInstruction::I32LeS),
+    "i32.le_u" => Numeric(NumericInstruction::I32LeU),
+    "i32.ge_s" => Numeric(NumericInstruction::I32GeS),
+    "i32.ge_u" => Numeric(NumericInstruction::I32GeU),
+    "i64.eqz" => Numeric(NumericInstruction::I64Eqz),
+    "i64.eq" => Numeric(NumericInstruction::I64Eq),
+    "i64.ne" => Numeric(NumericInstruction::I64Ne),
+    "i64.lt_s" => Numeric(NumericInstruction::I64LtS),
+    "i64.lt_u" => Numeric(NumericInstruction::I64LtU),
+    "i64.gt_s" => Numeric(NumericInstruction::I64GtS),
+    "i64.gt_u" => Numeric(NumericInstruction::I64GtU),
+    "i64.le_s" => Numeric(NumericInstruction::I64LeS),
+    "i64.le_u" => Numeric(NumericInstruction::I64LeU),
+    "i64.ge_s" => Numeric(NumericInstruction::I64GeS),
+    "i64.ge_u" => Numeric(NumericInstruction::I64GeU),
+    "f32.eq" => Numeric(NumericInstruction::F32Eq),
+    "f32.ne" => Numeric(NumericInstruction::F32Ne),
+    "f32.lt" => Numeric(NumericInstruction::F32Lt),
+    "f32.gt" => Numeric(NumericInstruction::F32Gt),
+    "f32.le" => Numeric(NumericInstruction::F32Le),
+    "f32.ge" => Numeric(NumericInstruction::F32Ge),
+    "f64.eq" => Numeric(NumericInstruction::F64Eq),
+    "f64.ne" => Numeric(NumericInstruction::F64Ne),
+    "f64.lt" => Numeric(NumericInstruction::F64Lt),
+    "f64.gt" => Numeric(NumericInstruction::F64Gt),
+    "f64.le" => Numeric(NumericInstruction::F64Le),
+    "f64.ge" => Numeric(NumericInstruction::F64Ge),
+    "i32.clz" => Numeric(NumericInstruction::I32Clz),
+    "i32.ctz" => Numeric(NumericInstruction::I32Ctz),
+    "i32.popcnt" => Numeric(NumericInstruction::I32Popcnt),
+    "i32.add" => Numeric(NumericInstruction::I32Add),
+    "i32.sub" => Numeric(NumericInstruction::I32Sub),
+    "i32.mul" => Numeric(NumericInstruction::I32Mul),
+    "i32.div_s" => Numeric(NumericInstruction::I32DivS),
+    "i32.div_u" => Numeric(NumericInstruction::I32DivU),
+    "i32.rem_s" => Numeric(NumericInstruction::I32RemS),
+    "i32.rem_u" => Numeric(NumericInstruction::I32RemU),
+    "i32.and" => Numeric(NumericInstruction::I32And),
+    "i32.or" => Numeric(NumericInstruction::I32Or),
+    "i32.xor" => Numeric(NumericInstruction::I32Xor),
+    "i32.shl" => Numeric(NumericInstruction::I32Shl),
+    "i32.shr_s" => Numeric(NumericInstruction::I32ShrS),
+    "i32.shr_u" => Numeric(NumericInstruction::I32ShrU),
+    "i32.rotl" => Numeric(NumericInstruction::I32Rotl),
+    "i32.rotr" => Numeric(NumericInstruction::I32Rotr),
+    "i64.clz" => Numeric(NumericInstruction::I64Clz),
+    "i64.ctz" => Numeric(NumericInstruction::I64Ctz),
+    "i64.popcnt" => Numeric(NumericInstruction::I64Popcnt),
+    "i64.add" => Numeric(NumericInstruction::I64Add),
+    "i64.sub" => Numeric(NumericInstruction::I64Sub),
+    "i64.mul" => Numeric(NumericInstruction::I64Mul),
+    "i64.div_s" => Numeric(NumericInstruction::I64DivS),
+    "i64.div_u" => Numeric(NumericInstruction::I64DivU),
+    "i64.rem_s" => Numeric(NumericInstruction::I64RemS),
+    "i64.rem_u" => Numeric(NumericInstruction::I64RemU),
+    "i64.and" => Numeric(NumericInstruction::I64And),
+    "i64.or" => Numeric(NumericInstruction::I64Or),
+    "i64.xor" => Numeric(NumericInstruction::I64Xor),
+    "i64.shl" => Numeric(NumericInstruction::I64Shl),
+    "i64.shr_s" => Numeric(NumericInstruction::I64ShrS),
+    "i64.shr_u" => Numeric(NumericInstruction::I64ShrU),
+    "i64.rotl" => Numeric(NumericInstruction::I64Rotl),
+    "i64.rotr" => Numeric(NumericInstruction::I64Rotr),
+    "f32.abs" => Numeric(NumericInstruction::F32Abs),
+    "f32.neg" => Numeric(NumericInstruction::F32Neg),
+    "f32.ceil" => Numeric(NumericInstruction::F32Ceil),
+    "f32.floor" => Numeric(NumericInstruction::F32Floor),
+    "f32.trunc" => Numeric(NumericInstruction::F32Trunc),
+    "f32.nearest" => Numeric(NumericInstruction::F32Nearest),
+    "f32.sqrt" => Numeric(NumericInstruction::F32Sqrt),
+    "f32.add" => Numeric(NumericInstruction::F32Add),
+    "f32.sub" => Numeric(NumericInstruction::F32Sub),
+    "f32.mul" => Numeric(NumericInstruction::F32Mul),
+    "f32.div" => Numeric(NumericInstruction::F32Div),
+    "f32.min" => Numeric(NumericInstruction::F32Min),
+    "f32.max" => Numeric(NumericInstruction::F32Max),
+    "f32.copysign" => Numeric(NumericInstruction::F32Copysign),
+    "f64.abs" => Numeric(NumericInstruction::F64Abs),
+    "f64.neg" => Numeric(NumericInstruction::F64Neg),
+    "f64.ceil" => Numeric(NumericInstruction::F64Ceil),
+    "f64.floor" => Numeric(NumericInstruction::F64Floor),
+    "f64.trunc" => Numeric(NumericInstruction::F64Trunc),
+    "f64.nearest" => Numeric(NumericInstruction::F64Nearest),
+    "f64.sqrt" => Numeric(NumericInstruction::F64Sqrt),
+    "f64.add" => Numeric(NumericInstruction::F64Add),
+    "f64.sub" => Numeric(NumericInstruction::F64Sub),
+    "f64.mul" => Numeric(NumericInstruction::F64Mul),
+    "f64.div" => Numeric(NumericInstruction::F64Div),
+    "f64.min" => Numeric(NumericInstruction::F64Min),
+    "f64.max" => Numeric(NumericInstruction::F64Max),
+    "f64.copysign" => Numeric(NumericInstruction::F64Copysign),
+    "i32.wrap_i64" => Numeric(NumericInstruction::I32WrapI64),
+    "i32.trunc_f32_s" => Numeric(NumericInstruction::I32TruncF32S),
+    "i32.trunc_f32_u" => Numeric(NumericInstruction::I32TruncF32U),
+    "i32.trunc_f64_s" => Numeric(NumericInstruction::I32TruncF64S),
+    "i32.trunc_f64_u" => Numeric(NumericInstruction::I32TruncF64U),
+    "i64.extend_i32_s" => Numeric(NumericInstruction::I64ExtendI32S),
+    "i64.extend_i32_u" => Numeric(NumericInstruction::I64ExtendI32U),
+    "i64.trunc_f32_s" => Numeric(NumericInstruction::I64TruncF32S),
+    "i64.trunc_f32_u" => Numeric(NumericInstruction::I64TruncF32U),
+    "i64.trunc_f64_s" => Numeric(NumericInstruction::I64TruncF64S),
+    "i64.trunc_f64_u" => Numeric(NumericInstruction::I64TruncF64U),
+    "f32.convert_i32_s" => Numeric(NumericInstruction::F32ConvertI32S),
+    "f32.convert_i32_u" => Numeric(NumericInstruction::F32ConvertI32U),
+    "f32.convert_i64_s" => Numeric(NumericInstruction::F32ConvertI64S),
+    "f32.convert_i64_u" => Numeric(NumericInstruction::F32ConvertI64U),
+    "f32.demote_f64" => Numeric(NumericInstruction::F32DemoteF64),
+    "f64.convert_i32_s" => Numeric(NumericInstruction::F64ConvertI32S),
+    "f64.convert_i32_u" => Numeric(NumericInstruction::F64ConvertI32U),
+    "f64.convert_i64_s" => Numeric(NumericInstruction::F64ConvertI64S),
+    "f64.convert_i64_u" => Numeric(NumericInstruction::F64ConvertI64U),
+    "f64.promote_f32" => Numeric(NumericInstruction::F64PromoteF32),
+    "i32.reinterpret_f32" => Numeric(NumericInstruction::I32ReinterpretF32),
+    "i64.reinterpret_f64" => Numeric(NumericInstruction::I64ReinterpretF64),
+    "f32.reinterpret_i32" => Numeric(NumericInstruction::F32ReinterpretI32),
+    "f64.reinterpret_i64" => Numeric(NumericInstruction::F64ReinterpretI64),
+    "i32.extend8_s" => Numeric(NumericInstruction::I32Extend8S),
+    "i32.extend16_s" => Numeric(NumericInstruction::I32Extend16S),
+    "i64.extend8_s" => Numeric(NumericInstruction::I64Extend8S),
+    "i64.extend16_s" => Numeric(NumericInstruction::I64Extend16S),
+    "i64.extend32_s" => Numeric(NumericInstruction::I64Extend32S),
+    "i32.trunc_sat_f32_s" => Numeric(NumericInstruction::I32TruncSatF32S),
+    "i32.trunc_sat_f32_u" => Numeric(NumericInstruction::I32TruncSatF32U),
+    "i32.trunc_sat_f64_s" => Numeric(NumericInstruction::I32TruncSatF64S),
+    "i32.trunc_sat_f64_u" => Numeric(NumericInstruction::I32TruncSatF64U),
+    "i64.trunc_sat_f32_s" => Numeric(NumericInstruction::I64TruncSatF32S),
+    "i64.trunc_sat_f32_u" => Numeric(NumericInstruction::I64TruncSatF32U),
+    "i64.trunc_sat_f64_s" => Numeric(NumericInstruction::I64TruncSatF64S),
+    "i64.trunc_sat_f64_u" => Numeric(NumericInstruction::I64TruncSatF64U),
+    "i8x16.swizzle" => Vector(VectorInstruction::I8x16Swizzle),
+    "i8x16.splat" => Vector(VectorInstruction::I8x16Splat),
+    "i16x8.splat" => Vector(VectorInstruction::I16x8Splat),
+    "i32x4.splat" => Vector(VectorInstruction::I32x4Splat),
+    "i64x2.splat" => Vector(VectorInstruction::I64x2Splat),
+    "f32x4.splat" => Vector(VectorInstruction::F32x4Splat),
+    "f64x2.splat" => Vector(VectorInstruction::F64x2Splat),
+    "i8x16.eq" => Vector(VectorInstruction::I8x16Eq),
+    "i8x16.ne" => Vector(VectorInstruction::I8x16Ne),
+    "i8x16.lt_s" => Vector(VectorInstruction::I8x16LtS),
+    "i8x16.lt_u" => Vector(VectorInstruction::I8x16LtU),
+    "i8x16.gt_s" => Vector(VectorInstruction::I8x16GtS),
+    "i8x16.gt_u" => Vector(VectorInstruction::I8x16GtU),
+    "i8x16.le_s" => Vector(VectorInstruction::I8x16LeS),
+    "i8x16.le_u" => Vector(VectorInstruction::I8x16LeU),
+    "i8x16.ge_s" => Vector(VectorInstruction::I8x16GeS),
+    "i8x16.ge_u" => Vector(VectorInstruction::I8x16GeU),
+    "i16x8.eq" => Vector(VectorInstruction::I16x8Eq),
+    "i16x8.ne" => Vector(VectorInstruction::I16x8Ne),
+    "i16x8.lt_s" => Vector(VectorInstruction::I16x8LtS),
+    "i16x8.lt_u" => Vector(VectorInstruction::I16x8LtU),
+    "i16x8.gt_s" => Vector(VectorInstruction::I16x8GtS),
+    "i16x8.gt_u" => Vector(VectorInstruction::I16x8GtU),
+    "i16x8.le_s" => Vector(VectorInstruction::I16x8LeS),
+    "i16x8.le_u" => Vector(VectorInstruction::I16x8LeU),
+    "i16x8.ge_s" => Vector(VectorInstruction::I16x8GeS),
+    "i16x8.ge_u" => Vector(VectorInstruction::I16x8GeU),
+    "i32x4.eq" => Vector(VectorInstruction::I32x4Eq),
+    "i32x4.ne" => Vector(VectorInstruction::I32x4Ne),
+    "i32x4.lt_s" => Vector(VectorInstruction::I32x4LtS),
+    "i32x4.lt_u" => Vector(VectorInstruction::I32x4LtU),
+    "i32x4.gt_s" => Vector(VectorInstruction::I32x4GtS),
+    "i32x4.gt_u" => Vector(VectorInstruction::I32x4GtU),
+    "i32x4.le_s" => Vector(VectorInstruction::I32x4LeS),
+    "i32x4.le_u" => Vector(VectorInstruction::I32x4LeU),
+    "i32x4.ge_s" => Vector(VectorInstruction::I32x4GeS),
+    "i32x4.ge_u" => Vector(VectorInstruction::I32x4GeU),
+    "f32x4.eq" => Vector(VectorInstruction::F32x4Eq),
+    "f32x4.ne" => Vector(VectorInstruction::F32x4Ne),
+    "f32x4.lt" => Vector(VectorInstruction::F32x4Lt),
+    "f32x4.gt" => Vector(VectorInstruction::F32x4Gt),
+    "f32x4.le" => Vector(VectorInstruction::F32x4Le),
+    "f32x4.ge" => Vector(VectorInstruction::F32x4Ge),
+    "f64x2.eq" => Vector(VectorInstruction::F64x2Eq),
+    "f64x2.ne" => Vector(VectorInstruction::F64x2Ne),
+    "f64x2.lt" => Vector(VectorInstruction::F64x2Lt),
+    "f64x2.gt" => Vector(VectorInstruction::F64x2Gt),
+    "f64x2.le" => Vector(VectorInstruction::F64x2Le),
+    "f64x2.ge" => Vector(VectorInstruction::F64x2Ge),
+    "v128.not" => Vector(VectorInstruction::V128Not),
+    "v128.and" => Vector(VectorInstruction::V128And),
+    "v128.andnot" => Vector(VectorInstruction::V128Andnot),
+    "v128.or" => Vector(VectorInstruction::V128Or),
+    "v128.xor" => Vector(VectorInstruction::V128Xor),
+    "v128.bitselect" => Vector(VectorInstruction::V128Bitselect),
+    "v128.any_true" => Vector(VectorInstruction::V128AnyTrue),
+    "f32x4.demote_f64x2_zero" => Vector(VectorInstruction::F32x4DemoteF64x2Zero),
+    "f64x2.promote_low_f32x4" => Vector(VectorInstruction::F64x2PromoteLowF32x4),
+    "i8x16.abs" => Vector(VectorInstruction::I8x16Abs),
+    "i8x16.neg" => Vector(VectorInstruction::I8x16Neg),
+    "i8x16.popcnt" => Vector(VectorInstruction::I8x16Popcnt),
+    "i8x16.all_true" => Vector(VectorInstruction::I8x16AllTrue),
+    "i8x16.bitmask" => Vector(VectorInstruction::I8x16Bitmask),
+    "i8x16.narrow_i16x8_s" => Vector(VectorInstruction::I8x16NarrowI16x8S),
+    "i8x16.narrow_i16x8_u" => Vector(VectorInstruction::I8x16NarrowI16x8U),
+    "f32x4.ceil" => Vector(VectorInstruction::F32x4Ceil),
+    "f32x4.floor" => Vector(VectorInstruction::F32x4Floor),
+    "f32x4.trunc" => Vector(VectorInstruction::F32x4Trunc),
+    "f32x4.nearest" => Vector(VectorInstruction::F32x4Nearest),
+    "i8x16.shl" => Vector(VectorInstruction::I8x16Shl),
+    "i8x16.shr_s" => Vector(VectorInstruction::I8x16ShrS),
+    "i8x16.shr_u" => Vector(VectorInstruction::I8x16ShrU),
+    "i8x16.add" => Vector(VectorInstruction::I8x16Add),
+    "i8x16.add_sat_s" => Vector(VectorInstruction::I8x16AddSatS),
+    "i8x16.add_sat_u" => Vector(VectorInstruction::I8x16AddSatU),
+    "i8x16.sub" => Vector(VectorInstruction::I8x16Sub),
+    "i8x16.sub_sat_s" => Vector(VectorInstruction::I8x16SubSatS),
+    "i8x16.sub_sat_u" => Vector(VectorInstruction::I8x16SubSatU),
+    "f64x2.ceil" => Vector(VectorInstruction::F64x2Ceil),
+    "f64x2.floor" => Vector(VectorInstruction::F64x2Floor),
+    "i8x16.min_s" => Vector(VectorInstruction::I8x16MinS),
+    "i8x16.min_u" => Vector(VectorInstruction::I8x16MinU),
+    "i8x16.max_s" => Vector(VectorInstruction::I8x16MaxS),
+    "i8x16.max_u" => Vector(VectorInstruction::I8x16MaxU),
+    "f64x2.trunc" => Vector(VectorInstruction::F64x2Trunc),
+    "i8x16.avgr_u" => Vector(VectorInstruction::I8x16AvgrU),
+    "i16x8.extadd_pairwise_i8x16_s" => Vector(VectorInstruction::I16x8ExtaddPairwiseI8x16S),
+    "i16x8.extadd_pairwise_i8x16_u" => Vector(VectorInstruction::I16x8ExtaddPairwiseI8x16U),
+    "i32x4.extadd_pairwise_i16x8_s" => Vector(VectorInstruction::I32x4ExtaddPairwiseI16x8S),
+    "i32x4.extadd_pairwise_i16x8_u" => Vector(VectorInstruction::I32x4ExtaddPairwiseI16x8U),
+    "i16x8.abs" => Vector(VectorInstruction::I16x8Abs),
+    "i16x8.neg" => Vector(VectorInstruction::I16x8Neg),
+    "i16x8.q15mulr_sat_s" => Vector(VectorInstruction::I16x8Q15mulrSatS),
+    "i16x8.all_true" => Vector(VectorInstruction::I16x8AllTrue),
+    "i16x8.bitmask" => Vector(VectorInstruction::I16x8Bitmask),
+    "i16x8.narrow_i32x4_s" => Vector(VectorInstruction::I16x8NarrowI32x4S),
+    "i16x8.narrow_i32x4_u" => Vector(VectorInstruction::I16x8NarrowI32x4U),
+    "i16x8.extend_low_i8x16_s" => Vector(VectorInstruction::I16x8ExtendLowI8x16S),
+    "i16x8.extend_high_i8x16_s" => Vector(VectorInstruction::I16x8ExtendHighI8x16S),
+    "i16x8.extend_low_i8x16_u" => Vector(VectorInstruction::I16x8ExtendLowI8x16U),
+    "i16x8.extend_high_i8x16_u" => Vector(VectorInstruction::I16x8ExtendHighI8x16U),
+    "i16x8.shl" => Vector(VectorInstruction::I16x8Shl),
+    "i16x8.shr_s" => Vector(VectorInstruction::I16x8ShrS),
+    "i16x8.shr_u" => Vector(VectorInstruction::I16x8ShrU),
+    "i16x8.add" => Vector(VectorInstruction::I16x8Add),
+    "i16x8.add_sat_s" => Vector(VectorInstruction::I16x8AddSatS),
+    "i16x8.add_sat_u" => Vector(VectorInstruction::I16x8AddSatU),
+    "i16x8.sub" => Vector(VectorInstruction::I16x8Sub),
+    "i16x8.sub_sat_s" => Vector(VectorInstruction::I16x8SubSatS),
+    "i16x8.sub_sat_u" => Vector(VectorInstruction::I16x8SubSatU),
+    "f64x2.nearest" => Vector(VectorInstruction::F64x2Nearest),
+    "i16x8.mul" => Vector(VectorInstruction::I16x8Mul),
+    "i16x8.min_s" => Vector(VectorInstruction::I16x8MinS),
+    "i16x8.min_u" => Vector(VectorInstruction::I16x8MinU),
+    "i16x8.max_s" => Vector(VectorInstruction::I16x8MaxS),
+    "i16x8.max_u" => Vector(VectorInstruction::I16x8MaxU),
+    "i16x8.avgr_u" => Vector(VectorInstruction::I16x8AvgrU),
+    "i16x8.extmul_low_i8x16_s" => Vector(VectorInstruction::I16x8ExtmulLowI8x16S),
+    "i16x8.extmul_high_i8x16_s" => Vector(VectorInstruction::I16x8ExtmulHighI8x16S),
+    "i16x8.extmul_low_i8x16_u" => Vector(VectorInstruction::I16x8ExtmulLowI8x16U),
+    "i16x8.extmul_high_i8x16_u" => Vector(VectorInstruction::I16x8ExtmulHighI8x16U),
+    "i32x4.abs" => Vector(VectorInstruction::I32x4Abs),
+    "i32x4.neg" => Vector(VectorInstruction::I32x4Neg),
+    "i32x4.all_true" => Vector(VectorInstruction::I32x4AllTrue),
+    "i32x4.bitmask" => Vector(VectorInstruction::I32x4Bitmask),
+    "i32x4.extend_low_i16x8_s" => Vector(VectorInstruction::I32x4ExtendLowI16x8S),
+    "i32x4.extend_high_i16x8_s" => Vector(VectorInstruction::I32x4ExtendHighI16x8S),
+    "i32x4.extend_low_i16x8_u" => Vector(VectorInstruction::I32x4ExtendLowI16x8U),
+    "i32x4.extend_high_i16x8_u" => Vector(VectorInstruction::I32x4ExtendHighI16x8U),
+    "i32x4.shl" => Vector(VectorInstruction::I32x4Shl),
+    "i32x4.shr_s" => Vector(VectorInstruction::I32x4ShrS),
+    "i32x4.shr_u" => Vector(VectorInstruction::I32x4ShrU),
+    "i32x4.add" => Vector(VectorInstruction::I32x4Add),
+    "i32x4.sub" => Vector(VectorInstruction::I32x4Sub),
+    "i32x4.mul" => Vector(VectorInstruction::I32x4Mul),
+    "i32x4.min_s" => Vector(VectorInstruction::I32x4MinS),
+    "i32x4.min_u" => Vector(VectorInstruction::I32x4MinU),
+    "i32x4.max_s" => Vector(VectorInstruction::I32x4MaxS),
+    "i32x4.max_u" => Vector(VectorInstruction::I32x4MaxU),
+    "i32x4.dot_i16x8_s" => Vector(VectorInstruction::I32x4DotI16x8S),
+    "i32x4.extmul_low_i16x8_s" => Vector(VectorInstruction::I32x4ExtmulLowI16x8S),
+    "i32x4.extmul_high_i16x8_s" => Vector(VectorInstruction::I32x4ExtmulHighI16x8S),
+    "i32x4.extmul_low_i16x8_u" => Vector(VectorInstruction::I32x4ExtmulLowI16x8U),
+    "i32x4.extmul_high_i16x8_u" => Vector(VectorInstruction::I32x4ExtmulHighI16x8U),
+    "i64x2.abs" => Vector(VectorInstruction::I64x2Abs),
+    "i64x2.neg" => Vector(VectorInstruction::I64x2Neg),
+    "i64x2.all_true" => Vector(VectorInstruction::I64x2AllTrue),
+    "i64x2.bitmask" => Vector(VectorInstruction::I64x2Bitmask),
+    "i64x2.extend_low_i32x4_s" => Vector(VectorInstruction::I64x2ExtendLowI32x4S),
+    "i64x2.extend_high_i32x4_s" => Vector(VectorInstruction::I64x2ExtendHighI32x4S),
+    "i64x2.extend_low_i32x4_u" => Vector(VectorInstruction::I64x2ExtendLowI32x4U),
+    "i64x2.extend_high_i32x4_u" => Vector(VectorInstruction::I64x2ExtendHighI32x4U),
+    "i64x2.shl" => Vector(VectorInstruction::I64x2Shl),
+    "i64x2.shr_s" => Vector(VectorInstruction::I64x2ShrS),
+    "i64x2.shr_u" => Vector(VectorInstruction::I64x2ShrU),
+    "i64x2.add" => Vector(VectorInstruction::I64x2Add),
+    "i64x2.sub" => Vector(VectorInstruction::I64x2Sub),
+    "i64x2.mul" => Vector(VectorInstruction::I64x2Mul),
+    "i64x2.eq" => Vector(VectorInstruction::I64x2Eq),
+    "i64x2.ne" => Vector(VectorInstruction::I64x2Ne),
+    "i64x2.lt_s" => Vector(VectorInstruction::I64x2LtS),
+    "i64x2.gt_s" => Vector(VectorInstruction::I64x2GtS),
+    "i64x2.le_s" => Vector(VectorInstruction::I64x2LeS),
+    "i64x2.ge_s" => Vector(VectorInstruction::I64x2GeS),
+    "i64x2.extmul_low_i32x4_s" => Vector(VectorInstruction::I64x2ExtmulLowI32x4S),
+    "i64x2.extmul_high_i32x4_s" => Vector(VectorInstruction::I64x2ExtmulHighI32x4S),
+    "i64x2.extmul_low_i32x4_u" => Vector(VectorInstruction::I64x2ExtmulLowI32x4U),
+    "i64x2.extmul_high_i32x4_u" => Vector(VectorInstruction::I64x2ExtmulHighI32x4U),
+    "f32x4.abs" => Vector(VectorInstruction::F32x4Abs),
+    "f32x4.neg" => Vector(VectorInstruction::F32x4Neg),
+    "f32x4.sqrt" => Vector(VectorInstruction::F32x4Sqrt),
+    "f32x4.add" => Vector(VectorInstruction::F32x4Add),
+    "f32x4.sub" => Vector(VectorInstruction::F32x4Sub),
+    "f32x4.mul" => Vector(VectorInstruction::F32x4Mul),
+    "f32x4.div" => Vector(VectorInstruction::F32x4Div),
+    "f32x4.min" => Vector(VectorInstruction::F32x4Min),
+    "f32x4.max" => Vector(VectorInstruction::F32x4Max),
+    "f32x4.pmin" => Vector(VectorInstruction::F32x4Pmin),
+    "f32x4.pmax" => Vector(VectorInstruction::F32x4Pmax),
+    "f64x2.abs" => Vector(VectorInstruction::F64x2Abs),
+    "f64x2.neg" => Vector(VectorInstruction::F64x2Neg),
+    "f64x2.sqrt" => Vector(VectorInstruction::F64x2Sqrt),
+    "f64x2.add" => Vector(VectorInstruction::F64x2Add),
+    "f64x2.sub" => Vector(VectorInstruction::F64x2Sub),
+    "f64x2.mul" => Vector(VectorInstruction::F64x2Mul),
+    "f64x2.div" => Vector(VectorInstruction::F64x2Div),
+    "f64x2.min" => Vector(VectorInstruction::F64x2Min),
+    "f64x2.max" => Vector(VectorInstruction::F64x2Max),
+    "f64x2.pmin" => Vector(VectorInstruction::F64x2Pmin),
+    "f64x2.pmax" => Vector(VectorInstruction::F64x2Pmax),
+    "i32x4.trunc_sat_f32x4_s" => Vector(VectorInstruction::I32x4TruncSatF32x4S),
+    "i32x4.trunc_sat_f32x4_u" => Vector(VectorInstruction::I32x4TruncSatF32x4U),
+    "f32x4.convert_i32x4_s" => Vector(VectorInstruction::F32x4ConvertI32x4S),
+    "f32x4.convert_i32x4_u" => Vector(VectorInstruction::F32x4ConvertI32x4U),
+    "i32x4.trunc_sat_f64x2_s_zero" => Vector(VectorInstruction::I32x4TruncSatF64x2SZero),
+    "i32x4.trunc_sat_f64x2_u_zero" => Vector(VectorInstruction::I32x4TruncSatF64x2UZero),
+    "f64x2.convert_low_i32x4_s" => Vector(VectorInstruction::F64x2ConvertLowI32x4S),
+    "f64x2.convert_low_i32x4_u" => Vector(VectorInstruction::F64x2ConvertLowI32x4U),
+}
@@ -0,0 +1,289 @@
+use std::vec::Vec;
+
+use super::{parser::Parser, TextError, TextErrorKind};
+
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub(crate) enum TokenKind {
+    LParen,
+    RParen,
+    /// A token starting with a lowercase letter, such as an instruction
+    /// name, `nan` or `offset=4`.
+    Keyword,
+    /// A symbolic identifier starting with `$`.
+    Id,
+    /// Any other sequence of identifier characters. Number literals are
+    /// reserved tokens.
+    Reserved,
+    /// A string literal, whose decoded bytes are
+    /// `ParseBuffer::strings[start..end]`.
+    String {
+        start: usize,
+        end: usize,
+    },
+}
+
+/// A token and the range `offset..offset + len` of the source it was read
+/// from.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub offset: usize,
+    pub len: usize,
+}
+
+/// The tokens of a text module.
+///
+/// The decoded contents of all string literals are stored one after the
+/// other in a single buffer, so that a module parsed from the tokens can
+/// borrow strings containing escape sequences, and so that the
+/// concatenation of consecutive strings, like the ones of a data segment,
+/// is a single slice.
+///
+/// [Reference](https://webassembly.github.io/spec/core/text/lexical.html)
+#[derive(Debug)]
+pub struct ParseBuffer<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    strings: Vec<u8>,
+}
+
+impl<'a> ParseBuffer<'a> {
+    /// Splits `source` into tokens, skipping white space and comments.
+    pub fn new(source: &'a str) -> Result<Self, TextError> {
+        let mut lexer = Lexer {
+            bytes: source.as_bytes(),
+            pos: 0,
+            tokens: Vec::new(),
+            strings: Vec::new(),
+        };
+        lexer.run()?;
+        Ok(ParseBuffer {
+            source,
+            tokens: lexer.tokens,
+            strings: lexer.strings,
+        })
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub(crate) fn parser(&self) -> Parser<'_> {
+        Parser::new(self.source, &self.tokens, &self.strings)
+    }
+}
+
+/// Returns true for the characters that make up keywords, identifiers
+/// and numbers.
+///
+/// [Reference](https://webassembly.github.io/spec/core/text/values.html#text-idchar)
+pub(crate) fn is_idchar(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"!#$%&'*+-./:<=>?@\\^_`|~".contains(&c)
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    tokens: Vec<Token>,
+    strings: Vec<u8>,
+}
+
+impl<'a> Lexer<'a> {
+    fn error(&self, kind: TextErrorKind, offset: usize) -> TextError {
+        TextError { kind, offset }
+    }
+
+    fn peek_at(&self, n: usize) -> Option<u8> {
+        self.bytes.get(self.pos + n).copied()
+    }
+
+    fn push(&mut self, kind: TokenKind, offset: usize) {
+        self.tokens.push(Token {
+            kind,
+            offset,
+            len: self.pos - offset,
+        });
+    }
+
+    fn run(&mut self) -> Result<(), TextError> {
+        while let Some(c) = self.peek_at(0) {
+            let offset = self.pos;
+            match (c, self.peek_at(1)) {
+                (b' ' | b'\t' | b'\n' | b'\r', _) => self.pos += 1,
+                (b';', Some(b';')) => {
+                    while self.peek_at(0).is_some_and(|c| c != b'\n') {
+                        self.pos += 1;
+                    }
+                }
+                (b'(', Some(b';')) => self.block_comment()?,
+                (b'(', _) => {
+                    self.pos += 1;
+                    self.push(TokenKind::LParen, offset);
+                }
+                (b')', _) => {
+                    self.pos += 1;
+                    self.push(TokenKind::RParen, offset);
+                }
+                (b'"', _) => {
+                    let kind = self.string()?;
+                    self.push(kind, offset);
+                }
+                (c, _) if is_idchar(c) => {
+                    while self.peek_at(0).is_some_and(is_idchar) {
+                        self.pos += 1;
+                    }
+                    let kind = match c {
+                        b'$' if self.pos - offset > 1 => TokenKind::Id,
+                        b'a'..=b'z' => TokenKind::Keyword,
+                        _ => TokenKind::Reserved,
+                    };
+                    self.push(kind, offset);
+                }
+                _ => return Err(self.error(TextErrorKind::UnexpectedCharacter, offset)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips a block comment. Block comments can be nested.
+    fn block_comment(&mut self) -> Result<(), TextError> {
+        let offset = self.pos;
+        let mut depth = 0;
+        loop {
+            match (self.peek_at(0), self.peek_at(1)) {
+                (Some(b'('), Some(b';')) => {
+                    depth += 1;
+                    self.pos += 2;
+                }
+                (Some(b';'), Some(b')')) => {
+                    depth -= 1;
+                    self.pos += 2;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                (Some(_), _) => self.pos += 1,
+                (None, _) => return Err(self.error(TextErrorKind::UnterminatedComment, offset)),
+            }
+        }
+    }
+
+    /// Decodes a string literal into `self.strings`.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/text/values.html#strings)
+    fn string(&mut self) -> Result<TokenKind, TextError> {
+        let offset = self.pos;
+        let start = self.strings.len();
+        self.pos += 1;
+        loop {
+            let c = self
+                .peek_at(0)
+                .ok_or_else(|| self.error(TextErrorKind::UnterminatedString, offset))?;
+            match c {
+                b'"' => {
+                    self.pos += 1;
+                    break;
+                }
+                b'\\' => {
+                    let escape = self.pos;
+                    self.pos += 1;
+                    self.escape()
+                        .ok_or_else(|| self.error(TextErrorKind::InvalidEscape, escape))?;
+                }
+                c if c < 0x20 || c == 0x7F => {
+                    return Err(self.error(TextErrorKind::UnexpectedCharacter, self.pos))
+                }
+                c => {
+                    self.strings.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        Ok(TokenKind::String {
+            start,
+            end: self.strings.len(),
+        })
+    }
+
+    /// Decodes the escape sequence following a `\`.
+    fn escape(&mut self) -> Option<()> {
+        let c = self.peek_at(0)?;
+        self.pos += 1;
+        let byte = match c {
+            b't' => b'\t',
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b'"' | b'\'' | b'\\' => c,
+            b'u' => {
+                if self.peek_at(0)? != b'{' {
+                    return None;
+                }
+                self.pos += 1;
+                let mut value: u32 = 0;
+                let mut digits = 0;
+                while let Some(d) = self.peek_at(0).and_then(|c| (c as char).to_digit(16)) {
+                    value = value.checked_mul(16)?.checked_add(d)?;
+                    digits += 1;
+                    self.pos += 1;
+                }
+                if digits == 0 || self.peek_at(0)? != b'}' {
+                    return None;
+                }
+                self.pos += 1;
+                let c = char::from_u32(value)?;
+                let mut buf = [0; 4];
+                self.strings
+                    .extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                return Some(());
+            }
+            c => {
+                let high = (c as char).to_digit(16)?;
+                let low = (self.peek_at(0)? as char).to_digit(16)?;
+                self.pos += 1;
+                (high * 16 + low) as u8
+            }
+        };
+        self.strings.push(byte);
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens() {
+        let buffer = ParseBuffer::new(
+            "(module ;; comment\n (; nested (; block ;) ;) $f 0x1_0 \"a\\41\\u{e9}\")",
+        )
+        .unwrap();
+        let kinds: Vec<_> = buffer.tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                TokenKind::LParen,
+                TokenKind::Keyword,
+                TokenKind::Id,
+                TokenKind::Reserved,
+                TokenKind::String { start: 0, end: 4 },
+                TokenKind::RParen,
+            ]
+        );
+        assert_eq!(buffer.strings, "aAé".as_bytes());
+        assert_eq!(buffer.tokens[3].offset, 48);
+        assert_eq!(buffer.tokens[3].len, 5);
+
+        let error = ParseBuffer::new("(func\n  \"\\q\")").unwrap_err();
+        assert_eq!(error.kind, TextErrorKind::InvalidEscape);
+        assert_eq!(error.line_col("(func\n  \"\\q\")"), (2, 4));
+        assert_eq!(
+            ParseBuffer::new("(; (; ;)").unwrap_err().kind,
+            TextErrorKind::UnterminatedComment
+        );
+        assert_eq!(
+            ParseBuffer::new("\"abc").unwrap_err().kind,
+            TextErrorKind::UnterminatedString
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Parsing of the WebAssembly text format.
+//!
+//! Text modules are lowered to the same [`Module`] structure as binary
+//! modules, so that both formats share validation and execution.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/text/index.html)
+
+mod error;
+mod instructions;
+mod lexer;
+mod module;
+mod parser;
+mod values;
+
+pub use error::*;
+pub(crate) use instructions::{lane_name, memarg_lane_name, memarg_name, plain_name};
+pub(crate) use lexer::is_idchar;
+pub use lexer::ParseBuffer;
+#[cfg(feature = "wast")]
+pub(crate) use parser::Parser;
+
+use crate::parse::modules::module::Module;
+
+/// Parses a text module, either wrapped in `(module $id? ...)` or as a
+/// sequence of module fields.
+///
+/// The module borrows names and data from `buffer`, which holds the
+/// decoded string literals of the source.
+///
+/// [Reference](https://webassembly.github.io/spec/core/text/modules.html#text-module)
+pub fn module_parser<'a>(buffer: &'a ParseBuffer) -> Result<Module<'a>, TextError> {
+    let mut p = buffer.parser();
+    let wrapped = p.take_field("module");
+    if wrapped {
+        p.id();
+    }
+    let module = module::ModuleParser::parse(&mut p)?;
+    if wrapped {
+        p.rparen()?;
+    }
+    if !p.is_eof() {
+        return Err(p.unexpected());
+    }
+    Ok(module)
+}
+
+/// Parses the fields of a module up to the closing parenthesis of the
+/// enclosing `(module ...)`, which is consumed.
+#[cfg(feature = "wast")]
+pub(crate) fn module_fields_parser<'a>(p: &mut Parser<'a>) -> Result<Module<'a>, TextError> {
+    let module = module::ModuleParser::parse(p)?;
+    p.rparen()?;
+    Ok(module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exec::{ExternVal, Linker, Store, Value},
+        validate::validate,
+    };
+
+    const FAC: &str = r#"
+        (module
+          (import "env" "print" (func $print (param i32)))
+          (type $unary (func (param i64) (result i64)))
+          (memory (export "mem") (data "hi\00"))
+          (global $calls (mut i32) (i32.const 0))
+
+          ;; Folded instructions.
+          (func $fac (export "fac") (type $unary)
+            (global.set $calls (i32.add (global.get $calls) (i32.const 1)))
+            (if (result i64) (i64.eqz (local.get 0))
+              (then (i64.const 1))
+              (else
+                (i64.mul (local.get 0) (call $fac (i64.sub (local.get 0) (i64.const 1)))))))
+
+          ;; Flat instructions with labels.
+          (func (export "sum") (param $n i32) (result i32) (local $acc i32)
+            block $done
+              loop $loop
+                local.get $n
+                i32.eqz
+                br_if $done
+                local.get $acc
+                local.get $n
+                i32.add
+                local.set $acc
+                local.get $n
+                i32.const -1
+                i32.add
+                local.set $n
+                br $loop
+              end $loop
+            end
+            local.get $acc
+            call $print
+            local.get $acc)
+
+          (func (export "calls") (result i32) global.get $calls)
+          (func (export "load") (param i32) (result i32)
+            (i32.load8_u offset=1 (local.get 0))))
+    "#;
+
+    #[test]
+    fn test_run_text_module() {
+        let buffer = ParseBuffer::new(FAC).unwrap();
+        let module = validate(module_parser(&buffer).unwrap()).unwrap();
+
+        let mut store = Store::new();
+        let mut linker = Linker::new();
+        linker.func_wrap(&mut store, "env", "print", |_: i32| {});
+        let addr = linker.instantiate(&mut store, module).unwrap();
+        let export = |store: &Store, name| match store.module(addr).export(name) {
+            Some(ExternVal::Func(f)) => f,
+            _ => panic!("missing export {}", name),
+        };
+
+        let fac = export(&store, "fac");
+        assert_eq!(
+            store.invoke(fac, &[Value::I64(20)]),
+            Ok(vec![Value::I64(2_432_902_008_176_640_000)])
+        );
+        let calls = export(&store, "calls");
+        assert_eq!(store.invoke(calls, &[]), Ok(vec![Value::I32(21)]));
+        let sum = export(&store, "sum");
+        assert_eq!(
+            store.invoke(sum, &[Value::I32(100)]),
+            Ok(vec![Value::I32(5050)])
+        );
+        let load = export(&store, "load");
+        assert_eq!(
+            store.invoke(load, &[Value::I32(0)]),
+            Ok(vec![Value::I32(i32::from(b'i'))])
+        );
+    }
+
+    #[test]
+    fn test_tables_and_segments() {
+        let source = r#"
+            (type $binary (func (param i32 i32) (result i32)))
+            (table $t funcref (elem $add $sub))
+            (memory 1)
+            (elem declare func $add)
+            (elem $passive funcref (ref.func $sub) (item ref.null func))
+            (data $d "\01\02\03\04")
+            (func $add (type $binary) (i32.add (local.get 0) (local.get 1)))
+            (func $sub (type $binary) (i32.sub (local.get 0) (local.get 1)))
+            (func (export "apply") (param i32 i32 i32) (result i32)
+              (call_indirect $t (type $binary) (local.get 1) (local.get 2) (local.get 0)))
+            (func (export "branch") (param i32) (result i32)
+              (block $a (block $b (br_table $a $b 1 (local.get 0))) (return (i32.const 1)))
+              (select (i32.const 2) (i32.const 3) (local.get 0)))
+            (func (export "init") (result i32)
+              (memory.init $d (i32.const 8) (i32.const 1) (i32.const 3))
+              data.drop $d
+              (v128.store offset=16 (i32.const 0) (v128.const i32x4 -1 0 0x10 0))
+              (i32.add (i32.load offset=8 (i32.const 0)) (i32.load8_u (i32.const 24))))
+        "#;
+        let buffer = ParseBuffer::new(source).unwrap();
+        let module = validate(module_parser(&buffer).unwrap()).unwrap();
+        assert!(module.m.is_some());
+
+        let mut store = Store::new();
+        let addr = Linker::new().instantiate(&mut store, module).unwrap();
+        let mut call = |name, args: &[Value]| match store.module(addr).export(name) {
+            Some(ExternVal::Func(f)) => store.invoke(f, args),
+            _ => panic!("missing export {}", name),
+        };
+        let apply = [Value::I32(1), Value::I32(7), Value::I32(2)];
+        assert_eq!(call("apply", &apply), Ok(vec![Value::I32(5)]));
+        assert_eq!(call("branch", &[Value::I32(0)]), Ok(vec![Value::I32(3)]));
+        assert_eq!(call("branch", &[Value::I32(1)]), Ok(vec![Value::I32(1)]));
+        assert_eq!(call("branch", &[Value::I32(5)]), Ok(vec![Value::I32(2)]));
+        assert_eq!(call("init", &[]), Ok(vec![Value::I32(0x0004_0302 + 0x10)]));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source| {
+            let buffer = ParseBuffer::new(source).unwrap();
+            module_parser(&buffer).unwrap_err().kind
+        };
+        assert_eq!(
+            error("(func call $f)"),
+            TextErrorKind::UnknownId(Namespace::Func)
+        );
+        assert_eq!(
+            error("(func $f) (func $f)"),
+            TextErrorKind::DuplicateId(Namespace::Func)
+        );
+        assert_eq!(
+            error("(func block $a end $b)"),
+            TextErrorKind::MismatchingLabel
+        );
+        assert_eq!(
+            error("(type (func)) (func (type 0) (param i32))"),
+            TextErrorKind::InlineFunctionType
+        );
+        assert_eq!(
+            error("(memory 1) (import \"m\" \"n\" (memory 1))"),
+            TextErrorKind::ImportAfterDefinition(Namespace::Memory)
+        );
+        assert_eq!(error("(func i32.frob)"), TextErrorKind::UnknownOperator);
+        assert_eq!(
+            error("(func (nop) (local i32))"),
+            TextErrorKind::UnexpectedToken
+        );
+        assert_eq!(error("(func br 0drop)"), TextErrorKind::UnknownOperator);
+        assert_eq!(
+            error("(type (func (result i32) (param i32)))"),
+            TextErrorKind::ResultBeforeParameter
+        );
+        assert_eq!(
+            error("(func v128.const i32x4 0 0 0 drop)"),
+            TextErrorKind::WrongLaneCount
+        );
+        assert_eq!(
+            error("(memory 1) (func i32.load offset=4294967296)"),
+            TextErrorKind::U32OutOfRange
+        );
+        assert_eq!(
+            error("(func i32.const 0x1_0000_0000)"),
+            TextErrorKind::ConstantOutOfRange
+        );
+        assert_eq!(
+            error("(func i32.load align=3)"),
+            TextErrorKind::InvalidAlignment
+        );
+        assert_eq!(error("(module (func)"), TextErrorKind::UnexpectedEnd);
+    }
+
+    #[test]
+    fn test_nesting() {
+        let parse = |source: String| {
+            let buffer = ParseBuffer::new(&source).unwrap();
+            module_parser(&buffer).map(drop).map_err(|e| e.kind)
+        };
+        let flat = |depth| format!("(func {} {})", "block ".repeat(depth), "end ".repeat(depth));
+        let folded = |depth| {
+            format!(
+                "(func (result i32) {} i32.const 0 {})",
+                "(i32.eqz ".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+        assert_eq!(parse(flat(1024)), Ok(()));
+        assert_eq!(parse(flat(1025)), Err(TextErrorKind::NestingTooDeep));
+        let ifs = |depth| {
+            format!(
+                "(func {} {})",
+                "i32.const 0 (if (then ".repeat(depth),
+                "))".repeat(depth)
+            )
+        };
+        assert_eq!(parse(folded(1024)), Ok(()));
+        assert_eq!(parse(folded(100_000)), Err(TextErrorKind::NestingTooDeep));
+        assert_eq!(parse(ifs(1024)), Ok(()));
+        assert_eq!(parse(ifs(100_000)), Err(TextErrorKind::NestingTooDeep));
+    }
+}
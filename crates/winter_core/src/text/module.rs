@@ -0,0 +1,856 @@
+use std::{boxed::Box, collections::BTreeMap, vec::Vec};
+
+use super::{
+    parser::{Index, Parser, Result},
+    Namespace, TextErrorKind,
+};
+use crate::parse::{
+    instructions::{BlockType, Expr, Instr, NumericInstruction},
+    modules::{
+        code_section::{Code, CodeSection, Func, Locals},
+        data_count_section::DataCountSection,
+        data_section::{Data, DataMode, DataSection},
+        element_section::{Elem, ElemInit, ElemKind, ElemMode, ElementSection},
+        export_section::{Export, ExportDesc, ExportSection},
+        function_section::FuncSection,
+        global_section::{Global, GlobalSection},
+        import_section::{Import, ImportDesc, ImportSection},
+        memory_section::{Mem, MemSection},
+        module::Module,
+        start_section::StartSection,
+        table_section::{Table, TableSection},
+        type_section::TypeSection,
+        FuncIdx, GlobalIdx, MemIdx, TableIdx, TypeIdx,
+    },
+    types::{FuncType, Limits, TableType},
+    Span,
+};
+
+/// Size of a memory page in bytes, used by inline data segments.
+const PAGE_SIZE: usize = 65536;
+
+/// The symbolic identifiers bound in an index space.
+#[derive(Default)]
+struct Space<'a> {
+    ids: BTreeMap<&'a str, u32>,
+    len: u32,
+    /// Whether a definition that is not an import has been seen.
+    defined: bool,
+}
+
+impl<'a> Space<'a> {
+    fn push(
+        &mut self,
+        id: Option<&'a str>,
+        ns: Namespace,
+        p: &Parser,
+        offset: usize,
+    ) -> Result<()> {
+        if let Some(id) = id {
+            if self.ids.insert(id, self.len).is_some() {
+                return Err(p.error_at(TextErrorKind::DuplicateId(ns), offset));
+            }
+        }
+        self.len += 1;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Spaces<'a> {
+    types: Space<'a>,
+    funcs: Space<'a>,
+    tables: Space<'a>,
+    mems: Space<'a>,
+    globals: Space<'a>,
+    elems: Space<'a>,
+    datas: Space<'a>,
+}
+
+impl<'a> Spaces<'a> {
+    fn get_mut(&mut self, ns: Namespace) -> &mut Space<'a> {
+        match ns {
+            Namespace::Type => &mut self.types,
+            Namespace::Func => &mut self.funcs,
+            Namespace::Table => &mut self.tables,
+            Namespace::Memory => &mut self.mems,
+            Namespace::Global => &mut self.globals,
+            Namespace::Elem => &mut self.elems,
+            Namespace::Data => &mut self.datas,
+            Namespace::Local | Namespace::Label => unreachable!("not a module index space"),
+        }
+    }
+}
+
+/// Returns the index space of the imports and definitions introduced by
+/// `keyword`.
+fn namespace(keyword: &str) -> Option<Namespace> {
+    match keyword {
+        "func" => Some(Namespace::Func),
+        "table" => Some(Namespace::Table),
+        "memory" => Some(Namespace::Memory),
+        "global" => Some(Namespace::Global),
+        _ => None,
+    }
+}
+
+/// The definitions of a module, in the order of their index spaces.
+#[derive(Default)]
+struct Fields<'a> {
+    imports: Vec<Import<'a>>,
+    funcs: Vec<TypeIdx>,
+    code: Vec<Code<'a>>,
+    tables: Vec<Table>,
+    mems: Vec<Mem>,
+    globals: Vec<Global>,
+    exports: Vec<Export<'a>>,
+    start: Option<FuncIdx>,
+    elems: Vec<Elem>,
+    datas: Vec<Data<'a>>,
+    func_count: u32,
+    table_count: u32,
+    mem_count: u32,
+    global_count: u32,
+}
+
+/// Returns `Some(f(items))` unless `items` is empty.
+fn section<T, S>(items: Vec<T>, f: impl FnOnce(Vec<T>) -> S) -> Option<S> {
+    (!items.is_empty()).then(|| f(items))
+}
+
+/// Returns the constant expression `i32.const 0`, the offset of inline
+/// element and data segments.
+fn zero_offset() -> Expr {
+    let instr = Instr::Numeric(Box::new(NumericInstruction::I32Const(0)));
+    Expr {
+        instr: core::iter::once(instr).collect(),
+        spans: Vec::new(),
+    }
+}
+
+/// Parses the fields of a module and lowers them to a [`Module`].
+///
+/// Identifiers can be used before their definition, so the fields are
+/// read twice: the first pass binds the identifiers of every index space
+/// and collects the explicit type definitions, the second one parses the
+/// definitions and resolves their references.
+///
+/// [Reference](https://webassembly.github.io/spec/core/text/modules.html)
+pub(crate) struct ModuleParser<'a> {
+    pub(super) p: Parser<'a>,
+    spaces: Spaces<'a>,
+    /// Explicit type definitions followed by the types of inline type
+    /// uses that do not match any of them.
+    types: Vec<FuncType>,
+    /// Names of the parameters and locals of the current function.
+    pub(super) locals: Vec<Option<&'a str>>,
+    /// Labels of the blocks enclosing the current instruction, innermost
+    /// last.
+    pub(super) labels: Vec<Option<&'a str>>,
+    /// Whether `memory.init` or `data.drop` are used, which requires the
+    /// data count section.
+    pub(super) uses_data_count: bool,
+}
+
+impl<'a> ModuleParser<'a> {
+    /// Parses module fields up to the closing parenthesis of the module or
+    /// the end of the input.
+    pub(crate) fn parse(p: &mut Parser<'a>) -> Result<Module<'a>> {
+        let mut parser = ModuleParser {
+            p: p.clone(),
+            spaces: Spaces::default(),
+            types: Vec::new(),
+            locals: Vec::new(),
+            labels: Vec::new(),
+            uses_data_count: false,
+        };
+        parser.declare()?;
+        let module = parser.fields()?;
+        *p = parser.p;
+        Ok(module)
+    }
+
+    /// Binds the identifiers of all definitions and collects the type
+    /// definitions, without consuming the fields.
+    fn declare(&mut self) -> Result<()> {
+        let mut p = self.p.clone();
+        while p.peek_lparen() {
+            p.lparen()?;
+            let offset = p.offset();
+            match p.keyword()? {
+                "type" => {
+                    let id = p.id();
+                    self.spaces.types.push(id, Namespace::Type, &p, offset)?;
+                    if !p.take_field("func") {
+                        return Err(p.unexpected());
+                    }
+                    let (func_type, _) = p.functype()?;
+                    if p.peek_field() == Some("param") {
+                        return Err(p.error(TextErrorKind::ResultBeforeParameter));
+                    }
+                    p.rparen()?;
+                    self.types.push(func_type);
+                }
+                "import" => {
+                    p.name()?;
+                    p.name()?;
+                    p.lparen()?;
+                    let offset = p.offset();
+                    let ns = namespace(p.keyword()?)
+                        .ok_or_else(|| p.error_at(TextErrorKind::UnexpectedToken, offset))?;
+                    let id = p.id();
+                    self.declare_item(ns, id, true, &p, offset)?;
+                    p.skip_field()?;
+                }
+                keyword @ ("func" | "table" | "memory" | "global") => {
+                    let ns = namespace(keyword).unwrap();
+                    let id = p.id();
+                    while p.take_field("export") {
+                        p.skip_field()?;
+                    }
+                    let import = p.peek_field() == Some("import");
+                    self.declare_item(ns, id, import, &p, offset)?;
+                    // Inline element and data segments.
+                    if ns == Namespace::Table && p.peek_reftype() {
+                        self.spaces.elems.push(None, Namespace::Elem, &p, offset)?;
+                    }
+                    if ns == Namespace::Memory && p.peek_field() == Some("data") {
+                        self.spaces.datas.push(None, Namespace::Data, &p, offset)?;
+                    }
+                }
+                "elem" => {
+                    let id = p.id();
+                    self.spaces.elems.push(id, Namespace::Elem, &p, offset)?;
+                }
+                "data" => {
+                    let id = p.id();
+                    self.spaces.datas.push(id, Namespace::Data, &p, offset)?;
+                }
+                "export" | "start" => {}
+                _ => return Err(p.error_at(TextErrorKind::UnexpectedToken, offset)),
+            }
+            p.skip_field()?;
+        }
+        Ok(())
+    }
+
+    fn declare_item(
+        &mut self,
+        ns: Namespace,
+        id: Option<&'a str>,
+        import: bool,
+        p: &Parser,
+        offset: usize,
+    ) -> Result<()> {
+        if import {
+            let kinds = [
+                Namespace::Func,
+                Namespace::Table,
+                Namespace::Memory,
+                Namespace::Global,
+            ];
+            if let Some(defined) = kinds.into_iter().find(|&k| self.spaces.get_mut(k).defined) {
+                return Err(p.error_at(TextErrorKind::ImportAfterDefinition(defined), offset));
+            }
+        }
+        let space = self.spaces.get_mut(ns);
+        space.defined |= !import;
+        space.push(id, ns, p, offset)
+    }
+
+    fn fields(&mut self) -> Result<Module<'a>> {
+        let mut f = Fields::default();
+        while self.p.peek_lparen() {
+            self.p.lparen()?;
+            let offset = self.p.offset();
+            match self.p.keyword()? {
+                "type" => {
+                    self.p.skip_field()?;
+                    continue;
+                }
+                "import" => self.import(&mut f)?,
+                "func" => self.func(&mut f)?,
+                "table" => self.table(&mut f)?,
+                "memory" => self.memory(&mut f)?,
+                "global" => self.global(&mut f)?,
+                "export" => self.export(&mut f)?,
+                "start" => {
+                    let start = self.index(Namespace::Func)?;
+                    if f.start.replace(FuncIdx(start)).is_some() {
+                        return Err(self.p.error_at(TextErrorKind::MultipleStart, offset));
+                    }
+                }
+                "elem" => self.elem(&mut f)?,
+                "data" => self.data(&mut f)?,
+                _ => return Err(self.p.error_at(TextErrorKind::UnexpectedToken, offset)),
+            }
+            self.p.rparen()?;
+        }
+
+        let data_count = self.uses_data_count.then_some(DataCountSection {
+            count: f.datas.len() as u32,
+            span: Span::default(),
+        });
+        Ok(Module {
+            custom_sections: Vec::new(),
+            names: None,
+            producers: None,
+            target_features: None,
+            func_type: section(core::mem::take(&mut self.types), |function_types| {
+                TypeSection {
+                    function_types,
+                    ..Default::default()
+                }
+            }),
+            import: section(f.imports, |imports| ImportSection {
+                imports,
+                ..Default::default()
+            }),
+            type_idx: section(f.funcs, |functions| FuncSection {
+                functions,
+                ..Default::default()
+            }),
+            table: section(f.tables, |tables| TableSection {
+                tables,
+                ..Default::default()
+            }),
+            mem: section(f.mems, |tables| MemSection {
+                tables,
+                ..Default::default()
+            }),
+            global: section(f.globals, |globals| GlobalSection {
+                globals,
+                ..Default::default()
+            }),
+            export: section(f.exports, |exports| ExportSection {
+                exports,
+                ..Default::default()
+            }),
+            start: f.start.map(|start| StartSection {
+                start,
+                span: Span::default(),
+            }),
+            elem: section(f.elems, |elems| ElementSection {
+                elems,
+                ..Default::default()
+            }),
+            m: data_count,
+            code: section(f.code, |code| CodeSection {
+                code,
+                ..Default::default()
+            }),
+            data: section(f.datas, |data| DataSection {
+                data,
+                ..Default::default()
+            }),
+        })
+    }
+
+    /// Parses a reference to a definition of `ns` and returns its index.
+    pub(super) fn index(&mut self, ns: Namespace) -> Result<u32> {
+        let offset = self.p.offset();
+        let id = match self.p.index()? {
+            Index::Num(index) => return Ok(index),
+            Index::Id(id) => id,
+        };
+        let index = match ns {
+            Namespace::Local => self.locals.iter().position(|l| *l == Some(id)),
+            // Labels are referenced by their depth.
+            Namespace::Label => self.labels.iter().rev().position(|l| *l == Some(id)),
+            ns => self.spaces.get_mut(ns).ids.get(id).map(|&i| i as usize),
+        };
+        index
+            .map(|i| i as u32)
+            .ok_or_else(|| self.p.error_at(TextErrorKind::UnknownId(ns), offset))
+    }
+
+    /// Returns the index of `func_type`, adding it to the types if it is
+    /// not defined yet.
+    fn intern_type(&mut self, func_type: FuncType) -> TypeIdx {
+        let index = match self.types.iter().position(|t| *t == func_type) {
+            Some(index) => index,
+            None => {
+                self.types.push(func_type);
+                self.types.len() - 1
+            }
+        };
+        TypeIdx(index as u32)
+    }
+
+    /// Parses a type use, `(type x)?` followed by optional parameters and
+    /// results, and returns the type index and the parameter names.
+    ///
+    /// [Reference](https://webassembly.github.io/spec/core/text/modules.html#type-uses)
+    pub(super) fn type_use(&mut self) -> Result<(TypeIdx, Vec<Option<&'a str>>)> {
+        let offset = self.p.offset();
+        let index = match self.p.take_field("type") {
+            true => {
+                let index = self.index(Namespace::Type)?;
+                self.p.rparen()?;
+                Some(index)
+            }
+            false => None,
+        };
+        let inline = matches!(self.p.peek_field(), Some("param" | "result"));
+        let (func_type, names) = self.p.functype()?;
+        if self.p.peek_field() == Some("param") {
+            return Err(self.p.unexpected());
+        }
+        let index = match index {
+            Some(index) => index,
+            None => return Ok((self.intern_type(func_type), names)),
+        };
+        // An undefined index without inline types is left for validation to
+        // reject.
+        let defined = match self.types.get(index as usize) {
+            Some(defined) => defined,
+            None if !inline => return Ok((TypeIdx(index), Vec::new())),
+            None => {
+                return Err(self
+                    .p
+                    .error_at(TextErrorKind::UnknownId(Namespace::Type), offset))
+            }
+        };
+        if !inline {
+            let names = core::iter::repeat_n(None, defined.params.len()).collect();
+            return Ok((TypeIdx(index), names));
+        }
+        if *defined != func_type {
+            return Err(self.p.error_at(TextErrorKind::InlineFunctionType, offset));
+        }
+        Ok((TypeIdx(index), names))
+    }
+
+    /// Parses a type use whose parameters cannot be named, as in blocks and
+    /// `call_indirect`.
+    pub(super) fn anonymous_type_use(&mut self) -> Result<TypeIdx> {
+        let offset = self.p.offset();
+        let (index, names) = self.type_use()?;
+        if names.iter().any(Option::is_some) {
+            return Err(self.p.error_at(TextErrorKind::UnexpectedToken, offset));
+        }
+        Ok(index)
+    }
+
+    /// Parses the type of a block, which is encoded as a value type when
+    /// it has no parameters and at most one result.
+    pub(super) fn block_type(&mut self) -> Result<BlockType> {
+        if self.p.peek_field() == Some("type") {
+            return Ok(BlockType::TypeIndex(self.anonymous_type_use()?));
+        }
+        let offset = self.p.offset();
+        let (func_type, names) = self.p.functype()?;
+        if names.iter().any(Option::is_some) {
+            return Err(self.p.error_at(TextErrorKind::UnexpectedToken, offset));
+        }
+        Ok(
+            match (func_type.params.is_empty(), func_type.ret.as_slice()) {
+                (true, []) => BlockType::Empty,
+                (true, [t]) => BlockType::ValType(*t),
+                _ => BlockType::TypeIndex(self.intern_type(func_type)),
+            },
+        )
+    }
+
+    /// Parses `(export "name")*` and exports the definition at `index`.
+    fn inline_exports(
+        &mut self,
+        f: &mut Fields<'a>,
+        desc: fn(u32) -> ExportDesc,
+        index: u32,
+    ) -> Result<()> {
+        while self.p.take_field("export") {
+            let name = self.p.name()?;
+            self.p.rparen()?;
+            f.exports.push(Export {
+                name,
+                desc: desc(index),
+            });
+        }
+        Ok(())
+    }
+
+    /// Parses an optional `(import "module" "name")`.
+    fn inline_import(&mut self) -> Result<Option<(&'a str, &'a str)>> {
+        if !self.p.take_field("import") {
+            return Ok(None);
+        }
+        let mod_name = self.p.name()?;
+        let name = self.p.name()?;
+        self.p.rparen()?;
+        Ok(Some((mod_name, name)))
+    }
+
+    fn import(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        let mod_name = self.p.name()?;
+        let name = self.p.name()?;
+        self.p.lparen()?;
+        let offset = self.p.offset();
+        let keyword = self.p.keyword()?;
+        self.p.id();
+        let desc = match keyword {
+            "func" => {
+                f.func_count += 1;
+                ImportDesc::Func(self.type_use()?.0)
+            }
+            "table" => {
+                f.table_count += 1;
+                ImportDesc::Table(self.p.tabletype()?)
+            }
+            "memory" => {
+                f.mem_count += 1;
+                ImportDesc::Mem(self.p.limits()?)
+            }
+            "global" => {
+                f.global_count += 1;
+                ImportDesc::Global(self.p.globaltype()?)
+            }
+            _ => return Err(self.p.error_at(TextErrorKind::UnexpectedToken, offset)),
+        };
+        self.p.rparen()?;
+        f.imports.push(Import {
+            mod_name,
+            name,
+            desc,
+        });
+        Ok(())
+    }
+
+    fn func(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        let index = f.func_count;
+        f.func_count += 1;
+        self.inline_exports(f, |i| ExportDesc::Func(FuncIdx(i)), index)?;
+        if let Some((mod_name, name)) = self.inline_import()? {
+            let desc = ImportDesc::Func(self.type_use()?.0);
+            f.imports.push(Import {
+                mod_name,
+                name,
+                desc,
+            });
+            return Ok(());
+        }
+
+        let offset = self.p.offset();
+        let (type_idx, params) = self.type_use()?;
+        self.locals.clear();
+        for param in params {
+            self.declare_local(param, offset)?;
+        }
+        let mut locals: Vec<Locals> = Vec::new();
+        while self.p.take_field("local") {
+            let offset = self.p.offset();
+            let types = match self.p.id() {
+                Some(id) => {
+                    self.declare_local(Some(id), offset)?;
+                    [self.p.valtype()?].to_vec()
+                }
+                None => {
+                    let types = self.p.valtypes()?;
+                    self.locals.extend(types.iter().map(|_| None));
+                    types
+                }
+            };
+            self.p.rparen()?;
+            for val_type in types {
+                match locals.last_mut() {
+                    Some(l) if l.val_type == val_type => l.count += 1,
+                    _ => locals.push(Locals { count: 1, val_type }),
+                }
+            }
+        }
+        self.labels.clear();
+        let expr = self.expr()?;
+        self.locals.clear();
+
+        f.funcs.push(type_idx);
+        f.code.push(Code::new(Func { locals, expr }));
+        Ok(())
+    }
+
+    fn declare_local(&mut self, id: Option<&'a str>, offset: usize) -> Result<()> {
+        if id.is_some() && self.locals.contains(&id) {
+            return Err(self
+                .p
+                .error_at(TextErrorKind::DuplicateId(Namespace::Local), offset));
+        }
+        self.locals.push(id);
+        Ok(())
+    }
+
+    /// Parses instructions up to the end of the current field.
+    pub(super) fn expr(&mut self) -> Result<Expr> {
+        let mut instr = Vec::new();
+        self.instrs(&mut instr)?;
+        Ok(Expr {
+            instr,
+            spans: Vec::new(),
+        })
+    }
+
+    /// Parses the offset of an active segment: `(offset instr*)` or a
+    /// single folded instruction.
+    fn offset_expr(&mut self) -> Result<Expr> {
+        if self.p.take_field("offset") {
+            let expr = self.expr()?;
+            self.p.rparen()?;
+            return Ok(expr);
+        }
+        let mut instr = Vec::new();
+        self.folded_instr(&mut instr)?;
+        Ok(Expr {
+            instr,
+            spans: Vec::new(),
+        })
+    }
+
+    fn table(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        let index = f.table_count;
+        f.table_count += 1;
+        self.inline_exports(f, |i| ExportDesc::Table(TableIdx(i)), index)?;
+        if let Some((mod_name, name)) = self.inline_import()? {
+            let desc = ImportDesc::Table(self.p.tabletype()?);
+            f.imports.push(Import {
+                mod_name,
+                name,
+                desc,
+            });
+            return Ok(());
+        }
+        if !self.p.peek_reftype() {
+            let table_type = self.p.tabletype()?;
+            f.tables.push(Table { table_type });
+            return Ok(());
+        }
+
+        // A table with an inline element segment, whose size is the
+        // number of elements.
+        let ref_type = self.p.reftype()?;
+        if !self.p.take_field("elem") {
+            return Err(self.p.unexpected());
+        }
+        let (elem_type, init) = match self.p.peek_lparen() {
+            true => {
+                let mut exprs = Vec::new();
+                while self.p.peek_lparen() {
+                    exprs.push(self.elem_expr()?);
+                }
+                (ElemKind::RefType(ref_type), ElemInit::Expr(exprs))
+            }
+            false => (ElemKind::FuncRef, ElemInit::FuncIdx(self.func_indices()?)),
+        };
+        self.p.rparen()?;
+        let len = match &init {
+            ElemInit::Expr(exprs) => exprs.len(),
+            ElemInit::FuncIdx(funcs) => funcs.len(),
+        } as u32;
+        f.tables.push(Table {
+            table_type: TableType {
+                ref_type,
+                limits: Limits {
+                    min: len,
+                    max: Some(len),
+                },
+            },
+        });
+        f.elems.push(Elem {
+            elem_type,
+            init,
+            mode: ElemMode::Active {
+                table: TableIdx(index),
+                offset: zero_offset(),
+            },
+        });
+        Ok(())
+    }
+
+    fn memory(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        let index = f.mem_count;
+        f.mem_count += 1;
+        self.inline_exports(f, |i| ExportDesc::Mem(MemIdx(i)), index)?;
+        if let Some((mod_name, name)) = self.inline_import()? {
+            let desc = ImportDesc::Mem(self.p.limits()?);
+            f.imports.push(Import {
+                mod_name,
+                name,
+                desc,
+            });
+            return Ok(());
+        }
+        if !self.p.take_field("data") {
+            let mem_type = self.p.limits()?;
+            f.mems.push(Mem { mem_type });
+            return Ok(());
+        }
+
+        // A memory with an inline data segment, just large enough to hold
+        // the data.
+        let init = self.p.strings();
+        self.p.rparen()?;
+        let pages = init.len().div_ceil(PAGE_SIZE) as u32;
+        f.mems.push(Mem {
+            mem_type: Limits {
+                min: pages,
+                max: Some(pages),
+            },
+        });
+        f.datas.push(Data {
+            init,
+            mode: DataMode::Active {
+                memory: MemIdx(index),
+                offset: zero_offset(),
+            },
+        });
+        Ok(())
+    }
+
+    fn global(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        let index = f.global_count;
+        f.global_count += 1;
+        self.inline_exports(f, |i| ExportDesc::Global(GlobalIdx(i)), index)?;
+        if let Some((mod_name, name)) = self.inline_import()? {
+            let desc = ImportDesc::Global(self.p.globaltype()?);
+            f.imports.push(Import {
+                mod_name,
+                name,
+                desc,
+            });
+            return Ok(());
+        }
+        let global_type = self.p.globaltype()?;
+        let expr = self.expr()?;
+        f.globals.push(Global { global_type, expr });
+        Ok(())
+    }
+
+    fn export(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        let name = self.p.name()?;
+        self.p.lparen()?;
+        let offset = self.p.offset();
+        let ns = namespace(self.p.keyword()?)
+            .ok_or_else(|| self.p.error_at(TextErrorKind::UnexpectedToken, offset))?;
+        let index = self.index(ns)?;
+        self.p.rparen()?;
+        let desc = match ns {
+            Namespace::Func => ExportDesc::Func(FuncIdx(index)),
+            Namespace::Table => ExportDesc::Table(TableIdx(index)),
+            Namespace::Memory => ExportDesc::Mem(MemIdx(index)),
+            _ => ExportDesc::Global(GlobalIdx(index)),
+        };
+        f.exports.push(Export { name, desc });
+        Ok(())
+    }
+
+    fn func_indices(&mut self) -> Result<Vec<FuncIdx>> {
+        let mut funcs = Vec::new();
+        while self.p.peek_index() {
+            funcs.push(FuncIdx(self.index(Namespace::Func)?));
+        }
+        Ok(funcs)
+    }
+
+    /// Parses an element expression: `(item instr*)` or a single folded
+    /// instruction.
+    fn elem_expr(&mut self) -> Result<Expr> {
+        if self.p.take_field("item") {
+            let expr = self.expr()?;
+            self.p.rparen()?;
+            return Ok(expr);
+        }
+        let mut instr = Vec::new();
+        self.folded_instr(&mut instr)?;
+        Ok(Expr {
+            instr,
+            spans: Vec::new(),
+        })
+    }
+
+    /// Parses the elements of a segment: `func` followed by function
+    /// indices, or a reference type followed by element expressions.
+    fn elem_list(&mut self) -> Result<(ElemKind, ElemInit)> {
+        if self.p.take_keyword("func") {
+            return Ok((ElemKind::FuncRef, ElemInit::FuncIdx(self.func_indices()?)));
+        }
+        let ref_type = self.p.reftype()?;
+        let mut exprs = Vec::new();
+        while self.p.peek_lparen() {
+            exprs.push(self.elem_expr()?);
+        }
+        Ok((ElemKind::RefType(ref_type), ElemInit::Expr(exprs)))
+    }
+
+    fn elem(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        if self.p.take_keyword("declare") {
+            let (elem_type, init) = self.elem_list()?;
+            f.elems.push(Elem {
+                elem_type,
+                init,
+                mode: ElemMode::Declarative,
+            });
+            return Ok(());
+        }
+
+        let table = if self.p.take_field("table") {
+            let table = self.index(Namespace::Table)?;
+            self.p.rparen()?;
+            Some(table)
+        } else if self.p.peek_index() {
+            Some(self.index(Namespace::Table)?)
+        } else {
+            None
+        };
+        // Element lists start with a keyword, so a parenthesis starts the
+        // offset of an active segment.
+        if table.is_none() && !self.p.peek_lparen() {
+            let (elem_type, init) = self.elem_list()?;
+            f.elems.push(Elem {
+                elem_type,
+                init,
+                mode: ElemMode::Passive,
+            });
+            return Ok(());
+        }
+
+        let offset = self.offset_expr()?;
+        let (elem_type, init) = match self.p.peek_index() || self.p.peek_rparen() {
+            true => (ElemKind::FuncRef, ElemInit::FuncIdx(self.func_indices()?)),
+            false => self.elem_list()?,
+        };
+        f.elems.push(Elem {
+            elem_type,
+            init,
+            mode: ElemMode::Active {
+                table: TableIdx(table.unwrap_or(0)),
+                offset,
+            },
+        });
+        Ok(())
+    }
+
+    fn data(&mut self, f: &mut Fields<'a>) -> Result<()> {
+        self.p.id();
+        let memory = if self.p.take_field("memory") {
+            let memory = self.index(Namespace::Memory)?;
+            self.p.rparen()?;
+            Some(memory)
+        } else if self.p.peek_index() {
+            Some(self.index(Namespace::Memory)?)
+        } else {
+            None
+        };
+        let mode = match memory.is_some() || self.p.peek_lparen() {
+            true => DataMode::Active {
+                memory: MemIdx(memory.unwrap_or(0)),
+                offset: self.offset_expr()?,
+            },
+            false => DataMode::Passive,
+        };
+        let init = self.p.strings();
+        f.datas.push(Data { init, mode });
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use std::vec::Vec;
+
+use super::{
+    lexer::{Token, TokenKind},
+    values::{parse_f32, parse_f64, parse_int, parse_u32},
+    TextError, TextErrorKind,
+};
+use crate::parse::types::{
+    FuncType, GlobalType, Limits, NumType, RefType, TableType, ValType, VecType,
+};
+
+pub(crate) type Result<T> = core::result::Result<T, TextError>;
+
+/// A reference to a definition, by its symbolic identifier or its index.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub(crate) enum Index<'a> {
+    Id(&'a str),
+    Num(u32),
+}
+
+/// A cursor over the tokens of a [`ParseBuffer`](super::ParseBuffer) with
+/// parsers for the tokens and the small phrases of the grammar. Cloning a
+/// parser is cheap and allows looking ahead arbitrarily.
+#[derive(Clone)]
+pub(crate) struct Parser<'a> {
+    source: &'a str,
+    tokens: &'a [Token],
+    strings: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str, tokens: &'a [Token], strings: &'a [u8]) -> Self {
+        Parser {
+            source,
+            tokens,
+            strings,
+            pos: 0,
+        }
+    }
+
+    /// Returns the source offset of the next token.
+    pub fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.source.len(), |t| t.offset)
+    }
+
+    /// Returns an error of kind `kind` at the next token.
+    pub fn error(&self, kind: TextErrorKind) -> TextError {
+        self.error_at(kind, self.offset())
+    }
+
+    pub fn error_at(&self, kind: TextErrorKind, offset: usize) -> TextError {
+        TextError { kind, offset }
+    }
+
+    /// Returns an error for an unexpected next token.
+    pub fn unexpected(&self) -> TextError {
+        if self.is_eof() {
+            self.error(TextErrorKind::UnexpectedEnd)
+        } else {
+            self.error(TextErrorKind::UnexpectedToken)
+        }
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.pos == self.tokens.len()
+    }
+
+    pub fn peek_at(&self, n: usize) -> Option<Token> {
+        self.tokens.get(self.pos + n).copied()
+    }
+
+    pub fn peek(&self) -> Option<Token> {
+        self.peek_at(0)
+    }
+
+    fn text(&self, token: Token) -> &'a str {
+        &self.source[token.offset..token.offset + token.len]
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.peek()?;
+        self.pos += 1;
+        Some(token)
+    }
+
+    /// Returns the text of the next token if it is of kind `kind`.
+    fn peek_kind_at(&self, n: usize, kind: TokenKind) -> Option<&'a str> {
+        self.peek_at(n)
+            .filter(|t| t.kind == kind)
+            .map(|t| self.text(t))
+    }
+
+    pub fn peek_lparen(&self) -> bool {
+        self.peek_kind_at(0, TokenKind::LParen).is_some()
+    }
+
+    pub fn peek_rparen(&self) -> bool {
+        self.peek_kind_at(0, TokenKind::RParen).is_some()
+    }
+
+    pub fn lparen(&mut self) -> Result<()> {
+        if !self.peek_lparen() {
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    pub fn rparen(&mut self) -> Result<()> {
+        if !self.peek_rparen() {
+            return Err(self.unexpected());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    pub fn peek_keyword(&self) -> Option<&'a str> {
+        self.peek_kind_at(0, TokenKind::Keyword)
+    }
+
+    pub fn keyword(&mut self) -> Result<&'a str> {
+        let keyword = self.peek_keyword().ok_or_else(|| self.unexpected())?;
+        self.pos += 1;
+        Ok(keyword)
+    }
+
+    /// Consumes the keyword `keyword` if it is next.
+    pub fn take_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.peek_keyword() == Some(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    pub fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        match self.take_keyword(keyword) {
+            true => Ok(()),
+            false => Err(self.unexpected()),
+        }
+    }
+
+    /// Returns the keyword of the next field (a parenthesized phrase
+    /// starting with a keyword), if any.
+    pub fn peek_field(&self) -> Option<&'a str> {
+        self.peek_kind_at(0, TokenKind::LParen)?;
+        self.peek_kind_at(1, TokenKind::Keyword)
+    }
+
+    /// Consumes the opening parenthesis and keyword of the field `keyword`
+    /// if it is next.
+    pub fn take_field(&mut self, keyword: &str) -> bool {
+        let found = self.peek_field() == Some(keyword);
+        if found {
+            self.pos += 2;
+        }
+        found
+    }
+
+    /// Skips the tokens up to and including the parenthesis closing the
+    /// current field.
+    pub fn skip_field(&mut self) -> Result<()> {
+        let mut depth = 1;
+        while depth > 0 {
+            match self.advance().map(|t| t.kind) {
+                Some(TokenKind::LParen) => depth += 1,
+                Some(TokenKind::RParen) => depth -= 1,
+                Some(_) => {}
+                None => return Err(self.error(TextErrorKind::UnexpectedEnd)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes an optional symbolic identifier.
+    pub fn id(&mut self) -> Option<&'a str> {
+        let id = self.peek_kind_at(0, TokenKind::Id)?;
+        self.pos += 1;
+        Some(id)
+    }
+
+    /// Returns true if the token `n` tokens ahead is an index: an
+    /// identifier or an unsigned integer.
+    pub fn peek_index_at(&self, n: usize) -> bool {
+        self.peek_kind_at(n, TokenKind::Id).is_some()
+            || self
+                .peek_kind_at(n, TokenKind::Reserved)
+                .is_some_and(|t| t.starts_with(|c: char| c.is_ascii_digit()))
+    }
+
+    pub fn peek_index(&self) -> bool {
+        self.peek_index_at(0)
+    }
+
+    /// Consumes a number token and converts it with `f`.
+    fn number<T>(
+        &mut self,
+        f: impl FnOnce(&str) -> core::result::Result<T, TextErrorKind>,
+    ) -> Result<T> {
+        let token = self
+            .peek()
+            .filter(|t| matches!(t.kind, TokenKind::Reserved | TokenKind::Keyword))
+            .ok_or_else(|| self.unexpected())?;
+        let value = f(self.text(token)).map_err(|kind| self.error(kind))?;
+        self.pos += 1;
+        Ok(value)
+    }
+
+    pub fn u32(&mut self) -> Result<u32> {
+        self.number(parse_u32)
+    }
+
+    /// Parses an integer of `bits` bits. See [`parse_int`].
+    pub fn int(&mut self, bits: u32) -> Result<u64> {
+        self.number(|text| parse_int(text, bits))
+    }
+
+    pub fn f32(&mut self) -> Result<f32> {
+        self.number(parse_f32)
+    }
+
+    pub fn f64(&mut self) -> Result<f64> {
+        self.number(parse_f64)
+    }
+
+    /// Parses a keyword of the form `key=value`, such as `offset=4`, if the
+    /// next token is one, and returns the value. A keyword whose value is
+    /// not a number is an unknown operator.
+    pub fn key_value(&mut self, key: &str) -> Result<Option<u32>> {
+        let value = match self.peek_keyword().and_then(|k| k.strip_prefix(key)) {
+            Some(value) => parse_u32(value).map_err(|kind| match kind {
+                TextErrorKind::UnexpectedToken => self.error(TextErrorKind::UnknownOperator),
+                kind => self.error(kind),
+            })?,
+            None => return Ok(None),
+        };
+        self.pos += 1;
+        Ok(Some(value))
+    }
+
+    /// Parses an index without resolving identifiers.
+    pub fn index(&mut self) -> Result<Index<'a>> {
+        match self.id() {
+            Some(id) => Ok(Index::Id(id)),
+            None => self.u32().map(Index::Num),
+        }
+    }
+
+    pub fn string(&mut self) -> Result<&'a [u8]> {
+        match self.peek().map(|t| t.kind) {
+            Some(TokenKind::String { start, end }) => {
+                self.pos += 1;
+                Ok(&self.strings[start..end])
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Parses any number of consecutive strings and returns their
+    /// concatenation.
+    pub fn strings(&mut self) -> &'a [u8] {
+        let (mut start, mut end) = (0, 0);
+        while let Some(TokenKind::String { start: s, end: e }) = self.peek().map(|t| t.kind) {
+            if start == end {
+                start = s;
+            }
+            end = e;
+            self.pos += 1;
+        }
+        &self.strings[start..end]
+    }
+
+    /// Parses a string that must be valid UTF-8.
+    pub fn name(&mut self) -> Result<&'a str> {
+        let offset = self.offset();
+        core::str::from_utf8(self.string()?)
+            .map_err(|_| self.error_at(TextErrorKind::MalformedUtf8, offset))
+    }
+
+    /// Parses a value type if one is next.
+    pub fn opt_valtype(&mut self) -> Option<ValType> {
+        let t = match self.peek_keyword()? {
+            "i32" => ValType::NumType(NumType::I32),
+            "i64" => ValType::NumType(NumType::I64),
+            "f32" => ValType::NumType(NumType::F32),
+            "f64" => ValType::NumType(NumType::F64),
+            "v128" => ValType::VecType(VecType::V128),
+            "funcref" => ValType::RefType(RefType::FuncRef),
+            "externref" => ValType::RefType(RefType::ExternRef),
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(t)
+    }
+
+    pub fn valtype(&mut self) -> Result<ValType> {
+        self.opt_valtype().ok_or_else(|| self.unexpected())
+    }
+
+    /// Parses value types up to the end of the current field.
+    pub fn valtypes(&mut self) -> Result<Vec<ValType>> {
+        let mut types = Vec::new();
+        while !self.peek_rparen() {
+            types.push(self.valtype()?);
+        }
+        Ok(types)
+    }
+
+    pub fn peek_reftype(&self) -> bool {
+        matches!(self.peek_keyword(), Some("funcref" | "externref"))
+    }
+
+    pub fn reftype(&mut self) -> Result<RefType> {
+        let t = match self.peek_keyword() {
+            Some("funcref") => RefType::FuncRef,
+            Some("externref") => RefType::ExternRef,
+            _ => return Err(self.unexpected()),
+        };
+        self.pos += 1;
+        Ok(t)
+    }
+
+    /// Parses the heap type of `ref.null`.
+    pub fn heaptype(&mut self) -> Result<RefType> {
+        let t = match self.peek_keyword() {
+            Some("func") => RefType::FuncRef,
+            Some("extern") => RefType::ExternRef,
+            _ => return Err(self.unexpected()),
+        };
+        self.pos += 1;
+        Ok(t)
+    }
+
+    pub fn limits(&mut self) -> Result<Limits> {
+        let min = self.u32()?;
+        let max = match self.peek_index() {
+            true => Some(self.u32()?),
+            false => None,
+        };
+        Ok(Limits { min, max })
+    }
+
+    pub fn tabletype(&mut self) -> Result<TableType> {
+        let limits = self.limits()?;
+        let ref_type = self.reftype()?;
+        Ok(TableType { ref_type, limits })
+    }
+
+    pub fn globaltype(&mut self) -> Result<GlobalType> {
+        if self.take_field("mut") {
+            let value_type = self.valtype()?;
+            self.rparen()?;
+            return Ok(GlobalType {
+                value_type,
+                mutable: true,
+            });
+        }
+        Ok(GlobalType {
+            value_type: self.valtype()?,
+            mutable: false,
+        })
+    }
+
+    /// Parses `(param ...)*` and `(result ...)*` fields. Parameters may be
+    /// named, one per field, with `(param $x t)`; the names are returned
+    /// along with the type.
+    pub fn functype(&mut self) -> Result<(FuncType, Vec<Option<&'a str>>)> {
+        let mut params = Vec::new();
+        let mut names = Vec::new();
+        while self.take_field("param") {
+            match self.id() {
+                Some(id) => {
+                    params.push(self.valtype()?);
+                    names.push(Some(id));
+                }
+                None => {
+                    let types = self.valtypes()?;
+                    names.extend(types.iter().map(|_| None));
+                    params.extend(types);
+                }
+            }
+            self.rparen()?;
+        }
+        let mut ret = Vec::new();
+        while self.take_field("result") {
+            ret.extend(self.valtypes()?);
+            self.rparen()?;
+        }
+        Ok((FuncType { params, ret }, names))
+    }
+
+    /// Parses the lanes of `v128.const` and returns them packed into a
+    /// single little-endian value.
+    pub fn v128_const(&mut self) -> Result<u128> {
+        let shape = self.keyword()?;
+        let (lanes, bits) = match shape {
+            "i8x16" => (16, 8),
+            "i16x8" => (8, 16),
+            "i32x4" | "f32x4" => (4, 32),
+            "i64x2" | "f64x2" => (2, 64),
+            _ => return Err(self.error(TextErrorKind::UnexpectedToken)),
+        };
+        let count = (0..)
+            .take_while(|&n| {
+                self.peek_at(n).is_some_and(|t| match t.kind {
+                    TokenKind::Reserved => true,
+                    TokenKind::Keyword => {
+                        ["inf", "nan"].iter().any(|k| self.text(t).starts_with(k))
+                    }
+                    _ => false,
+                })
+            })
+            .count();
+        if count != lanes as usize {
+            return Err(self.error(TextErrorKind::WrongLaneCount));
+        }
+        let mut value: u128 = 0;
+        for i in 0..lanes {
+            let lane = match shape {
+                "f32x4" => u64::from(self.f32()?.to_bits()),
+                "f64x2" => self.f64()?.to_bits(),
+                _ => self.int(bits)?,
+            };
+            value |= u128::from(lane) << (i * bits);
+        }
+        Ok(value)
+    }
+}
@@ -0,0 +1,330 @@
+//! Number literals of the text format.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/text/values.html)
+
+use std::string::String;
+
+use super::TextErrorKind;
+
+type Result<T> = core::result::Result<T, TextErrorKind>;
+
+/// Splits the optional sign from the front of `text`. Returns true if the
+/// number is negative.
+fn sign(text: &str) -> (bool, &str) {
+    match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    }
+}
+
+/// Returns true if `digits` is a sequence of digits of `radix`, in which
+/// single underscores may separate digits.
+fn well_formed(digits: &str, radix: u32) -> bool {
+    !digits.is_empty()
+        && !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+        && digits.chars().all(|c| c == '_' || c.is_digit(radix))
+}
+
+fn digits(digits: &str, radix: u32) -> impl Iterator<Item = u64> + '_ {
+    digits
+        .chars()
+        .filter_map(move |c| c.to_digit(radix))
+        .map(u64::from)
+}
+
+/// Parses a decimal or hexadecimal number without sign.
+fn unsigned(text: &str) -> Result<u64> {
+    match text.strip_prefix("0x") {
+        Some(text) => value(text, 16),
+        None => value(text, 10),
+    }
+}
+
+fn value(text: &str, radix: u32) -> Result<u64> {
+    if !well_formed(text, radix) {
+        return Err(TextErrorKind::UnknownOperator);
+    }
+    digits(text, radix).try_fold(0u64, |value, d| {
+        value
+            .checked_mul(radix.into())
+            .and_then(|value| value.checked_add(d))
+            .ok_or(TextErrorKind::ConstantOutOfRange)
+    })
+}
+
+/// Parses an unsigned integer, such as an index or a limit. A signed
+/// number is a well-formed token in the wrong place.
+pub(crate) fn parse_u32(text: &str) -> Result<u32> {
+    let (_, unsigned_text) = sign(text);
+    if unsigned_text.len() < text.len() {
+        unsigned(unsigned_text)?;
+        return Err(TextErrorKind::UnexpectedToken);
+    }
+    unsigned(text)
+        .map_err(|kind| match kind {
+            TextErrorKind::ConstantOutOfRange => TextErrorKind::U32OutOfRange,
+            kind => kind,
+        })?
+        .try_into()
+        .map_err(|_| TextErrorKind::U32OutOfRange)
+}
+
+/// Parses an integer of `bits` bits, which may be written as a signed or
+/// an unsigned number, and returns its two's complement representation.
+pub(crate) fn parse_int(text: &str, bits: u32) -> Result<u64> {
+    let (negative, text) = sign(text);
+    let magnitude = unsigned(text)?;
+    let mask = u64::MAX >> (64 - bits);
+    let in_range = if negative {
+        magnitude <= 1 << (bits - 1)
+    } else {
+        magnitude <= mask
+    };
+    if !in_range {
+        return Err(TextErrorKind::ConstantOutOfRange);
+    }
+    let value = if negative {
+        magnitude.wrapping_neg()
+    } else {
+        magnitude
+    };
+    Ok(value & mask)
+}
+
+/// The parameters of a binary floating-point format.
+struct Format {
+    mantissa_bits: u32,
+    exponent_bits: u32,
+}
+
+const F32: Format = Format {
+    mantissa_bits: 23,
+    exponent_bits: 8,
+};
+
+const F64: Format = Format {
+    mantissa_bits: 52,
+    exponent_bits: 11,
+};
+
+impl Format {
+    fn bias(&self) -> i64 {
+        (1 << (self.exponent_bits - 1)) - 1
+    }
+
+    fn infinity(&self) -> u64 {
+        ((1 << self.exponent_bits) - 1) << self.mantissa_bits
+    }
+
+    /// Parses a float and returns its bit pattern. `decimal` converts a
+    /// decimal number without underscores to the bit pattern of the
+    /// nearest float.
+    fn parse(&self, text: &str, decimal: fn(&str) -> Option<u64>) -> Result<u64> {
+        let (negative, text) = sign(text);
+        let bits = if text == "inf" {
+            self.infinity()
+        } else if text == "nan" {
+            self.infinity() | 1 << (self.mantissa_bits - 1)
+        } else if let Some(payload) = text.strip_prefix("nan:0x") {
+            match value(payload, 16)? {
+                payload if payload != 0 && payload < 1 << self.mantissa_bits => {
+                    self.infinity() | payload
+                }
+                _ => return Err(TextErrorKind::ConstantOutOfRange),
+            }
+        } else if let Some(text) = text.strip_prefix("0x") {
+            self.hex(text)?
+        } else {
+            if !well_formed_decimal(text) {
+                return Err(TextErrorKind::UnknownOperator);
+            }
+            let text: String = text.chars().filter(|&c| c != '_').collect();
+            let bits = decimal(&text).ok_or(TextErrorKind::UnknownOperator)?;
+            if bits == self.infinity() {
+                return Err(TextErrorKind::ConstantOutOfRange);
+            }
+            bits
+        };
+        let sign = u64::from(negative) << (self.mantissa_bits + self.exponent_bits);
+        Ok(bits | sign)
+    }
+
+    /// Converts a hexadecimal float (after the `0x` prefix) to the nearest
+    /// float, rounding ties to even.
+    fn hex(&self, text: &str) -> Result<u64> {
+        let (mantissa, exponent) = match text.find(['p', 'P']) {
+            Some(i) => (&text[..i], Some(&text[i + 1..])),
+            None => (text, None),
+        };
+        let (int, frac) = match mantissa.find('.') {
+            Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+            None => (mantissa, ""),
+        };
+        if !well_formed(int, 16) || !(frac.is_empty() || well_formed(frac, 16)) {
+            return Err(TextErrorKind::UnknownOperator);
+        }
+        let mut exp: i64 = match exponent {
+            Some(exponent) => {
+                let (negative, exponent) = sign(exponent);
+                if !well_formed(exponent, 10) {
+                    return Err(TextErrorKind::UnknownOperator);
+                }
+                // Larger exponents overflow or underflow any float anyway.
+                let value = digits(exponent, 10)
+                    .fold(0i64, |value, d| (value * 10 + d as i64).min(1 << 20));
+                if negative {
+                    -value
+                } else {
+                    value
+                }
+            }
+            None => 0,
+        };
+
+        // The value is `m * 2^exp`. Digits that do not fit in `m` are only
+        // needed to round correctly, so they are folded into its lowest bit.
+        let mut m: u64 = 0;
+        for d in digits(int, 16) {
+            if m < 1 << 60 {
+                m = m * 16 + d;
+            } else {
+                exp += 4;
+                m |= u64::from(d != 0);
+            }
+        }
+        for d in digits(frac, 16) {
+            if m < 1 << 60 {
+                m = m * 16 + d;
+                exp -= 4;
+            } else {
+                m |= u64::from(d != 0);
+            }
+        }
+        if m == 0 {
+            return Ok(0);
+        }
+
+        let mantissa_bits = i64::from(self.mantissa_bits);
+        let top = 63 - i64::from(m.leading_zeros()) + exp;
+        if top > self.bias() {
+            return Err(TextErrorKind::ConstantOutOfRange);
+        }
+        // The result is a multiple of 2^quantum.
+        let quantum = top.max(1 - self.bias()) - mantissa_bits;
+        let shift = quantum - exp;
+        let mantissa = if shift <= 0 {
+            m << -shift
+        } else if shift >= 128 {
+            0
+        } else {
+            let m = u128::from(m);
+            let rounded = m >> shift;
+            let rest = m & ((1 << shift) - 1);
+            let half = 1 << (shift - 1);
+            let round_up = rest > half || (rest == half && rounded & 1 == 1);
+            (rounded + u128::from(round_up)) as u64
+        };
+        // Adding the mantissa (including its implicit leading bit) carries
+        // into the exponent field, which handles subnormals as well as
+        // rounding up to the next power of two.
+        let bits =
+            (((quantum + mantissa_bits + self.bias() - 1) as u64) << self.mantissa_bits) + mantissa;
+        if bits >= self.infinity() {
+            return Err(TextErrorKind::ConstantOutOfRange);
+        }
+        Ok(bits)
+    }
+}
+
+/// Returns true if `text` is a decimal float: digits, optionally followed
+/// by a fraction and an exponent.
+fn well_formed_decimal(text: &str) -> bool {
+    let (mantissa, exponent) = match text.find(['e', 'E']) {
+        Some(i) => (&text[..i], Some(&text[i + 1..])),
+        None => (text, None),
+    };
+    let (int, frac) = match mantissa.find('.') {
+        Some(i) => (&mantissa[..i], &mantissa[i + 1..]),
+        None => (mantissa, ""),
+    };
+    well_formed(int, 10)
+        && (frac.is_empty() || well_formed(frac, 10))
+        && exponent.is_none_or(|e| well_formed(sign(e).1, 10))
+}
+
+pub(crate) fn parse_f32(text: &str) -> Result<f32> {
+    F32.parse(text, |text| {
+        text.parse::<f32>().ok().map(|f| f.to_bits().into())
+    })
+    .map(|bits| f32::from_bits(bits as u32))
+}
+
+pub(crate) fn parse_f64(text: &str) -> Result<f64> {
+    F64.parse(text, |text| text.parse::<f64>().ok().map(f64::to_bits))
+        .map(f64::from_bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_int() {
+        assert_eq!(parse_u32("0x1_F"), Ok(31));
+        assert_eq!(parse_u32("4294967296"), Err(TextErrorKind::U32OutOfRange));
+        assert_eq!(parse_u32("-1"), Err(TextErrorKind::UnexpectedToken));
+        assert_eq!(parse_u32("1__0"), Err(TextErrorKind::UnknownOperator));
+        assert_eq!(parse_int("-1", 32), Ok(0xFFFF_FFFF));
+        assert_eq!(parse_int("0xFFFF_FFFF", 32), Ok(0xFFFF_FFFF));
+        assert_eq!(parse_int("-0x8000_0000", 32), Ok(0x8000_0000));
+        assert_eq!(
+            parse_int("-0x8000_0001", 32),
+            Err(TextErrorKind::ConstantOutOfRange)
+        );
+        assert_eq!(parse_int("255", 8), Ok(255));
+        assert_eq!(parse_int("-128", 8), Ok(128));
+        assert_eq!(parse_int("18446744073709551615", 64), Ok(u64::MAX));
+        assert_eq!(
+            parse_int("18446744073709551616", 64),
+            Err(TextErrorKind::ConstantOutOfRange)
+        );
+    }
+
+    #[test]
+    fn test_float() {
+        assert_eq!(parse_f32("1.5"), Ok(1.5));
+        assert_eq!(parse_f64("-1_000.25e-2"), Ok(-10.0025));
+        assert_eq!(parse_f64("1e400"), Err(TextErrorKind::ConstantOutOfRange));
+        assert_eq!(parse_f32("0x1.8p1"), Ok(3.0));
+        assert_eq!(parse_f64("0x10"), Ok(16.0));
+        assert_eq!(parse_f64("-0x0.0"), Ok(-0.0));
+        assert_eq!(parse_f32("0x1p-149"), Ok(f32::from_bits(1)));
+        assert_eq!(parse_f32("0x1p-150"), Ok(0.0));
+        assert_eq!(parse_f32("0x1.8p-149"), Ok(f32::from_bits(2)));
+        assert_eq!(parse_f32("0x1.fffffep127"), Ok(f32::MAX));
+        assert_eq!(
+            parse_f32("0x1.ffffffp127"),
+            Err(TextErrorKind::ConstantOutOfRange)
+        );
+        // Ties round to even, and the digits past the precision of the
+        // mantissa still break ties.
+        assert_eq!(parse_f32("0x1.000001p0"), Ok(1.0));
+        assert_eq!(
+            parse_f32("0x1.0000010000000000001p0"),
+            Ok(1.0 + f32::EPSILON)
+        );
+        assert_eq!(
+            parse_f64("0x1.fffffffffffff8p-1023"),
+            Ok(f64::from_bits(0x0010_0000_0000_0000))
+        );
+
+        assert_eq!(parse_f32("-inf"), Ok(f32::NEG_INFINITY));
+        assert_eq!(parse_f32("nan").map(f32::to_bits), Ok(0x7FC0_0000));
+        assert_eq!(parse_f32("-nan:0x1").map(f32::to_bits), Ok(0xFF80_0001));
+        assert_eq!(parse_f64("nan:0x0"), Err(TextErrorKind::ConstantOutOfRange));
+        assert_eq!(parse_f64("1.e"), Err(TextErrorKind::UnknownOperator));
+    }
+}
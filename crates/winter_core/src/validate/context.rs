@@ -0,0 +1,193 @@
+use std::{collections::BTreeSet, vec::Vec};
+
+use super::ValidationErrorKind;
+use crate::parse::{
+    instructions::{Expr, Instr, ReferenceInstruction},
+    modules::{
+        element_section::ElemInit, export_section::ExportDesc, import_section::ImportDesc,
+        module::Module, DataIdx, ElemIdx, FuncIdx, GlobalIdx, LocalIdx, MemIdx, TableIdx, TypeIdx,
+    },
+    types::{FuncType, GlobalType, MemType, RefType, TableType, ValType},
+};
+
+/// Validation context: the types of every definition in the module, in
+/// their respective index spaces.
+///
+/// [Reference](https://webassembly.github.io/spec/core/valid/conventions.html#contexts)
+#[derive(Debug, Default)]
+pub(crate) struct Context {
+    pub types: Vec<FuncType>,
+    /// Type index of every function, imported functions first.
+    pub funcs: Vec<TypeIdx>,
+    pub tables: Vec<TableType>,
+    pub mems: Vec<MemType>,
+    pub globals: Vec<GlobalType>,
+    pub elems: Vec<RefType>,
+    /// Number of data segments as declared by the data count section, if
+    /// present.
+    pub datas: Option<u32>,
+    /// Functions that are referenced outside of function bodies and may be
+    /// used with `ref.func`.
+    pub refs: BTreeSet<u32>,
+    pub imported_funcs: usize,
+    pub imported_globals: usize,
+}
+
+impl Context {
+    pub fn new(module: &Module) -> Result<Context, ValidationErrorKind> {
+        let mut c = Context::default();
+        if let Some(t) = &module.func_type {
+            c.types = t.function_types.clone();
+        }
+
+        if let Some(imports) = &module.import {
+            for import in &imports.imports {
+                match import.desc {
+                    ImportDesc::Func(t) => {
+                        c.func_type_by_idx(t)?;
+                        c.funcs.push(t);
+                        c.imported_funcs += 1;
+                    }
+                    ImportDesc::Table(t) => c.tables.push(t),
+                    ImportDesc::Mem(m) => c.mems.push(m),
+                    ImportDesc::Global(g) => {
+                        c.globals.push(g);
+                        c.imported_globals += 1;
+                    }
+                }
+            }
+        }
+        if let Some(funcs) = &module.type_idx {
+            for t in &funcs.functions {
+                c.func_type_by_idx(*t)?;
+                c.funcs.push(*t);
+            }
+        }
+        if let Some(tables) = &module.table {
+            c.tables.extend(tables.tables.iter().map(|t| t.table_type));
+        }
+        if let Some(mems) = &module.mem {
+            c.mems.extend(mems.tables.iter().map(|m| m.mem_type));
+        }
+        if let Some(globals) = &module.global {
+            c.globals
+                .extend(globals.globals.iter().map(|g| g.global_type));
+        }
+        if let Some(elems) = &module.elem {
+            c.elems.extend(elems.elems.iter().map(|e| e.ref_type()));
+        }
+        c.datas = module.m.as_ref().map(|d| d.count);
+
+        // Collect the functions referenced outside of function bodies.
+        if let Some(exports) = &module.export {
+            for export in &exports.exports {
+                if let ExportDesc::Func(x) = export.desc {
+                    c.refs.insert(x.0);
+                }
+            }
+        }
+        if let Some(globals) = &module.global {
+            for global in &globals.globals {
+                c.collect_refs(&global.expr);
+            }
+        }
+        if let Some(elems) = &module.elem {
+            for elem in &elems.elems {
+                match &elem.init {
+                    ElemInit::FuncIdx(funcs) => c.refs.extend(funcs.iter().map(|f| f.0)),
+                    ElemInit::Expr(exprs) => exprs.iter().for_each(|e| c.collect_refs(e)),
+                }
+            }
+        }
+
+        Ok(c)
+    }
+
+    fn collect_refs(&mut self, expr: &Expr) {
+        for instr in &expr.instr {
+            if let Instr::Reference(r) = instr {
+                if let ReferenceInstruction::RefFunc(x) = **r {
+                    self.refs.insert(x.0);
+                }
+            }
+        }
+    }
+
+    pub fn func_type_by_idx(&self, x: TypeIdx) -> Result<&FuncType, ValidationErrorKind> {
+        self.types
+            .get(x.0 as usize)
+            .ok_or(ValidationErrorKind::UnknownType(x.0))
+    }
+
+    pub fn func(&self, x: FuncIdx) -> Result<&FuncType, ValidationErrorKind> {
+        let t = self
+            .funcs
+            .get(x.0 as usize)
+            .ok_or(ValidationErrorKind::UnknownFunction(x.0))?;
+        self.func_type_by_idx(*t)
+    }
+
+    pub fn table(&self, x: TableIdx) -> Result<&TableType, ValidationErrorKind> {
+        self.tables
+            .get(x.0 as usize)
+            .ok_or(ValidationErrorKind::UnknownTable(x.0))
+    }
+
+    pub fn mem(&self, x: MemIdx) -> Result<&MemType, ValidationErrorKind> {
+        self.mems
+            .get(x.0 as usize)
+            .ok_or(ValidationErrorKind::UnknownMemory(x.0))
+    }
+
+    pub fn global(&self, x: GlobalIdx) -> Result<&GlobalType, ValidationErrorKind> {
+        self.globals
+            .get(x.0 as usize)
+            .ok_or(ValidationErrorKind::UnknownGlobal(x.0))
+    }
+
+    pub fn elem(&self, x: ElemIdx) -> Result<RefType, ValidationErrorKind> {
+        self.elems
+            .get(x.0 as usize)
+            .copied()
+            .ok_or(ValidationErrorKind::UnknownElem(x.0))
+    }
+
+    pub fn data(&self, x: DataIdx) -> Result<(), ValidationErrorKind> {
+        match self.datas {
+            None => Err(ValidationErrorKind::DataCountRequired),
+            Some(n) if x.0 < n => Ok(()),
+            Some(_) => Err(ValidationErrorKind::UnknownData(x.0)),
+        }
+    }
+}
+
+/// Types of the locals of a function, stored as runs of identical types
+/// since a function may declare a very large number of locals.
+#[derive(Debug, Default)]
+pub(crate) struct Locals {
+    /// Each entry is the exclusive end index of a run and its type.
+    runs: Vec<(u64, ValType)>,
+}
+
+impl Locals {
+    pub fn push(&mut self, count: u32, val_type: ValType) -> Result<(), ValidationErrorKind> {
+        let end = self.len() + count as u64;
+        if end > u32::MAX as u64 {
+            return Err(ValidationErrorKind::TooManyLocals);
+        }
+        self.runs.push((end, val_type));
+        Ok(())
+    }
+
+    pub fn len(&self) -> u64 {
+        self.runs.last().map_or(0, |r| r.0)
+    }
+
+    pub fn get(&self, x: LocalIdx) -> Result<ValType, ValidationErrorKind> {
+        let i = self.runs.partition_point(|r| r.0 <= x.0 as u64);
+        self.runs
+            .get(i)
+            .map(|r| r.1)
+            .ok_or(ValidationErrorKind::UnknownLocal(x.0))
+    }
+}
@@ -0,0 +1,144 @@
+use core::fmt;
+
+use crate::parse::{
+    modules::{DataIdx, ElemIdx, FuncIdx, GlobalIdx},
+    ParseError,
+};
+
+/// The reason why a module failed validation. The [`fmt::Display`]
+/// implementation uses the messages of the specification's test suite.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum ValidationErrorKind {
+    /// Operand types did not match what the instruction expected.
+    TypeMismatch,
+    UnknownType(u32),
+    UnknownFunction(u32),
+    UnknownTable(u32),
+    UnknownMemory(u32),
+    UnknownGlobal(u32),
+    UnknownLocal(u32),
+    UnknownLabel(u32),
+    UnknownElem(u32),
+    UnknownData(u32),
+    /// `memory.init` or `data.drop` used without a data count section.
+    DataCountRequired,
+    /// The data count section does not match the number of data segments.
+    DataCountMismatch,
+    /// The function and code sections have a different number of entries.
+    FuncCodeMismatch,
+    /// The sum of the locals of a function does not fit in a `u32`.
+    TooManyLocals,
+    /// `global.set` on an immutable global.
+    ImmutableGlobal,
+    /// A non-constant instruction in a constant expression.
+    ConstantExpressionRequired,
+    /// The start function does not have type `[] -> []`.
+    InvalidStartFunction,
+    DuplicateExportName,
+    /// More than one memory is defined or imported.
+    MultipleMemories,
+    /// The minimum of some limits is greater than their maximum.
+    InvalidLimits,
+    /// A memory has more than 65536 pages.
+    MemorySizeTooLarge,
+    /// The alignment of a memory access is larger than its natural
+    /// alignment.
+    InvalidAlignment,
+    InvalidLaneIndex,
+    /// `select` with a type annotation that is not exactly one type.
+    InvalidResultArity,
+    /// `ref.func` on a function that is not declared outside of function
+    /// bodies.
+    UndeclaredFunctionReference(u32),
+    /// The body of a function, which is decoded on first use, is malformed.
+    Malformed(ParseError),
+}
+
+impl fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationErrorKind::TypeMismatch => write!(f, "type mismatch"),
+            ValidationErrorKind::UnknownType(i) => write!(f, "unknown type {}", i),
+            ValidationErrorKind::UnknownFunction(i) => write!(f, "unknown function {}", i),
+            ValidationErrorKind::UnknownTable(i) => write!(f, "unknown table {}", i),
+            ValidationErrorKind::UnknownMemory(i) => write!(f, "unknown memory {}", i),
+            ValidationErrorKind::UnknownGlobal(i) => write!(f, "unknown global {}", i),
+            ValidationErrorKind::UnknownLocal(i) => write!(f, "unknown local {}", i),
+            ValidationErrorKind::UnknownLabel(i) => write!(f, "unknown label {}", i),
+            ValidationErrorKind::UnknownElem(i) => write!(f, "unknown elem segment {}", i),
+            ValidationErrorKind::UnknownData(i) => write!(f, "unknown data segment {}", i),
+            ValidationErrorKind::DataCountRequired => write!(f, "data count section required"),
+            ValidationErrorKind::DataCountMismatch => {
+                write!(f, "data count and data section have inconsistent lengths")
+            }
+            ValidationErrorKind::FuncCodeMismatch => {
+                write!(f, "function and code section have inconsistent lengths")
+            }
+            ValidationErrorKind::TooManyLocals => write!(f, "too many locals"),
+            ValidationErrorKind::ImmutableGlobal => write!(f, "global is immutable"),
+            ValidationErrorKind::ConstantExpressionRequired => {
+                write!(f, "constant expression required")
+            }
+            ValidationErrorKind::InvalidStartFunction => write!(f, "start function"),
+            ValidationErrorKind::DuplicateExportName => write!(f, "duplicate export name"),
+            ValidationErrorKind::MultipleMemories => write!(f, "multiple memories"),
+            ValidationErrorKind::InvalidLimits => {
+                write!(f, "size minimum must not be greater than maximum")
+            }
+            ValidationErrorKind::MemorySizeTooLarge => {
+                write!(f, "memory size must be at most 65536 pages (4GiB)")
+            }
+            ValidationErrorKind::InvalidAlignment => {
+                write!(f, "alignment must not be larger than natural")
+            }
+            ValidationErrorKind::InvalidLaneIndex => write!(f, "invalid lane index"),
+            ValidationErrorKind::InvalidResultArity => write!(f, "invalid result arity"),
+            ValidationErrorKind::UndeclaredFunctionReference(i) => {
+                write!(f, "undeclared function reference {}", i)
+            }
+            ValidationErrorKind::Malformed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// The part of the module in which a validation error was found.
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum Location {
+    /// A module level definition such as an import, export or type.
+    Module,
+    /// An instruction in the body of a function. `func` is an index in the
+    /// function index space (which includes imported functions) and
+    /// `offset` is the offset of the instruction from the start of the code
+    /// section of the binary module, or 0 if the module was not decoded
+    /// from a binary.
+    Func { func: FuncIdx, offset: usize },
+    /// The initializer of a global.
+    Global(GlobalIdx),
+    /// An element segment.
+    Elem(ElemIdx),
+    /// A data segment.
+    Data(DataIdx),
+}
+
+/// Error returned when a module is not valid.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    pub location: Location,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Location::Module => write!(f, "{}", self.kind),
+            Location::Func { func, offset } => write!(
+                f,
+                "{} in function {} at offset {:#x}",
+                self.kind, func.0, offset
+            ),
+            Location::Global(x) => write!(f, "{} in global {}", self.kind, x.0),
+            Location::Elem(x) => write!(f, "{} in elem segment {}", self.kind, x.0),
+            Location::Data(x) => write!(f, "{} in data segment {}", self.kind, x.0),
+        }
+    }
+}
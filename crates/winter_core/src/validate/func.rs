@@ -0,0 +1,612 @@
+use std::vec::Vec;
+
+use super::{
+    context::{Context, Locals},
+    signatures::{numeric_signature, vector_signature, F32, F64, FUNCREF, I32, I64, V128},
+    ValidationErrorKind,
+};
+use crate::parse::{
+    instructions::{
+        BlockType, ControlInstruction, Instr, LaneIdx, MemArg, MemoryInstruction,
+        ParametricInstruction, ReferenceInstruction, TableInstruction, VariableInstruction,
+        VectorInstruction,
+    },
+    modules::{code_section::Func, LabelIdx, MemIdx},
+    types::{FuncType, RefType, ValType},
+};
+
+type Result<T> = core::result::Result<T, ValidationErrorKind>;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum FrameKind {
+    Func,
+    Block,
+    Loop,
+    If,
+    Else,
+}
+
+#[derive(Debug)]
+struct CtrlFrame {
+    kind: FrameKind,
+    start_types: Vec<ValType>,
+    end_types: Vec<ValType>,
+    height: usize,
+    unreachable: bool,
+}
+
+impl CtrlFrame {
+    fn label_types(&self) -> &[ValType] {
+        if self.kind == FrameKind::Loop {
+            &self.start_types
+        } else {
+            &self.end_types
+        }
+    }
+}
+
+/// The instructions left to validate in a block, and the `else` branch
+/// that follows them if the block is the `then` branch of an `if`.
+struct Body<'f> {
+    instrs: core::slice::Iter<'f, Instr>,
+    els: &'f [Instr],
+}
+
+impl<'f> Body<'f> {
+    fn new(instrs: &'f [Instr], els: &'f [Instr]) -> Self {
+        Body {
+            instrs: instrs.iter(),
+            els,
+        }
+    }
+}
+
+/// Validates function bodies with the algorithm described in the appendix
+/// of the specification. An operand of type `None` is of unknown type, which
+/// only happens in unreachable code.
+///
+/// [Reference](https://webassembly.github.io/spec/core/appendix/algorithm.html)
+pub(crate) struct FuncValidator<'c> {
+    ctx: &'c Context,
+    locals: Locals,
+    vals: Vec<Option<ValType>>,
+    ctrls: Vec<CtrlFrame>,
+    /// Number of instructions visited so far.
+    visited: usize,
+}
+
+impl<'c> FuncValidator<'c> {
+    pub fn new(ctx: &'c Context) -> Self {
+        Self {
+            ctx,
+            locals: Locals::default(),
+            vals: Vec::new(),
+            ctrls: Vec::new(),
+            visited: 0,
+        }
+    }
+
+    /// Position of the last visited instruction in the body, counting every
+    /// instruction in the order in which it appears in the binary.
+    pub fn position(&self) -> usize {
+        self.visited.saturating_sub(1)
+    }
+
+    pub fn validate(&mut self, func_type: &FuncType, func: &Func) -> Result<()> {
+        for param in &func_type.params {
+            self.locals.push(1, *param)?;
+        }
+        for local in &func.locals {
+            self.locals.push(local.count, local.val_type)?;
+        }
+
+        self.push_ctrl(FrameKind::Func, Vec::new(), func_type.ret.clone());
+        self.instrs(&func.expr.instr)?;
+        self.pop_ctrl()?;
+        Ok(())
+    }
+
+    fn push_val(&mut self, val: Option<ValType>) {
+        self.vals.push(val);
+    }
+
+    fn push_vals(&mut self, vals: &[ValType]) {
+        self.vals.extend(vals.iter().map(|v| Some(*v)));
+    }
+
+    fn pop_val(&mut self) -> Result<Option<ValType>> {
+        let frame = self.ctrls.last().unwrap();
+        if self.vals.len() == frame.height {
+            if frame.unreachable {
+                return Ok(None);
+            }
+            return Err(ValidationErrorKind::TypeMismatch);
+        }
+        Ok(self.vals.pop().unwrap())
+    }
+
+    fn pop_expect(&mut self, expect: ValType) -> Result<Option<ValType>> {
+        match self.pop_val()? {
+            Some(actual) if actual != expect => Err(ValidationErrorKind::TypeMismatch),
+            _ => Ok(Some(expect)),
+        }
+    }
+
+    fn pop_vals(&mut self, vals: &[ValType]) -> Result<()> {
+        for val in vals.iter().rev() {
+            self.pop_expect(*val)?;
+        }
+        Ok(())
+    }
+
+    fn push_ctrl(&mut self, kind: FrameKind, start_types: Vec<ValType>, end_types: Vec<ValType>) {
+        self.ctrls.push(CtrlFrame {
+            kind,
+            start_types: start_types.clone(),
+            end_types,
+            height: self.vals.len(),
+            unreachable: false,
+        });
+        self.push_vals(&start_types);
+    }
+
+    fn pop_ctrl(&mut self) -> Result<CtrlFrame> {
+        let end_types = self.ctrls.last().unwrap().end_types.clone();
+        self.pop_vals(&end_types)?;
+        if self.vals.len() != self.ctrls.last().unwrap().height {
+            return Err(ValidationErrorKind::TypeMismatch);
+        }
+        Ok(self.ctrls.pop().unwrap())
+    }
+
+    fn unreachable(&mut self) {
+        let frame = self.ctrls.last_mut().unwrap();
+        self.vals.truncate(frame.height);
+        frame.unreachable = true;
+    }
+
+    fn label(&self, l: LabelIdx) -> Result<Vec<ValType>> {
+        let n = self.ctrls.len();
+        if l.0 as usize >= n {
+            return Err(ValidationErrorKind::UnknownLabel(l.0));
+        }
+        Ok(self.ctrls[n - 1 - l.0 as usize].label_types().to_vec())
+    }
+
+    fn op(&mut self, params: &[ValType], results: &[ValType]) -> Result<()> {
+        self.pop_vals(params)?;
+        self.push_vals(results);
+        Ok(())
+    }
+
+    fn block_type(&self, bt: &BlockType) -> Result<(Vec<ValType>, Vec<ValType>)> {
+        Ok(match bt {
+            BlockType::Empty => (Vec::new(), Vec::new()),
+            BlockType::ValType(t) => (Vec::new(), [*t].to_vec()),
+            BlockType::TypeIndex(x) => {
+                let t = self.ctx.func_type_by_idx(*x)?;
+                (t.params.clone(), t.ret.clone())
+            }
+        })
+    }
+
+    /// Validates the instructions of a function body. Nested blocks are
+    /// validated with a stack of the instructions left in the blocks that
+    /// contain them rather than by recursion, so that deeply nested blocks
+    /// cannot exhaust the native stack.
+    fn instrs(&mut self, instrs: &[Instr]) -> Result<()> {
+        let mut bodies = Vec::new();
+        bodies.push(Body::new(instrs, &[]));
+        while let Some(body) = bodies.last_mut() {
+            let instr = match body.instrs.next() {
+                Some(instr) => instr,
+                None => {
+                    let els = body.els;
+                    bodies.pop();
+                    // The end of the function is validated by `validate`.
+                    if bodies.is_empty() {
+                        break;
+                    }
+                    // An `if` without `else` is validated as if its `else`
+                    // branch were empty, which requires its parameter and
+                    // result types to be equal.
+                    let frame = self.pop_ctrl()?;
+                    match frame.kind {
+                        FrameKind::If => {
+                            self.push_ctrl(FrameKind::Else, frame.start_types, frame.end_types);
+                            bodies.push(Body::new(els, &[]));
+                        }
+                        _ => self.push_vals(&frame.end_types),
+                    }
+                    continue;
+                }
+            };
+            self.visited += 1;
+            match instr {
+                Instr::Control(i) => match &**i {
+                    ControlInstruction::Block(bt, body) | ControlInstruction::Loop(bt, body) => {
+                        let kind = match **i {
+                            ControlInstruction::Loop(..) => FrameKind::Loop,
+                            _ => FrameKind::Block,
+                        };
+                        let (params, results) = self.block_type(bt)?;
+                        self.pop_vals(&params)?;
+                        self.push_ctrl(kind, params, results);
+                        bodies.push(Body::new(body, &[]));
+                    }
+                    ControlInstruction::If(bt, then, els) => {
+                        let (params, results) = self.block_type(bt)?;
+                        self.pop_expect(I32)?;
+                        self.pop_vals(&params)?;
+                        self.push_ctrl(FrameKind::If, params, results);
+                        bodies.push(Body::new(then, els));
+                    }
+                    i => self.control(i)?,
+                },
+                instr => self.instr(instr)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Validates an instruction that is not structured.
+    fn instr(&mut self, instr: &Instr) -> Result<()> {
+        match instr {
+            Instr::Control(i) => self.control(i),
+            Instr::Reference(i) => self.reference(i),
+            Instr::Parametric(i) => self.parametric(i),
+            Instr::Variable(i) => self.variable(i),
+            Instr::Table(i) => self.table(i),
+            Instr::Memory(i) => self.memory(i),
+            Instr::Numeric(i) => {
+                let (params, results) = numeric_signature(i);
+                self.op(params, results)
+            }
+            Instr::Vector(i) => self.vector(i),
+        }
+    }
+
+    fn control(&mut self, instr: &ControlInstruction) -> Result<()> {
+        match instr {
+            ControlInstruction::Unreachable => self.unreachable(),
+            ControlInstruction::Nop => {}
+            ControlInstruction::Block(..)
+            | ControlInstruction::Loop(..)
+            | ControlInstruction::If(..) => {
+                unreachable!("structured instructions are validated by instrs")
+            }
+            ControlInstruction::Br(l) => {
+                let types = self.label(*l)?;
+                self.pop_vals(&types)?;
+                self.unreachable();
+            }
+            ControlInstruction::BrIf(l) => {
+                self.pop_expect(I32)?;
+                let types = self.label(*l)?;
+                self.op(&types, &types)?;
+            }
+            ControlInstruction::BrTable(labels, default) => {
+                self.pop_expect(I32)?;
+                let default_types = self.label(*default)?;
+                for l in labels {
+                    let types = self.label(*l)?;
+                    if types.len() != default_types.len() {
+                        return Err(ValidationErrorKind::TypeMismatch);
+                    }
+                    // Operands may be of unknown type in unreachable code, so
+                    // they are checked against each target and restored.
+                    let mut popped = Vec::with_capacity(types.len());
+                    for t in types.iter().rev() {
+                        popped.push(self.pop_expect(*t)?);
+                    }
+                    self.vals.extend(popped.into_iter().rev());
+                }
+                self.pop_vals(&default_types)?;
+                self.unreachable();
+            }
+            ControlInstruction::Return => {
+                let types = self.ctrls[0].end_types.clone();
+                self.pop_vals(&types)?;
+                self.unreachable();
+            }
+            ControlInstruction::Call(x) => {
+                let t = self.ctx.func(*x)?;
+                self.op(&t.params, &t.ret)?;
+            }
+            ControlInstruction::CallIndirect(y, x) => {
+                if self.ctx.table(*x)?.ref_type != RefType::FuncRef {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+                let t = self.ctx.func_type_by_idx(*y)?;
+                self.pop_expect(I32)?;
+                self.op(&t.params, &t.ret)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn reference(&mut self, instr: &ReferenceInstruction) -> Result<()> {
+        match instr {
+            ReferenceInstruction::RefNull(t) => self.push_vals(&[ValType::RefType(*t)]),
+            ReferenceInstruction::RefIsNull => {
+                match self.pop_val()? {
+                    None | Some(ValType::RefType(_)) => {}
+                    Some(_) => return Err(ValidationErrorKind::TypeMismatch),
+                }
+                self.push_vals(&[I32]);
+            }
+            ReferenceInstruction::RefFunc(x) => {
+                self.ctx.func(*x)?;
+                if !self.ctx.refs.contains(&x.0) {
+                    return Err(ValidationErrorKind::UndeclaredFunctionReference(x.0));
+                }
+                self.push_vals(&[FUNCREF]);
+            }
+        }
+        Ok(())
+    }
+
+    fn parametric(&mut self, instr: &ParametricInstruction) -> Result<()> {
+        match instr {
+            ParametricInstruction::Drop => {
+                self.pop_val()?;
+            }
+            ParametricInstruction::Select(None) => {
+                self.pop_expect(I32)?;
+                let t1 = self.pop_val()?;
+                let t2 = self.pop_val()?;
+                let is_num_or_vec = |t: Option<ValType>| {
+                    matches!(t, None | Some(ValType::NumType(_) | ValType::VecType(_)))
+                };
+                if !is_num_or_vec(t1) || !is_num_or_vec(t2) {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+                match (t1, t2) {
+                    (Some(a), Some(b)) if a != b => return Err(ValidationErrorKind::TypeMismatch),
+                    (None, t) | (t, _) => self.push_val(t),
+                }
+            }
+            ParametricInstruction::Select(Some(types)) => {
+                if types.len() != 1 {
+                    return Err(ValidationErrorKind::InvalidResultArity);
+                }
+                self.pop_expect(I32)?;
+                self.pop_expect(types[0])?;
+                self.pop_expect(types[0])?;
+                self.push_vals(types);
+            }
+        }
+        Ok(())
+    }
+
+    fn variable(&mut self, instr: &VariableInstruction) -> Result<()> {
+        match instr {
+            VariableInstruction::LocalGet(x) => {
+                let t = self.locals.get(*x)?;
+                self.op(&[], &[t])
+            }
+            VariableInstruction::LocalSet(x) => {
+                let t = self.locals.get(*x)?;
+                self.op(&[t], &[])
+            }
+            VariableInstruction::LocalTee(x) => {
+                let t = self.locals.get(*x)?;
+                self.op(&[t], &[t])
+            }
+            VariableInstruction::GlobalGet(x) => {
+                let t = self.ctx.global(*x)?.value_type;
+                self.op(&[], &[t])
+            }
+            VariableInstruction::GlobalSet(x) => {
+                let g = self.ctx.global(*x)?;
+                if !g.mutable {
+                    return Err(ValidationErrorKind::ImmutableGlobal);
+                }
+                let t = g.value_type;
+                self.op(&[t], &[])
+            }
+        }
+    }
+
+    fn table(&mut self, instr: &TableInstruction) -> Result<()> {
+        match instr {
+            TableInstruction::TableGet(x) => {
+                let t = ValType::RefType(self.ctx.table(*x)?.ref_type);
+                self.op(&[I32], &[t])
+            }
+            TableInstruction::TableSet(x) => {
+                let t = ValType::RefType(self.ctx.table(*x)?.ref_type);
+                self.op(&[I32, t], &[])
+            }
+            TableInstruction::TableSize(x) => {
+                self.ctx.table(*x)?;
+                self.op(&[], &[I32])
+            }
+            TableInstruction::TableGrow(x) => {
+                let t = ValType::RefType(self.ctx.table(*x)?.ref_type);
+                self.op(&[t, I32], &[I32])
+            }
+            TableInstruction::TableFill(x) => {
+                let t = ValType::RefType(self.ctx.table(*x)?.ref_type);
+                self.op(&[I32, t, I32], &[])
+            }
+            TableInstruction::TableCopy(x, y) => {
+                if self.ctx.table(*x)?.ref_type != self.ctx.table(*y)?.ref_type {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+                self.op(&[I32, I32, I32], &[])
+            }
+            TableInstruction::TableInit(y, x) => {
+                if self.ctx.table(*x)?.ref_type != self.ctx.elem(*y)? {
+                    return Err(ValidationErrorKind::TypeMismatch);
+                }
+                self.op(&[I32, I32, I32], &[])
+            }
+            TableInstruction::ElemDrop(y) => {
+                self.ctx.elem(*y)?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks that memory 0 exists and that `memarg` is not aligned more than
+    /// the natural alignment of an access of `1 << max_align` bytes.
+    fn memarg(&self, memarg: &MemArg, max_align: u32) -> Result<()> {
+        self.ctx.mem(MemIdx(0))?;
+        if memarg.align > max_align {
+            return Err(ValidationErrorKind::InvalidAlignment);
+        }
+        Ok(())
+    }
+
+    fn load(&mut self, memarg: &MemArg, max_align: u32, t: ValType) -> Result<()> {
+        self.memarg(memarg, max_align)?;
+        self.op(&[I32], &[t])
+    }
+
+    fn store(&mut self, memarg: &MemArg, max_align: u32, t: ValType) -> Result<()> {
+        self.memarg(memarg, max_align)?;
+        self.op(&[I32, t], &[])
+    }
+
+    fn memory(&mut self, instr: &MemoryInstruction) -> Result<()> {
+        match instr {
+            MemoryInstruction::I32Load(m) => self.load(m, 2, I32),
+            MemoryInstruction::I64Load(m) => self.load(m, 3, I64),
+            MemoryInstruction::F32Load(m) => self.load(m, 2, F32),
+            MemoryInstruction::F64Load(m) => self.load(m, 3, F64),
+            MemoryInstruction::I32Load8S(m) | MemoryInstruction::I32Load8U(m) => {
+                self.load(m, 0, I32)
+            }
+            MemoryInstruction::I32Load16S(m) | MemoryInstruction::I32Load16U(m) => {
+                self.load(m, 1, I32)
+            }
+            MemoryInstruction::I64Load8S(m) | MemoryInstruction::I64Load8U(m) => {
+                self.load(m, 0, I64)
+            }
+            MemoryInstruction::I64Load16S(m) | MemoryInstruction::I64Load16U(m) => {
+                self.load(m, 1, I64)
+            }
+            MemoryInstruction::I64Load32S(m) | MemoryInstruction::I64Load32U(m) => {
+                self.load(m, 2, I64)
+            }
+            MemoryInstruction::I32Store(m) => self.store(m, 2, I32),
+            MemoryInstruction::I64Store(m) => self.store(m, 3, I64),
+            MemoryInstruction::F32Store(m) => self.store(m, 2, F32),
+            MemoryInstruction::F64Store(m) => self.store(m, 3, F64),
+            MemoryInstruction::I32Store8(m) => self.store(m, 0, I32),
+            MemoryInstruction::I32Store16(m) => self.store(m, 1, I32),
+            MemoryInstruction::I64Store8(m) => self.store(m, 0, I64),
+            MemoryInstruction::I64Store16(m) => self.store(m, 1, I64),
+            MemoryInstruction::I64Store32(m) => self.store(m, 2, I64),
+            MemoryInstruction::MemorySize(x) => {
+                self.ctx.mem(*x)?;
+                self.op(&[], &[I32])
+            }
+            MemoryInstruction::MemoryGrow(x) => {
+                self.ctx.mem(*x)?;
+                self.op(&[I32], &[I32])
+            }
+            MemoryInstruction::MemoryFill(x) => {
+                self.ctx.mem(*x)?;
+                self.op(&[I32, I32, I32], &[])
+            }
+            MemoryInstruction::MemoryCopy(x, y) => {
+                self.ctx.mem(*x)?;
+                self.ctx.mem(*y)?;
+                self.op(&[I32, I32, I32], &[])
+            }
+            MemoryInstruction::MemoryInit(d, x) => {
+                self.ctx.mem(*x)?;
+                self.ctx.data(*d)?;
+                self.op(&[I32, I32, I32], &[])
+            }
+            MemoryInstruction::DataDrop(d) => self.ctx.data(*d),
+        }
+    }
+
+    fn lane(lane: LaneIdx, lanes: u8) -> Result<()> {
+        if lane >= lanes {
+            return Err(ValidationErrorKind::InvalidLaneIndex);
+        }
+        Ok(())
+    }
+
+    fn load_lane(&mut self, memarg: &MemArg, lane: LaneIdx, max_align: u32) -> Result<()> {
+        self.memarg(memarg, max_align)?;
+        Self::lane(lane, 16 >> max_align)?;
+        self.op(&[I32, V128], &[V128])
+    }
+
+    fn store_lane(&mut self, memarg: &MemArg, lane: LaneIdx, max_align: u32) -> Result<()> {
+        self.memarg(memarg, max_align)?;
+        Self::lane(lane, 16 >> max_align)?;
+        self.op(&[I32, V128], &[])
+    }
+
+    fn extract_lane(&mut self, lane: LaneIdx, lanes: u8, t: ValType) -> Result<()> {
+        Self::lane(lane, lanes)?;
+        self.op(&[V128], &[t])
+    }
+
+    fn replace_lane(&mut self, lane: LaneIdx, lanes: u8, t: ValType) -> Result<()> {
+        Self::lane(lane, lanes)?;
+        self.op(&[V128, t], &[V128])
+    }
+
+    fn vector(&mut self, instr: &VectorInstruction) -> Result<()> {
+        if let Some((params, results)) = vector_signature(instr) {
+            return self.op(params, results);
+        }
+
+        match instr {
+            VectorInstruction::V128Load(m) => self.load(m, 4, V128),
+            VectorInstruction::V128Load8x8S(m)
+            | VectorInstruction::V128Load8x8U(m)
+            | VectorInstruction::V128Load16x4S(m)
+            | VectorInstruction::V128Load16x4U(m)
+            | VectorInstruction::V128Load32x2S(m)
+            | VectorInstruction::V128Load32x2U(m)
+            | VectorInstruction::V128Load64Splat(m)
+            | VectorInstruction::V128Load64Zero(m) => self.load(m, 3, V128),
+            VectorInstruction::V128Load8Splat(m) => self.load(m, 0, V128),
+            VectorInstruction::V128Load16Splat(m) => self.load(m, 1, V128),
+            VectorInstruction::V128Load32Splat(m) | VectorInstruction::V128Load32Zero(m) => {
+                self.load(m, 2, V128)
+            }
+            VectorInstruction::V128Store(m) => self.store(m, 4, V128),
+            VectorInstruction::V128Const(_) => self.op(&[], &[V128]),
+            VectorInstruction::I8x16Shuffle(lanes) => {
+                for lane in lanes {
+                    Self::lane(*lane, 32)?;
+                }
+                self.op(&[V128, V128], &[V128])
+            }
+            VectorInstruction::I8x16ExtractLaneS(l) | VectorInstruction::I8x16ExtractLaneU(l) => {
+                self.extract_lane(*l, 16, I32)
+            }
+            VectorInstruction::I16x8ExtractLaneS(l) | VectorInstruction::I16x8ExtractLaneU(l) => {
+                self.extract_lane(*l, 8, I32)
+            }
+            VectorInstruction::I32x4ExtractLane(l) => self.extract_lane(*l, 4, I32),
+            VectorInstruction::I64x2ExtractLane(l) => self.extract_lane(*l, 2, I64),
+            VectorInstruction::F32x4ExtractLane(l) => self.extract_lane(*l, 4, F32),
+            VectorInstruction::F64x2ExtractLane(l) => self.extract_lane(*l, 2, F64),
+            VectorInstruction::I8x16ReplaceLane(l) => self.replace_lane(*l, 16, I32),
+            VectorInstruction::I16x8ReplaceLane(l) => self.replace_lane(*l, 8, I32),
+            VectorInstruction::I32x4ReplaceLane(l) => self.replace_lane(*l, 4, I32),
+            VectorInstruction::I64x2ReplaceLane(l) => self.replace_lane(*l, 2, I64),
+            VectorInstruction::F32x4ReplaceLane(l) => self.replace_lane(*l, 4, F32),
+            VectorInstruction::F64x2ReplaceLane(l) => self.replace_lane(*l, 2, F64),
+            VectorInstruction::V128Load8Lane(m, l) => self.load_lane(m, *l, 0),
+            VectorInstruction::V128Load16Lane(m, l) => self.load_lane(m, *l, 1),
+            VectorInstruction::V128Load32Lane(m, l) => self.load_lane(m, *l, 2),
+            VectorInstruction::V128Load64Lane(m, l) => self.load_lane(m, *l, 3),
+            VectorInstruction::V128Store8Lane(m, l) => self.store_lane(m, *l, 0),
+            VectorInstruction::V128Store16Lane(m, l) => self.store_lane(m, *l, 1),
+            VectorInstruction::V128Store32Lane(m, l) => self.store_lane(m, *l, 2),
+            VectorInstruction::V128Store64Lane(m, l) => self.store_lane(m, *l, 3),
+            _ => unreachable!("vector instruction without immediates: {:?}", instr),
+        }
+    }
+}
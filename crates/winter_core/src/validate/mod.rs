@@ -0,0 +1,37 @@
+//! Validation of parsed modules.
+//!
+//! [Reference](https://webassembly.github.io/spec/core/valid/index.html)
+
+mod context;
+mod error;
+mod func;
+mod module;
+mod signatures;
+
+use core::ops::Deref;
+
+pub use error::*;
+pub use module::*;
+
+use crate::parse::modules::module::Module;
+
+/// A module that passed validation.
+#[derive(Debug)]
+pub struct ValidatedModule<'a> {
+    module: Module<'a>,
+}
+
+impl<'a> ValidatedModule<'a> {
+    /// Returns the validated module.
+    pub fn into_inner(self) -> Module<'a> {
+        self.module
+    }
+}
+
+impl<'a> Deref for ValidatedModule<'a> {
+    type Target = Module<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.module
+    }
+}
@@ -0,0 +1,400 @@
+use std::{collections::BTreeSet, vec::Vec};
+
+use super::{
+    context::Context,
+    func::FuncValidator,
+    signatures::{F32, F64, FUNCREF, I32, I64, V128},
+    Location, ValidatedModule, ValidationError, ValidationErrorKind,
+};
+use crate::parse::{
+    instructions::{
+        Expr, Instr, NumericInstruction, ReferenceInstruction, VariableInstruction,
+        VectorInstruction,
+    },
+    modules::{
+        data_section::DataMode,
+        element_section::{ElemInit, ElemMode},
+        export_section::ExportDesc,
+        module::Module,
+        DataIdx, ElemIdx, FuncIdx, GlobalIdx,
+    },
+    types::{Limits, ValType},
+};
+
+/// Maximum number of pages of a memory.
+const MAX_PAGES: u32 = 65536;
+
+fn module_error(kind: ValidationErrorKind) -> ValidationError {
+    ValidationError {
+        kind,
+        location: Location::Module,
+    }
+}
+
+/// Validates `module` against the rules of the specification and returns it
+/// wrapped in a [`ValidatedModule`].
+///
+/// [Reference](https://webassembly.github.io/spec/core/valid/modules.html)
+pub fn validate(module: Module) -> Result<ValidatedModule, ValidationError> {
+    let ctx = Context::new(&module).map_err(module_error)?;
+
+    for table in &ctx.tables {
+        limits(&table.limits, u32::MAX).map_err(module_error)?;
+    }
+    for mem in &ctx.mems {
+        limits(mem, MAX_PAGES).map_err(module_error)?;
+    }
+    if ctx.mems.len() > 1 {
+        return Err(module_error(ValidationErrorKind::MultipleMemories));
+    }
+
+    let funcs = module.type_idx.as_ref().map_or(0, |f| f.functions.len());
+    let codes = module.code.as_ref().map_or(0, |c| c.code.len());
+    if funcs != codes {
+        return Err(module_error(ValidationErrorKind::FuncCodeMismatch));
+    }
+
+    let datas = module.data.as_ref().map_or(0, |d| d.data.len());
+    if matches!(ctx.datas, Some(count) if count as usize != datas) {
+        return Err(module_error(ValidationErrorKind::DataCountMismatch));
+    }
+
+    if let Some(globals) = &module.global {
+        for (i, global) in globals.globals.iter().enumerate() {
+            // Initializers may only refer to imported globals.
+            const_expr(
+                &ctx,
+                &global.expr,
+                global.global_type.value_type,
+                ctx.imported_globals,
+            )
+            .map_err(|kind| ValidationError {
+                kind,
+                location: Location::Global(GlobalIdx((ctx.imported_globals + i) as u32)),
+            })?;
+        }
+    }
+
+    if let Some(elems) = &module.elem {
+        for (i, elem) in elems.elems.iter().enumerate() {
+            let ref_type = elem.ref_type();
+            let check = || {
+                if let ElemMode::Active { table, offset } = &elem.mode {
+                    if ctx.table(*table)?.ref_type != ref_type {
+                        return Err(ValidationErrorKind::TypeMismatch);
+                    }
+                    const_expr(&ctx, offset, I32, ctx.globals.len())?;
+                }
+                match &elem.init {
+                    ElemInit::FuncIdx(funcs) => {
+                        for f in funcs {
+                            ctx.func(*f)?;
+                        }
+                    }
+                    ElemInit::Expr(exprs) => {
+                        for e in exprs {
+                            const_expr(&ctx, e, ValType::RefType(ref_type), ctx.globals.len())?;
+                        }
+                    }
+                }
+                Ok(())
+            };
+            check().map_err(|kind| ValidationError {
+                kind,
+                location: Location::Elem(ElemIdx(i as u32)),
+            })?;
+        }
+    }
+
+    if let Some(data) = &module.data {
+        for (i, data) in data.data.iter().enumerate() {
+            if let DataMode::Active { memory, offset } = &data.mode {
+                ctx.mem(*memory)
+                    .and_then(|_| const_expr(&ctx, offset, I32, ctx.globals.len()))
+                    .map_err(|kind| ValidationError {
+                        kind,
+                        location: Location::Data(DataIdx(i as u32)),
+                    })?;
+            }
+        }
+    }
+
+    if let Some(start) = &module.start {
+        let t = ctx.func(start.start).map_err(module_error)?;
+        if !t.params.is_empty() || !t.ret.is_empty() {
+            return Err(module_error(ValidationErrorKind::InvalidStartFunction));
+        }
+    }
+
+    if let Some(exports) = &module.export {
+        let mut names = BTreeSet::new();
+        for export in &exports.exports {
+            if !names.insert(export.name) {
+                return Err(module_error(ValidationErrorKind::DuplicateExportName));
+            }
+            match export.desc {
+                ExportDesc::Func(x) => ctx.func(x).map(|_| ()),
+                ExportDesc::Table(x) => ctx.table(x).map(|_| ()),
+                ExportDesc::Mem(x) => ctx.mem(x).map(|_| ()),
+                ExportDesc::Global(x) => ctx.global(x).map(|_| ()),
+            }
+            .map_err(module_error)?;
+        }
+    }
+
+    if let Some(section) = &module.code {
+        let base = section.span.start;
+        for (i, code) in section.code.iter().enumerate() {
+            let func = FuncIdx((ctx.imported_funcs + i) as u32);
+            let func_type = ctx.func(func).map_err(module_error)?;
+            let body = code.func().map_err(|e| ValidationError {
+                location: Location::Func {
+                    func,
+                    offset: e.offset - base,
+                },
+                kind: ValidationErrorKind::Malformed(e),
+            })?;
+            let mut validator = FuncValidator::new(&ctx);
+            validator
+                .validate(func_type, body)
+                .map_err(|kind| ValidationError {
+                    kind,
+                    location: Location::Func {
+                        func,
+                        offset: body
+                            .expr
+                            .spans
+                            .get(validator.position())
+                            .map_or(0, |span| span.start - base),
+                    },
+                })?;
+        }
+    }
+
+    Ok(ValidatedModule { module })
+}
+
+/// Checks that `limits` are within `range` and that the minimum is not greater
+/// than the maximum.
+fn limits(limits: &Limits, range: u32) -> Result<(), ValidationErrorKind> {
+    let too_large = if range == MAX_PAGES {
+        ValidationErrorKind::MemorySizeTooLarge
+    } else {
+        ValidationErrorKind::InvalidLimits
+    };
+    if limits.min > range {
+        return Err(too_large);
+    }
+    if let Some(max) = limits.max {
+        if max > range {
+            return Err(too_large);
+        }
+        if limits.min > max {
+            return Err(ValidationErrorKind::InvalidLimits);
+        }
+    }
+    Ok(())
+}
+
+/// Validates a constant expression producing a value of type `expected`.
+/// Only the first `globals` globals may be referenced by `global.get`.
+///
+/// [Reference](https://webassembly.github.io/spec/core/valid/instructions.html#constant-expressions)
+fn const_expr(
+    ctx: &Context,
+    expr: &Expr,
+    expected: ValType,
+    globals: usize,
+) -> Result<(), ValidationErrorKind> {
+    let mut stack = Vec::new();
+    for instr in &expr.instr {
+        let t = match instr {
+            Instr::Numeric(n) => match **n {
+                NumericInstruction::I32Const(_) => I32,
+                NumericInstruction::I64Const(_) => I64,
+                NumericInstruction::F32Const(_) => F32,
+                NumericInstruction::F64Const(_) => F64,
+                _ => return Err(ValidationErrorKind::ConstantExpressionRequired),
+            },
+            Instr::Vector(v) if matches!(**v, VectorInstruction::V128Const(_)) => V128,
+            Instr::Reference(r) => match **r {
+                ReferenceInstruction::RefNull(t) => ValType::RefType(t),
+                ReferenceInstruction::RefFunc(x) => {
+                    ctx.func(x)?;
+                    FUNCREF
+                }
+                ReferenceInstruction::RefIsNull => {
+                    return Err(ValidationErrorKind::ConstantExpressionRequired)
+                }
+            },
+            Instr::Variable(v) => match **v {
+                VariableInstruction::GlobalGet(x) => {
+                    if x.0 as usize >= globals {
+                        return Err(ValidationErrorKind::UnknownGlobal(x.0));
+                    }
+                    let g = ctx.global(x)?;
+                    if g.mutable {
+                        return Err(ValidationErrorKind::ConstantExpressionRequired);
+                    }
+                    g.value_type
+                }
+                _ => return Err(ValidationErrorKind::ConstantExpressionRequired),
+            },
+            _ => return Err(ValidationErrorKind::ConstantExpressionRequired),
+        };
+        stack.push(t);
+    }
+
+    if stack.len() != 1 || stack[0] != expected {
+        return Err(ValidationErrorKind::TypeMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse::modules::module::module_parser, text};
+
+    /// Encodes a section with the given id and entries.
+    fn section(id: u8, entries: &[&[u8]]) -> Vec<u8> {
+        let mut content = vec![entries.len() as u8];
+        for e in entries {
+            content.extend_from_slice(e);
+        }
+        let mut s = vec![id, content.len() as u8];
+        s.extend(content);
+        s
+    }
+
+    /// Builds a module with one function of type `[i32 i32] -> [i32]`,
+    /// exported under each of `exports`, with the given body.
+    fn module(body: &[u8], exports: &[&str], start: bool) -> Vec<u8> {
+        let mut m = vec![0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00];
+        m.extend(section(1, &[&[0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F]]));
+        m.extend(section(3, &[&[0x00]]));
+        let exports: Vec<Vec<u8>> = exports
+            .iter()
+            .map(|name| {
+                let mut e = vec![name.len() as u8];
+                e.extend(name.as_bytes());
+                e.extend([0x00, 0x00]);
+                e
+            })
+            .collect();
+        let exports: Vec<&[u8]> = exports.iter().map(|e| &e[..]).collect();
+        m.extend(section(7, &exports));
+        if start {
+            m.extend([0x08, 0x01, 0x00]);
+        }
+        let mut code = vec![(body.len() + 1) as u8, 0x00];
+        code.extend(body);
+        m.extend(section(10, &[&code]));
+        m
+    }
+
+    fn check(bytes: &[u8]) -> Result<(), ValidationError> {
+        validate(module_parser(bytes).unwrap()).map(|_| ())
+    }
+
+    #[test]
+    fn test_valid_function() {
+        // local.get 0, local.get 1, i32.add, end
+        assert_eq!(
+            check(&module(
+                &[0x20, 0x00, 0x20, 0x01, 0x6A, 0x0B],
+                &["add"],
+                false
+            )),
+            Ok(())
+        );
+
+        // block (result i32) local.get 0 br 0 end, end
+        assert_eq!(
+            check(&module(
+                &[0x02, 0x7F, 0x20, 0x00, 0x0C, 0x00, 0x0B, 0x0B],
+                &[],
+                false
+            )),
+            Ok(())
+        );
+
+        // unreachable, i32.add, end
+        assert_eq!(check(&module(&[0x00, 0x6A, 0x0B], &[], false)), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_function() {
+        // local.get 0, i64.const 1, i32.add, end, with i32.add at the offset 9
+        // of the code section
+        assert_eq!(
+            check(&module(&[0x20, 0x00, 0x42, 0x01, 0x6A, 0x0B], &[], false)),
+            Err(ValidationError {
+                kind: ValidationErrorKind::TypeMismatch,
+                location: Location::Func {
+                    func: FuncIdx(0),
+                    offset: 9
+                },
+            })
+        );
+
+        // local.get 2, end
+        assert_eq!(
+            check(&module(&[0x20, 0x02, 0x0B], &[], false))
+                .unwrap_err()
+                .kind,
+            ValidationErrorKind::UnknownLocal(2)
+        );
+
+        // call 1, end
+        assert_eq!(
+            check(&module(&[0x10, 0x01, 0x0B], &[], false))
+                .unwrap_err()
+                .kind,
+            ValidationErrorKind::UnknownFunction(1)
+        );
+
+        // local.get 0, local.get 0, local.get 0, end: too many results
+        assert_eq!(
+            check(&module(
+                &[0x20, 0x00, 0x20, 0x00, 0x6A, 0x20, 0x00, 0x0B],
+                &[],
+                false
+            ))
+            .unwrap_err()
+            .kind,
+            ValidationErrorKind::TypeMismatch
+        );
+    }
+
+    #[test]
+    fn test_invalid_module() {
+        let body = [0x20, 0x00, 0x0B];
+        assert_eq!(
+            check(&module(&body, &["f", "f"], false)),
+            Err(module_error(ValidationErrorKind::DuplicateExportName))
+        );
+        assert_eq!(
+            check(&module(&body, &[], true)),
+            Err(module_error(ValidationErrorKind::InvalidStartFunction))
+        );
+    }
+
+    #[test]
+    fn test_deep_nesting() {
+        let check = |innermost: &str| {
+            let source = format!(
+                "(func {} {} {})",
+                "block ".repeat(1024),
+                innermost,
+                "end ".repeat(1024)
+            );
+            let buffer = text::ParseBuffer::new(&source).unwrap();
+            validate(text::module_parser(&buffer).unwrap())
+                .map(drop)
+                .map_err(|e| e.kind)
+        };
+        assert_eq!(check("nop"), Ok(()));
+        assert_eq!(check("i32.const 0"), Err(ValidationErrorKind::TypeMismatch));
+    }
+}
@@ -0,0 +1,365 @@
+//! Operand and result types of the instructions whose typing only depends
+//! on their opcode.
+
+use crate::parse::{
+    instructions::{NumericInstruction, VectorInstruction},
+    types::{NumType, RefType, ValType, VecType},
+};
+
+pub(crate) const I32: ValType = ValType::NumType(NumType::I32);
+pub(crate) const I64: ValType = ValType::NumType(NumType::I64);
+pub(crate) const F32: ValType = ValType::NumType(NumType::F32);
+pub(crate) const F64: ValType = ValType::NumType(NumType::F64);
+pub(crate) const V128: ValType = ValType::VecType(VecType::V128);
+pub(crate) const FUNCREF: ValType = ValType::RefType(RefType::FuncRef);
+
+type Signature = (&'static [ValType], &'static [ValType]);
+
+/// Returns the operand and result types of a numeric instruction.
+pub(crate) fn numeric_signature(instr: &NumericInstruction) -> Signature {
+    match instr {
+        NumericInstruction::I32Const(_) => (&[], &[I32]),
+        NumericInstruction::I64Const(_) => (&[], &[I64]),
+        NumericInstruction::F32Const(_) => (&[], &[F32]),
+        NumericInstruction::F64Const(_) => (&[], &[F64]),
+        NumericInstruction::I32Eqz
+        | NumericInstruction::I32Clz
+        | NumericInstruction::I32Ctz
+        | NumericInstruction::I32Popcnt
+        | NumericInstruction::I32Extend8S
+        | NumericInstruction::I32Extend16S => (&[I32], &[I32]),
+        NumericInstruction::I32Eq
+        | NumericInstruction::I32Ne
+        | NumericInstruction::I32LtS
+        | NumericInstruction::I32LtU
+        | NumericInstruction::I32GtS
+        | NumericInstruction::I32GtU
+        | NumericInstruction::I32LeS
+        | NumericInstruction::I32LeU
+        | NumericInstruction::I32GeS
+        | NumericInstruction::I32GeU
+        | NumericInstruction::I32Add
+        | NumericInstruction::I32Sub
+        | NumericInstruction::I32Mul
+        | NumericInstruction::I32DivS
+        | NumericInstruction::I32DivU
+        | NumericInstruction::I32RemS
+        | NumericInstruction::I32RemU
+        | NumericInstruction::I32And
+        | NumericInstruction::I32Or
+        | NumericInstruction::I32Xor
+        | NumericInstruction::I32Shl
+        | NumericInstruction::I32ShrS
+        | NumericInstruction::I32ShrU
+        | NumericInstruction::I32Rotl
+        | NumericInstruction::I32Rotr => (&[I32, I32], &[I32]),
+        NumericInstruction::I64Eqz | NumericInstruction::I32WrapI64 => (&[I64], &[I32]),
+        NumericInstruction::I64Eq
+        | NumericInstruction::I64Ne
+        | NumericInstruction::I64LtS
+        | NumericInstruction::I64LtU
+        | NumericInstruction::I64GtS
+        | NumericInstruction::I64GtU
+        | NumericInstruction::I64LeS
+        | NumericInstruction::I64LeU
+        | NumericInstruction::I64GeS
+        | NumericInstruction::I64GeU => (&[I64, I64], &[I32]),
+        NumericInstruction::F32Eq
+        | NumericInstruction::F32Ne
+        | NumericInstruction::F32Lt
+        | NumericInstruction::F32Gt
+        | NumericInstruction::F32Le
+        | NumericInstruction::F32Ge => (&[F32, F32], &[I32]),
+        NumericInstruction::F64Eq
+        | NumericInstruction::F64Ne
+        | NumericInstruction::F64Lt
+        | NumericInstruction::F64Gt
+        | NumericInstruction::F64Le
+        | NumericInstruction::F64Ge => (&[F64, F64], &[I32]),
+        NumericInstruction::I64Clz
+        | NumericInstruction::I64Ctz
+        | NumericInstruction::I64Popcnt
+        | NumericInstruction::I64Extend8S
+        | NumericInstruction::I64Extend16S
+        | NumericInstruction::I64Extend32S => (&[I64], &[I64]),
+        NumericInstruction::I64Add
+        | NumericInstruction::I64Sub
+        | NumericInstruction::I64Mul
+        | NumericInstruction::I64DivS
+        | NumericInstruction::I64DivU
+        | NumericInstruction::I64RemS
+        | NumericInstruction::I64RemU
+        | NumericInstruction::I64And
+        | NumericInstruction::I64Or
+        | NumericInstruction::I64Xor
+        | NumericInstruction::I64Shl
+        | NumericInstruction::I64ShrS
+        | NumericInstruction::I64ShrU
+        | NumericInstruction::I64Rotl
+        | NumericInstruction::I64Rotr => (&[I64, I64], &[I64]),
+        NumericInstruction::F32Abs
+        | NumericInstruction::F32Neg
+        | NumericInstruction::F32Ceil
+        | NumericInstruction::F32Floor
+        | NumericInstruction::F32Trunc
+        | NumericInstruction::F32Nearest
+        | NumericInstruction::F32Sqrt => (&[F32], &[F32]),
+        NumericInstruction::F32Add
+        | NumericInstruction::F32Sub
+        | NumericInstruction::F32Mul
+        | NumericInstruction::F32Div
+        | NumericInstruction::F32Min
+        | NumericInstruction::F32Max
+        | NumericInstruction::F32Copysign => (&[F32, F32], &[F32]),
+        NumericInstruction::F64Abs
+        | NumericInstruction::F64Neg
+        | NumericInstruction::F64Ceil
+        | NumericInstruction::F64Floor
+        | NumericInstruction::F64Trunc
+        | NumericInstruction::F64Nearest
+        | NumericInstruction::F64Sqrt => (&[F64], &[F64]),
+        NumericInstruction::F64Add
+        | NumericInstruction::F64Sub
+        | NumericInstruction::F64Mul
+        | NumericInstruction::F64Div
+        | NumericInstruction::F64Min
+        | NumericInstruction::F64Max
+        | NumericInstruction::F64Copysign => (&[F64, F64], &[F64]),
+        NumericInstruction::I32TruncF32S
+        | NumericInstruction::I32TruncF32U
+        | NumericInstruction::I32ReinterpretF32
+        | NumericInstruction::I32TruncSatF32S
+        | NumericInstruction::I32TruncSatF32U => (&[F32], &[I32]),
+        NumericInstruction::I32TruncF64S
+        | NumericInstruction::I32TruncF64U
+        | NumericInstruction::I32TruncSatF64S
+        | NumericInstruction::I32TruncSatF64U => (&[F64], &[I32]),
+        NumericInstruction::I64ExtendI32S | NumericInstruction::I64ExtendI32U => (&[I32], &[I64]),
+        NumericInstruction::I64TruncF32S
+        | NumericInstruction::I64TruncF32U
+        | NumericInstruction::I64TruncSatF32S
+        | NumericInstruction::I64TruncSatF32U => (&[F32], &[I64]),
+        NumericInstruction::I64TruncF64S
+        | NumericInstruction::I64TruncF64U
+        | NumericInstruction::I64ReinterpretF64
+        | NumericInstruction::I64TruncSatF64S
+        | NumericInstruction::I64TruncSatF64U => (&[F64], &[I64]),
+        NumericInstruction::F32ConvertI32S
+        | NumericInstruction::F32ConvertI32U
+        | NumericInstruction::F32ReinterpretI32 => (&[I32], &[F32]),
+        NumericInstruction::F32ConvertI64S | NumericInstruction::F32ConvertI64U => (&[I64], &[F32]),
+        NumericInstruction::F32DemoteF64 => (&[F64], &[F32]),
+        NumericInstruction::F64ConvertI32S | NumericInstruction::F64ConvertI32U => (&[I32], &[F64]),
+        NumericInstruction::F64ConvertI64S
+        | NumericInstruction::F64ConvertI64U
+        | NumericInstruction::F64ReinterpretI64 => (&[I64], &[F64]),
+        NumericInstruction::F64PromoteF32 => (&[F32], &[F64]),
+    }
+}
+
+/// Returns the operand and result types of a vector instruction that takes
+/// no immediates, or `None` if the typing of the instruction depends on its
+/// immediates.
+pub(crate) fn vector_signature(instr: &VectorInstruction) -> Option<Signature> {
+    Some(match instr {
+        VectorInstruction::I8x16Swizzle
+        | VectorInstruction::I8x16Eq
+        | VectorInstruction::I8x16Ne
+        | VectorInstruction::I8x16LtS
+        | VectorInstruction::I8x16LtU
+        | VectorInstruction::I8x16GtS
+        | VectorInstruction::I8x16GtU
+        | VectorInstruction::I8x16LeS
+        | VectorInstruction::I8x16LeU
+        | VectorInstruction::I8x16GeS
+        | VectorInstruction::I8x16GeU
+        | VectorInstruction::I16x8Eq
+        | VectorInstruction::I16x8Ne
+        | VectorInstruction::I16x8LtS
+        | VectorInstruction::I16x8LtU
+        | VectorInstruction::I16x8GtS
+        | VectorInstruction::I16x8GtU
+        | VectorInstruction::I16x8LeS
+        | VectorInstruction::I16x8LeU
+        | VectorInstruction::I16x8GeS
+        | VectorInstruction::I16x8GeU
+        | VectorInstruction::I32x4Eq
+        | VectorInstruction::I32x4Ne
+        | VectorInstruction::I32x4LtS
+        | VectorInstruction::I32x4LtU
+        | VectorInstruction::I32x4GtS
+        | VectorInstruction::I32x4GtU
+        | VectorInstruction::I32x4LeS
+        | VectorInstruction::I32x4LeU
+        | VectorInstruction::I32x4GeS
+        | VectorInstruction::I32x4GeU
+        | VectorInstruction::F32x4Eq
+        | VectorInstruction::F32x4Ne
+        | VectorInstruction::F32x4Lt
+        | VectorInstruction::F32x4Gt
+        | VectorInstruction::F32x4Le
+        | VectorInstruction::F32x4Ge
+        | VectorInstruction::F64x2Eq
+        | VectorInstruction::F64x2Ne
+        | VectorInstruction::F64x2Lt
+        | VectorInstruction::F64x2Gt
+        | VectorInstruction::F64x2Le
+        | VectorInstruction::F64x2Ge
+        | VectorInstruction::V128And
+        | VectorInstruction::V128Andnot
+        | VectorInstruction::V128Or
+        | VectorInstruction::V128Xor
+        | VectorInstruction::I8x16NarrowI16x8S
+        | VectorInstruction::I8x16NarrowI16x8U
+        | VectorInstruction::I8x16Add
+        | VectorInstruction::I8x16AddSatS
+        | VectorInstruction::I8x16AddSatU
+        | VectorInstruction::I8x16Sub
+        | VectorInstruction::I8x16SubSatS
+        | VectorInstruction::I8x16SubSatU
+        | VectorInstruction::I8x16MinS
+        | VectorInstruction::I8x16MinU
+        | VectorInstruction::I8x16MaxS
+        | VectorInstruction::I8x16MaxU
+        | VectorInstruction::I8x16AvgrU
+        | VectorInstruction::I16x8Q15mulrSatS
+        | VectorInstruction::I16x8NarrowI32x4S
+        | VectorInstruction::I16x8NarrowI32x4U
+        | VectorInstruction::I16x8Add
+        | VectorInstruction::I16x8AddSatS
+        | VectorInstruction::I16x8AddSatU
+        | VectorInstruction::I16x8Sub
+        | VectorInstruction::I16x8SubSatS
+        | VectorInstruction::I16x8SubSatU
+        | VectorInstruction::I16x8Mul
+        | VectorInstruction::I16x8MinS
+        | VectorInstruction::I16x8MinU
+        | VectorInstruction::I16x8MaxS
+        | VectorInstruction::I16x8MaxU
+        | VectorInstruction::I16x8AvgrU
+        | VectorInstruction::I16x8ExtmulLowI8x16S
+        | VectorInstruction::I16x8ExtmulHighI8x16S
+        | VectorInstruction::I16x8ExtmulLowI8x16U
+        | VectorInstruction::I16x8ExtmulHighI8x16U
+        | VectorInstruction::I32x4Add
+        | VectorInstruction::I32x4Sub
+        | VectorInstruction::I32x4Mul
+        | VectorInstruction::I32x4MinS
+        | VectorInstruction::I32x4MinU
+        | VectorInstruction::I32x4MaxS
+        | VectorInstruction::I32x4MaxU
+        | VectorInstruction::I32x4DotI16x8S
+        | VectorInstruction::I32x4ExtmulLowI16x8S
+        | VectorInstruction::I32x4ExtmulHighI16x8S
+        | VectorInstruction::I32x4ExtmulLowI16x8U
+        | VectorInstruction::I32x4ExtmulHighI16x8U
+        | VectorInstruction::I64x2Add
+        | VectorInstruction::I64x2Sub
+        | VectorInstruction::I64x2Mul
+        | VectorInstruction::I64x2Eq
+        | VectorInstruction::I64x2Ne
+        | VectorInstruction::I64x2LtS
+        | VectorInstruction::I64x2GtS
+        | VectorInstruction::I64x2LeS
+        | VectorInstruction::I64x2GeS
+        | VectorInstruction::I64x2ExtmulLowI32x4S
+        | VectorInstruction::I64x2ExtmulHighI32x4S
+        | VectorInstruction::I64x2ExtmulLowI32x4U
+        | VectorInstruction::I64x2ExtmulHighI32x4U
+        | VectorInstruction::F32x4Add
+        | VectorInstruction::F32x4Sub
+        | VectorInstruction::F32x4Mul
+        | VectorInstruction::F32x4Div
+        | VectorInstruction::F32x4Min
+        | VectorInstruction::F32x4Max
+        | VectorInstruction::F32x4Pmin
+        | VectorInstruction::F32x4Pmax
+        | VectorInstruction::F64x2Add
+        | VectorInstruction::F64x2Sub
+        | VectorInstruction::F64x2Mul
+        | VectorInstruction::F64x2Div
+        | VectorInstruction::F64x2Min
+        | VectorInstruction::F64x2Max
+        | VectorInstruction::F64x2Pmin
+        | VectorInstruction::F64x2Pmax => (&[V128, V128], &[V128]),
+        VectorInstruction::I8x16Splat
+        | VectorInstruction::I16x8Splat
+        | VectorInstruction::I32x4Splat => (&[I32], &[V128]),
+        VectorInstruction::I64x2Splat => (&[I64], &[V128]),
+        VectorInstruction::F32x4Splat => (&[F32], &[V128]),
+        VectorInstruction::F64x2Splat => (&[F64], &[V128]),
+        VectorInstruction::V128Not
+        | VectorInstruction::F32x4DemoteF64x2Zero
+        | VectorInstruction::F64x2PromoteLowF32x4
+        | VectorInstruction::I8x16Abs
+        | VectorInstruction::I8x16Neg
+        | VectorInstruction::I8x16Popcnt
+        | VectorInstruction::F32x4Ceil
+        | VectorInstruction::F32x4Floor
+        | VectorInstruction::F32x4Trunc
+        | VectorInstruction::F32x4Nearest
+        | VectorInstruction::F64x2Ceil
+        | VectorInstruction::F64x2Floor
+        | VectorInstruction::F64x2Trunc
+        | VectorInstruction::I16x8ExtaddPairwiseI8x16S
+        | VectorInstruction::I16x8ExtaddPairwiseI8x16U
+        | VectorInstruction::I32x4ExtaddPairwiseI16x8S
+        | VectorInstruction::I32x4ExtaddPairwiseI16x8U
+        | VectorInstruction::I16x8Abs
+        | VectorInstruction::I16x8Neg
+        | VectorInstruction::I16x8ExtendLowI8x16S
+        | VectorInstruction::I16x8ExtendHighI8x16S
+        | VectorInstruction::I16x8ExtendLowI8x16U
+        | VectorInstruction::I16x8ExtendHighI8x16U
+        | VectorInstruction::F64x2Nearest
+        | VectorInstruction::I32x4Abs
+        | VectorInstruction::I32x4Neg
+        | VectorInstruction::I32x4ExtendLowI16x8S
+        | VectorInstruction::I32x4ExtendHighI16x8S
+        | VectorInstruction::I32x4ExtendLowI16x8U
+        | VectorInstruction::I32x4ExtendHighI16x8U
+        | VectorInstruction::I64x2Abs
+        | VectorInstruction::I64x2Neg
+        | VectorInstruction::I64x2ExtendLowI32x4S
+        | VectorInstruction::I64x2ExtendHighI32x4S
+        | VectorInstruction::I64x2ExtendLowI32x4U
+        | VectorInstruction::I64x2ExtendHighI32x4U
+        | VectorInstruction::F32x4Abs
+        | VectorInstruction::F32x4Neg
+        | VectorInstruction::F32x4Sqrt
+        | VectorInstruction::F64x2Abs
+        | VectorInstruction::F64x2Neg
+        | VectorInstruction::F64x2Sqrt
+        | VectorInstruction::I32x4TruncSatF32x4S
+        | VectorInstruction::I32x4TruncSatF32x4U
+        | VectorInstruction::F32x4ConvertI32x4S
+        | VectorInstruction::F32x4ConvertI32x4U
+        | VectorInstruction::I32x4TruncSatF64x2SZero
+        | VectorInstruction::I32x4TruncSatF64x2UZero
+        | VectorInstruction::F64x2ConvertLowI32x4S
+        | VectorInstruction::F64x2ConvertLowI32x4U => (&[V128], &[V128]),
+        VectorInstruction::V128Bitselect => (&[V128, V128, V128], &[V128]),
+        VectorInstruction::V128AnyTrue
+        | VectorInstruction::I8x16AllTrue
+        | VectorInstruction::I8x16Bitmask
+        | VectorInstruction::I16x8AllTrue
+        | VectorInstruction::I16x8Bitmask
+        | VectorInstruction::I32x4AllTrue
+        | VectorInstruction::I32x4Bitmask
+        | VectorInstruction::I64x2AllTrue
+        | VectorInstruction::I64x2Bitmask => (&[V128], &[I32]),
+        VectorInstruction::I8x16Shl
+        | VectorInstruction::I8x16ShrS
+        | VectorInstruction::I8x16ShrU
+        | VectorInstruction::I16x8Shl
+        | VectorInstruction::I16x8ShrS
+        | VectorInstruction::I16x8ShrU
+        | VectorInstruction::I32x4Shl
+        | VectorInstruction::I32x4ShrS
+        | VectorInstruction::I32x4ShrU
+        | VectorInstruction::I64x2Shl
+        | VectorInstruction::I64x2ShrS
+        | VectorInstruction::I64x2ShrU => (&[V128, I32], &[V128]),
+        _ => return None,
+    })
+}
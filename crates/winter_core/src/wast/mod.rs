@@ -0,0 +1,318 @@
+//! Interpreter for `.wast` scripts, the format of the WebAssembly
+//! specification test suite.
+//!
+//! A script is a sequence of directives that define modules, invoke their
+//! exports and assert the results of invocations, the traps they raise and
+//! the rejection of malformed, invalid or unlinkable modules. [`run`]
+//! executes every directive and reports its outcome, so that a failing
+//! assertion does not stop the script.
+//!
+//! [Reference](https://github.com/WebAssembly/spec/tree/main/interpreter#scripts)
+
+mod script;
+mod spectest;
+
+use std::{
+    collections::BTreeMap,
+    prelude::v1::format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use script::{Action, Directive, Expected, ScriptModule};
+
+use crate::{
+    exec::{Error, ExternVal, Linker, ModuleAddr, Store, Value},
+    parse::{
+        modules::module::{module_parser, Module},
+        types::ValType,
+    },
+    text::{self, module_fields_parser, ParseBuffer, TextError},
+    validate::validate,
+};
+
+/// The outcome of a directive of a script.
+#[derive(Debug)]
+pub struct Outcome {
+    /// One-based line of the directive in the script.
+    pub line: usize,
+    /// Keyword of the directive, such as `assert_return`.
+    pub directive: &'static str,
+    /// Why the directive failed, if it did.
+    pub result: Result<(), String>,
+}
+
+/// Runs the script `source` and returns the outcome of each of its
+/// directives. Returns an error if the script cannot be parsed; modules
+/// defined by the script are only parsed when their directive runs.
+pub fn run(source: &str) -> Result<Vec<Outcome>, TextError> {
+    let buffer = ParseBuffer::new(source)?;
+    let directives = match script::is_inline_module(&buffer.parser()) {
+        // The whole script is parsed as a module, like a quoted one.
+        true => [(0, script::inline_module(source))].into_iter().collect(),
+        false => script::directives(&mut buffer.parser())?,
+    };
+    let mut runner = Runner::new();
+    Ok(directives
+        .into_iter()
+        .map(|(offset, directive)| Outcome {
+            line: source[..offset].matches('\n').count() + 1,
+            directive: directive.keyword(),
+            result: runner.run(directive),
+        })
+        .collect())
+}
+
+/// The state of a running script.
+struct Runner {
+    store: Store,
+    linker: Linker,
+    /// The instances of the modules defined with a name.
+    modules: BTreeMap<String, ModuleAddr>,
+    /// The instance of the last defined module.
+    current: Option<ModuleAddr>,
+}
+
+impl Runner {
+    fn new() -> Self {
+        let mut store = Store::new();
+        let mut linker = Linker::new();
+        spectest::define(&mut store, &mut linker);
+        Runner {
+            store,
+            linker,
+            modules: BTreeMap::new(),
+            current: None,
+        }
+    }
+
+    fn run(&mut self, directive: Directive) -> Result<(), String> {
+        match directive {
+            Directive::Module(def) => {
+                let addr = self
+                    .instantiate(def.module)?
+                    .map_err(|e| format!("instantiation failed: {}", e))?;
+                if let Some(name) = def.name {
+                    self.modules.insert(name.to_string(), addr);
+                }
+                self.current = Some(addr);
+                Ok(())
+            }
+            Directive::Register { name, module } => {
+                let addr = self.module(module)?;
+                self.linker.instance(&self.store, name, addr);
+                Ok(())
+            }
+            Directive::Action(action) => self
+                .act(&action)?
+                .map(drop)
+                .map_err(|e| format!("unexpected error: {}", e)),
+            Directive::AssertReturn(action, expected) => {
+                let results = self
+                    .act(&action)?
+                    .map_err(|e| format!("unexpected error: {}", e))?;
+                let matching = results.len() == expected.len()
+                    && expected.iter().zip(&results).all(|(e, v)| matches(e, v));
+                match matching {
+                    true => Ok(()),
+                    false => Err(format!("expected {:?}, got {:?}", expected, results)),
+                }
+            }
+            Directive::AssertTrap(action, message)
+            | Directive::AssertExhaustion(action, message) => {
+                let result = self.act(&action)?;
+                trapped(result.map(drop), message)
+            }
+            Directive::AssertInstantiationTrap(module, message) => {
+                let result = self.instantiate(module)?;
+                trapped(result.map(drop), message)
+            }
+            Directive::AssertMalformed(module, message) => {
+                match self.with_module(module, |_, _| ()) {
+                    Ok(()) => Err(format!("module is well-formed, expected \"{}\"", message)),
+                    Err(e) => expected_message(&e, message),
+                }
+            }
+            Directive::AssertInvalid(module, message) => {
+                match self
+                    .with_module(module, |_, module| validate(module).map(drop))
+                    .map_err(malformed)?
+                {
+                    Ok(()) => Err(format!("module is valid, expected \"{}\"", message)),
+                    Err(e) => expected_message(&e.to_string(), message),
+                }
+            }
+            Directive::AssertUnlinkable(module, message) => match self.instantiate(module)? {
+                Ok(_) => Err(format!("module is linked, expected \"{}\"", message)),
+                Err(Error::Trap { trap, .. }) => Err(format!("instantiation trapped: {}", trap)),
+                Err(e) => expected_message(&e.to_string(), message),
+            },
+        }
+    }
+
+    /// Returns the instance of the module named `name`, or of the last
+    /// defined module.
+    fn module(&self, name: Option<&str>) -> Result<ModuleAddr, String> {
+        match name {
+            Some(name) => self
+                .modules
+                .get(name)
+                .copied()
+                .ok_or_else(|| format!("unknown module {}", name)),
+            None => self.current.ok_or_else(|| "no module defined".to_string()),
+        }
+    }
+
+    /// Parses `module` and calls `f` with it. Returns the message of the
+    /// error if the module is malformed.
+    fn with_module<R>(
+        &mut self,
+        module: ScriptModule,
+        f: impl FnOnce(&mut Self, Module) -> R,
+    ) -> Result<R, String> {
+        match module {
+            ScriptModule::Text(mut p) => {
+                let module = module_fields_parser(&mut p).map_err(|e| e.to_string())?;
+                Ok(f(self, module))
+            }
+            ScriptModule::Binary(binary) => {
+                let module = module_parser(binary)
+                    .and_then(|module| module.decode_funcs().map(|()| module))
+                    .map_err(|e| e.to_string())?;
+                Ok(f(self, module))
+            }
+            ScriptModule::Quote(source) => {
+                let source = core::str::from_utf8(source)
+                    .map_err(|_| "malformed UTF-8 encoding".to_string())?;
+                let buffer = ParseBuffer::new(source).map_err(|e| e.to_string())?;
+                let module = text::module_parser(&buffer).map_err(|e| e.to_string())?;
+                Ok(f(self, module))
+            }
+        }
+    }
+
+    /// Parses, validates and instantiates `module`. Returns an error if the
+    /// module is malformed or invalid, and the result of the instantiation
+    /// otherwise.
+    fn instantiate(&mut self, module: ScriptModule) -> Result<Result<ModuleAddr, Error>, String> {
+        self.with_module(module, |runner, module| {
+            let module = validate(module).map_err(|e| format!("invalid: {}", e))?;
+            Ok(runner.linker.instantiate(&mut runner.store, module))
+        })
+        .map_err(malformed)?
+    }
+
+    /// Performs `action`. Returns an error if the action refers to an
+    /// undefined module or export, and the result of the action otherwise.
+    fn act(&mut self, action: &Action) -> Result<Result<Vec<Value>, Error>, String> {
+        let (module, name) = match *action {
+            Action::Invoke { module, name, .. } | Action::Get { module, name } => (module, name),
+        };
+        let export = self.store.module(self.module(module)?).export(name);
+        match (action, export) {
+            (Action::Invoke { args, .. }, Some(ExternVal::Func(f))) => {
+                Ok(self.store.invoke(f, args))
+            }
+            (Action::Get { .. }, Some(ExternVal::Global(g))) => {
+                Ok(Ok(core::iter::once(self.store.global(g).value).collect()))
+            }
+            _ => Err(format!("unknown export {}", name)),
+        }
+    }
+}
+
+/// Describes the failure of a module that was expected to be well-formed.
+fn malformed(message: String) -> String {
+    format!("malformed: {}", message)
+}
+
+/// Checks that `actual`, the message of an error, starts with `message`,
+/// like the reference interpreter does.
+fn expected_message(actual: &str, message: &str) -> Result<(), String> {
+    match actual.starts_with(message) {
+        true => Ok(()),
+        false => Err(format!("expected \"{}\", got \"{}\"", message, actual)),
+    }
+}
+
+/// Checks that `result` is a trap whose message starts with `message`.
+fn trapped(result: Result<(), Error>, message: &str) -> Result<(), String> {
+    match result {
+        Err(Error::Trap { trap, .. }) if trap.to_string().starts_with(message) => Ok(()),
+        Err(e) => Err(format!("expected trap \"{}\", got {}", message, e)),
+        Ok(()) => Err(format!("expected trap \"{}\"", message)),
+    }
+}
+
+/// Returns true if the float with bit pattern `bits`, `width` bits wide, is
+/// a NaN: the canonical NaN, or any arithmetic NaN if `canonical` is false.
+///
+/// [Reference](https://webassembly.github.io/spec/core/syntax/values.html#canonical-nan)
+fn is_nan(bits: u64, width: u32, canonical: bool) -> bool {
+    let mantissa_bits = if width == 32 { 23 } else { 52 };
+    let exponent_mask = (1 << (width - 1 - mantissa_bits)) - 1;
+    let quiet = 1 << (mantissa_bits - 1);
+    let payload = bits & ((1 << mantissa_bits) - 1);
+    (bits >> mantissa_bits) & exponent_mask == exponent_mask
+        && match canonical {
+            true => payload == quiet,
+            false => payload & quiet != 0,
+        }
+}
+
+fn matches(expected: &Expected, value: &Value) -> bool {
+    match (expected, value) {
+        (Expected::Value(Value::F32(e)), Value::F32(v)) => e.to_bits() == v.to_bits(),
+        (Expected::Value(Value::F64(e)), Value::F64(v)) => e.to_bits() == v.to_bits(),
+        (Expected::Value(e), v) => e == v,
+        (Expected::Nan { bits, canonical }, Value::F32(v)) => {
+            *bits == 32 && is_nan(v.to_bits().into(), 32, *canonical)
+        }
+        (Expected::Nan { bits, canonical }, Value::F64(v)) => {
+            *bits == 64 && is_nan(v.to_bits(), 64, *canonical)
+        }
+        (Expected::Lanes { bits, lanes }, Value::V128(v)) => {
+            lanes.iter().enumerate().all(|(i, lane)| {
+                let lane_bits = v >> (i as u32 * bits);
+                let value = match bits {
+                    32 => Value::F32(f32::from_bits(lane_bits as u32)),
+                    _ => Value::F64(f64::from_bits(lane_bits as u64)),
+                };
+                matches(lane, &value)
+            })
+        }
+        (Expected::Ref(t), v) => v.val_type() == ValType::RefType(*t) && !v.is_null(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let source = r#"
+            (module $m
+              (func (export "add") (param i32 i32) (result i32)
+                (i32.add (local.get 0) (local.get 1)))
+              (func (export "div") (param i32) (result i32)
+                (i32.div_u (i32.const 1) (local.get 0))))
+            (register "m" $m)
+            (assert_return (invoke "add" (i32.const 1) (i32.const 2)) (i32.const 3))
+            (assert_return (invoke "add" (i32.const 1) (i32.const 2)) (i32.const 4))
+            (assert_trap (invoke "div" (i32.const 0)) "integer divide by zero")
+            (assert_invalid (module (func (result i32))) "type mismatch")
+            (assert_malformed (module quote "(func") "unexpected end")
+            (assert_unlinkable (module (import "m" "sub" (func))) "unknown import")
+        "#;
+        let outcomes = run(source).unwrap();
+        let failures: Vec<_> = outcomes
+            .iter()
+            .filter(|o| o.result.is_err())
+            .map(|o| (o.line, o.directive))
+            .collect();
+        assert_eq!(outcomes.len(), 8);
+        assert_eq!(failures, [(9, "assert_return")]);
+    }
+}
@@ -0,0 +1,296 @@
+use std::vec::Vec;
+
+use crate::{
+    exec::{ExternAddr, Value},
+    parse::types::RefType,
+    text::{Parser, TextError, TextErrorKind},
+};
+
+type Result<T> = core::result::Result<T, TextError>;
+
+/// A module defined by a script.
+pub(super) enum ScriptModule<'a> {
+    /// A module in the text format. The parser is positioned at the first
+    /// field of the module, which is parsed when the directive runs.
+    Text(Parser<'a>),
+    /// A module in the binary format, given as strings.
+    Binary(&'a [u8]),
+    /// A module in the text format, given as strings.
+    Quote(&'a [u8]),
+}
+
+pub(super) struct ModuleDef<'a> {
+    pub name: Option<&'a str>,
+    pub module: ScriptModule<'a>,
+}
+
+pub(super) enum Action<'a> {
+    /// Invokes the exported function `name` of `module`, or of the last
+    /// defined module.
+    Invoke {
+        module: Option<&'a str>,
+        name: &'a str,
+        args: Vec<Value>,
+    },
+    /// Reads the exported global `name` of `module`, or of the last defined
+    /// module.
+    Get {
+        module: Option<&'a str>,
+        name: &'a str,
+    },
+}
+
+/// A pattern for a result of an action.
+#[derive(Debug)]
+pub(super) enum Expected {
+    /// The value, with floats compared by their bit pattern.
+    Value(Value),
+    /// A float NaN of `bits` bits: the canonical NaN, with either sign, or
+    /// any arithmetic NaN if `canonical` is false.
+    Nan { bits: u32, canonical: bool },
+    /// A vector of floats of `bits` bits matching the patterns of its
+    /// lanes.
+    Lanes { bits: u32, lanes: Vec<Expected> },
+    /// Any non-null reference of the type.
+    Ref(RefType),
+}
+
+/// A command of a script.
+///
+/// [Reference](https://github.com/WebAssembly/spec/tree/main/interpreter#scripts)
+pub(super) enum Directive<'a> {
+    Module(ModuleDef<'a>),
+    /// Makes the exports of `module`, or of the last defined module,
+    /// available for import under the module name `name`.
+    Register {
+        name: &'a str,
+        module: Option<&'a str>,
+    },
+    Action(Action<'a>),
+    AssertReturn(Action<'a>, Vec<Expected>),
+    AssertTrap(Action<'a>, &'a str),
+    AssertExhaustion(Action<'a>, &'a str),
+    /// The module is linked but traps during instantiation.
+    AssertInstantiationTrap(ScriptModule<'a>, &'a str),
+    AssertMalformed(ScriptModule<'a>, &'a str),
+    AssertInvalid(ScriptModule<'a>, &'a str),
+    AssertUnlinkable(ScriptModule<'a>, &'a str),
+}
+
+impl Directive<'_> {
+    /// Returns the keyword of the directive.
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            Directive::Module(_) => "module",
+            Directive::Register { .. } => "register",
+            Directive::Action(Action::Invoke { .. }) => "invoke",
+            Directive::Action(Action::Get { .. }) => "get",
+            Directive::AssertReturn(..) => "assert_return",
+            Directive::AssertTrap(..) | Directive::AssertInstantiationTrap(..) => "assert_trap",
+            Directive::AssertExhaustion(..) => "assert_exhaustion",
+            Directive::AssertMalformed(..) => "assert_malformed",
+            Directive::AssertInvalid(..) => "assert_invalid",
+            Directive::AssertUnlinkable(..) => "assert_unlinkable",
+        }
+    }
+}
+
+/// Parses the directives of a script, along with the offset at which each
+/// of them starts.
+pub(super) fn directives<'a>(p: &mut Parser<'a>) -> Result<Vec<(usize, Directive<'a>)>> {
+    let mut directives = Vec::new();
+    while !p.is_eof() {
+        let offset = p.offset();
+        directives.push((offset, directive(p)?));
+    }
+    Ok(directives)
+}
+
+/// Returns true if the script consists of the fields of a single module
+/// rather than of directives.
+pub(super) fn is_inline_module(p: &Parser) -> bool {
+    matches!(
+        p.peek_field(),
+        Some(
+            "type"
+                | "import"
+                | "func"
+                | "table"
+                | "memory"
+                | "global"
+                | "export"
+                | "start"
+                | "elem"
+                | "data"
+        )
+    )
+}
+
+/// Returns the directive defining the inline module `source`.
+pub(super) fn inline_module(source: &str) -> Directive<'_> {
+    Directive::Module(ModuleDef {
+        name: None,
+        module: ScriptModule::Quote(source.as_bytes()),
+    })
+}
+
+fn directive<'a>(p: &mut Parser<'a>) -> Result<Directive<'a>> {
+    if p.peek_field() == Some("module") {
+        return module(p).map(Directive::Module);
+    }
+    if p.peek_field().is_some_and(|k| k == "invoke" || k == "get") {
+        return action(p).map(Directive::Action);
+    }
+    p.lparen()?;
+    let offset = p.offset();
+    let directive = match p.keyword()? {
+        "register" => Directive::Register {
+            name: p.name()?,
+            module: p.id(),
+        },
+        "assert_return" => {
+            let action = action(p)?;
+            let mut results = Vec::new();
+            while !p.peek_rparen() {
+                results.push(expected(p)?);
+            }
+            Directive::AssertReturn(action, results)
+        }
+        "assert_trap" if p.peek_field() == Some("module") => {
+            let module = module(p)?.module;
+            Directive::AssertInstantiationTrap(module, p.name()?)
+        }
+        "assert_trap" => Directive::AssertTrap(action(p)?, p.name()?),
+        "assert_exhaustion" => Directive::AssertExhaustion(action(p)?, p.name()?),
+        "assert_malformed" => Directive::AssertMalformed(module(p)?.module, p.name()?),
+        "assert_invalid" => Directive::AssertInvalid(module(p)?.module, p.name()?),
+        "assert_unlinkable" => Directive::AssertUnlinkable(module(p)?.module, p.name()?),
+        _ => return Err(p.error_at(TextErrorKind::UnexpectedToken, offset)),
+    };
+    p.rparen()?;
+    Ok(directive)
+}
+
+fn module<'a>(p: &mut Parser<'a>) -> Result<ModuleDef<'a>> {
+    p.lparen()?;
+    p.expect_keyword("module")?;
+    let name = p.id();
+    let module = if p.take_keyword("binary") {
+        ScriptModule::Binary(p.strings())
+    } else if p.take_keyword("quote") {
+        ScriptModule::Quote(p.strings())
+    } else {
+        let fields = p.clone();
+        p.skip_field()?;
+        return Ok(ModuleDef {
+            name,
+            module: ScriptModule::Text(fields),
+        });
+    };
+    p.rparen()?;
+    Ok(ModuleDef { name, module })
+}
+
+fn action<'a>(p: &mut Parser<'a>) -> Result<Action<'a>> {
+    p.lparen()?;
+    let action = match p.keyword()? {
+        "invoke" => {
+            let module = p.id();
+            let name = p.name()?;
+            let mut args = Vec::new();
+            while !p.peek_rparen() {
+                args.push(value(p)?);
+            }
+            Action::Invoke { module, name, args }
+        }
+        "get" => Action::Get {
+            module: p.id(),
+            name: p.name()?,
+        },
+        _ => return Err(p.unexpected()),
+    };
+    p.rparen()?;
+    Ok(action)
+}
+
+/// Parses a constant argument of an invocation.
+fn value(p: &mut Parser) -> Result<Value> {
+    p.lparen()?;
+    let value = match p.keyword()? {
+        "i32.const" => Value::I32(p.int(32)? as u32 as i32),
+        "i64.const" => Value::I64(p.int(64)? as i64),
+        "f32.const" => Value::F32(p.f32()?),
+        "f64.const" => Value::F64(p.f64()?),
+        "v128.const" => Value::V128(p.v128_const()?),
+        "ref.null" => Value::null(p.heaptype()?),
+        "ref.extern" => Value::ExternRef(Some(ExternAddr(p.u32()? as usize))),
+        _ => return Err(p.unexpected()),
+    };
+    p.rparen()?;
+    Ok(value)
+}
+
+/// Parses a NaN pattern if one is next.
+fn nan(p: &mut Parser, bits: u32) -> Option<Expected> {
+    let canonical = match p.peek_keyword()? {
+        "nan:canonical" => true,
+        "nan:arithmetic" => false,
+        _ => return None,
+    };
+    p.keyword().ok()?;
+    Some(Expected::Nan { bits, canonical })
+}
+
+/// Parses a pattern for a result of an action.
+fn expected(p: &mut Parser) -> Result<Expected> {
+    if !p.peek_field().is_some_and(|k| {
+        matches!(
+            k,
+            "f32.const" | "f64.const" | "v128.const" | "ref.func" | "ref.extern"
+        )
+    }) {
+        return value(p).map(Expected::Value);
+    }
+    p.lparen()?;
+    let expected = match p.keyword()? {
+        "f32.const" => match nan(p, 32) {
+            Some(nan) => nan,
+            None => Expected::Value(Value::F32(p.f32()?)),
+        },
+        "f64.const" => match nan(p, 64) {
+            Some(nan) => nan,
+            None => Expected::Value(Value::F64(p.f64()?)),
+        },
+        "v128.const" => match p.peek_keyword() {
+            Some("f32x4") => {
+                p.keyword()?;
+                let mut lanes = Vec::new();
+                for _ in 0..4 {
+                    lanes.push(match nan(p, 32) {
+                        Some(nan) => nan,
+                        None => Expected::Value(Value::F32(p.f32()?)),
+                    });
+                }
+                Expected::Lanes { bits: 32, lanes }
+            }
+            Some("f64x2") => {
+                p.keyword()?;
+                let mut lanes = Vec::new();
+                for _ in 0..2 {
+                    lanes.push(match nan(p, 64) {
+                        Some(nan) => nan,
+                        None => Expected::Value(Value::F64(p.f64()?)),
+                    });
+                }
+                Expected::Lanes { bits: 64, lanes }
+            }
+            _ => Expected::Value(Value::V128(p.v128_const()?)),
+        },
+        "ref.func" => Expected::Ref(RefType::FuncRef),
+        "ref.extern" if p.peek_rparen() => Expected::Ref(RefType::ExternRef),
+        "ref.extern" => Expected::Value(Value::ExternRef(Some(ExternAddr(p.u32()? as usize)))),
+        _ => return Err(p.unexpected()),
+    };
+    p.rparen()?;
+    Ok(expected)
+}
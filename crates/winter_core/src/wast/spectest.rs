@@ -0,0 +1,55 @@
+use crate::{
+    exec::{ExternVal, Linker, Store, Value},
+    parse::types::{GlobalType, Limits, NumType, RefType, TableType, ValType},
+};
+
+/// Defines the `spectest` module imported by the scripts of the test
+/// suite in `linker`, allocating its entities in `store`. The `print`
+/// functions do nothing.
+///
+/// [Reference](https://github.com/WebAssembly/spec/tree/main/interpreter#spectest-host-module)
+pub(super) fn define(store: &mut Store, linker: &mut Linker) {
+    linker
+        .func_wrap(store, "spectest", "print", || ())
+        .func_wrap(store, "spectest", "print_i32", |_: i32| ())
+        .func_wrap(store, "spectest", "print_i64", |_: i64| ())
+        .func_wrap(store, "spectest", "print_f32", |_: f32| ())
+        .func_wrap(store, "spectest", "print_f64", |_: f64| ())
+        .func_wrap(store, "spectest", "print_i32_f32", |_: i32, _: f32| ())
+        .func_wrap(store, "spectest", "print_f64_f64", |_: f64, _: f64| ());
+
+    let globals = [
+        ("global_i32", NumType::I32, Value::I32(666)),
+        ("global_i64", NumType::I64, Value::I64(666)),
+        ("global_f32", NumType::F32, Value::F32(666.6)),
+        ("global_f64", NumType::F64, Value::F64(666.6)),
+    ];
+    for (name, t, value) in globals {
+        let global_type = GlobalType {
+            value_type: ValType::NumType(t),
+            mutable: false,
+        };
+        let addr = store.alloc_host_global(global_type, value);
+        linker.define("spectest", name, ExternVal::Global(addr));
+    }
+
+    let table_type = TableType {
+        ref_type: RefType::FuncRef,
+        limits: Limits {
+            min: 10,
+            max: Some(20),
+        },
+    };
+    let table = store
+        .alloc_host_table(table_type, Value::FuncRef(None))
+        .expect("the spectest table is small");
+    let memory = store
+        .alloc_host_memory(Limits {
+            min: 1,
+            max: Some(2),
+        })
+        .expect("the spectest memory is small");
+    linker
+        .define("spectest", "table", ExternVal::Table(table))
+        .define("spectest", "memory", ExternVal::Mem(memory));
+}
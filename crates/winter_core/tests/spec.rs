@@ -0,0 +1,369 @@
+//! Runs the scripts of the WebAssembly specification test suite vendored in
+//! `tests/testsuite`, one test per script.
+//!
+//! Every failing directive is printed with its location. A script fails if
+//! a directive fails that is not listed as a known failure, with the reason
+//! why: the snapshot of the test suite predates the current specification,
+//! or expects another message for a malformed module. Scripts of proposals
+//! written in a syntax that has changed since are ignored.
+
+use std::fs;
+
+use winter_core::wast;
+
+/// The snapshot of the test suite predates the proposals, mostly reference
+/// types and bulk memory, that the specification has since adopted.
+const OUTDATED: &str = "outdated";
+/// The parser rejects the module for another reason than the reference
+/// interpreter, which decodes sections past their declared sizes.
+const MESSAGE: &str = "message";
+
+/// Runs the script at `path` in the test suite. `known` lists the lines of
+/// the directives that are known to fail, with the reason why.
+fn run(path: &str, known: &[(usize, &str)]) {
+    let path = format!("{}/tests/testsuite/{}", env!("CARGO_MANIFEST_DIR"), path);
+    let source = fs::read_to_string(&path).unwrap();
+    let outcomes = wast::run(&source).unwrap_or_else(|e| {
+        let (line, col) = e.line_col(&source);
+        panic!("{}:{}:{}: {}", path, line, col, e)
+    });
+
+    let (mut passed, mut known_failures, mut failed) = (0, 0, 0);
+    for outcome in &outcomes {
+        let message = match &outcome.result {
+            Ok(()) => {
+                passed += 1;
+                continue;
+            }
+            Err(message) => message,
+        };
+        let reason = known
+            .iter()
+            .find(|(line, _)| *line == outcome.line)
+            .map(|(_, reason)| reason);
+        match reason {
+            Some(_) => known_failures += 1,
+            None => failed += 1,
+        }
+        print!(
+            "{}:{}: {}: {}",
+            path, outcome.line, outcome.directive, message
+        );
+        match reason {
+            Some(reason) => println!(" (known failure: {})", reason),
+            None => println!(),
+        }
+    }
+    println!(
+        "{}: {} passed, {} known failures, {} failed",
+        path, passed, known_failures, failed
+    );
+    assert_eq!(failed, 0, "{} directives failed in {}", failed, path);
+}
+
+macro_rules! spec {
+    ($($(#[$attr:meta])* $name:ident => $path:literal $([$($line:literal: $reason:expr),* $(,)?])?,)*) => {
+        $(
+            #[test]
+            $(#[$attr])*
+            fn $name() {
+                run($path, &[$($(($line, $reason)),*)?]);
+            }
+        )*
+    };
+}
+
+spec! {
+    address => "address.wast",
+    align => "align.wast",
+    // Integers cut by the end of their section are reported as truncated.
+    binary_leb128 => "binary-leb128.wast" [289: MESSAGE, 346: MESSAGE],
+    binary => "binary.wast" [
+        // The reserved byte of `call_indirect` is a table index since
+        // reference types.
+        49: OUTDATED, 68: OUTDATED, 87: OUTDATED, 105: OUTDATED, 123: OUTDATED,
+        // The truncated bytes of a data segment fail the last alternative
+        // of its flags instead.
+        695: MESSAGE,
+        // The reference interpreter reads the next section as an entry.
+        625: MESSAGE, 762: MESSAGE,
+    ],
+    block => "block.wast",
+    br => "br.wast",
+    br_if => "br_if.wast",
+    br_table => "br_table.wast",
+    break_drop => "break-drop.wast",
+    call => "call.wast",
+    call_indirect => "call_indirect.wast",
+    comments => "comments.wast",
+    const_ => "const.wast",
+    conversions => "conversions.wast",
+    // A section longer than the binary is reported as truncated.
+    custom => "custom.wast" [114: MESSAGE],
+    // `(data $m ...)` names the segment since bulk memory, and out of bounds
+    // segments trap during instantiation instead of failing to link.
+    data => "data.wast" [
+        5: OUTDATED,
+        161: OUTDATED, 169: OUTDATED, 177: OUTDATED, 185: OUTDATED, 193: OUTDATED, 210: OUTDATED,
+        219: OUTDATED, 226: OUTDATED, 234: OUTDATED, 242: OUTDATED, 250: OUTDATED, 257: OUTDATED,
+        265: OUTDATED, 272: OUTDATED,
+    ],
+    // `(elem $t ...)` names the segment since bulk memory, and out of bounds
+    // segments trap during instantiation instead of failing to link.
+    elem => "elem.wast" [
+        4: OUTDATED,
+        142: OUTDATED, 151: OUTDATED, 160: OUTDATED, 169: OUTDATED, 177: OUTDATED, 185: OUTDATED,
+        194: OUTDATED, 202: OUTDATED, 211: OUTDATED, 219: OUTDATED, 228: OUTDATED, 236: OUTDATED,
+    ],
+    endianness => "endianness.wast",
+    exports => "exports.wast",
+    f32 => "f32.wast",
+    f32_bitwise => "f32_bitwise.wast",
+    f32_cmp => "f32_cmp.wast",
+    f64 => "f64.wast",
+    f64_bitwise => "f64_bitwise.wast",
+    f64_cmp => "f64_cmp.wast",
+    fac => "fac.wast",
+    float_exprs => "float_exprs.wast",
+    float_literals => "float_literals.wast",
+    float_memory => "float_memory.wast",
+    float_misc => "float_misc.wast",
+    forward => "forward.wast",
+    // Functions may return multiple values since multi-value.
+    func => "func.wast" [492: OUTDATED, 496: OUTDATED],
+    func_ptrs => "func_ptrs.wast",
+    global => "global.wast",
+    // The snapshot names malformed mutabilities `invalid mutability`.
+    globals => "globals.wast" [304: OUTDATED, 317: OUTDATED, 334: OUTDATED, 346: OUTDATED],
+    i32 => "i32.wast",
+    i64 => "i64.wast",
+    if_ => "if.wast",
+    // Modules may have multiple tables since reference types.
+    imports => "imports.wast" [309: OUTDATED, 313: OUTDATED, 317: OUTDATED],
+    inline_module => "inline-module.wast",
+    int_exprs => "int_exprs.wast",
+    int_literals => "int_literals.wast",
+    labels => "labels.wast",
+    left_to_right => "left-to-right.wast",
+    // Out of bounds segments trap during instantiation since bulk memory,
+    // after the preceding segments are written.
+    linking => "linking.wast" [
+        206: OUTDATED, 227: OUTDATED, 236: OUTDATED, 238: OUTDATED, 248: OUTDATED,
+        298: OUTDATED, 334: OUTDATED, 342: OUTDATED, 344: OUTDATED, 354: OUTDATED,
+    ],
+    load => "load.wast",
+    local_get => "local_get.wast",
+    local_set => "local_set.wast",
+    local_tee => "local_tee.wast",
+    loop_ => "loop.wast",
+    memory => "memory.wast",
+    memory_grow => "memory_grow.wast",
+    memory_redundancy => "memory_redundancy.wast",
+    memory_size => "memory_size.wast",
+    memory_trap => "memory_trap.wast",
+    names => "names.wast",
+    nop => "nop.wast",
+    return_ => "return.wast",
+    select => "select.wast",
+    skip_stack_guard_page => "skip-stack-guard-page.wast",
+    stack => "stack.wast",
+    start => "start.wast",
+    store => "store.wast",
+    switch => "switch.wast",
+    table => "table.wast",
+    token => "token.wast",
+    traps => "traps.wast",
+    // Functions may return multiple values since multi-value.
+    type_ => "type.wast" [52: OUTDATED, 56: OUTDATED],
+    typecheck => "typecheck.wast",
+    unreachable => "unreachable.wast",
+    unreached_invalid => "unreached-invalid.wast",
+    unwind => "unwind.wast",
+    utf8_custom_section_id => "utf8-custom-section-id.wast",
+    utf8_import_field => "utf8-import-field.wast",
+    utf8_import_module => "utf8-import-module.wast",
+    utf8_invalid_encoding => "utf8-invalid-encoding.wast",
+
+    multi_value_binary => "proposals/multi-value/binary.wast" [
+        // The reserved byte of `call_indirect` is a table index since
+        // reference types.
+        49: OUTDATED, 68: OUTDATED, 87: OUTDATED, 105: OUTDATED, 123: OUTDATED,
+        // The truncated bytes of a data segment fail the last alternative
+        // of its flags instead.
+        695: MESSAGE,
+    ],
+    multi_value_block => "proposals/multi-value/block.wast",
+    multi_value_br => "proposals/multi-value/br.wast",
+    multi_value_call => "proposals/multi-value/call.wast",
+    multi_value_call_indirect => "proposals/multi-value/call_indirect.wast",
+    multi_value_fac => "proposals/multi-value/fac.wast",
+    multi_value_func => "proposals/multi-value/func.wast",
+    multi_value_if => "proposals/multi-value/if.wast",
+    multi_value_loop => "proposals/multi-value/loop.wast",
+    multi_value_type => "proposals/multi-value/type.wast",
+    nontrapping_float_to_int_conversions =>
+        "proposals/nontrapping-float-to-int-conversions/conversions.wast",
+    sign_extension_i32 => "proposals/sign-extension-ops/i32.wast",
+    sign_extension_i64 => "proposals/sign-extension-ops/i64.wast",
+
+    bulk_memory_binary => "proposals/bulk-memory-operations/binary.wast" [
+        // The reserved byte of `call_indirect` is a table index since
+        // reference types.
+        294: OUTDATED, 313: OUTDATED, 332: OUTDATED, 350: OUTDATED, 368: OUTDATED,
+        // Element segments with expressions are decoded as in the current
+        // specification.
+        724: OUTDATED, 750: OUTDATED, 800: OUTDATED,
+        // The truncated bytes of a data segment fail the last alternative
+        // of its flags instead.
+        1102: MESSAGE,
+        // The reference interpreter reads the next section as an entry.
+        1032: MESSAGE, 1169: MESSAGE,
+    ],
+    // `ref.null` takes a heap type since reference types.
+    bulk_memory_bulk => "proposals/bulk-memory-operations/bulk.wast" [6: OUTDATED],
+    // A section longer than the binary is reported as truncated.
+    bulk_memory_custom => "proposals/bulk-memory-operations/custom.wast" [114: MESSAGE],
+    bulk_memory_data => "proposals/bulk-memory-operations/data.wast",
+    // `ref.null` takes a heap type since reference types.
+    bulk_memory_elem => "proposals/bulk-memory-operations/elem.wast" [4: OUTDATED, 68: OUTDATED],
+    // Modules may have multiple tables since reference types.
+    bulk_memory_imports => "proposals/bulk-memory-operations/imports.wast" [309: OUTDATED, 313: OUTDATED, 317: OUTDATED],
+    bulk_memory_linking => "proposals/bulk-memory-operations/linking.wast",
+    bulk_memory_memory_copy => "proposals/bulk-memory-operations/memory_copy.wast",
+    bulk_memory_memory_fill => "proposals/bulk-memory-operations/memory_fill.wast",
+    bulk_memory_memory_init => "proposals/bulk-memory-operations/memory_init.wast",
+    bulk_memory_table_copy => "proposals/bulk-memory-operations/table_copy.wast",
+    bulk_memory_table_init => "proposals/bulk-memory-operations/table_init.wast",
+    reference_types_binary => "proposals/reference-types/binary.wast" [
+        // Element segments with expressions are decoded as in the current
+        // specification.
+        1031: OUTDATED, 1057: OUTDATED, 1107: OUTDATED,
+        // The truncated bytes of a data segment fail the last alternative
+        // of its flags instead.
+        1409: MESSAGE,
+        // The reference interpreter reads the next section as an entry.
+        1339: MESSAGE, 1476: MESSAGE,
+    ],
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_br_table => "proposals/reference-types/br_table.wast",
+    // `ref.null` takes a heap type since reference types.
+    reference_types_bulk => "proposals/reference-types/bulk.wast" [6: OUTDATED],
+    // Unknown sections are malformed rather than invalid, and a section
+    // longer than the binary is reported as truncated.
+    reference_types_custom => "proposals/reference-types/custom.wast" [92: OUTDATED, 114: MESSAGE],
+    reference_types_data => "proposals/reference-types/data.wast",
+    // `ref.null` takes a heap type since reference types.
+    reference_types_elem => "proposals/reference-types/elem.wast" [4: OUTDATED, 79: OUTDATED],
+    reference_types_exports => "proposals/reference-types/exports.wast",
+    // `anyref` and `nullref` were removed from reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_globals => "proposals/reference-types/globals.wast",
+    reference_types_imports => "proposals/reference-types/imports.wast",
+    // `anyref` and `nullref` were removed from reference types.
+    reference_types_linking => "proposals/reference-types/linking.wast" [
+        96: OUTDATED, 104: OUTDATED, 106: OUTDATED, 119: OUTDATED, 123: OUTDATED, 127: OUTDATED,
+        133: OUTDATED, 137: OUTDATED, 141: OUTDATED, 145: OUTDATED, 149: OUTDATED, 153: OUTDATED,
+        317: OUTDATED, 322: OUTDATED, 324: OUTDATED, 330: OUTDATED, 334: OUTDATED, 338: OUTDATED,
+        342: OUTDATED, 346: OUTDATED, 350: OUTDATED,
+    ],
+    reference_types_memory_copy => "proposals/reference-types/memory_copy.wast",
+    reference_types_memory_fill => "proposals/reference-types/memory_fill.wast",
+    reference_types_memory_grow => "proposals/reference-types/memory_grow.wast",
+    // `data.drop` does not need a memory.
+    reference_types_memory_init => "proposals/reference-types/memory_init.wast" [188: OUTDATED],
+    reference_types_ref_func => "proposals/reference-types/ref_func.wast" [
+        // `anyref` was removed from reference types.
+        6: OUTDATED, 58: OUTDATED, 59: OUTDATED, 60: OUTDATED, 62: OUTDATED, 63: OUTDATED,
+        64: OUTDATED, 65: OUTDATED, 66: OUTDATED, 67: OUTDATED, 68: OUTDATED,
+        // References in globals declare functions since reference types.
+        79: OUTDATED,
+    ],
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_ref_is_null => "proposals/reference-types/ref_is_null.wast",
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_ref_null => "proposals/reference-types/ref_null.wast",
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_select => "proposals/reference-types/select.wast",
+    // Subtyping was removed from reference types.
+    reference_types_table_sub => "proposals/reference-types/table-sub.wast" [1: OUTDATED, 11: OUTDATED, 22: OUTDATED],
+    reference_types_table_copy => "proposals/reference-types/table_copy.wast",
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_table_fill => "proposals/reference-types/table_fill.wast",
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_table_get => "proposals/reference-types/table_get.wast",
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_table_grow => "proposals/reference-types/table_grow.wast",
+    // `elem.drop` does not need a table.
+    reference_types_table_init => "proposals/reference-types/table_init.wast" [192: OUTDATED, 204: OUTDATED],
+    // `ref.null` takes a heap type since reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_table_set => "proposals/reference-types/table_set.wast",
+    // `anyref` was removed from reference types.
+    #[ignore = "outdated syntax"]
+    reference_types_table_size => "proposals/reference-types/table_size.wast",
+    reference_types_unreached_invalid => "proposals/reference-types/unreached-invalid.wast",
+    simd_address => "proposals/simd/simd_address.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_align => "proposals/simd/simd_align.wast",
+    simd_bit_shift => "proposals/simd/simd_bit_shift.wast",
+    simd_bitwise => "proposals/simd/simd_bitwise.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_boolean => "proposals/simd/simd_boolean.wast",
+    // The snapshot predates the renumbering of SIMD opcodes.
+    simd_const => "proposals/simd/simd_const.wast" [
+        1566: OUTDATED, 1581: OUTDATED, 1583: OUTDATED, 1598: OUTDATED, 1600: OUTDATED,
+        1615: OUTDATED, 1617: OUTDATED, 1630: OUTDATED, 1634: OUTDATED, 1649: OUTDATED,
+        1651: OUTDATED, 1664: OUTDATED,
+    ],
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_conversions => "proposals/simd/simd_conversions.wast",
+    simd_f32x4 => "proposals/simd/simd_f32x4.wast",
+    simd_f32x4_arith => "proposals/simd/simd_f32x4_arith.wast",
+    simd_f32x4_cmp => "proposals/simd/simd_f32x4_cmp.wast",
+    simd_f64x2 => "proposals/simd/simd_f64x2.wast",
+    // NaN results are arithmetic NaNs since the final SIMD proposal.
+    simd_f64x2_arith => "proposals/simd/simd_f64x2_arith.wast" [5299: OUTDATED],
+    simd_f64x2_cmp => "proposals/simd/simd_f64x2_cmp.wast",
+    simd_i16x8_arith => "proposals/simd/simd_i16x8_arith.wast",
+    // `i64x2.abs` was added to the final SIMD proposal.
+    simd_i16x8_arith2 => "proposals/simd/simd_i16x8_arith2.wast" [339: OUTDATED],
+    simd_i16x8_cmp => "proposals/simd/simd_i16x8_cmp.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_i16x8_sat_arith => "proposals/simd/simd_i16x8_sat_arith.wast",
+    simd_i32x4_arith => "proposals/simd/simd_i32x4_arith.wast",
+    simd_i32x4_arith2 => "proposals/simd/simd_i32x4_arith2.wast",
+    simd_i32x4_cmp => "proposals/simd/simd_i32x4_cmp.wast",
+    simd_i64x2_arith => "proposals/simd/simd_i64x2_arith.wast",
+    simd_i8x16_arith => "proposals/simd/simd_i8x16_arith.wast",
+    simd_i8x16_arith2 => "proposals/simd/simd_i8x16_arith2.wast",
+    simd_i8x16_cmp => "proposals/simd/simd_i8x16_cmp.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_i8x16_sat_arith => "proposals/simd/simd_i8x16_sat_arith.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_lane => "proposals/simd/simd_lane.wast",
+    // `v8x16.swizzle` was renamed `i8x16.swizzle`.
+    simd_load => "proposals/simd/simd_load.wast" [120: OUTDATED, 127: OUTDATED],
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_load_extend => "proposals/simd/simd_load_extend.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_load_splat => "proposals/simd/simd_load_splat.wast",
+    // The snapshot predates the renaming of SIMD instructions.
+    #[ignore = "outdated instruction names"]
+    simd_splat => "proposals/simd/simd_splat.wast",
+    simd_store => "proposals/simd/simd_store.wast",
+}